@@ -0,0 +1,68 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Copy `text` with wl-copy under Wayland or xclip under X. Returns the tool
+/// that took it, or `None` with no display server or no working tool.
+pub fn copy(text: &str) -> Option<&'static str> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() && pipe_to("wl-copy", &[], text) {
+        return Some("wl-copy");
+    }
+    if std::env::var_os("DISPLAY").is_some() && pipe_to("xclip", &["-selection", "clipboard"], text) {
+        return Some("xclip");
+    }
+    None
+}
+
+/// The clipboard's text from wl-paste under Wayland or xclip under X.
+pub fn paste() -> Option<String> {
+    if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        read_from("wl-paste", &["--no-newline"])
+    } else if std::env::var_os("DISPLAY").is_some() {
+        read_from("xclip", &["-selection", "clipboard", "-o"])
+    } else {
+        None
+    }
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> bool {
+    let Ok(mut child) = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let wrote = child
+        .stdin
+        .take()
+        .is_some_and(|mut stdin| stdin.write_all(text.as_bytes()).is_ok());
+    child.wait().is_ok_and(|s| s.success()) && wrote
+}
+
+fn read_from(program: &str, args: &[&str]) -> Option<String> {
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_tools_fail_quietly() {
+        assert!(!pipe_to("/nonexistent/wl-copy", &[], "x"));
+        assert_eq!(read_from("/nonexistent/wl-paste", &[]), None);
+        assert!(pipe_to("cat", &[], "x"));
+        assert_eq!(read_from("echo", &["-n", "hi"]).as_deref(), Some("hi"));
+    }
+}
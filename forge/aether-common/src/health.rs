@@ -0,0 +1,165 @@
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::os::unix::net::UnixStream;
+use std::time::{Duration, Instant};
+
+/// Where a daemon listens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    Tcp(String),
+    Unix(String),
+}
+
+impl Target {
+    /// `host:port` is TCP; anything else, or anything starting with `/`, is a socket path.
+    pub fn from_addr(addr: &str) -> Self {
+        if addr.contains(':') && !addr.starts_with('/') {
+            Target::Tcp(addr.to_string())
+        } else {
+            Target::Unix(addr.to_string())
+        }
+    }
+
+    /// aetherd: `127.0.0.1:$AETHERD_TCP_PORT`, else `$AETHERD_SOCKET`.
+    pub fn aetherd() -> Self {
+        match std::env::var("AETHERD_TCP_PORT").ok().and_then(|p| p.parse::<u16>().ok()) {
+            Some(port) => Target::Tcp(format!("127.0.0.1:{port}")),
+            None => Target::Unix(std::env::var("AETHERD_SOCKET").unwrap_or_else(|_| "/tmp/aetherd.sock".to_string())),
+        }
+    }
+
+    /// cfcd: `$CFCD_HOST`, else `$CFCD_SOCKET`.
+    pub fn cfcd() -> Self {
+        match std::env::var("CFCD_HOST") {
+            Ok(host) => Target::Tcp(host),
+            Err(_) => Target::Unix(std::env::var("CFCD_SOCKET").unwrap_or_else(|_| "/tmp/cfcd.sock".to_string())),
+        }
+    }
+
+    /// The brain's HTTP server: `$BRAIN_HOST`.
+    pub fn brain() -> Self {
+        Target::Tcp(std::env::var("BRAIN_HOST").unwrap_or_else(|_| "10.0.2.2:9200".to_string()))
+    }
+}
+
+/// One answer, or lack of one, from a daemon's `/v0/health`.
+#[derive(Clone, Debug)]
+pub struct Probe {
+    /// It answered with `"ok": true`.
+    pub ok: bool,
+    pub latency: Duration,
+    pub version: Option<String>,
+    /// Why it isn't `ok`.
+    pub error: Option<String>,
+}
+
+/// GET `/v0/health` from `target`, waiting up to `timeout` for each of
+/// connecting, writing and reading.
+pub fn probe(target: &Target, timeout: Duration) -> Probe {
+    let start = Instant::now();
+    let result = get_health(target, timeout);
+    let latency = start.elapsed();
+    let (ok, version, error) = match result {
+        Ok(body) => match serde_json::from_str::<serde_json::Value>(&body) {
+            Ok(v) => {
+                let ok = v.get("ok").and_then(|o| o.as_bool()) == Some(true);
+                let version = v.get("version").and_then(|v| v.as_str()).map(str::to_string);
+                (ok, version, (!ok).then(|| "health reported not ok".to_string()))
+            }
+            Err(e) => (false, None, Some(format!("bad health body: {e}"))),
+        },
+        Err(e) => (false, None, Some(e.to_string())),
+    };
+    Probe { ok, latency, version, error }
+}
+
+/// Whether `target` answers `/v0/health` with `"ok": true`.
+pub fn is_up(target: &Target, timeout: Duration) -> bool {
+    probe(target, timeout).ok
+}
+
+fn get_health(target: &Target, timeout: Duration) -> io::Result<String> {
+    let request = "GET /v0/health HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+    let mut response = String::new();
+    match target {
+        Target::Tcp(host) => {
+            let addr = host
+                .to_socket_addrs()?
+                .next()
+                .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("{host}: no address")))?;
+            let mut stream = TcpStream::connect_timeout(&addr, timeout)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            stream.write_all(request.as_bytes())?;
+            stream.read_to_string(&mut response)?;
+        }
+        Target::Unix(path) => {
+            let mut stream = UnixStream::connect(path)?;
+            stream.set_read_timeout(Some(timeout))?;
+            stream.set_write_timeout(Some(timeout))?;
+            stream.write_all(request.as_bytes())?;
+            stream.read_to_string(&mut response)?;
+        }
+    }
+    match response.split_once("\r\n\r\n") {
+        Some((_, body)) => Ok(body.to_string()),
+        None => Err(io::Error::new(io::ErrorKind::InvalidData, "no HTTP response")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    const TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Answer one request on a fresh port with `body`, returning the port's target.
+    fn serve_once(body: &'static str) -> Target {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = stream.read(&mut buf);
+            let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}", body.len());
+            let _ = stream.write_all(response.as_bytes());
+        });
+        Target::Tcp(addr.to_string())
+    }
+
+    #[test]
+    fn addresses_with_a_port_are_tcp() {
+        assert_eq!(Target::from_addr("127.0.0.1:9100"), Target::Tcp("127.0.0.1:9100".to_string()));
+        assert_eq!(Target::from_addr("/tmp/aurorad.sock"), Target::Unix("/tmp/aurorad.sock".to_string()));
+        assert_eq!(Target::from_addr("/tmp/odd:name.sock"), Target::Unix("/tmp/odd:name.sock".to_string()));
+    }
+
+    #[test]
+    fn healthy_daemon_is_up_with_its_version() {
+        let p = probe(&serve_once(r#"{"ok":true,"version":"1.2.3"}"#), TIMEOUT);
+        assert!(p.ok);
+        assert_eq!(p.version.as_deref(), Some("1.2.3"));
+        assert!(p.error.is_none());
+    }
+
+    #[test]
+    fn not_ok_and_garbage_bodies_are_down() {
+        let p = probe(&serve_once(r#"{"ok":false}"#), TIMEOUT);
+        assert!(!p.ok);
+        assert_eq!(p.error.as_deref(), Some("health reported not ok"));
+
+        let p = probe(&serve_once("<html>"), TIMEOUT);
+        assert!(!p.ok);
+        assert!(p.error.unwrap().starts_with("bad health body"));
+    }
+
+    #[test]
+    fn nothing_listening_is_down() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let target = Target::Tcp(listener.local_addr().unwrap().to_string());
+        drop(listener);
+        assert!(!is_up(&target, TIMEOUT));
+        assert!(!is_up(&Target::Unix("/nonexistent/aether.sock".to_string()), TIMEOUT));
+    }
+}
@@ -0,0 +1,301 @@
+// Without the feature no language is known, so nothing builds tokens
+#![cfg_attr(not(feature = "highlight"), allow(dead_code))]
+
+use std::ops::Range;
+
+/// What a highlighted run of source is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token {
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+/// Colours lines of one language, carrying block comments from line to line.
+pub struct Highlighter {
+    #[cfg(feature = "highlight")]
+    lang: &'static Lang,
+    #[cfg(feature = "highlight")]
+    in_comment: bool,
+}
+
+impl Highlighter {
+    /// A highlighter for a language name or fence info string (`rust`, `py`)
+    /// or a file extension.
+    /// `None` when the language is unknown or highlighting isn't built in.
+    pub fn for_name(name: &str) -> Option<Self> {
+        #[cfg(feature = "highlight")]
+        {
+            let name = name.trim().to_ascii_lowercase();
+            let lang = LANGS.iter().find(|l| l.names.contains(&name.as_str()))?;
+            Some(Self { lang, in_comment: false })
+        }
+        #[cfg(not(feature = "highlight"))]
+        {
+            let _ = name;
+            None
+        }
+    }
+
+    /// Tokens in `line` as byte ranges, in order. Text between them is plain.
+    pub fn line(&mut self, line: &str) -> Vec<(Range<usize>, Token)> {
+        #[cfg(feature = "highlight")]
+        {
+            self.tokens(line)
+        }
+        #[cfg(not(feature = "highlight"))]
+        {
+            let _ = line;
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(feature = "highlight")]
+struct Lang {
+    /// Names, fence info strings and file extensions that mean this language.
+    names: &'static [&'static str],
+    keywords: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    /// The line comment marker only counts at the start of a word, as `#`
+    /// in shell, so `a#b` and `${#arr}` aren't comments.
+    comment_at_word_start: bool,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+#[cfg(feature = "highlight")]
+const LANGS: &[Lang] = &[
+    Lang {
+        names: &["rust", "rs"],
+        keywords: &[
+            "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "false", "fn",
+            "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+            "self", "Self", "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where",
+            "while",
+        ],
+        line_comment: Some("//"),
+        comment_at_word_start: false,
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"'],
+    },
+    Lang {
+        names: &["python", "py"],
+        keywords: &[
+            "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del", "elif", "else",
+            "except", "False", "finally", "for", "from", "global", "if", "import", "in", "is", "lambda", "None",
+            "not", "or", "pass", "raise", "return", "True", "try", "while", "with", "yield",
+        ],
+        line_comment: Some("#"),
+        comment_at_word_start: false,
+        block_comment: None,
+        quotes: &['"', '\''],
+    },
+    Lang {
+        names: &["sh", "bash", "shell", "zsh", "console"],
+        keywords: &[
+            "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if", "in", "local",
+            "return", "then", "until", "while",
+        ],
+        line_comment: Some("#"),
+        comment_at_word_start: true,
+        block_comment: None,
+        quotes: &['"', '\''],
+    },
+    Lang {
+        names: &["c", "h", "cpp", "cc", "hpp", "c++"],
+        keywords: &[
+            "auto", "bool", "break", "case", "char", "class", "const", "continue", "default", "do", "double",
+            "else", "enum", "extern", "false", "float", "for", "if", "int", "long", "namespace", "nullptr",
+            "return", "short", "signed", "sizeof", "static", "struct", "switch", "true", "typedef", "union",
+            "unsigned", "void", "volatile", "while",
+        ],
+        line_comment: Some("//"),
+        comment_at_word_start: false,
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\''],
+    },
+    Lang {
+        names: &["javascript", "js", "jsx", "typescript", "ts", "tsx"],
+        keywords: &[
+            "async", "await", "break", "case", "catch", "class", "const", "continue", "default", "else",
+            "export", "extends", "false", "finally", "for", "function", "if", "import", "in", "instanceof",
+            "interface", "let", "new", "null", "of", "return", "switch", "this", "throw", "true", "try", "type",
+            "typeof", "undefined", "var", "while", "yield",
+        ],
+        line_comment: Some("//"),
+        comment_at_word_start: false,
+        block_comment: Some(("/*", "*/")),
+        quotes: &['"', '\'', '`'],
+    },
+    Lang {
+        names: &["json"],
+        keywords: &["true", "false", "null"],
+        line_comment: None,
+        comment_at_word_start: false,
+        block_comment: None,
+        quotes: &['"'],
+    },
+    Lang {
+        names: &["toml"],
+        keywords: &["true", "false"],
+        line_comment: Some("#"),
+        comment_at_word_start: false,
+        block_comment: None,
+        quotes: &['"', '\''],
+    },
+];
+
+#[cfg(feature = "highlight")]
+impl Lang {
+    /// Whether a line comment may start right after `before`.
+    fn comment_can_start(&self, before: &str) -> bool {
+        !self.comment_at_word_start
+            || before.chars().next_back().is_none_or(|c| c.is_whitespace() || ";|&()".contains(c))
+    }
+}
+
+#[cfg(feature = "highlight")]
+impl Highlighter {
+    fn tokens(&mut self, line: &str) -> Vec<(Range<usize>, Token)> {
+        let lang = self.lang;
+        let mut out = Vec::new();
+        let mut i = 0;
+        if self.in_comment {
+            let (_, close) = lang.block_comment.unwrap_or_default();
+            match line.find(close) {
+                Some(end) => {
+                    i = end + close.len();
+                    self.in_comment = false;
+                }
+                None => i = line.len(),
+            }
+            out.push((0..i, Token::Comment));
+        }
+
+        while let Some(c) = line[i..].chars().next() {
+            let rest = &line[i..];
+            if lang.line_comment.is_some_and(|m| rest.starts_with(m)) && lang.comment_can_start(&line[..i]) {
+                out.push((i..line.len(), Token::Comment));
+                break;
+            }
+            if let Some((open, close)) = lang.block_comment.filter(|(open, _)| rest.starts_with(open)) {
+                let end = match rest[open.len()..].find(close) {
+                    Some(end) => i + open.len() + end + close.len(),
+                    None => {
+                        self.in_comment = true;
+                        line.len()
+                    }
+                };
+                out.push((i..end, Token::Comment));
+                i = end;
+                continue;
+            }
+            if lang.quotes.contains(&c) {
+                // Up to the matching quote, skipping escaped ones
+                let mut end = line.len();
+                let mut escaped = false;
+                for (j, d) in rest.char_indices().skip(1) {
+                    match d {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        _ if d == c => {
+                            end = i + j + 1;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                out.push((i..end, Token::String));
+                i = end;
+                continue;
+            }
+            if c.is_alphanumeric() || c == '_' {
+                // Numbers keep their decimal point
+                let len = rest
+                    .find(|d: char| !(d.is_alphanumeric() || d == '_' || (c.is_ascii_digit() && d == '.')))
+                    .unwrap_or(rest.len());
+                let word = &rest[..len];
+                if c.is_ascii_digit() {
+                    out.push((i..i + len, Token::Number));
+                } else if lang.keywords.contains(&word) {
+                    out.push((i..i + len, Token::Keyword));
+                }
+                i += len;
+                continue;
+            }
+            i += c.len_utf8();
+        }
+        out
+    }
+}
+
+#[cfg(all(test, feature = "highlight"))]
+mod tests {
+    use super::*;
+
+    /// Each token in `line` as (text, kind).
+    fn tokens<'a>(h: &mut Highlighter, line: &'a str) -> Vec<(&'a str, Token)> {
+        h.line(line).into_iter().map(|(r, t)| (&line[r], t)).collect()
+    }
+
+    #[test]
+    fn languages_are_found_by_name_or_extension() {
+        assert!(Highlighter::for_name("rust").is_some());
+        assert!(Highlighter::for_name(" PY ").is_some());
+        assert!(Highlighter::for_name("tsx").is_some());
+        assert!(Highlighter::for_name("cobol").is_none());
+    }
+
+    #[test]
+    fn rust_keywords_strings_numbers_and_comments() {
+        let mut h = Highlighter::for_name("rs").unwrap();
+        assert_eq!(
+            tokens(&mut h, r#"let s = "a \"q\"" + 3.14; // done"#),
+            [
+                ("let", Token::Keyword),
+                (r#""a \"q\"""#, Token::String),
+                ("3.14", Token::Number),
+                ("// done", Token::Comment),
+            ]
+        );
+        // Keywords are whole words only
+        assert!(tokens(&mut h, "letter formula").is_empty());
+    }
+
+    #[test]
+    fn block_comments_carry_across_lines() {
+        let mut h = Highlighter::for_name("c").unwrap();
+        assert_eq!(tokens(&mut h, "int x; /* starts"), [("int", Token::Keyword), ("/* starts", Token::Comment)]);
+        assert_eq!(tokens(&mut h, "still int inside"), [("still int inside", Token::Comment)]);
+        assert_eq!(
+            tokens(&mut h, "ends */ return 0;"),
+            [("ends */", Token::Comment), ("return", Token::Keyword), ("0", Token::Number)]
+        );
+        assert_eq!(tokens(&mut h, "return"), [("return", Token::Keyword)]);
+        // A comment opened and closed on one line leaves no state behind
+        assert_eq!(tokens(&mut h, "/* a */ if"), [("/* a */", Token::Comment), ("if", Token::Keyword)]);
+        assert_eq!(tokens(&mut h, "if"), [("if", Token::Keyword)]);
+    }
+
+    #[test]
+    fn unterminated_strings_run_to_the_end_of_the_line() {
+        let mut h = Highlighter::for_name("py").unwrap();
+        assert_eq!(tokens(&mut h, "x = 'open"), [("'open", Token::String)]);
+        assert_eq!(tokens(&mut h, "def"), [("def", Token::Keyword)]);
+    }
+
+    #[test]
+    fn shell_hash_is_a_comment_only_at_a_word_start() {
+        let mut h = Highlighter::for_name("sh").unwrap();
+        assert_eq!(tokens(&mut h, "# note"), [("# note", Token::Comment)]);
+        assert_eq!(tokens(&mut h, "ls # list"), [("# list", Token::Comment)]);
+        assert_eq!(tokens(&mut h, "cd /;# x"), [("# x", Token::Comment)]);
+        assert!(tokens(&mut h, "echo a#b").iter().all(|&(_, t)| t != Token::Comment));
+        assert!(tokens(&mut h, "echo ${#arr}").iter().all(|&(_, t)| t != Token::Comment));
+        // Python has no such rule
+        let mut py = Highlighter::for_name("py").unwrap();
+        assert_eq!(tokens(&mut py, "a#b"), [("#b", Token::Comment)]);
+    }
+}
@@ -0,0 +1,132 @@
+use std::io::{self, Read};
+use std::time::Duration;
+
+/// Largest request (headers + body) we'll buffer.
+pub const MAX_REQUEST_BYTES: usize = 4 * 1024 * 1024;
+/// How long a client may stall mid-request before we give up.
+pub const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read a full HTTP request: headers, then as many body bytes as
+/// `Content-Length` promises, so bodies spanning several reads arrive intact.
+/// Returns an empty string if the client closes or idles out before sending anything.
+pub fn read_request(stream: &mut impl Read) -> io::Result<String> {
+    let mut data = Vec::new();
+    let mut buf = [0u8; 16384];
+
+    let header_end = loop {
+        let n = match stream.read(&mut buf) {
+            Ok(n) => n,
+            // An idle keep-alive connection timing out is a normal close
+            Err(e) if data.is_empty() && matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                return Ok(String::new());
+            }
+            Err(e) => return Err(e),
+        };
+        if n == 0 {
+            break None;
+        }
+        data.extend_from_slice(&buf[..n]);
+        if let Some(pos) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break Some(pos);
+        }
+        if data.len() > MAX_REQUEST_BYTES {
+            return Err(too_large(format!("request headers exceed {MAX_REQUEST_BYTES} bytes")));
+        }
+    };
+
+    if let Some(end) = header_end {
+        let headers = String::from_utf8_lossy(&data[..end]);
+        let content_length = headers
+            .lines()
+            .filter_map(|l| l.split_once(':'))
+            .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-length"))
+            .and_then(|(_, v)| v.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        if content_length > MAX_REQUEST_BYTES {
+            return Err(too_large(format!("request body too large ({content_length} bytes)")));
+        }
+
+        let total = end + 4 + content_length;
+        while data.len() < total {
+            let n = stream.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            data.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&data).to_string())
+}
+
+/// The body of a request read by `read_request`.
+pub fn parse_body(req: &str) -> &str {
+    req.split("\r\n\r\n").nth(1).unwrap_or("")
+}
+
+fn too_large(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out its data a few bytes per read, like a slow socket.
+    struct Trickle<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    /// Fails every read with `kind`.
+    struct Failing(io::ErrorKind);
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(self.0.into())
+        }
+    }
+
+    #[test]
+    fn body_spanning_several_reads_arrives_whole() {
+        let req = "POST /v0/jobs HTTP/1.1\r\nContent-Length: 26\r\n\r\n{\"job_type\":\"introspect\"}\n";
+        let got = read_request(&mut Trickle { data: req.as_bytes(), chunk: 5 }).unwrap();
+        assert_eq!(got, req);
+        assert_eq!(parse_body(&got), "{\"job_type\":\"introspect\"}\n");
+    }
+
+    #[test]
+    fn content_length_is_case_insensitive() {
+        let req = "POST / HTTP/1.1\r\ncontent-LENGTH: 3\r\n\r\nabc";
+        let got = read_request(&mut Trickle { data: req.as_bytes(), chunk: 1 }).unwrap();
+        assert_eq!(parse_body(&got), "abc");
+    }
+
+    #[test]
+    fn request_without_body_stops_at_headers() {
+        let req = "GET /v0/health HTTP/1.1\r\nHost: x\r\n\r\n";
+        assert_eq!(read_request(&mut Trickle { data: req.as_bytes(), chunk: 7 }).unwrap(), req);
+    }
+
+    #[test]
+    fn oversized_body_is_refused() {
+        let req = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_REQUEST_BYTES + 1);
+        let err = read_request(&mut Trickle { data: req.as_bytes(), chunk: 64 }).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn idle_timeout_before_any_bytes_is_a_clean_close() {
+        assert_eq!(read_request(&mut Failing(io::ErrorKind::WouldBlock)).unwrap(), "");
+        assert!(read_request(&mut Failing(io::ErrorKind::ConnectionReset)).is_err());
+    }
+}
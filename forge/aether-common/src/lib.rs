@@ -0,0 +1,12 @@
+/// Base64 encoding, for WebSocket handshakes and OSC 52.
+pub mod base64;
+/// The system clipboard through wl-copy/wl-paste or xclip.
+pub mod clipboard;
+/// Reading HTTP/1.1 requests off daemon sockets.
+pub mod http;
+/// Probing a daemon's `/v0/health`, and where each daemon listens.
+pub mod health;
+/// Network interface addresses.
+pub mod netif;
+/// Keyword, string and comment highlighting for a few languages.
+pub mod highlight;
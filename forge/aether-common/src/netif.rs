@@ -0,0 +1,35 @@
+use std::ffi::CStr;
+use std::net::Ipv4Addr;
+
+/// (interface, IPv4 address) pairs from `getifaddrs`; empty if the call fails.
+pub fn ipv4_addrs() -> Vec<(String, Ipv4Addr)> {
+    let mut out = Vec::new();
+    let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+    if unsafe { libc::getifaddrs(&mut ifap) } != 0 {
+        return out;
+    }
+    let mut cur = ifap;
+    while !cur.is_null() {
+        let ifa = unsafe { &*cur };
+        if !ifa.ifa_addr.is_null() && unsafe { (*ifa.ifa_addr).sa_family } as i32 == libc::AF_INET {
+            let sin = unsafe { &*(ifa.ifa_addr as *const libc::sockaddr_in) };
+            let addr = Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr));
+            let name = unsafe { CStr::from_ptr(ifa.ifa_name) }.to_string_lossy().to_string();
+            out.push((name, addr));
+        }
+        cur = ifa.ifa_next;
+    }
+    unsafe { libc::freeifaddrs(ifap) };
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loopback_is_listed() {
+        let addrs = ipv4_addrs();
+        assert!(addrs.iter().any(|(_, addr)| addr.is_loopback()), "got {addrs:?}");
+    }
+}
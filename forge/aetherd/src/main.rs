@@ -1,10 +1,16 @@
-use std::io::{Read, Write};
+mod policy;
+
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
 use std::net::TcpListener;
 use std::os::unix::net::UnixListener;
 use std::path::Path;
+use std::sync::Mutex;
 
+use aether_common::http::{parse_body, read_request, REQUEST_READ_TIMEOUT};
 use serde::Serialize;
 
+use policy::{CheckRequest, Policy};
+
 #[derive(Serialize)]
 struct HealthResponse {
     ok: bool,
@@ -22,37 +28,228 @@ fn write_http_json(stream: &mut dyn Write, status: &str, body: &str) -> anyhow::
     Ok(())
 }
 
-fn parse_body(req: &str) -> &str {
-    req.split("\r\n\r\n").nth(1).unwrap_or("")
+/// Re-stat the audit log after this many writes instead of on every one.
+const AUDIT_RESTAT_EVERY: u32 = 100;
+
+/// Size-based rotation state for `audit.jsonl`. The mutex also keeps
+/// concurrent writers from interleaving partial lines.
+struct AuditLog {
+    log_dir: String,
+    max_bytes: u64,
+    keep: u32,
+    /// Cached file size, refreshed every `AUDIT_RESTAT_EVERY` writes.
+    size: Option<u64>,
+    writes_since_stat: u32,
+}
+
+static AUDIT_LOG: Mutex<Option<AuditLog>> = Mutex::new(None);
+
+impl AuditLog {
+    fn from_env() -> Self {
+        Self {
+            log_dir: std::env::var("AETHER_LOG_DIR")
+                .unwrap_or_else(|_| "/tmp/aether_logs".to_string()),
+            max_bytes: std::env::var("AETHER_LOG_MAX_BYTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10 * 1024 * 1024),
+            keep: std::env::var("AETHER_LOG_KEEP")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            size: None,
+            writes_since_stat: 0,
+        }
+    }
+
+    fn path(&self) -> String {
+        format!("{}/audit.jsonl", self.log_dir)
+    }
+
+    /// Shift audit.jsonl -> .1 -> .2 ... dropping anything past `keep`.
+    fn rotate(&mut self) {
+        let base = self.path();
+        if self.keep == 0 {
+            let _ = std::fs::remove_file(&base);
+        } else {
+            for i in (1..self.keep).rev() {
+                let _ = std::fs::rename(format!("{base}.{i}"), format!("{base}.{}", i + 1));
+            }
+            let _ = std::fs::rename(&base, format!("{base}.1"));
+        }
+        self.size = Some(0);
+    }
+
+    fn append(&mut self, entry: &str) {
+        let log_path = self.path();
+        if self.size.is_none() || self.writes_since_stat >= AUDIT_RESTAT_EVERY {
+            let _ = std::fs::create_dir_all(&self.log_dir);
+            self.size = Some(std::fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0));
+            self.writes_since_stat = 0;
+        }
+
+        let size = self.size.unwrap_or(0);
+        if size > 0 && size + entry.len() as u64 > self.max_bytes {
+            self.rotate();
+        }
+
+        if let Ok(mut f) = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_path)
+        {
+            if f.write_all(entry.as_bytes()).is_ok() {
+                self.size = Some(self.size.unwrap_or(0) + entry.len() as u64);
+            }
+        }
+        self.writes_since_stat += 1;
+    }
+}
+
+/// Maximum entries returned by the audit read endpoints.
+const AUDIT_READ_MAX: usize = 1000;
+
+/// Last `n` lines of a file, read backwards in chunks so a large log
+/// isn't loaded whole.
+fn read_tail_lines(path: &str, n: usize) -> std::io::Result<Vec<String>> {
+    let mut f = std::fs::File::open(path)?;
+    let mut pos = f.seek(SeekFrom::End(0))?;
+    let mut tail: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let mut newlines = 0;
+
+    // Stop once we hold more than `n` newlines (the extra one bounds the
+    // oldest wanted line) or reach the start of the file.
+    while pos > 0 && newlines <= n {
+        let len = (chunk.len() as u64).min(pos) as usize;
+        pos -= len as u64;
+        f.seek(SeekFrom::Start(pos))?;
+        f.read_exact(&mut chunk[..len])?;
+        newlines += chunk[..len].iter().filter(|&&b| b == b'\n').count();
+        let mut merged = chunk[..len].to_vec();
+        merged.extend_from_slice(&tail);
+        tail = merged;
+    }
+
+    let text = String::from_utf8_lossy(&tail);
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let start = lines.len().saturating_sub(n);
+    Ok(lines[start..].iter().map(|l| l.to_string()).collect())
+}
+
+/// Value of `key` in a `a=1&b=2` query string.
+fn query_param<'a>(query: &'a str, key: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|kv| kv.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v)
 }
 
-fn append_audit_log(event: &str) {
-    let log_dir = std::env::var("AETHER_LOG_DIR")
-        .unwrap_or_else(|_| "/tmp/aether_logs".to_string());
+/// `GET /v0/audit/tail?n=50`
+fn audit_tail(query: &str) -> (&'static str, String) {
+    let n = match query_param(query, "n") {
+        Some(v) => match v.parse::<usize>() {
+            Ok(n) => n.min(AUDIT_READ_MAX),
+            Err(_) => {
+                return ("400 Bad Request", "{\"ok\":false,\"error\":\"invalid n\"}".to_string())
+            }
+        },
+        None => 50,
+    };
 
-    let _ = std::fs::create_dir_all(&log_dir);
-    let log_path = format!("{}/audit.jsonl", log_dir);
+    let guard = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let path = match guard.as_ref() {
+        Some(log) => log.path(),
+        None => AuditLog::from_env().path(),
+    };
+    let entries: Vec<serde_json::Value> = read_tail_lines(&path, n)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+    drop(guard);
 
+    let body = serde_json::json!({"ok": true, "entries": entries});
+    ("200 OK", body.to_string())
+}
+
+/// `GET /v0/audit/query?event_type=policy_check&since=<ts>`
+fn audit_query(query: &str) -> (&'static str, String) {
+    let event_type = query_param(query, "event_type");
+    let since = match query_param(query, "since") {
+        Some(v) => match v.parse::<u64>() {
+            Ok(ts) => ts,
+            Err(_) => {
+                return ("400 Bad Request", "{\"ok\":false,\"error\":\"invalid since\"}".to_string())
+            }
+        },
+        None => 0,
+    };
+
+    let guard = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    let path = match guard.as_ref() {
+        Some(log) => log.path(),
+        None => AuditLog::from_env().path(),
+    };
+    let mut entries: Vec<serde_json::Value> = Vec::new();
+    if let Ok(f) = std::fs::File::open(&path) {
+        for line in BufReader::new(f).lines().map_while(Result::ok) {
+            let entry: serde_json::Value = match serde_json::from_str(&line) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            if entry.get("ts").and_then(|t| t.as_u64()).unwrap_or(0) < since {
+                continue;
+            }
+            if let Some(want) = event_type {
+                let ty = entry.pointer("/event/type").and_then(|t| t.as_str());
+                if ty != Some(want) {
+                    continue;
+                }
+            }
+            entries.push(entry);
+        }
+    }
+    drop(guard);
+
+    // Keep the most recent matches if there are too many
+    let start = entries.len().saturating_sub(AUDIT_READ_MAX);
+    let body = serde_json::json!({"ok": true, "entries": &entries[start..]});
+    ("200 OK", body.to_string())
+}
+
+/// A client-supplied body as audit data: its JSON when it parses, else the
+/// raw text as a string, so it can never break out of the entry.
+fn audit_event(body: &str) -> serde_json::Value {
+    match body.trim() {
+        "" => serde_json::Value::Null,
+        text => serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(body.to_string())),
+    }
+}
+
+/// One audit.jsonl line.
+fn audit_entry(timestamp: u64, event: &serde_json::Value) -> String {
+    format!("{}\n", serde_json::json!({"ts": timestamp, "event": event}))
+}
+
+fn append_audit_log(event: &serde_json::Value) {
     let timestamp = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .unwrap()
         .as_secs();
 
-    let entry = format!("{{\"ts\":{},\"event\":{}}}\n", timestamp, event);
+    let entry = audit_entry(timestamp, event);
 
-    if let Ok(mut f) = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&log_path)
-    {
-        let _ = f.write_all(entry.as_bytes());
-    }
+    let mut guard = AUDIT_LOG.lock().unwrap_or_else(|e| e.into_inner());
+    guard.get_or_insert_with(AuditLog::from_env).append(&entry);
 }
 
-fn handle_conn(stream: &mut (impl Read + Write)) -> anyhow::Result<()> {
-    let mut buf = [0u8; 16384];
-    let n = stream.read(&mut buf)?;
-    let req = String::from_utf8_lossy(&buf[..n]);
+fn handle_conn(stream: &mut (impl Read + Write), policy: &Policy) -> anyhow::Result<()> {
+    let req = read_request(stream)?;
+    if req.is_empty() {
+        return Ok(());
+    }
 
     let mut lines = req.lines();
     let first = lines.next().unwrap_or("");
@@ -69,20 +266,40 @@ fn handle_conn(stream: &mut (impl Read + Write)) -> anyhow::Result<()> {
         return write_http_json(stream, "200 OK", &body);
     }
 
+    // Audit read endpoints
+    let (route, query) = path.split_once('?').unwrap_or((path, ""));
+    if method == "GET" && route == "/v0/audit/tail" {
+        let (status, body) = audit_tail(query);
+        return write_http_json(stream, status, &body);
+    }
+    if method == "GET" && route == "/v0/audit/query" {
+        let (status, body) = audit_query(query);
+        return write_http_json(stream, status, &body);
+    }
+
     // Audit logging endpoint
     if method == "POST" && path == "/v0/audit" {
-        let body_str = parse_body(&req);
-        append_audit_log(body_str);
+        append_audit_log(&audit_event(parse_body(&req)));
         let resp = "{\"ok\":true,\"logged\":true}";
         return write_http_json(stream, "200 OK", resp);
     }
 
-    // Policy check endpoint (v0: always allow, but log)
+    // Policy check endpoint: evaluate against the rules file and log
     if method == "POST" && path == "/v0/policy/check" {
         let body_str = parse_body(&req);
-        append_audit_log(&format!("{{\"type\":\"policy_check\",\"request\":{}}}", body_str));
-        let resp = "{\"ok\":true,\"allowed\":true,\"reason\":\"v0_allow_all\"}";
-        return write_http_json(stream, "200 OK", resp);
+        let check: CheckRequest = serde_json::from_str(body_str).unwrap_or(CheckRequest {
+            capability: String::new(),
+            target: String::new(),
+        });
+        let decision = policy.check(&check);
+        append_audit_log(&serde_json::json!({
+            "type": "policy_check",
+            "request": audit_event(body_str),
+            "allowed": decision.allowed,
+            "reason": decision.reason,
+        }));
+        let resp = serde_json::to_string(&decision)?;
+        return write_http_json(stream, "200 OK", &resp);
     }
 
     let body = "{\"ok\":false,\"error\":\"not_found\"}";
@@ -130,12 +347,21 @@ fn main() -> anyhow::Result<()> {
     eprintln!("  audit log: {}/audit.jsonl",
         std::env::var("AETHER_LOG_DIR").unwrap_or_else(|_| "/tmp/aether_logs".to_string()));
 
+    let policy_path = std::env::var("AETHER_POLICY_FILE")
+        .unwrap_or_else(|_| "/tmp/aether_policy.json".to_string());
+    let policy = Policy::load(&policy_path)?;
+    match policy.rule_count() {
+        Some(n) => eprintln!("  policy: {} rules from {} (deny unknown)", n, policy_path),
+        None => eprintln!("  policy: no rules file at {}, allowing all", policy_path),
+    }
+
     match listener {
         Listener::Unix(l) => {
             for conn in l.incoming() {
                 match conn {
                     Ok(mut stream) => {
-                        if let Err(err) = handle_conn(&mut stream) {
+                        let _ = stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT));
+                        if let Err(err) = handle_conn(&mut stream, &policy) {
                             eprintln!("aetherd error: {err:?}");
                         }
                     }
@@ -147,7 +373,8 @@ fn main() -> anyhow::Result<()> {
             for conn in l.incoming() {
                 match conn {
                     Ok(mut stream) => {
-                        if let Err(err) = handle_conn(&mut stream) {
+                        let _ = stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT));
+                        if let Err(err) = handle_conn(&mut stream, &policy) {
                             eprintln!("aetherd error: {err:?}");
                         }
                     }
@@ -159,3 +386,26 @@ fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn audit_entries_stay_one_valid_line() {
+        let forged = "x\"},\"allowed\":true}\n{\"ts\":0,\"event\":\"forged";
+        let line = audit_entry(42, &audit_event(forged));
+        assert_eq!(line.matches('\n').count(), 1);
+        assert!(line.ends_with('\n'));
+        let parsed: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["ts"], 42);
+        assert_eq!(parsed["event"], forged);
+    }
+
+    #[test]
+    fn json_bodies_are_kept_as_json() {
+        let event = audit_event(r#"{"type":"facet_open","facet":"files"}"#);
+        assert_eq!(event["facet"], "files");
+        assert_eq!(audit_event("  "), serde_json::Value::Null);
+    }
+}
@@ -0,0 +1,127 @@
+use serde::{Deserialize, Serialize};
+
+/// One allow/deny rule. `capability` names a `Capability` variant from
+/// nebula's facet module (e.g. "NetworkAccess", "FileAccess").
+#[derive(Deserialize)]
+pub struct Rule {
+    pub name: String,
+    pub capability: String,
+    pub allow: bool,
+    /// Optional glob on the request target (path or host). `*` matches any
+    /// run of characters, `?` a single one. Absent means any target.
+    #[serde(default)]
+    pub target: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct RulesFile {
+    rules: Vec<Rule>,
+}
+
+/// Body of a `/v0/policy/check` request.
+#[derive(Deserialize)]
+pub struct CheckRequest {
+    #[serde(default, alias = "action")]
+    pub capability: String,
+    #[serde(default, alias = "path", alias = "host")]
+    pub target: String,
+}
+
+#[derive(Serialize)]
+pub struct Decision {
+    pub ok: bool,
+    pub allowed: bool,
+    pub reason: String,
+}
+
+/// Loaded rules. With no rules file everything is allowed, as in v0;
+/// once a file is present, anything no rule matches is denied.
+pub enum Policy {
+    AllowAll,
+    Rules(Vec<Rule>),
+}
+
+impl Policy {
+    pub fn load(path: &str) -> anyhow::Result<Self> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Policy::AllowAll),
+            Err(e) => return Err(e.into()),
+        };
+        let file: RulesFile = serde_json::from_str(&data)?;
+        Ok(Policy::Rules(file.rules))
+    }
+
+    /// First matching rule wins.
+    pub fn check(&self, req: &CheckRequest) -> Decision {
+        let rules = match self {
+            Policy::AllowAll => {
+                return Decision {
+                    ok: true,
+                    allowed: true,
+                    reason: "v0_allow_all".to_string(),
+                }
+            }
+            Policy::Rules(rules) => rules,
+        };
+
+        for rule in rules {
+            if rule.capability != req.capability {
+                continue;
+            }
+            let target_ok = match &rule.target {
+                Some(pattern) => glob_match(pattern, &req.target),
+                None => true,
+            };
+            if target_ok {
+                return Decision {
+                    ok: true,
+                    allowed: rule.allow,
+                    reason: rule.name.clone(),
+                };
+            }
+        }
+
+        Decision {
+            ok: true,
+            allowed: false,
+            reason: "deny_unknown".to_string(),
+        }
+    }
+
+    pub fn rule_count(&self) -> Option<usize> {
+        match self {
+            Policy::AllowAll => None,
+            Policy::Rules(rules) => Some(rules.len()),
+        }
+    }
+}
+
+/// Minimal glob: `*` matches any run (including empty), `?` any one char.
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            // Let the last `*` swallow one more char and retry
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    while pi < p.len() && p[pi] == '*' {
+        pi += 1;
+    }
+    pi == p.len()
+}
@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Consecutive failures that open a breaker.
+const FAILURE_THRESHOLD: u32 = 5;
+/// How long an open breaker rejects calls before letting a probe through.
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+enum State {
+    Closed { failures: u32 },
+    Open { until: Instant },
+    /// Cooldown is over and one probe call is in flight.
+    HalfOpen,
+}
+
+/// Circuit breaker for one backend. After `FAILURE_THRESHOLD` failures in a
+/// row, calls are refused for `COOLDOWN`; then a single probe decides whether
+/// to close again or reopen.
+pub struct Breaker {
+    state: Mutex<State>,
+}
+
+impl Default for Breaker {
+    fn default() -> Self {
+        Breaker { state: Mutex::new(State::Closed { failures: 0 }) }
+    }
+}
+
+impl Breaker {
+    /// Whether a call may go ahead. The first caller after the cooldown
+    /// becomes the half-open probe; others are refused until it reports.
+    pub fn allow(&self) -> bool {
+        let Ok(mut state) = self.state.lock() else { return true };
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until } if Instant::now() >= until => {
+                *state = State::HalfOpen;
+                true
+            }
+            State::Open { .. } | State::HalfOpen => false,
+        }
+    }
+
+    pub fn record(&self, ok: bool) {
+        let Ok(mut state) = self.state.lock() else { return };
+        *state = match (&*state, ok) {
+            (_, true) => State::Closed { failures: 0 },
+            (State::Closed { failures }, false) if failures + 1 < FAILURE_THRESHOLD => {
+                State::Closed { failures: failures + 1 }
+            }
+            (_, false) => State::Open { until: Instant::now() + COOLDOWN },
+        };
+    }
+
+    /// "closed", "open", or "half_open", for `/v0/health`.
+    pub fn state_name(&self) -> &'static str {
+        let Ok(state) = self.state.lock() else { return "closed" };
+        match *state {
+            State::Closed { .. } => "closed",
+            State::Open { until } if Instant::now() >= until => "half_open",
+            State::Open { .. } => "open",
+            State::HalfOpen => "half_open",
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use aether_common::health::{self, Target};
+use serde::Serialize;
+
+/// How long one `/v0/health/all` answer is reused, so dashboards polling
+/// it don't each hit every backend.
+const CACHE_TTL: Duration = Duration::from_secs(2);
+/// A daemon slower than this to answer its own health check counts as down.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// One daemon's health as seen from aurorad.
+#[derive(Clone, Serialize)]
+pub struct Component {
+    pub ok: bool,
+    /// Whether the stack counts as down without it.
+    pub required: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// aurorad's circuit breaker for the backend, if it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breaker: Option<&'static str>,
+}
+
+impl Component {
+    /// aurorad itself: answering at all means it's up.
+    pub fn local() -> Self {
+        Self {
+            ok: true,
+            required: true,
+            latency_ms: 0,
+            version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            error: None,
+            breaker: None,
+        }
+    }
+
+    /// GET `/v0/health` from `target`. Up means it answered with `"ok": true`.
+    pub fn probe(target: &Target, required: bool) -> Self {
+        let p = health::probe(target, PROBE_TIMEOUT);
+        Self {
+            ok: p.ok,
+            required,
+            latency_ms: p.latency.as_millis() as u64,
+            version: p.version,
+            error: p.error,
+            breaker: None,
+        }
+    }
+}
+
+/// The whole stack's health: every component, and whether all the required
+/// ones are up.
+#[derive(Clone, Serialize)]
+pub struct StackHealth {
+    pub ok: bool,
+    /// UNIX seconds when the components were probed.
+    pub checked_at: u64,
+    pub components: BTreeMap<&'static str, Component>,
+}
+
+impl StackHealth {
+    pub fn new(components: BTreeMap<&'static str, Component>, checked_at: u64) -> Self {
+        let ok = components.values().all(|c| c.ok || !c.required);
+        Self { ok, checked_at, components }
+    }
+}
+
+/// The last `StackHealth`, reused for `CACHE_TTL`.
+#[derive(Default)]
+pub struct HealthCache {
+    last: Mutex<Option<(Instant, StackHealth)>>,
+}
+
+impl HealthCache {
+    /// The cached health if it's fresh, else the result of `probe`. Callers
+    /// arriving mid-probe wait for it rather than probing again.
+    pub fn get(&self, probe: impl FnOnce() -> StackHealth) -> StackHealth {
+        let Ok(mut last) = self.last.lock() else { return probe() };
+        match &*last {
+            Some((at, health)) if at.elapsed() < CACHE_TTL => health.clone(),
+            _ => {
+                let health = probe();
+                *last = Some((Instant::now(), health.clone()));
+                health
+            }
+        }
+    }
+}
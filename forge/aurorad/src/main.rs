@@ -2,14 +2,40 @@ use std::io::{Read, Write};
 use std::net::{TcpListener, TcpStream};
 use std::os::unix::net::{UnixListener, UnixStream};
 use std::path::Path;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
 
+use anyhow::Context;
+use aether_common::health::Target;
+use aether_common::http::{parse_body, read_request, REQUEST_READ_TIMEOUT};
 use serde::{Deserialize, Serialize};
 
+mod breaker;
+mod health;
+mod metrics;
+mod notify;
+mod routes;
+mod ws;
+
+use breaker::Breaker;
+use health::{Component, HealthCache, StackHealth};
+use metrics::{Backend, Metrics};
+use notify::{Notification, Notifications};
+use routes::{Route, Routes};
+use ws::Events;
+
 #[derive(Serialize)]
 struct HealthResponse {
     ok: bool,
     service: &'static str,
     version: &'static str,
+    breakers: BreakerStates,
+}
+
+#[derive(Serialize)]
+struct BreakerStates {
+    cfcd: &'static str,
+    brain: &'static str,
 }
 
 #[derive(Deserialize)]
@@ -24,12 +50,67 @@ struct JobResponse {
     ok: bool,
     job_id: String,
     job_type: String,
+    /// Correlation ID, also sent on to cfcd/brain and in aurorad's logs.
+    request_id: String,
     result: serde_json::Value,
 }
 
+/// State shared by every connection thread.
+#[derive(Default)]
+struct Shared {
+    metrics: Metrics,
+    cfcd_breaker: Breaker,
+    brain_breaker: Breaker,
+    events: Events,
+    notifications: Notifications,
+    routes: Routes,
+    health: HealthCache,
+}
+
+/// A forward refused because the backend's breaker is open.
+#[derive(Debug)]
+struct CircuitOpen;
+
+impl std::fmt::Display for CircuitOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("circuit open")
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+fn is_circuit_open(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<CircuitOpen>().is_some()
+}
+
+impl Shared {
+    fn breaker(&self, backend: Backend) -> &Breaker {
+        match backend {
+            Backend::Cfcd => &self.cfcd_breaker,
+            Backend::Brain => &self.brain_breaker,
+        }
+    }
+
+    /// Run a forward through `backend`'s breaker, timing it. Fails fast
+    /// with `CircuitOpen` while the breaker is open.
+    fn forward<T>(&self, backend: Backend, f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let breaker = self.breaker(backend);
+        if !breaker.allow() {
+            return Err(CircuitOpen.into());
+        }
+        let result = self.metrics.time_forward(backend, f);
+        breaker.record(result.is_ok());
+        result
+    }
+}
+
 fn write_http_json(stream: &mut dyn Write, status: &str, body: &str) -> anyhow::Result<()> {
+    write_http_text(stream, status, "application/json", body)
+}
+
+fn write_http_text(stream: &mut dyn Write, status: &str, content_type: &str, body: &str) -> anyhow::Result<()> {
     let resp = format!(
-        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\n\r\n{}",
         body.len(),
         body
     );
@@ -37,10 +118,6 @@ fn write_http_json(stream: &mut dyn Write, status: &str, body: &str) -> anyhow::
     Ok(())
 }
 
-fn parse_body(req: &str) -> &str {
-    req.split("\r\n\r\n").nth(1).unwrap_or("")
-}
-
 fn now_secs() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -49,12 +126,19 @@ fn now_secs() -> u64 {
 }
 
 /// Send an HTTP request and read the response body.
-fn send_http_request(stream: &mut dyn Write, reader: &mut dyn Read, method: &str, path: &str, body: &str) -> anyhow::Result<String> {
+fn send_http_request(
+    stream: &mut dyn Write,
+    reader: &mut dyn Read,
+    method: &str,
+    path: &str,
+    body: &str,
+    request_id: &str,
+) -> anyhow::Result<String> {
     let request = if body.is_empty() {
-        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\nX-Request-Id: {request_id}\r\n\r\n")
     } else {
         format!(
-            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nX-Request-Id: {request_id}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
             body.len()
         )
     };
@@ -71,33 +155,47 @@ fn send_http_request(stream: &mut dyn Write, reader: &mut dyn Read, method: &str
     }
 }
 
+/// cfcd answers from a local model, but learning updates can take a while.
+const CFCD_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+const CFCD_WRITE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
 /// Forward an HTTP request to cfcd via Unix socket or TCP.
-fn forward_to_cfcd(method: &str, path: &str, body: &str) -> anyhow::Result<String> {
+fn forward_to_cfcd(method: &str, path: &str, body: &str, request_id: &str) -> anyhow::Result<String> {
     // Check for TCP host (CFCD_HOST=host:port)
     if let Ok(host) = std::env::var("CFCD_HOST") {
         let mut stream = TcpStream::connect(&host)?;
+        stream.set_read_timeout(Some(CFCD_READ_TIMEOUT))?;
+        stream.set_write_timeout(Some(CFCD_WRITE_TIMEOUT))?;
         let mut reader = stream.try_clone()?;
-        return send_http_request(&mut stream, &mut reader, method, path, body);
+        return send_http_request(&mut stream, &mut reader, method, path, body, request_id);
     }
 
     // Fall back to Unix socket
     let cfcd_socket =
         std::env::var("CFCD_SOCKET").unwrap_or_else(|_| "/tmp/cfcd.sock".to_string());
     let mut stream = UnixStream::connect(&cfcd_socket)?;
+    stream.set_read_timeout(Some(CFCD_READ_TIMEOUT))?;
+    stream.set_write_timeout(Some(CFCD_WRITE_TIMEOUT))?;
     let mut reader = stream.try_clone()?;
-    send_http_request(&mut stream, &mut reader, method, path, body)
+    send_http_request(&mut stream, &mut reader, method, path, body, request_id)
 }
 
-/// Forward a request to the brain server at a specific path.
-fn forward_to_brain_path(path: &str, body: &str) -> anyhow::Result<String> {
+/// Brain queries can take 30+ seconds (LLM latency); a brain silent for
+/// longer than this has stalled.
+const BRAIN_READ_TIMEOUT: std::time::Duration =
+    std::time::Duration::from_secs(if cfg!(test) { 1 } else { 60 });
+
+/// Forward a request to the brain server at a specific path. A brain that
+/// stalls or answers without a complete HTTP response is an error, so it
+/// counts against the brain breaker.
+fn forward_to_brain_path(path: &str, body: &str, request_id: &str) -> anyhow::Result<String> {
     let host = std::env::var("BRAIN_HOST").unwrap_or_else(|_| "10.0.2.2:9200".to_string());
     let mut stream = TcpStream::connect(&host)?;
-    // Brain queries can take 30+ seconds (LLM latency)
-    stream.set_read_timeout(Some(std::time::Duration::from_secs(60)))?;
+    stream.set_read_timeout(Some(BRAIN_READ_TIMEOUT))?;
     stream.set_write_timeout(Some(std::time::Duration::from_secs(5)))?;
 
     let request = format!(
-        "POST {path} HTTP/1.1\r\nHost: {host}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        "POST {path} HTTP/1.1\r\nHost: {host}\r\nX-Request-Id: {request_id}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
         body.len()
     );
     stream.write_all(request.as_bytes())?;
@@ -109,45 +207,262 @@ fn forward_to_brain_path(path: &str, body: &str) -> anyhow::Result<String> {
         match stream.read(&mut buf) {
             Ok(0) => break,
             Ok(n) => response.extend_from_slice(&buf[..n]),
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => break,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock || e.kind() == std::io::ErrorKind::TimedOut => {
+                anyhow::bail!("brain stalled after {} bytes", response.len())
+            }
             Err(e) => return Err(e.into()),
         }
     }
 
-    let resp_str = String::from_utf8_lossy(&response).to_string();
-    if let Some(idx) = resp_str.find("\r\n\r\n") {
-        Ok(resp_str[idx + 4..].to_string())
-    } else {
-        Ok(resp_str)
+    let resp_str = String::from_utf8_lossy(&response);
+    match resp_str.find("\r\n\r\n") {
+        Some(idx) => Ok(resp_str[idx + 4..].to_string()),
+        None => anyhow::bail!("brain closed the connection before its headers ended"),
     }
 }
 
-/// Route job types to cfcd endpoints.
-fn route_job_to_cfcd(job_type: &str, params: &serde_json::Value) -> anyhow::Result<String> {
-    let (method, path) = match job_type {
-        "predict_next_state" => ("POST", "/v0/predict"),
-        "encode_state" => ("POST", "/v0/encode_state"),
-        "introspect" => ("GET", "/v0/introspect"),
-        "trigger_learning" => ("POST", "/v0/update_weights"),
-        "enable_learning" => ("POST", "/v0/learning/enable"),
-        "disable_learning" => ("POST", "/v0/learning/disable"),
-        "save_weights" => ("POST", "/v0/weights/save"),
-        _ => ("POST", "/v0/predict"), // default
+/// Relay a streaming brain response as it arrives instead of buffering it.
+/// The brain replies with newline-delimited JSON (`{"delta":...}` lines and
+/// a final `{"done":true,...}`), which is copied through a line at a time.
+/// The whole relay counts as one brain forward: a brain that can't be
+/// reached, stalls or hangs up before its done line trips the breaker, and
+/// the client gets a done line with the error in its place. Returns the
+/// done line's `ok`.
+fn stream_from_brain(client: &mut dyn Write, shared: &Shared, path: &str, body: &str, request_id: &str) -> anyhow::Result<bool> {
+    let host = std::env::var("BRAIN_HOST").unwrap_or_else(|_| "10.0.2.2:9200".to_string());
+    let stream_headers = |status: &str| {
+        format!("HTTP/1.1 {status}\r\nContent-Type: application/x-ndjson\r\nX-Request-Id: {request_id}\r\nConnection: close\r\n\r\n")
     };
+    let mut sent_headers = false;
+    // A client hanging up mid-stream isn't the brain's fault
+    let mut client_err = None;
+
+    let relayed = shared.forward(Backend::Brain, || {
+        let mut brain = TcpStream::connect(&host)?;
+        // Allow long pauses between deltas while the model thinks
+        brain.set_read_timeout(Some(BRAIN_READ_TIMEOUT))?;
+        brain.set_write_timeout(Some(std::time::Duration::from_secs(5)))?;
+
+        let request = format!(
+            "POST {path} HTTP/1.1\r\nHost: {host}\r\nX-Request-Id: {request_id}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        brain.write_all(request.as_bytes())?;
+
+        // Skip the brain's own headers, then forward each line as it completes
+        let mut pending = Vec::new();
+        let mut buf = [0u8; 4096];
+        let mut in_body = false;
+        loop {
+            let n = brain.read(&mut buf)?;
+            if n == 0 {
+                anyhow::bail!("brain closed the stream before its done line");
+            }
+            pending.extend_from_slice(&buf[..n]);
+            if !in_body {
+                let Some(pos) = pending.windows(4).position(|w| w == b"\r\n\r\n") else {
+                    continue;
+                };
+                pending.drain(..pos + 4);
+                in_body = true;
+            }
+            while let Some(nl) = pending.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = pending.drain(..=nl).collect();
+                let sent = (|| {
+                    if !sent_headers {
+                        client.write_all(stream_headers("200 OK").as_bytes())?;
+                        sent_headers = true;
+                    }
+                    client.write_all(&line)?;
+                    client.flush()
+                })();
+                if let Err(e) = sent {
+                    client_err = Some(e);
+                    return Ok(false);
+                }
+                if let Some(ok) = done_outcome(&line) {
+                    return Ok(ok);
+                }
+            }
+        }
+    });
+    if let Some(e) = client_err {
+        return Err(e.into());
+    }
 
-    let body = if method == "GET" {
+    let e = match relayed {
+        Ok(ok) => return Ok(ok),
+        Err(e) => e,
+    };
+    eprintln!("[{request_id}] brain stream failed: {e:?} (is brain_server running?)");
+    let done = serde_json::json!({
+        "done": true,
+        "ok": false,
+        "error": format!("brain unavailable: {e}"),
+        "request_id": request_id,
+    });
+    if !sent_headers {
+        let status = if is_circuit_open(&e) { "503 Service Unavailable" } else { "200 OK" };
+        client.write_all(stream_headers(status).as_bytes())?;
+    }
+    client.write_all(format!("{done}\n").as_bytes())?;
+    client.flush()?;
+    Ok(false)
+}
+
+/// `ok` from the brain's final `{"done":true,...}` line; `None` for any other line.
+fn done_outcome(line: &[u8]) -> Option<bool> {
+    let v = serde_json::from_slice::<serde_json::Value>(line).ok()?;
+    if v.get("done").and_then(|d| d.as_bool()) != Some(true) {
+        return None;
+    }
+    Some(v.get("ok").and_then(|ok| ok.as_bool()) == Some(true))
+}
+
+/// Probe aetherd, cfcd and the brain in parallel for `/v0/health/all`.
+/// Components named in `AURORAD_HEALTH_OPTIONAL` (comma-separated) are
+/// reported but don't make the stack unhealthy.
+fn probe_stack(shared: &Shared) -> StackHealth {
+    let optional = std::env::var("AURORAD_HEALTH_OPTIONAL").unwrap_or_default();
+    let required = |name: &str| !optional.split(',').any(|o| o.trim() == name);
+
+    let targets = [("aetherd", Target::aetherd()), ("cfcd", Target::cfcd()), ("brain", Target::brain())];
+    let mut components: std::collections::BTreeMap<_, _> = std::thread::scope(|s| {
+        let probes: Vec<_> = targets
+            .iter()
+            .map(|(name, target)| (*name, s.spawn(|| Component::probe(target, required(name)))))
+            .collect();
+        probes.into_iter().filter_map(|(name, probe)| Some((name, probe.join().ok()?))).collect()
+    });
+    components.insert("aurorad", Component::local());
+    for (name, backend) in [("cfcd", Backend::Cfcd), ("brain", Backend::Brain)] {
+        if let Some(c) = components.get_mut(name) {
+            c.breaker = Some(shared.breaker(backend).state_name());
+        }
+    }
+    StackHealth::new(components, now_secs())
+}
+
+/// Route job types to cfcd endpoints.
+fn route_job_to_cfcd(route: &Route, params: &serde_json::Value, request_id: &str) -> anyhow::Result<String> {
+    let body = if route.method == "GET" {
         String::new()
     } else {
         serde_json::to_string(params)?
     };
 
-    forward_to_cfcd(method, path, &body)
+    forward_to_cfcd(&route.method, &route.path, &body, request_id)
+}
+
+/// Tell `/v0/stream` subscribers that a job finished, and pass on
+/// successful proactive insights.
+fn publish_job_events(events: &Events, resp: &JobResponse) {
+    let ts = now_secs();
+    events.publish(&serde_json::json!({
+        "type": "job_completed",
+        "job_id": resp.job_id,
+        "job_type": resp.job_type,
+        "request_id": resp.request_id,
+        "ok": resp.ok,
+        "ts": ts,
+    }));
+    let failed = resp.result.get("error").is_some();
+    if resp.job_type == "brain_proactive" && resp.ok && !failed {
+        events.publish(&serde_json::json!({
+            "type": "brain_proactive",
+            "job_id": resp.job_id,
+            "ts": ts,
+            "result": resp.result,
+        }));
+    }
+}
+
+/// Value of request header `name`, matched case-insensitively.
+fn header<'a>(req: &'a str, name: &str) -> Option<&'a str> {
+    let headers = req.split("\r\n\r\n").next().unwrap_or("");
+    headers
+        .lines()
+        .skip(1)
+        .filter_map(|l| l.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
 }
 
-fn handle_conn(stream: &mut (impl Read + Write)) -> anyhow::Result<()> {
-    let mut buf = [0u8; 16384];
-    let n = stream.read(&mut buf)?;
-    let req = String::from_utf8_lossy(&buf[..n]);
+/// Longest inbound `X-Request-Id` adopted as is.
+const MAX_REQUEST_ID_LEN: usize = 64;
+
+static NEXT_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+/// The client's `X-Request-Id` if it sent a plain token, else a fresh one,
+/// so one query can be followed through TUI, aurorad, cfcd and brain logs.
+fn request_id(req: &str) -> String {
+    let inbound = header(req, "x-request-id").filter(|id| {
+        !id.is_empty()
+            && id.len() <= MAX_REQUEST_ID_LEN
+            && id.bytes().all(|b| b.is_ascii_alphanumeric() || b"-_.:".contains(&b))
+    });
+    match inbound {
+        Some(id) => id.to_string(),
+        None => {
+            let millis = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            format!("{millis:x}-{:x}", NEXT_REQUEST.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+}
+
+/// Whether the client asked for the connection to close after this request.
+fn wants_close(req: &str) -> bool {
+    header(req, "connection").is_some_and(|v| v.eq_ignore_ascii_case("close"))
+}
+
+/// Serve requests on one connection until the client closes it, asks to
+/// close, or idles past the read timeout. `slot` covers the first request;
+/// later ones take a fresh slot once they arrive, so a connection idling
+/// between requests doesn't count against the cap.
+fn serve_conn(
+    stream: &mut (impl Read + Write),
+    shared: &Shared,
+    active: &Arc<AtomicUsize>,
+    slot: ActiveGuard,
+) -> anyhow::Result<()> {
+    let mut slot = Some(slot);
+    loop {
+        let req = read_request(stream)?;
+        if req.is_empty() {
+            return Ok(());
+        }
+        // Subscribers are capped by `Events` instead; they'd hold a slot for as long as they listen
+        let _slot = if is_stream_request(&req) {
+            slot = None;
+            None
+        } else {
+            let Some(slot) = slot.take().or_else(|| ActiveGuard::acquire(active)) else {
+                write_busy(stream)?;
+                return Ok(());
+            };
+            Some(slot)
+        };
+        let request_id = request_id(&req);
+        let keep_alive = handle_conn(stream, &req, &request_id, shared).with_context(|| format!("[{request_id}]"))?;
+        if !keep_alive {
+            return Ok(());
+        }
+    }
+}
+
+fn is_stream_request(req: &str) -> bool {
+    let mut parts = req.lines().next().unwrap_or("").split_whitespace();
+    parts.next() == Some("GET") && parts.next() == Some("/v0/stream")
+}
+
+/// Handle one request, tagged `request_id` in logs and replies. Returns
+/// whether the connection can take another.
+fn handle_conn(stream: &mut (impl Read + Write), req: &str, request_id: &str, shared: &Shared) -> anyhow::Result<bool> {
+    let metrics = &shared.metrics;
+    let keep_alive = !wants_close(req);
 
     let mut lines = req.lines();
     let first = lines.next().unwrap_or("");
@@ -155,18 +470,84 @@ fn handle_conn(stream: &mut (impl Read + Write)) -> anyhow::Result<()> {
     let method = parts.next().unwrap_or("");
     let path = parts.next().unwrap_or("/");
 
+    // Subscribers live as long as their socket, so they aren't counted in flight
+    if method == "GET" && path == "/v0/stream" {
+        let upgrade = header(req, "upgrade").is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+        match header(req, "sec-websocket-key") {
+            Some(key) if upgrade => ws::serve_stream(stream, key, &shared.events)?,
+            _ => {
+                let body = "{\"ok\":false,\"error\":\"websocket upgrade required\"}";
+                write_http_json(stream, "400 Bad Request", body)?;
+            }
+        }
+        return Ok(false);
+    }
+
+    let _in_flight = metrics.request_started();
+
     if method == "GET" && path == "/v0/health" {
         let body = serde_json::to_string(&HealthResponse {
             ok: true,
             service: "aurorad",
             version: env!("CARGO_PKG_VERSION"),
+            breakers: BreakerStates {
+                cfcd: shared.cfcd_breaker.state_name(),
+                brain: shared.brain_breaker.state_name(),
+            },
         })?;
-        return write_http_json(stream, "200 OK", &body);
+        write_http_json(stream, "200 OK", &body)?;
+        return Ok(keep_alive);
+    }
+
+    // One call for the whole stack, cached briefly
+    if method == "GET" && path == "/v0/health/all" {
+        let health = shared.health.get(|| probe_stack(shared));
+        let status = if health.ok { "200 OK" } else { "503 Service Unavailable" };
+        write_http_json(stream, status, &serde_json::to_string(&health)?)?;
+        return Ok(keep_alive);
+    }
+
+    if method == "GET" && path == "/v0/metrics" {
+        write_http_text(stream, "200 OK", "text/plain; version=0.0.4", &metrics.render())?;
+        return Ok(keep_alive);
+    }
+
+    // Notifications from daemons and facets, for shells to show
+    if method == "POST" && path == "/v0/notify" {
+        let (status, body) = match serde_json::from_str::<Notification>(parse_body(req)) {
+            Ok(n) if !n.title.trim().is_empty() => match shared.notifications.post(n, now_secs()) {
+                Some(posted) => {
+                    shared.events.publish(&serde_json::json!({
+                        "type": "notification",
+                        "ts": posted.ts,
+                        "notification": posted,
+                    }));
+                    ("200 OK", serde_json::json!({"ok": true, "id": posted.id}))
+                }
+                None => ("500 Internal Server Error", serde_json::json!({"ok": false, "error": "notifications unavailable"})),
+            },
+            Ok(_) => ("400 Bad Request", serde_json::json!({"ok": false, "error": "title required"})),
+            Err(e) => ("400 Bad Request", serde_json::json!({"ok": false, "error": format!("bad notification: {e}")})),
+        };
+        write_http_json(stream, status, &body.to_string())?;
+        return Ok(keep_alive);
+    }
+
+    // `?after=<id>` for what's new since the last poll
+    if method == "GET" && (path == "/v0/notifications" || path.starts_with("/v0/notifications?")) {
+        let after = path
+            .split_once('?')
+            .and_then(|(_, query)| query.split('&').find_map(|kv| kv.strip_prefix("after=")))
+            .and_then(|v| v.parse().ok());
+        let (latest, notifications) = shared.notifications.since(after);
+        let body = serde_json::json!({"ok": true, "latest": latest, "notifications": notifications});
+        write_http_json(stream, "200 OK", &body.to_string())?;
+        return Ok(keep_alive);
     }
 
     // Forward jobs to cfcd
     if method == "POST" && path == "/v0/jobs" {
-        let body_str = parse_body(&req);
+        let body_str = parse_body(req);
         let jr: JobRequest =
             serde_json::from_str(body_str).unwrap_or(JobRequest {
                 job_type: None,
@@ -175,65 +556,112 @@ fn handle_conn(stream: &mut (impl Read + Write)) -> anyhow::Result<()> {
         let jt = jr
             .job_type
             .unwrap_or_else(|| "predict_next_state".to_string());
+        metrics.count_job(&jt);
 
-        // Route brain jobs to brain server, everything else to cfcd
-        let result_value = if jt == "brain" || jt == "brain_proactive" || jt == "brain_dashboard" {
-            let brain_path = match jt.as_str() {
-                "brain_proactive" => "/v0/brain/proactive",
-                "brain_dashboard" => "/v0/brain/dashboard",
-                _ => "/v0/brain",
-            };
+        if jt == "brain_stream" {
             let brain_body = serde_json::to_string(&jr.params)?;
-            match forward_to_brain_path(brain_path, &brain_body) {
-                Ok(resp_body) => {
-                    serde_json::from_str(&resp_body).unwrap_or(serde_json::json!({"raw": resp_body}))
-                }
-                Err(e) => {
-                    eprintln!("brain forward failed: {e:?} (is brain_server running?)");
-                    serde_json::json!({"error": format!("brain unavailable: {e}"), "ok": false})
+            // Streamed replies end when the connection closes
+            let ok = stream_from_brain(stream, shared, "/v0/brain/stream", &brain_body, request_id)
+                .context("brain stream")?;
+            shared.events.publish(&serde_json::json!({
+                "type": "job_completed",
+                "job_id": format!("job_{}", now_secs()),
+                "job_type": jt,
+                "request_id": request_id,
+                "ok": ok,
+                "ts": now_secs(),
+            }));
+            return Ok(false);
+        }
+
+        // Route brain jobs to brain server, everything else to cfcd
+        let is_brain = jt == "brain" || jt == "brain_proactive" || jt == "brain_dashboard";
+        let mut status = "200 OK";
+        let result_value = match shared.routes.get(&jt) {
+            _ if is_brain => {
+                let brain_path = match jt.as_str() {
+                    "brain_proactive" => "/v0/brain/proactive",
+                    "brain_dashboard" => "/v0/brain/dashboard",
+                    _ => "/v0/brain",
+                };
+                let brain_body = serde_json::to_string(&jr.params)?;
+                match shared.forward(Backend::Brain, || forward_to_brain_path(brain_path, &brain_body, request_id)) {
+                    Ok(resp_body) => {
+                        serde_json::from_str(&resp_body).unwrap_or(serde_json::json!({"raw": resp_body}))
+                    }
+                    Err(e) if is_circuit_open(&e) => {
+                        eprintln!("[{request_id}] brain circuit open, not forwarding {jt}");
+                        status = "503 Service Unavailable";
+                        serde_json::json!({"error": format!("brain unavailable: {e}"), "ok": false})
+                    }
+                    Err(e) => {
+                        eprintln!("[{request_id}] brain forward failed: {e:?} (is brain_server running?)");
+                        serde_json::json!({"error": format!("brain unavailable: {e}"), "ok": false})
+                    }
                 }
             }
-        } else {
-            // Forward to cfcd
-            match route_job_to_cfcd(&jt, &jr.params) {
+            Some(route) => match shared.forward(Backend::Cfcd, || route_job_to_cfcd(route, &jr.params, request_id)) {
                 Ok(resp_body) => {
                     serde_json::from_str(&resp_body).unwrap_or(serde_json::json!({"raw": resp_body}))
                 }
+                Err(e) if is_circuit_open(&e) => {
+                    eprintln!("[{request_id}] cfcd circuit open, not forwarding {jt}");
+                    status = "503 Service Unavailable";
+                    serde_json::json!({"error": format!("cfcd unavailable: {e}"), "ok": false})
+                }
                 Err(e) => {
-                    eprintln!("cfcd forward failed: {e:?} (is cfcd running?)");
+                    eprintln!("[{request_id}] cfcd forward failed: {e:?} (is cfcd running?)");
                     serde_json::json!({"error": format!("cfcd unavailable: {e}"), "mocked": true})
                 }
+            },
+            // A typo used to land on /v0/predict; say so instead
+            None => {
+                let body = serde_json::json!({"ok": false, "error": "unknown_job_type", "job_type": jt});
+                write_http_json(stream, "400 Bad Request", &body.to_string())?;
+                return Ok(keep_alive);
             }
         };
 
         let resp = JobResponse {
-            ok: true,
+            ok: status == "200 OK",
             job_id: format!("job_{}", now_secs()),
             job_type: jt,
+            request_id: request_id.to_string(),
             result: result_value,
         };
 
         let body = serde_json::to_string(&resp)?;
-        return write_http_json(stream, "200 OK", &body);
+        write_http_json(stream, status, &body)?;
+        publish_job_events(&shared.events, &resp);
+        return Ok(keep_alive);
     }
 
     // Proxy model endpoints directly to cfcd
     if path.starts_with("/v0/model/") || path.starts_with("/v0/cfcd/") {
         let cfcd_path = path.replacen("/v0/model/", "/v0/", 1)
             .replacen("/v0/cfcd/", "/v0/", 1);
-        let body_str = parse_body(&req);
+        let body_str = parse_body(req);
 
-        match forward_to_cfcd(method, &cfcd_path, body_str) {
-            Ok(resp_body) => return write_http_json(stream, "200 OK", &resp_body),
+        match shared.forward(Backend::Cfcd, || forward_to_cfcd(method, &cfcd_path, body_str, request_id)) {
+            Ok(resp_body) => write_http_json(stream, "200 OK", &resp_body)?,
             Err(e) => {
                 let err = serde_json::json!({"ok": false, "error": format!("cfcd: {e}")});
-                return write_http_json(stream, "502 Bad Gateway", &err.to_string());
+                let status = if is_circuit_open(&e) {
+                    eprintln!("[{request_id}] cfcd circuit open, not proxying {path}");
+                    "503 Service Unavailable"
+                } else {
+                    eprintln!("[{request_id}] cfcd proxy of {path} failed: {e:?} (is cfcd running?)");
+                    "502 Bad Gateway"
+                };
+                write_http_json(stream, status, &err.to_string())?;
             }
         }
+        return Ok(keep_alive);
     }
 
     let body = "{\"ok\":false,\"error\":\"not_found\"}";
-    write_http_json(stream, "404 Not Found", body)
+    write_http_json(stream, "404 Not Found", body)?;
+    Ok(keep_alive)
 }
 
 enum Listener {
@@ -241,6 +669,51 @@ enum Listener {
     Tcp(TcpListener),
 }
 
+/// Upper bound on connections handled at once. Brain jobs can hold a
+/// handler for up to a minute, so this mostly caps slow LLM requests.
+const MAX_CONCURRENT_CONNS: usize = 32;
+
+/// Handle a connection on its own thread so a slow brain job doesn't block
+/// health checks. Past the concurrency cap, reply 503 instead.
+fn dispatch<S: Read + Write + Send + 'static>(mut stream: S, active: &Arc<AtomicUsize>, shared: &Arc<Shared>) {
+    let Some(slot) = ActiveGuard::acquire(active) else {
+        let _ = write_busy(&mut stream);
+        return;
+    };
+
+    let active = Arc::clone(active);
+    let shared = Arc::clone(shared);
+    std::thread::spawn(move || {
+        if let Err(err) = serve_conn(&mut stream, &shared, &active, slot) {
+            eprintln!("aurorad error: {err:?}");
+        }
+    });
+}
+
+fn write_busy(stream: &mut impl Write) -> anyhow::Result<()> {
+    write_http_json(stream, "503 Service Unavailable", "{\"ok\":false,\"error\":\"busy\"}")
+}
+
+/// Releases a connection slot when the request finishes, even on panic.
+struct ActiveGuard(Arc<AtomicUsize>);
+
+impl ActiveGuard {
+    /// Take a slot, or `None` if `MAX_CONCURRENT_CONNS` are already taken.
+    fn acquire(active: &Arc<AtomicUsize>) -> Option<Self> {
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CONCURRENT_CONNS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     let tcp_port = std::env::var("AURORAD_TCP_PORT").ok()
         .and_then(|p| p.parse::<u16>().ok());
@@ -281,14 +754,25 @@ fn main() -> anyhow::Result<()> {
     let brain_host = std::env::var("BRAIN_HOST").unwrap_or_else(|_| "10.0.2.2:9200".to_string());
     eprintln!("  brain forwarding via TCP: {}", brain_host);
 
+    let routes_path = std::env::var("AURORAD_ROUTES_FILE")
+        .unwrap_or_else(|_| routes::DEFAULT_ROUTES_FILE.to_string());
+    let routes = Routes::load(&routes_path);
+    eprintln!("  cfcd job types: {} (extra routes from {})", routes.count(), routes_path);
+
+    serve(listener, Arc::new(Shared { routes, ..Default::default() }));
+    Ok(())
+}
+
+/// Accept connections forever, each handled on its own thread.
+fn serve(listener: Listener, shared: Arc<Shared>) {
+    let active = Arc::new(AtomicUsize::new(0));
     match listener {
         Listener::Unix(l) => {
             for conn in l.incoming() {
                 match conn {
-                    Ok(mut stream) => {
-                        if let Err(err) = handle_conn(&mut stream) {
-                            eprintln!("aurorad error: {err:?}");
-                        }
+                    Ok(stream) => {
+                        let _ = stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT));
+                        dispatch(stream, &active, &shared)
                     }
                     Err(err) => eprintln!("aurorad accept error: {err:?}"),
                 }
@@ -297,16 +781,218 @@ fn main() -> anyhow::Result<()> {
         Listener::Tcp(l) => {
             for conn in l.incoming() {
                 match conn {
-                    Ok(mut stream) => {
-                        if let Err(err) = handle_conn(&mut stream) {
-                            eprintln!("aurorad error: {err:?}");
-                        }
+                    Ok(stream) => {
+                        let _ = stream.set_read_timeout(Some(REQUEST_READ_TIMEOUT));
+                        dispatch(stream, &active, &shared)
                     }
                     Err(err) => eprintln!("aurorad accept error: {err:?}"),
                 }
             }
         }
     }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    /// Tests that point CFCD_HOST or BRAIN_HOST at a mock run one at a time.
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn lock_env() -> std::sync::MutexGuard<'static, ()> {
+        ENV_LOCK.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// aurorad on an ephemeral port.
+    fn start(shared: Shared) -> SocketAddr {
+        let l = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = l.local_addr().unwrap();
+        std::thread::spawn(move || serve(Listener::Tcp(l), Arc::new(shared)));
+        addr
+    }
+
+    /// A backend that answers every request with `body` after `delay`.
+    fn mock_backend(delay: Duration, body: &'static str) -> SocketAddr {
+        let l = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = l.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut conn in l.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let _ = read_request(&mut conn);
+                    std::thread::sleep(delay);
+                    let _ = write!(
+                        conn,
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    );
+                });
+            }
+        });
+        addr
+    }
+
+    /// A brain that answers a stream request with `lines` of NDJSON, then hangs up.
+    fn mock_brain_stream(lines: &'static str) -> SocketAddr {
+        let l = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = l.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut conn in l.incoming().flatten() {
+                let _ = read_request(&mut conn);
+                let _ = write!(conn, "HTTP/1.1 200 OK\r\nContent-Type: application/x-ndjson\r\nConnection: close\r\n\r\n{lines}");
+            }
+        });
+        addr
+    }
+
+    /// A brain that accepts a request, then never answers.
+    fn mock_brain_stalled() -> SocketAddr {
+        let l = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = l.local_addr().unwrap();
+        std::thread::spawn(move || {
+            for mut conn in l.incoming().flatten() {
+                std::thread::spawn(move || {
+                    let _ = read_request(&mut conn);
+                    std::thread::sleep(BRAIN_READ_TIMEOUT * 5);
+                });
+            }
+        });
+        addr
+    }
+
+    /// One request on its own connection: (status line, body).
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (String, String) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+        write!(
+            stream,
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap_or((&response, ""));
+        (head.lines().next().unwrap_or("").to_string(), body.to_string())
+    }
+
+    #[test]
+    fn health_answers_while_a_slow_job_is_in_flight() {
+        let _env = lock_env();
+        let cfcd = mock_backend(Duration::from_millis(1500), r#"{"ok":true,"predicted":[0.5]}"#);
+        std::env::set_var("CFCD_HOST", cfcd.to_string());
+        let aurorad = start(Shared::default());
+
+        let job = r#"{"job_type":"predict_next_state"}"#;
+        let slow = std::thread::spawn(move || request(aurorad, "POST", "/v0/jobs", job));
+        std::thread::sleep(Duration::from_millis(200));
+
+        let started = Instant::now();
+        let (status, body) = request(aurorad, "GET", "/v0/health", "");
+        assert!(status.contains("200"), "{status}");
+        assert!(body.contains("\"ok\":true"), "{body}");
+        assert!(started.elapsed() < Duration::from_millis(1000), "health waited on the job");
+
+        let (status, body) = slow.join().unwrap();
+        assert!(status.contains("200"), "{status}");
+        assert!(body.contains("predicted"), "{body}");
+    }
+    #[test]
+    fn metrics_scrape_counts_forwarded_jobs() {
+        let _env = lock_env();
+        let cfcd = mock_backend(Duration::ZERO, r#"{"ok":true,"predicted":[0.5]}"#);
+        std::env::set_var("CFCD_HOST", cfcd.to_string());
+        let aurorad = start(Shared::default());
+
+        for _ in 0..2 {
+            let (status, _) = request(aurorad, "POST", "/v0/jobs", r#"{"job_type":"predict_next_state"}"#);
+            assert!(status.contains("200"), "{status}");
+        }
+        let (status, body) = request(aurorad, "GET", "/v0/metrics", "");
+        assert!(status.contains("200"), "{status}");
+        assert!(body.contains("aurorad_jobs_total{job_type=\"predict_next_state\"} 2\n"), "{body}");
+        assert!(body.contains("aurorad_forward_duration_seconds_count{backend=\"cfcd\"} 2\n"), "{body}");
+        assert!(body.contains("aurorad_forward_failures_total{backend=\"cfcd\"} 0\n"), "{body}");
+        // Only the scrape itself is in flight
+        assert!(body.contains("aurorad_in_flight_requests 1\n"), "{body}");
+    }
+
+    #[test]
+    fn brain_stream_is_relayed_through_its_done_line() {
+        let _env = lock_env();
+        let brain = mock_brain_stream("{\"delta\":\"hi\"}\n{\"done\":true,\"ok\":true}\n");
+        std::env::set_var("BRAIN_HOST", brain.to_string());
+        let aurorad = start(Shared::default());
+
+        let (status, body) = request(aurorad, "POST", "/v0/jobs", r#"{"job_type":"brain_stream","params":{"input":"x"}}"#);
+        assert!(status.contains("200"), "{status}");
+        assert_eq!(body, "{\"delta\":\"hi\"}\n{\"done\":true,\"ok\":true}\n");
+        let (_, metrics) = request(aurorad, "GET", "/v0/metrics", "");
+        assert!(metrics.contains("aurorad_forward_failures_total{backend=\"brain\"} 0\n"), "{metrics}");
+    }
+
+    #[test]
+    fn brain_stream_cut_off_before_done_counts_as_a_failure() {
+        let _env = lock_env();
+        let brain = mock_brain_stream("{\"delta\":\"hi\"}\n");
+        std::env::set_var("BRAIN_HOST", brain.to_string());
+        let aurorad = start(Shared::default());
+
+        let (status, body) = request(aurorad, "POST", "/v0/jobs", r#"{"job_type":"brain_stream","params":{"input":"x"}}"#);
+        assert!(status.contains("200"), "{status}");
+        let lines: Vec<&str> = body.lines().collect();
+        assert_eq!(lines[0], "{\"delta\":\"hi\"}");
+        let done: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(done["done"], true);
+        assert_eq!(done["ok"], false);
+        let (_, metrics) = request(aurorad, "GET", "/v0/metrics", "");
+        assert!(metrics.contains("aurorad_forward_failures_total{backend=\"brain\"} 1\n"), "{metrics}");
+    }
+
+    #[test]
+    fn stalled_brain_counts_as_a_failure() {
+        let _env = lock_env();
+        std::env::set_var("BRAIN_HOST", mock_brain_stalled().to_string());
+        let aurorad = start(Shared::default());
+
+        let (status, body) = request(aurorad, "POST", "/v0/jobs", r#"{"job_type":"brain","params":{"input":"x"}}"#);
+        assert!(status.contains("200"), "{status}");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["result"]["ok"], false, "{body}");
+        let (_, metrics) = request(aurorad, "GET", "/v0/metrics", "");
+        assert!(metrics.contains("aurorad_forward_failures_total{backend=\"brain\"} 1\n"), "{metrics}");
+    }
+
+    #[test]
+    fn unknown_job_type_is_rejected() {
+        let aurorad = start(Shared::default());
+        let (status, body) = request(aurorad, "POST", "/v0/jobs", r#"{"job_type":"predcit_next_state"}"#);
+        assert!(status.contains("400"), "{status}");
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["error"], "unknown_job_type");
+        assert_eq!(body["job_type"], "predcit_next_state");
+    }
+
+    #[test]
+    fn idle_keep_alive_connections_free_their_slots() {
+        let aurorad = start(Shared::default());
+
+        // Fill every slot, then leave each connection open and idle
+        let idle: Vec<TcpStream> = (0..MAX_CONCURRENT_CONNS)
+            .map(|_| {
+                let mut stream = TcpStream::connect(aurorad).unwrap();
+                stream.set_read_timeout(Some(Duration::from_secs(10))).unwrap();
+                write!(stream, "GET /v0/health HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+                let response = read_request(&mut stream).unwrap();
+                assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+                stream
+            })
+            .collect();
+
+        let (status, _) = request(aurorad, "GET", "/v0/health", "");
+        assert!(status.contains("200"), "idle connections held their slots: {status}");
+        drop(idle);
+    }
 }
@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Upper bounds (seconds) of the forward latency buckets. Brain calls run
+/// into tens of seconds; cfcd answers in milliseconds.
+const LATENCY_BUCKETS: &[f64] = &[0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0];
+
+/// Job types counted by name; anything else is counted as "other" so
+/// client input can't grow the label set.
+const KNOWN_JOB_TYPES: &[&str] = &[
+    "brain",
+    "brain_stream",
+    "brain_proactive",
+    "brain_dashboard",
+    "predict_next_state",
+    "encode_state",
+    "introspect",
+    "trigger_learning",
+    "enable_learning",
+    "disable_learning",
+    "save_weights",
+];
+
+/// Which service a request was forwarded to.
+#[derive(Clone, Copy)]
+pub enum Backend {
+    Cfcd,
+    Brain,
+}
+
+impl Backend {
+    fn label(self) -> &'static str {
+        match self {
+            Backend::Cfcd => "cfcd",
+            Backend::Brain => "brain",
+        }
+    }
+}
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [AtomicU64; LATENCY_BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    fn observe(&self, secs: f64) {
+        for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&self.buckets) {
+            if secs <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros.fetch_add((secs * 1e6) as u64, Ordering::Relaxed);
+    }
+}
+
+/// Counters shared by every connection thread, served at `GET /v0/metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    jobs: Mutex<BTreeMap<&'static str, u64>>,
+    in_flight: AtomicUsize,
+    cfcd_latency: Histogram,
+    brain_latency: Histogram,
+    cfcd_failures: AtomicU64,
+    brain_failures: AtomicU64,
+}
+
+/// Counts a request as in flight until dropped.
+pub struct InFlight<'a>(&'a Metrics);
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        self.0.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl Metrics {
+    pub fn request_started(&self) -> InFlight<'_> {
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+        InFlight(self)
+    }
+
+    pub fn count_job(&self, job_type: &str) {
+        let label = KNOWN_JOB_TYPES
+            .iter()
+            .find(|&&k| k == job_type)
+            .copied()
+            .unwrap_or("other");
+        if let Ok(mut jobs) = self.jobs.lock() {
+            *jobs.entry(label).or_insert(0) += 1;
+        }
+    }
+
+    /// Run one forward to `backend`, recording its latency and any failure.
+    pub fn time_forward<T>(&self, backend: Backend, f: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let start = Instant::now();
+        let result = f();
+        let (latency, failures) = match backend {
+            Backend::Cfcd => (&self.cfcd_latency, &self.cfcd_failures),
+            Backend::Brain => (&self.brain_latency, &self.brain_failures),
+        };
+        latency.observe(start.elapsed().as_secs_f64());
+        if result.is_err() {
+            failures.fetch_add(1, Ordering::Relaxed);
+        }
+        result
+    }
+
+    /// Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP aurorad_jobs_total Jobs received, by job type.\n");
+        out.push_str("# TYPE aurorad_jobs_total counter\n");
+        if let Ok(jobs) = self.jobs.lock() {
+            for (job_type, n) in jobs.iter() {
+                let _ = writeln!(out, "aurorad_jobs_total{{job_type=\"{job_type}\"}} {n}");
+            }
+        }
+
+        out.push_str("# HELP aurorad_in_flight_requests Requests being handled right now.\n");
+        out.push_str("# TYPE aurorad_in_flight_requests gauge\n");
+        let _ = writeln!(out, "aurorad_in_flight_requests {}", self.in_flight.load(Ordering::Relaxed));
+
+        out.push_str("# HELP aurorad_forward_duration_seconds Time spent forwarding to a backend.\n");
+        out.push_str("# TYPE aurorad_forward_duration_seconds histogram\n");
+        for (backend, hist) in [(Backend::Cfcd, &self.cfcd_latency), (Backend::Brain, &self.brain_latency)] {
+            let name = backend.label();
+            for (bound, bucket) in LATENCY_BUCKETS.iter().zip(&hist.buckets) {
+                let _ = writeln!(
+                    out,
+                    "aurorad_forward_duration_seconds_bucket{{backend=\"{name}\",le=\"{bound}\"}} {}",
+                    bucket.load(Ordering::Relaxed)
+                );
+            }
+            let count = hist.count.load(Ordering::Relaxed);
+            let sum = hist.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+            let _ = writeln!(out, "aurorad_forward_duration_seconds_bucket{{backend=\"{name}\",le=\"+Inf\"}} {count}");
+            let _ = writeln!(out, "aurorad_forward_duration_seconds_sum{{backend=\"{name}\"}} {sum}");
+            let _ = writeln!(out, "aurorad_forward_duration_seconds_count{{backend=\"{name}\"}} {count}");
+        }
+
+        out.push_str("# HELP aurorad_forward_failures_total Forwards that failed to reach or read a backend.\n");
+        out.push_str("# TYPE aurorad_forward_failures_total counter\n");
+        for (backend, n) in [(Backend::Cfcd, &self.cfcd_failures), (Backend::Brain, &self.brain_failures)] {
+            let _ = writeln!(
+                out,
+                "aurorad_forward_failures_total{{backend=\"{}\"}} {}",
+                backend.label(),
+                n.load(Ordering::Relaxed)
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let metrics = Metrics::default();
+        metrics.cfcd_latency.observe(0.002);
+        metrics.cfcd_latency.observe(0.02);
+        metrics.cfcd_latency.observe(120.0);
+        let out = metrics.render();
+
+        let bucket = |le: &str| format!("aurorad_forward_duration_seconds_bucket{{backend=\"cfcd\",le=\"{le}\"}} ");
+        for (le, n) in [("0.005", 1), ("0.01", 1), ("0.05", 2), ("60", 2), ("+Inf", 3)] {
+            assert!(out.contains(&format!("{}{n}\n", bucket(le))), "le={le}:\n{out}");
+        }
+        assert!(out.contains("aurorad_forward_duration_seconds_count{backend=\"cfcd\"} 3\n"), "{out}");
+        assert!(out.contains("aurorad_forward_duration_seconds_sum{backend=\"cfcd\"} 120.022\n"), "{out}");
+        assert!(out.contains("aurorad_forward_duration_seconds_count{backend=\"brain\"} 0\n"), "{out}");
+    }
+
+    #[test]
+    fn unknown_job_types_are_counted_as_other() {
+        let metrics = Metrics::default();
+        metrics.count_job("brain");
+        metrics.count_job("made_up");
+        metrics.count_job("also_made_up");
+        let out = metrics.render();
+        assert!(out.contains("aurorad_jobs_total{job_type=\"brain\"} 1\n"), "{out}");
+        assert!(out.contains("aurorad_jobs_total{job_type=\"other\"} 2\n"), "{out}");
+        assert!(!out.contains("made_up"), "{out}");
+    }
+
+    #[test]
+    fn failed_forwards_are_counted_per_backend() {
+        let metrics = Metrics::default();
+        let _ = metrics.time_forward(Backend::Brain, || anyhow::bail!("down") as anyhow::Result<()>);
+        let _ = metrics.time_forward(Backend::Cfcd, || Ok(()));
+        let out = metrics.render();
+        assert!(out.contains("aurorad_forward_failures_total{backend=\"brain\"} 1\n"), "{out}");
+        assert!(out.contains("aurorad_forward_failures_total{backend=\"cfcd\"} 0\n"), "{out}");
+        assert!(out.contains("aurorad_forward_duration_seconds_count{backend=\"cfcd\"} 1\n"), "{out}");
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+/// Notifications kept for polling clients; older ones are dropped.
+const BACKLOG: usize = 64;
+
+/// A one-off alert for the user, in the brain's widget shape plus a
+/// priority: `{"type":"notification","title":"…","lines":["…"],"priority":"normal"}`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Notification {
+    #[serde(rename = "type", default = "default_type")]
+    pub widget_type: String,
+    pub title: String,
+    #[serde(default)]
+    pub lines: Vec<String>,
+    /// "urgent", "normal" or "low"; anything else is treated as normal.
+    #[serde(default = "default_priority")]
+    pub priority: String,
+    /// Who raised it, e.g. "cfcd" or a facet name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+fn default_type() -> String {
+    "notification".to_string()
+}
+
+fn default_priority() -> String {
+    "normal".to_string()
+}
+
+/// A posted notification with its id and arrival time.
+#[derive(Clone, Serialize)]
+pub struct Posted {
+    pub id: u64,
+    pub ts: u64,
+    #[serde(flatten)]
+    pub notification: Notification,
+}
+
+/// Recent notifications, numbered so a poller can ask for what's new.
+#[derive(Default)]
+pub struct Notifications {
+    inner: Mutex<Backlog>,
+}
+
+#[derive(Default)]
+struct Backlog {
+    latest: u64,
+    recent: VecDeque<Posted>,
+}
+
+impl Notifications {
+    /// Number and keep `notification`, returning it as posted.
+    pub fn post(&self, notification: Notification, ts: u64) -> Option<Posted> {
+        let Ok(mut backlog) = self.inner.lock() else { return None };
+        backlog.latest += 1;
+        let posted = Posted { id: backlog.latest, ts, notification };
+        if backlog.recent.len() == BACKLOG {
+            backlog.recent.pop_front();
+        }
+        backlog.recent.push_back(posted.clone());
+        Some(posted)
+    }
+
+    /// The latest id, and the notifications posted after `after`. Without
+    /// `after` only the id comes back, so a client that just started sees
+    /// what arrives from then on rather than the whole backlog.
+    pub fn since(&self, after: Option<u64>) -> (u64, Vec<Posted>) {
+        let Ok(backlog) = self.inner.lock() else { return (0, Vec::new()) };
+        let newer = match after {
+            Some(after) => backlog.recent.iter().filter(|p| p.id > after).cloned().collect(),
+            None => Vec::new(),
+        };
+        (backlog.latest, newer)
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Job types aurorad knows out of the box.
+const DEFAULT_ROUTES: &str = include_str!("routes.json");
+
+/// Where extra routes are read from without `AURORAD_ROUTES_FILE`. Routes
+/// decide where jobs are sent, so this lives somewhere only root can write.
+pub const DEFAULT_ROUTES_FILE: &str = "/etc/aether/aurorad_routes.json";
+
+/// Where a cfcd job type is forwarded.
+#[derive(Deserialize)]
+pub struct Route {
+    pub method: String,
+    pub path: String,
+}
+
+/// Job type → cfcd endpoint. Starts from the built-in table; a routes file
+/// adds job types or re-points existing ones, without a rebuild.
+pub struct Routes {
+    table: HashMap<String, Route>,
+}
+
+impl Default for Routes {
+    fn default() -> Self {
+        let table = serde_json::from_str(DEFAULT_ROUTES).expect("built-in routes.json is valid");
+        Routes { table }
+    }
+}
+
+impl Routes {
+    /// The built-in table plus whatever `path` adds, in the same
+    /// `{"job_type": {"method": "POST", "path": "/v0/…"}}` shape. A missing
+    /// file just means the built-in table; an unreadable or malformed one is
+    /// logged and ignored, so a bad edit can't keep aurorad from starting.
+    pub fn load(path: &str) -> Self {
+        let mut routes = Routes::default();
+        match Self::read_extra(path) {
+            Ok(extra) => routes.table.extend(extra),
+            Err(e) => eprintln!("aurorad: ignoring routes file {path}: {e:#}"),
+        }
+        routes
+    }
+
+    fn read_extra(path: &str) -> anyhow::Result<HashMap<String, Route>> {
+        let data = match std::fs::read_to_string(path) {
+            Ok(d) => d,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(HashMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn get(&self, job_type: &str) -> Option<&Route> {
+        self.table.get(job_type)
+    }
+
+    pub fn count(&self) -> usize {
+        self.table.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routes_file(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("aurorad_routes_{}_{name}.json", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn routes_file_adds_and_repoints_job_types() {
+        let path = routes_file(
+            "extra",
+            r#"{"score_sequence": {"method": "POST", "path": "/v0/score"},
+                "encode_state": {"method": "PUT", "path": "/v0/encode2"}}"#,
+        );
+        let routes = Routes::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(routes.count(), Routes::default().count() + 1);
+        assert_eq!(routes.get("score_sequence").unwrap().path, "/v0/score");
+        assert_eq!(routes.get("encode_state").unwrap().method, "PUT");
+    }
+
+    #[test]
+    fn malformed_routes_file_falls_back_to_the_built_in_table() {
+        let path = routes_file("bad", "{\"score_sequence\": ");
+        let routes = Routes::load(&path);
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(routes.count(), Routes::default().count());
+        assert!(routes.get("predict_next_state").is_some());
+        assert!(routes.get("score_sequence").is_none());
+    }
+
+    #[test]
+    fn missing_routes_file_is_the_built_in_table() {
+        let routes = Routes::load("/nonexistent/aurorad_routes.json");
+        assert_eq!(routes.count(), Routes::default().count());
+    }
+}
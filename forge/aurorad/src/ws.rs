@@ -0,0 +1,199 @@
+use std::io::Write;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aether_common::base64;
+
+/// Fixed GUID from RFC 6455, appended to the client key before hashing.
+const WS_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Ping an idle subscriber this often so a vanished client is noticed
+/// (the write fails) instead of holding its place forever.
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Upper bound on `/v0/stream` subscribers. They don't take connection
+/// slots, since each one listens for as long as its socket stays open.
+pub const MAX_SUBSCRIBERS: usize = 16;
+
+/// Fan-out of events to every `GET /v0/stream` subscriber.
+///
+/// Each event is one JSON object sent as one text frame:
+///
+/// - `{"type":"job_completed","job_id":"job_…","job_type":"…","ok":true,"ts":<unix secs>}`
+///   after every `POST /v0/jobs` has been answered.
+/// - `{"type":"brain_proactive","job_id":"job_…","ts":<unix secs>,"result":{…}}`
+///   when a `brain_proactive` job succeeds; `result` is the brain's reply.
+/// - `{"type":"notification","ts":<unix secs>,"notification":{"id":1,"ts":…,"type":"notification","title":"…","lines":[…],"priority":"normal"}}`
+///   for every `POST /v0/notify`.
+///
+/// Clients should ignore unknown `type`s so new events can be added.
+#[derive(Default)]
+pub struct Events {
+    subscribers: Mutex<Vec<Sender<String>>>,
+    /// Subscriptions not yet dropped.
+    listening: AtomicUsize,
+}
+
+/// One subscriber's events; frees its place under `MAX_SUBSCRIBERS` when dropped.
+pub struct Subscription<'a> {
+    rx: Receiver<String>,
+    events: &'a Events,
+}
+
+impl Drop for Subscription<'_> {
+    fn drop(&mut self) {
+        self.events.listening.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl Events {
+    /// Start receiving events, or `None` if `MAX_SUBSCRIBERS` are already listening.
+    pub fn subscribe(&self) -> Option<Subscription<'_>> {
+        if self.listening.fetch_add(1, Ordering::SeqCst) >= MAX_SUBSCRIBERS {
+            self.listening.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        let (tx, rx) = mpsc::channel();
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.push(tx);
+        }
+        Some(Subscription { rx, events: self })
+    }
+
+    /// Send `event` to every live subscriber, dropping ones that have gone.
+    pub fn publish(&self, event: &serde_json::Value) {
+        let text = event.to_string();
+        if let Ok(mut subs) = self.subscribers.lock() {
+            subs.retain(|tx| tx.send(text.clone()).is_ok());
+        }
+    }
+}
+
+/// Complete the handshake and push events as text frames until the client
+/// goes away. Client frames are never read; a close shows up as a failed write.
+/// Past `MAX_SUBSCRIBERS`, reply 503 instead of upgrading.
+pub fn serve_stream(stream: &mut dyn Write, key: &str, events: &Events) -> anyhow::Result<()> {
+    let Some(sub) = events.subscribe() else {
+        let body = "{\"ok\":false,\"error\":\"too many subscribers\"}";
+        write!(
+            stream,
+            "HTTP/1.1 503 Service Unavailable\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        return Ok(());
+    };
+    let resp = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    );
+    stream.write_all(resp.as_bytes())?;
+
+    loop {
+        match sub.rx.recv_timeout(PING_INTERVAL) {
+            Ok(text) => stream.write_all(&frame(0x1, text.as_bytes()))?,
+            Err(RecvTimeoutError::Timeout) => stream.write_all(&frame(0x9, &[]))?,
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        stream.flush()?;
+    }
+}
+
+/// `Sec-WebSocket-Accept` for a client's `Sec-WebSocket-Key`.
+fn accept_key(key: &str) -> String {
+    base64::encode(&sha1(format!("{}{WS_GUID}", key.trim()).as_bytes()))
+}
+
+/// A single unmasked, final frame (server-to-client frames are never masked).
+fn frame(opcode: u8, payload: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x80 | opcode];
+    match payload.len() {
+        n if n < 126 => out.push(n as u8),
+        n if n <= u16::MAX as usize => {
+            out.push(126);
+            out.extend_from_slice(&(n as u16).to_be_bytes());
+        }
+        n => {
+            out.push(127);
+            out.extend_from_slice(&(n as u64).to_be_bytes());
+        }
+    }
+    out.extend_from_slice(payload);
+    out
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut msg = data.to_vec();
+    msg.push(0x80);
+    while msg.len() % 64 != 56 {
+        msg.push(0);
+    }
+    msg.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in msg.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a.rotate_left(5).wrapping_add(f).wrapping_add(e).wrapping_add(k).wrapping_add(wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = t;
+        }
+        for (hv, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *hv = hv.wrapping_add(v);
+        }
+    }
+
+    let mut out = [0u8; 20];
+    for (chunk, v) in out.chunks_mut(4).zip(h) {
+        chunk.copy_from_slice(&v.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accept_key_matches_rfc_6455() {
+        assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn sha1_matches_known_vectors() {
+        assert_eq!(base64::encode(&sha1(b"")), "2jmj7l5rSw0yVb/vlWAYkK/YBwk=");
+        assert_eq!(base64::encode(&sha1(b"abc")), "qZk+NkcGgWq6PiVxeFDCbJzQ2J0=");
+    }
+
+    #[test]
+    fn subscribers_are_capped_until_one_leaves() {
+        let events = Events::default();
+        let subs: Vec<_> = (0..MAX_SUBSCRIBERS).map(|_| events.subscribe().unwrap()).collect();
+        assert!(events.subscribe().is_none());
+
+        events.publish(&serde_json::json!({"type": "ping"}));
+        assert_eq!(subs[0].rx.try_recv().unwrap(), r#"{"type":"ping"}"#);
+
+        drop(subs);
+        assert!(events.subscribe().is_some());
+    }
+}
@@ -14,6 +14,7 @@ import os
 import re
 import subprocess
 import sys
+import threading
 import time
 import traceback
 from http.server import HTTPServer, BaseHTTPRequestHandler
@@ -55,10 +56,26 @@ Widget types you can include in the widgets array:
 - {"type": "file", "title": "~/docs/notes.txt", "lines": ["Line 1 of file content", "Line 2 of file content"]}
 - {"type": "system", "title": "System Info", "lines": ["CPU: 12.3% (4 cores)", "Memory: 128/489MB", "Uptime: 5m 23s"]}
 - {"type": "info", "title": "Title", "lines": ["Line 1", "Line 2"]}
+- {"type": "progress", "title": "Backup", "value": 0.42, "label": "Copying photos"}  (value runs from 0 to 1)
 
 When no widgets are needed (creative writing, simple answers), use an empty widgets array.
+If you worked through a multi-step problem, you may add an optional "reasoning" string with a short summary of how you got there. Keep it out of "text".
 Be direct, helpful, and concise. You are the OS — act like it. No emoji unless asked."""
 
+# Streamed answers are shown as they arrive, so they can't be wrapped in JSON.
+# Widgets and reasoning go in a trailer after STREAM_META_MARKER instead, which
+# is held back from the stream and parsed into the final "done" event.
+STREAM_META_MARKER = "<<<AETHER_META>>>"
+BRAIN_STREAM_SYSTEM_PROMPT = (
+    BRAIN_SYSTEM_PROMPT.split("IMPORTANT:")[0]
+    + "Respond in plain text: no JSON, no markdown fences.\n"
+    + "If widgets would help, or you want to add reasoning, end your reply with a line containing only "
+    + f"{STREAM_META_MARKER} followed by JSON: {{\"widgets\": [...], \"reasoning\": \"...\"}}. Nothing after the JSON.\n\n"
+    + BRAIN_SYSTEM_PROMPT[BRAIN_SYSTEM_PROMPT.index("Widget types"):BRAIN_SYSTEM_PROMPT.index("When no widgets")]
+    + "Leave the trailer out when there are no widgets or reasoning to add.\n"
+    + "Be direct, helpful, and concise. You are the OS — act like it. No emoji unless asked."
+)
+
 # ---------------------------------------------------------------------------
 # Tool implementations (executed locally before calling Claude)
 # ---------------------------------------------------------------------------
@@ -332,9 +349,8 @@ def detect_and_run_tools(user_input: str) -> str:
 # Claude CLI integration
 # ---------------------------------------------------------------------------
 
-def call_claude(user_input: str, tool_context: str, history: list) -> str:
-    """Call Claude via CLI with tool context and conversation history."""
-    # Build the prompt with context
+def build_prompt(user_input: str, tool_context: str, history: list, reply_format: str) -> str:
+    """Assemble the user prompt from history, tool context and the query."""
     parts = []
 
     # Include recent conversation history (last 6 exchanges)
@@ -354,9 +370,134 @@ def call_claude(user_input: str, tool_context: str, history: list) -> str:
 
     parts.append(f"User query: {user_input}")
     parts.append("")
-    parts.append("Respond with ONLY valid JSON: {\"text\": \"...\", \"widgets\": [...]}")
+    parts.append(reply_format)
+
+    return "\n".join(parts)
+
+
+CLAUDE_ENV = {**os.environ,
+              "CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC": "1",
+              "DISABLE_AUTOUPDATER": "1"}
+
+
+class MetaSplitter:
+    """Splits streamed text at STREAM_META_MARKER: text before it is passed
+    on, anything after it is kept as the trailer. Holds back a partial marker
+    at the end of a delta until the next one shows whether it is one."""
+
+    def __init__(self):
+        self.held = ""
+        self.trailer = None
+
+    def feed(self, text: str) -> str:
+        if self.trailer is not None:
+            self.trailer += text
+            return ""
+        self.held += text
+        at = self.held.find(STREAM_META_MARKER)
+        if at >= 0:
+            shown, self.trailer = self.held[:at], self.held[at + len(STREAM_META_MARKER):]
+            self.held = ""
+            return shown
+        keep = next((k for k in range(len(STREAM_META_MARKER) - 1, 0, -1)
+                     if self.held.endswith(STREAM_META_MARKER[:k])), 0)
+        shown, self.held = self.held[:len(self.held) - keep], self.held[len(self.held) - keep:]
+        return shown
+
+    def finish(self) -> str:
+        """Text still held back once the stream ends without a marker."""
+        shown, self.held = self.held, ""
+        return shown
+
+    def meta(self) -> dict:
+        """Widgets and reasoning from the trailer, shaped like /v0/brain's."""
+        if not self.trailer:
+            return {"widgets": []}
+        cleaned = re.sub(r'^\s*```(?:json)?\s*|\s*```\s*$', '', self.trailer)
+        try:
+            parsed = json.loads(cleaned)
+        except json.JSONDecodeError:
+            return {"widgets": []}
+        if not isinstance(parsed, dict):
+            return {"widgets": []}
+        meta = {"widgets": parsed.get("widgets") if isinstance(parsed.get("widgets"), list) else []}
+        if isinstance(parsed.get("reasoning"), str):
+            meta["reasoning"] = parsed["reasoning"]
+        return meta
+
+
+STREAM_TIMEOUT_SECS = 90
+
+
+def call_claude_stream(user_input: str, tool_context: str, history: list, on_delta) -> dict:
+    """Call Claude via CLI, passing text deltas to on_delta as they arrive.
+    Returns {"text", "widgets"[, "reasoning"]} like call_claude's reply."""
+    full_prompt = build_prompt(user_input, tool_context, history, "Respond in plain text.")
+    chunks = []
+    splitter = MetaSplitter()
+
+    def show(text):
+        if text:
+            chunks.append(text)
+            on_delta(text)
 
-    full_prompt = "\n".join(parts)
+    try:
+        proc = subprocess.Popen(
+            ["claude", "-p", "--model", CLAUDE_MODEL,
+             "--system-prompt", BRAIN_STREAM_SYSTEM_PROMPT,
+             "--output-format", "stream-json", "--verbose",
+             "--include-partial-messages",
+             "--no-session-persistence",
+             "--max-budget-usd", "0.50",
+             full_prompt],
+            stdout=subprocess.PIPE, stderr=subprocess.PIPE, text=True,
+            cwd="/tmp", env=CLAUDE_ENV
+        )
+        # Reading stdout blocks until the CLI writes, so the deadline is
+        # enforced by killing it rather than by checking between lines
+        timed_out = threading.Event()
+
+        def expire():
+            timed_out.set()
+            proc.kill()
+
+        watchdog = threading.Timer(STREAM_TIMEOUT_SECS, expire)
+        watchdog.daemon = True
+        watchdog.start()
+        try:
+            saw_delta = False
+            for line in proc.stdout:
+                try:
+                    event = json.loads(line)
+                except json.JSONDecodeError:
+                    continue
+                if event.get("type") == "stream_event":
+                    delta = event.get("event", {}).get("delta", {})
+                    if delta.get("type") == "text_delta" and delta.get("text"):
+                        saw_delta = True
+                        show(splitter.feed(delta["text"]))
+                elif event.get("type") == "result" and not saw_delta:
+                    # CLI without partial messages: only the final text arrives
+                    show(splitter.feed(event.get("result", "")))
+            proc.wait(timeout=5)
+        finally:
+            watchdog.cancel()
+
+        show(splitter.finish())
+        if timed_out.is_set():
+            show("\n[Brain timed out.]")
+        elif proc.returncode != 0 and not chunks:
+            return {"text": f"Brain error: {proc.stderr.read()[:200]}", "widgets": []}
+        return {"text": "".join(chunks).rstrip(), **splitter.meta()}
+    except Exception as e:
+        return {"text": f"Brain error: {e}", "widgets": []}
+
+
+def call_claude(user_input: str, tool_context: str, history: list) -> str:
+    """Call Claude via CLI with tool context and conversation history."""
+    full_prompt = build_prompt(
+        user_input, tool_context, history,
+        "Respond with ONLY valid JSON: {\"text\": \"...\", \"widgets\": [...]}")
 
     # Call claude CLI from /tmp to avoid loading project context
     try:
@@ -369,9 +510,7 @@ def call_claude(user_input: str, tool_context: str, history: list) -> str:
              full_prompt],
             capture_output=True, text=True, timeout=90,
             cwd="/tmp",
-            env={**os.environ,
-                 "CLAUDE_CODE_DISABLE_NONESSENTIAL_TRAFFIC": "1",
-                 "DISABLE_AUTOUPDATER": "1"}
+            env=CLAUDE_ENV
         )
         if result.returncode != 0:
             stderr = result.stderr[:200]
@@ -717,6 +856,19 @@ class Brain:
 
         return result
 
+    def query_stream(self, user_input: str, on_delta) -> dict:
+        """Like query(), but streams the answer text through on_delta."""
+        tool_context = detect_and_run_tools(user_input)
+        print(f"[brain] Streaming Claude ({CLAUDE_MODEL})...")
+        result = call_claude_stream(user_input, tool_context, self.history, on_delta)
+
+        self.history.append({"role": "user", "content": user_input})
+        self.history.append({"role": "assistant", "content": result.get("text", "")})
+        if len(self.history) > MAX_HISTORY * 2:
+            self.history = self.history[-(MAX_HISTORY * 2):]
+
+        return result
+
 
 # ---------------------------------------------------------------------------
 # HTTP server
@@ -726,7 +878,12 @@ brain_instance = None
 
 class BrainHandler(BaseHTTPRequestHandler):
     def log_message(self, format, *args):
-        print(f"[brain] {args[0]}")
+        print(f"[brain] {self._request_tag()}{args[0]}")
+
+    def _request_tag(self) -> str:
+        """The X-Request-Id aurorad forwarded, as a log prefix."""
+        request_id = self.headers.get("X-Request-Id") if self.headers else None
+        return f"[{request_id}] " if request_id else ""
 
     def _send_json(self, status: int, data: dict):
         body = json.dumps(data).encode()
@@ -755,7 +912,7 @@ class BrainHandler(BaseHTTPRequestHandler):
                     self._send_json(400, {"ok": False, "error": "empty input"})
                     return
 
-                print(f"[brain] Query: {user_input[:80]}")
+                print(f"[brain] {self._request_tag()}Query: {user_input[:80]}")
                 start = time.time()
                 result = brain_instance.query(user_input)
                 elapsed = time.time() - start
@@ -768,6 +925,33 @@ class BrainHandler(BaseHTTPRequestHandler):
                 traceback.print_exc()
                 self._send_json(500, {"ok": False, "text": f"Brain error: {e}", "widgets": []})
 
+        elif self.path == "/v0/brain/stream":
+            # Newline-delimited JSON: {"delta": "..."} lines, then {"done": true, ...}
+            req = json.loads(body) if body else {}
+            user_input = req.get("input", "").strip()
+            self.send_response(200)
+            self.send_header("Content-Type", "application/x-ndjson")
+            self.send_header("Connection", "close")
+            self.end_headers()
+
+            def emit(obj):
+                self.wfile.write((json.dumps(obj) + "\n").encode())
+                self.wfile.flush()
+
+            if not user_input:
+                emit({"done": True, "ok": False, "error": "empty input"})
+                return
+            try:
+                print(f"[brain] {self._request_tag()}Stream query: {user_input[:80]}")
+                start = time.time()
+                result = brain_instance.query_stream(user_input, lambda d: emit({"delta": d}))
+                result["ok"] = True
+                result["latency_ms"] = int((time.time() - start) * 1000)
+                emit({"done": True, **result})
+            except Exception as e:
+                traceback.print_exc()
+                emit({"done": True, "ok": False, "text": f"Brain error: {e}", "widgets": []})
+
         elif self.path == "/v0/brain/proactive":
             try:
                 context = json.loads(body) if body else {}
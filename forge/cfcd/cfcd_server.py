@@ -382,11 +382,14 @@ class CFCDaemon:
         method = parts[0] if len(parts) >= 1 else "GET"
         path = parts[1] if len(parts) >= 2 else "/"
 
-        # Check Content-Length for body
+        # Check Content-Length for body, and the X-Request-Id aurorad forwards
         content_length = 0
+        request_id = None
         for line in headers.split("\r\n"):
             if line.lower().startswith("content-length:"):
                 content_length = int(line.split(":")[1].strip())
+            elif line.lower().startswith("x-request-id:"):
+                request_id = line.split(":", 1)[1].strip()
 
         # Read remaining body if needed
         while len(body.encode()) < content_length:
@@ -397,6 +400,8 @@ class CFCDaemon:
 
         # Handle request
         status_code, response = self.handle_request(method, path, body)
+        if status_code >= 400:
+            print(f"[{request_id or '-'}] {method} {path} -> {status_code}: {response.get('error')}")
 
         # Send HTTP response
         response_json = json.dumps(response)
@@ -29,6 +29,24 @@ pub struct BrainResponse {
     pub error: Option<String>,
 }
 
+/// A notification raised through aurorad's `/v0/notify`: the widget shape
+/// plus a priority.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    #[serde(default)]
+    pub lines: Vec<String>,
+    #[serde(default)]
+    pub priority: String,
+}
+
+#[derive(Deserialize)]
+struct NotificationsResponse {
+    latest: u64,
+    #[serde(default)]
+    notifications: Vec<Notification>,
+}
+
 /// Dashboard layout response from the brain server.
 #[derive(Clone, Debug, Deserialize)]
 pub struct DashboardResponse {
@@ -44,7 +62,8 @@ pub struct DashboardResponse {
     pub latency_ms: u64,
 }
 
-fn aurorad_addr() -> String {
+/// aurorad's address: host:port, or a Unix socket path.
+pub fn aurorad_addr() -> String {
     if let Ok(port) = std::env::var("AURORAD_TCP_PORT") {
         if let Ok(p) = port.parse::<u16>() {
             return format!("127.0.0.1:{}", p);
@@ -57,10 +76,14 @@ fn aurorad_addr() -> String {
 }
 
 fn http_post_aurorad(body_str: &str, timeout_secs: u64) -> Result<String, String> {
+    http_aurorad("POST", "/v0/jobs", body_str, timeout_secs)
+}
+
+fn http_aurorad(method: &str, path: &str, body_str: &str, timeout_secs: u64) -> Result<String, String> {
     let addr = aurorad_addr();
     let request = format!(
-        "POST /v0/jobs HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        body_str.len(), body_str
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        method, path, body_str.len(), body_str
     );
 
     let resp_body = if addr.contains(':') && !addr.starts_with('/') {
@@ -179,6 +202,18 @@ pub fn query_brain_dashboard(
     }
 }
 
+/// Notifications newer than `after`, and the latest id to pass next time.
+/// Without `after`, only the latest id.
+pub fn fetch_notifications(after: Option<u64>) -> Result<(u64, Vec<Notification>), String> {
+    let path = match after {
+        Some(id) => format!("/v0/notifications?after={}", id),
+        None => "/v0/notifications".to_string(),
+    };
+    let resp_body = http_aurorad("GET", &path, "", 2)?;
+    let parsed: NotificationsResponse = serde_json::from_str(&resp_body).map_err(|e| format!("parse: {e}"))?;
+    Ok((parsed.latest, parsed.notifications))
+}
+
 fn extract_body(resp: &str) -> String {
     if let Some(idx) = resp.find("\r\n\r\n") {
         resp[idx + 4..].to_string()
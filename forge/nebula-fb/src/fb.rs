@@ -7,8 +7,12 @@ use std::ptr::NonNull;
 
 use nix::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
 
+use crate::renderer::DirtyRect;
 use crate::theme;
 
+/// Above this fraction of the screen, a partial blit isn't worth the bookkeeping.
+const FULL_BLIT_FRACTION: f32 = 0.6;
+
 /// Framebuffer screen info (from FBIOGET_VSCREENINFO / FBIOGET_FSCREENINFO).
 #[derive(Debug, Clone)]
 pub struct ScreenInfo {
@@ -18,12 +22,33 @@ pub struct ScreenInfo {
     pub bpp: u32,    // bits per pixel
 }
 
+/// Pixel layouts `present` knows how to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PixelFormat {
+    Bgra32,
+    Rgb565,
+    Unsupported,
+}
+
+impl PixelFormat {
+    pub fn from_bpp(bpp: u32) -> Self {
+        match bpp {
+            32 => PixelFormat::Bgra32,
+            16 => PixelFormat::Rgb565,
+            _ => PixelFormat::Unsupported,
+        }
+    }
+}
+
 // Linux framebuffer ioctls
 const FBIOGET_VSCREENINFO: libc::c_int = 0x4600;
+const FBIOPUT_VSCREENINFO: libc::c_int = 0x4601;
 const FBIOGET_FSCREENINFO: libc::c_int = 0x4602;
+const FBIOPAN_DISPLAY: libc::c_int = 0x4606;
+const FBIO_WAITFORVSYNC: libc::c_ulong = 0x4004_4620; // _IOW('F', 0x20, u32)
 
 #[repr(C)]
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct FbVarScreenInfo {
     xres: u32,
     yres: u32,
@@ -58,7 +83,7 @@ struct FbVarScreenInfo {
 }
 
 #[repr(C)]
-#[derive(Default)]
+#[derive(Default, Clone)]
 struct FbBitfield {
     offset: u32,
     length: u32,
@@ -86,10 +111,21 @@ struct FbFixScreenInfo {
 }
 
 pub struct Framebuffer {
-    _file: File,
+    file: File,
     fb_ptr: *mut u8,
     fb_len: usize,
     pub info: ScreenInfo,
+    pub format: PixelFormat,
+    /// Two pages are mapped and `present` flips between them with FBIOPAN_DISPLAY.
+    pub page_flip: bool,
+    vinfo: FbVarScreenInfo,
+    /// The screen info from before `open` asked for a taller virtual screen,
+    /// put back on drop.
+    orig_vinfo: Option<FbVarScreenInfo>,
+    /// Page currently being scanned out.
+    front_page: u32,
+    /// Region written to the front page last flip, which the other page hasn't seen.
+    stale: Option<DirtyRect>,
     back_buffer: Vec<u8>,
 }
 
@@ -112,12 +148,26 @@ impl Framebuffer {
             return Err(format!("FBIOGET_VSCREENINFO failed: {}", std::io::Error::last_os_error()));
         }
 
-        // Get fixed screen info
+        // Ask for a double-height virtual screen so we can page flip
+        let mut orig_vinfo = None;
+        if vinfo.yres_virtual < vinfo.yres * 2 {
+            let mut want = vinfo.clone();
+            want.yres_virtual = vinfo.yres * 2;
+            want.yoffset = 0;
+            let ret = unsafe { libc::ioctl(fd, FBIOPUT_VSCREENINFO, &mut want as *mut _) };
+            if ret == 0 {
+                orig_vinfo = Some(vinfo.clone());
+                unsafe { libc::ioctl(fd, FBIOGET_VSCREENINFO, &mut vinfo as *mut _) };
+            }
+        }
+
+        // Get fixed screen info, after any resize since it can change line_length
         let mut finfo = FbFixScreenInfo::default();
         let ret = unsafe { libc::ioctl(fd, FBIOGET_FSCREENINFO, &mut finfo as *mut _) };
         if ret < 0 {
             return Err(format!("FBIOGET_FSCREENINFO failed: {}", std::io::Error::last_os_error()));
         }
+        let page_flip = can_page_flip(&vinfo, &finfo);
 
         let info = ScreenInfo {
             width: vinfo.xres,
@@ -126,7 +176,8 @@ impl Framebuffer {
             bpp: vinfo.bits_per_pixel,
         };
 
-        let fb_len = (finfo.line_length * vinfo.yres) as usize;
+        let pages = if page_flip { 2 } else { 1 };
+        let fb_len = (finfo.line_length * vinfo.yres * pages) as usize;
 
         // mmap the framebuffer
         let fb_nonnull = unsafe {
@@ -142,23 +193,34 @@ impl Framebuffer {
         };
         let fb_ptr = fb_nonnull.as_ptr() as *mut u8;
 
-        let back_buffer = vec![0u8; fb_len];
+        // The back buffer is tightly packed RGBA; only the fb side is padded to `stride`
+        let back_buffer = vec![0u8; (info.width * info.height * 4) as usize];
 
+        let format = PixelFormat::from_bpp(info.bpp);
         eprintln!(
-            "[fb] Opened {path}: {}x{} bpp={} stride={}",
-            info.width, info.height, info.bpp, info.stride
+            "[fb] Opened {path}: {}x{} bpp={} stride={} page_flip={}",
+            info.width, info.height, info.bpp, info.stride, page_flip
         );
+        if format == PixelFormat::Unsupported {
+            eprintln!("[fb] WARNING: {}bpp is not supported, nothing will be drawn", info.bpp);
+        }
 
         Ok(Self {
-            _file: file,
+            file,
             fb_ptr,
             fb_len,
             info,
+            format,
+            page_flip,
+            vinfo,
+            orig_vinfo,
+            front_page: 0,
+            stale: None,
             back_buffer,
         })
     }
 
-    /// Get a mutable slice to the back buffer for rendering.
+    /// Get a mutable slice to the back buffer for rendering (RGBA, `width * 4` per row).
     pub fn back_buffer_mut(&mut self) -> &mut [u8] {
         &mut self.back_buffer
     }
@@ -173,53 +235,131 @@ impl Framebuffer {
         self.info.height
     }
 
-    /// Blit the back buffer to the framebuffer (RGBA → BGRA conversion).
+    /// Blit the back buffer to the framebuffer, converting RGBA to the fb's format.
     pub fn present(&mut self) {
-        // tiny-skia renders RGBA premultiplied. Linux fb is typically BGRA (or BGRX).
-        // We need to swap R and B channels.
-        let src = &self.back_buffer;
+        let full = DirtyRect { x: 0, y: 0, w: self.info.width, h: self.info.height };
+        self.flip(full);
+    }
+
+    /// Blit only `region` of the back buffer, or everything if it covers most of the screen.
+    pub fn present_region(&mut self, region: DirtyRect) {
+        let screen = self.info.width * self.info.height;
+        if region.area() as f32 > screen as f32 * FULL_BLIT_FRACTION {
+            self.present();
+        } else {
+            self.flip(region);
+        }
+    }
+
+    /// Write `region` to the off-screen page and pan to it, or straight to
+    /// the visible page when page flipping isn't available.
+    fn flip(&mut self, region: DirtyRect) {
+        if !self.page_flip {
+            self.write_page(region, 0);
+            return;
+        }
+
+        // The off-screen page also missed whatever the last flip wrote
+        let back = 1 - self.front_page;
+        let write = match self.stale {
+            Some(stale) => region.union(stale),
+            None => region,
+        };
+        self.write_page(write, back);
+
+        let fd = self.file.as_raw_fd();
+        self.vinfo.yoffset = back * self.info.height;
+        let ret = unsafe { libc::ioctl(fd, FBIOPAN_DISPLAY, &mut self.vinfo as *mut _) };
+        if ret < 0 {
+            eprintln!("[fb] FBIOPAN_DISPLAY failed, drawing in place: {}", std::io::Error::last_os_error());
+            self.page_flip = false;
+            self.vinfo.yoffset = self.front_page * self.info.height;
+            self.write_page(write, self.front_page);
+            return;
+        }
+        // Not every driver supports this; the pan alone still avoids most tearing
+        let mut crtc: u32 = 0;
+        unsafe { libc::ioctl(fd, FBIO_WAITFORVSYNC as _, &mut crtc as *mut _) };
+
+        self.front_page = back;
+        self.stale = Some(region);
+    }
+
+    fn write_page(&mut self, region: DirtyRect, page: u32) {
         let dst = unsafe { std::slice::from_raw_parts_mut(self.fb_ptr, self.fb_len) };
         let stride = self.info.stride as usize;
-        let w = self.info.width as usize;
-        let bpp = (self.info.bpp / 8) as usize;
-
-        for y in 0..self.info.height as usize {
-            let src_row = &src[y * stride..y * stride + w * bpp];
-            let dst_row = &mut dst[y * stride..y * stride + w * bpp];
-            for x in 0..w {
-                let off = x * bpp;
-                // RGBA → BGRA
-                dst_row[off] = src_row[off + 2]; // B
-                dst_row[off + 1] = src_row[off + 1]; // G
-                dst_row[off + 2] = src_row[off]; // R
-                if bpp >= 4 {
-                    dst_row[off + 3] = src_row[off + 3]; // A
-                }
-            }
-        }
+        let page_base = page as usize * stride * self.info.height as usize;
+        blit(
+            &self.back_buffer,
+            self.info.width as usize,
+            &mut dst[page_base..],
+            stride,
+            self.format,
+            region,
+        );
     }
 
     /// Fill entire back buffer with a solid color.
     pub fn clear(&mut self, color: theme::Color) {
-        let stride = self.info.stride as usize;
-        let w = self.info.width as usize;
-        let bpp = (self.info.bpp / 8) as usize;
-        for y in 0..self.info.height as usize {
-            for x in 0..w {
-                let off = y * stride + x * bpp;
-                self.back_buffer[off] = color.r;
-                self.back_buffer[off + 1] = color.g;
-                self.back_buffer[off + 2] = color.b;
-                if bpp >= 4 {
-                    self.back_buffer[off + 3] = color.a;
-                }
-            }
+        let px = [color.r, color.g, color.b, color.a];
+        for chunk in self.back_buffer.chunks_exact_mut(4) {
+            chunk.copy_from_slice(&px);
         }
     }
 }
 
+/// Copy `region` of an RGBA buffer `width` pixels wide into `dst`, whose
+/// rows are `stride` bytes apart, converting to `format`.
+pub fn blit(src: &[u8], width: usize, dst: &mut [u8], stride: usize, format: PixelFormat, region: DirtyRect) {
+    let (x0, x1) = (region.x as usize, (region.x + region.w) as usize);
+    for y in region.y as usize..(region.y + region.h) as usize {
+        let src_row = &src[y * width * 4 + x0 * 4..y * width * 4 + x1 * 4];
+        let row = y * stride;
+        match format {
+            // tiny-skia renders RGBA premultiplied. Linux fb is typically BGRA (or BGRX).
+            PixelFormat::Bgra32 => bgra_row(src_row, &mut dst[row + x0 * 4..row + x1 * 4]),
+            PixelFormat::Rgb565 => pack_rgb565_row(src_row, &mut dst[row + x0 * 2..row + x1 * 2]),
+            PixelFormat::Unsupported => {}
+        }
+    }
+}
+
+/// Convert one row of RGBA pixels into BGRA.
+pub fn bgra_row(src: &[u8], dst: &mut [u8]) {
+    for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(4)) {
+        d[0] = s[2]; // B
+        d[1] = s[1]; // G
+        d[2] = s[0]; // R
+        d[3] = s[3]; // A
+    }
+}
+
+/// Pack 8-bit channels into RGB565 with rounding.
+pub fn rgb565(r: u8, g: u8, b: u8) -> u16 {
+    let r5 = (r as u16 * 31 + 127) / 255;
+    let g6 = (g as u16 * 63 + 127) / 255;
+    let b5 = (b as u16 * 31 + 127) / 255;
+    (r5 << 11) | (g6 << 5) | b5
+}
+
+/// Convert one row of RGBA pixels into native-endian RGB565.
+pub fn pack_rgb565_row(src: &[u8], dst: &mut [u8]) {
+    for (s, d) in src.chunks_exact(4).zip(dst.chunks_exact_mut(2)) {
+        d.copy_from_slice(&rgb565(s[0], s[1], s[2]).to_ne_bytes());
+    }
+}
+
+/// Whether both pages fit: some drivers accept a taller virtual screen
+/// without growing the memory behind it.
+fn can_page_flip(vinfo: &FbVarScreenInfo, finfo: &FbFixScreenInfo) -> bool {
+    vinfo.yres_virtual >= vinfo.yres * 2 && finfo.smem_len as usize >= finfo.line_length as usize * vinfo.yres as usize * 2
+}
+
 impl Drop for Framebuffer {
     fn drop(&mut self) {
+        if let Some(mut orig) = self.orig_vinfo.take() {
+            unsafe { libc::ioctl(self.file.as_raw_fd(), FBIOPUT_VSCREENINFO, &mut orig as *mut _) };
+        }
         if self.fb_len > 0 {
             if let Some(nn) = NonNull::new(self.fb_ptr as *mut libc::c_void) {
                 unsafe {
@@ -229,3 +369,101 @@ impl Drop for Framebuffer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_flip_needs_room_for_both_pages() {
+        let vinfo = FbVarScreenInfo { yres: 600, yres_virtual: 1200, ..Default::default() };
+        let mut finfo = FbFixScreenInfo { line_length: 3200, smem_len: 3200 * 1200, ..Default::default() };
+        assert!(can_page_flip(&vinfo, &finfo));
+
+        // Driver took the taller virtual screen but kept one page of memory
+        finfo.smem_len = 3200 * 600;
+        assert!(!can_page_flip(&vinfo, &finfo));
+
+        let short = FbVarScreenInfo { yres: 600, yres_virtual: 600, ..Default::default() };
+        finfo.smem_len = 3200 * 1200;
+        assert!(!can_page_flip(&short, &finfo));
+    }
+
+    #[test]
+    fn rgb565_packs_and_rounds_channels() {
+        assert_eq!(rgb565(0, 0, 0), 0x0000);
+        assert_eq!(rgb565(255, 255, 255), 0xFFFF);
+        assert_eq!(rgb565(255, 0, 0), 0xF800);
+        assert_eq!(rgb565(0, 255, 0), 0x07E0);
+        assert_eq!(rgb565(0, 0, 255), 0x001F);
+        // 128 rounds to 16/31 red, 32/63 green
+        assert_eq!(rgb565(128, 128, 0), (16 << 11) | (32 << 5));
+    }
+
+    #[test]
+    fn blit_packs_rgb565_rows_at_the_stride() {
+        // 2x2 back buffer: red, green / blue, white
+        let src = [
+            255, 0, 0, 255, 0, 255, 0, 255, //
+            0, 0, 255, 255, 255, 255, 255, 255,
+        ];
+        // Rows padded to 6 bytes rather than width * 2
+        let stride = 6;
+        let mut dst = [0xAAu8; 12];
+        let region = DirtyRect { x: 0, y: 0, w: 2, h: 2 };
+        blit(&src, 2, &mut dst, stride, PixelFormat::Rgb565, region);
+
+        let mut expected = Vec::new();
+        for px in [0xF800u16, 0x07E0] {
+            expected.extend_from_slice(&px.to_ne_bytes());
+        }
+        expected.extend_from_slice(&[0xAA, 0xAA]);
+        for px in [0x001Fu16, 0xFFFF] {
+            expected.extend_from_slice(&px.to_ne_bytes());
+        }
+        expected.extend_from_slice(&[0xAA, 0xAA]);
+        assert_eq!(dst.to_vec(), expected);
+    }
+
+    #[test]
+    fn blit_copies_bgra_rows_at_a_stride_wider_than_the_image() {
+        // 2x3 back buffer, tightly packed; pixel (x, y) is [y, x, 0, 255]
+        let (width, height) = (2usize, 3usize);
+        let src: Vec<u8> = (0..height)
+            .flat_map(|y| (0..width).flat_map(move |x| [y as u8, x as u8, 0, 255]))
+            .collect();
+        // Each scanline carries 8 bytes of padding past the 8 bytes of pixels
+        let stride = 16;
+        let mut dst = vec![0xEEu8; stride * height];
+        let region = DirtyRect { x: 0, y: 0, w: width as u32, h: height as u32 };
+        blit(&src, width, &mut dst, stride, PixelFormat::Bgra32, region);
+
+        for y in 0..height {
+            let row = &dst[y * stride..(y + 1) * stride];
+            for x in 0..width {
+                // BGRA: B=0, G=x, R=y, A=255
+                assert_eq!(&row[x * 4..x * 4 + 4], &[0, x as u8, y as u8, 255], "pixel ({x}, {y})");
+            }
+            assert!(row[width * 4..].iter().all(|&b| b == 0xEE), "padding of row {y} was written");
+        }
+    }
+
+    #[test]
+    fn blit_only_touches_the_region() {
+        let src = [10, 20, 30, 255, 40, 50, 60, 255];
+        let mut dst = [0u8; 8];
+        let region = DirtyRect { x: 1, y: 0, w: 1, h: 1 };
+        blit(&src, 2, &mut dst, 8, PixelFormat::Bgra32, region);
+        assert_eq!(dst, [0, 0, 0, 0, 60, 50, 40, 255]);
+    }
+
+    #[test]
+    fn unsupported_depths_are_not_written() {
+        assert_eq!(PixelFormat::from_bpp(24), PixelFormat::Unsupported);
+        assert_eq!(PixelFormat::from_bpp(8), PixelFormat::Unsupported);
+        let src = [1u8; 4];
+        let mut dst = [0u8; 4];
+        blit(&src, 1, &mut dst, 4, PixelFormat::Unsupported, DirtyRect { x: 0, y: 0, w: 1, h: 1 });
+        assert_eq!(dst, [0; 4]);
+    }
+}
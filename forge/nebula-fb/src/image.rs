@@ -0,0 +1,26 @@
+/// Decoded bitmaps — PNG into premultiplied RGBA, ready for `Renderer::draw_image`.
+
+use tiny_skia::Pixmap;
+
+/// Logo shown on the boot splash.
+pub static AETHER_LOGO_PNG: &[u8] = include_bytes!("../assets/aether-logo.png");
+
+pub struct Image {
+    pub(crate) pixmap: Pixmap,
+}
+
+impl Image {
+    /// Decode a PNG (any color type or bit depth) to premultiplied RGBA.
+    pub fn from_png(data: &[u8]) -> Result<Self, String> {
+        let pixmap = Pixmap::decode_png(data).map_err(|e| format!("decode png: {e}"))?;
+        Ok(Self { pixmap })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.pixmap.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.pixmap.height()
+    }
+}
@@ -1,12 +1,25 @@
 /// Input handling — keyboard from /dev/tty0 raw mode, mouse from evdev.
 
+use std::collections::VecDeque;
 use std::io::Read;
+use std::os::unix::fs::OpenOptionsExt;
 use std::os::unix::io::AsRawFd;
 
 /// Input events from keyboard and mouse.
+///
+/// A raw tty only delivers bytes, so modifiers are limited to what the
+/// terminal encodes: Ctrl+A..Z arrive as `Ctrl`, except Ctrl+H/I/J/M which
+/// are indistinguishable from Backspace/Tab/Enter. Ctrl+Shift looks like
+/// Ctrl, and Shift alone only shows up as the character's case. Alt+key
+/// arrives as ESC followed by the key, so Escape pressed quickly before a
+/// key also reads as `Alt`.
 #[derive(Debug, Clone)]
 pub enum InputEvent {
     Char(char),
+    /// Ctrl+letter, lowercase.
+    Ctrl(char),
+    /// Alt+printable character.
+    Alt(char),
     Backspace,
     Enter,
     Escape,
@@ -17,18 +30,187 @@ pub enum InputEvent {
     Right,
     PageUp,
     PageDown,
+    /// Button press; `button` is 1 left, 2 right, 3 middle.
     Mouse { x: i32, y: i32, button: u8 },
     MouseMove { x: i32, y: i32 },
+    /// Wheel ticks, evdev sign: `dy > 0` is away from the user, `dx > 0` right.
+    Scroll { dx: i32, dy: i32 },
     None,
 }
 
+// evdev event types and codes (linux/input-event-codes.h)
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const EV_ABS: u16 = 0x03;
+const SYN_REPORT: u16 = 0x00;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+const ABS_X: u16 = 0x00;
+const ABS_Y: u16 = 0x01;
+const BTN_LEFT: u16 = 0x110;
+const BTN_RIGHT: u16 = 0x111;
+const BTN_MIDDLE: u16 = 0x112;
+
+// EVIOCGBIT(0, 4) and EVIOCGABS(0)
+const EVIOCGBIT_TYPES: libc::c_ulong = 0x8004_4520;
+const EVIOCGABS_BASE: libc::c_ulong = 0x8018_4540;
+
+/// `struct input_event` as read from /dev/input/event*.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct RawInputEvent {
+    pub time: libc::timeval,
+    pub type_: u16,
+    pub code: u16,
+    pub value: i32,
+}
+
+const RAW_EVENT_SIZE: usize = std::mem::size_of::<RawInputEvent>();
+
+impl RawInputEvent {
+    /// Decode one event from exactly `RAW_EVENT_SIZE` bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < RAW_EVENT_SIZE {
+            return None;
+        }
+        Some(unsafe { std::ptr::read_unaligned(bytes.as_ptr() as *const RawInputEvent) })
+    }
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct AbsInfo {
+    value: i32,
+    minimum: i32,
+    maximum: i32,
+    fuzz: i32,
+    flat: i32,
+    resolution: i32,
+}
+
+/// Pointer state shared by all pointing devices, clamped to the screen.
+pub struct MouseState {
+    pub x: i32,
+    pub y: i32,
+    width: i32,
+    height: i32,
+    moved: bool,
+}
+
+impl MouseState {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            x: width as i32 / 2,
+            y: height as i32 / 2,
+            width: width as i32,
+            height: height as i32,
+            moved: false,
+        }
+    }
+
+    /// Fold one raw event into the state, queuing any resulting events.
+    /// `abs` is the device's (min, max) range for ABS_X and ABS_Y.
+    pub fn apply(&mut self, ev: &RawInputEvent, abs: [(i32, i32); 2], out: &mut VecDeque<InputEvent>) {
+        match (ev.type_, ev.code) {
+            (EV_REL, REL_X) => {
+                self.x = (self.x + ev.value).clamp(0, self.width - 1);
+                self.moved = true;
+            }
+            (EV_REL, REL_Y) => {
+                self.y = (self.y + ev.value).clamp(0, self.height - 1);
+                self.moved = true;
+            }
+            (EV_REL, REL_WHEEL) => out.push_back(InputEvent::Scroll { dx: 0, dy: ev.value }),
+            (EV_REL, REL_HWHEEL) => out.push_back(InputEvent::Scroll { dx: ev.value, dy: 0 }),
+            (EV_ABS, ABS_X) => {
+                self.x = scale_abs(ev.value, abs[0], self.width);
+                self.moved = true;
+            }
+            (EV_ABS, ABS_Y) => {
+                self.y = scale_abs(ev.value, abs[1], self.height);
+                self.moved = true;
+            }
+            (EV_KEY, BTN_LEFT | BTN_RIGHT | BTN_MIDDLE) if ev.value == 1 => {
+                let button = match ev.code {
+                    BTN_LEFT => 1,
+                    BTN_RIGHT => 2,
+                    _ => 3,
+                };
+                out.push_back(InputEvent::Mouse { x: self.x, y: self.y, button });
+            }
+            (EV_SYN, SYN_REPORT) if self.moved => {
+                self.moved = false;
+                out.push_back(InputEvent::MouseMove { x: self.x, y: self.y });
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Map an absolute axis value onto `0..size`.
+fn scale_abs(value: i32, (min, max): (i32, i32), size: i32) -> i32 {
+    if max <= min {
+        return value.clamp(0, size - 1);
+    }
+    let t = (value - min) as i64 * (size - 1) as i64 / (max - min) as i64;
+    (t as i32).clamp(0, size - 1)
+}
+
+/// An evdev device that reports relative or absolute motion.
+struct PointerDevice {
+    file: std::fs::File,
+    abs: [(i32, i32); 2],
+}
+
+impl PointerDevice {
+    fn open(path: &std::path::Path) -> Option<Self> {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(path)
+            .ok()?;
+        let fd = file.as_raw_fd();
+
+        let mut types: u32 = 0;
+        if unsafe { libc::ioctl(fd, EVIOCGBIT_TYPES as _, &mut types as *mut _) } < 0 {
+            return None;
+        }
+        if types & ((1 << EV_REL) | (1 << EV_ABS)) == 0 {
+            return None;
+        }
+
+        let mut abs = [(0, 0); 2];
+        for (axis, range) in abs.iter_mut().enumerate() {
+            let mut info = AbsInfo::default();
+            let req = EVIOCGABS_BASE + axis as libc::c_ulong;
+            if unsafe { libc::ioctl(fd, req as _, &mut info as *mut _) } == 0 {
+                *range = (info.minimum, info.maximum);
+            }
+        }
+        Some(Self { file, abs })
+    }
+}
+
+/// Bytes kept waiting for the rest of a sequence before they're discarded.
+const MAX_PENDING: usize = 16;
+
 pub struct InputReader {
     tty: std::fs::File,
     saved_termios: Option<libc::termios>,
+    /// Bytes read but not yet turned into events, e.g. half of a UTF-8 char.
+    pending: Vec<u8>,
+    pointers: Vec<PointerDevice>,
+    mouse: MouseState,
+    /// Decoded events waiting to be returned from `poll`.
+    queue: VecDeque<InputEvent>,
 }
 
 impl InputReader {
-    pub fn new() -> Result<Self, String> {
+    /// `width`/`height` bound the pointer position.
+    pub fn new(width: u32, height: u32) -> Result<Self, String> {
         // Open tty for raw keyboard input
         let tty = std::fs::OpenOptions::new()
             .read(true)
@@ -59,48 +241,130 @@ impl InputReader {
             unsafe { libc::tcsetattr(fd, libc::TCSANOW, &raw) };
         }
 
+        let mut pointers = Vec::new();
+        if let Ok(entries) = std::fs::read_dir("/dev/input") {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with("event") {
+                    pointers.extend(PointerDevice::open(&entry.path()));
+                }
+            }
+        }
+        eprintln!("[input] {} pointer device(s)", pointers.len());
+
         Ok(Self {
             tty,
             saved_termios: saved,
+            pending: Vec::new(),
+            pointers,
+            mouse: MouseState::new(width, height),
+            queue: VecDeque::new(),
         })
     }
 
     /// Non-blocking read of one input event.
     pub fn poll(&mut self) -> InputEvent {
-        let mut buf = [0u8; 8];
-
-        // Non-blocking read
-        let n = match self.tty.read(&mut buf) {
-            Ok(0) => return InputEvent::None,
-            Ok(n) => n,
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return InputEvent::None,
-            Err(_) => return InputEvent::None,
-        };
+        if let Some(event) = self.queue.pop_front() {
+            return event;
+        }
+        let key = self.poll_keyboard();
+        if !matches!(key, InputEvent::None) {
+            return key;
+        }
+        self.poll_evdev();
+        self.queue.pop_front().unwrap_or(InputEvent::None)
+    }
+
+    /// Drain pending evdev events from every pointer device into the queue.
+    fn poll_evdev(&mut self) {
+        let mut buf = [0u8; RAW_EVENT_SIZE * 32];
+        for dev in &mut self.pointers {
+            while let Ok(n) = dev.file.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                for chunk in buf[..n].chunks_exact(RAW_EVENT_SIZE) {
+                    if let Some(ev) = RawInputEvent::from_bytes(chunk) {
+                        self.mouse.apply(&ev, dev.abs, &mut self.queue);
+                    }
+                }
+            }
+        }
+    }
+
+    fn poll_keyboard(&mut self) -> InputEvent {
+        let mut buf = [0u8; 32];
+
+        // Non-blocking read; WouldBlock and errors just mean no new bytes
+        if let Ok(n) = self.tty.read(&mut buf) {
+            self.pending.extend_from_slice(&buf[..n]);
+        }
+        if self.pending.is_empty() {
+            return InputEvent::None;
+        }
 
-        // Parse escape sequences
-        if n == 1 {
-            match buf[0] {
-                0x1b => InputEvent::Escape,
-                0x0d | 0x0a => InputEvent::Enter,
-                0x7f | 0x08 => InputEvent::Backspace,
-                0x09 => InputEvent::Tab,
-                b if b >= 0x20 && b < 0x7f => InputEvent::Char(b as char),
-                _ => InputEvent::None,
+        match parse_key(&self.pending) {
+            Some((event, used)) => {
+                self.pending.drain(..used);
+                event
+            }
+            None => {
+                // Incomplete sequence: wait for the next read unless it's junk
+                if self.pending.len() > MAX_PENDING {
+                    self.pending.clear();
+                }
+                InputEvent::None
             }
-        } else if n >= 3 && buf[0] == 0x1b && buf[1] == b'[' {
-            match buf[2] {
-                b'A' => InputEvent::Up,
-                b'B' => InputEvent::Down,
-                b'C' => InputEvent::Right,
-                b'D' => InputEvent::Left,
-                b'5' if n >= 4 && buf[3] == b'~' => InputEvent::PageUp,
-                b'6' if n >= 4 && buf[3] == b'~' => InputEvent::PageDown,
-                _ => InputEvent::None,
+        }
+    }
+}
+
+/// Decode the first key in `bytes`. Returns the event and how many bytes it
+/// used, or `None` if the bytes are the start of a longer sequence.
+pub fn parse_key(bytes: &[u8]) -> Option<(InputEvent, usize)> {
+    let first = *bytes.first()?;
+    let event = match first {
+        0x1b => return parse_escape(bytes),
+        0x0d | 0x0a => InputEvent::Enter,
+        0x7f | 0x08 => InputEvent::Backspace,
+        0x09 => InputEvent::Tab,
+        0x01..=0x1a => InputEvent::Ctrl((b'a' + first - 1) as char),
+        0x20..=0x7e => InputEvent::Char(first as char),
+        0xc0..=0xf7 => {
+            // UTF-8 lead byte: the count of leading ones is the sequence length
+            let len = first.leading_ones() as usize;
+            if bytes.len() < len {
+                return None;
             }
-        } else {
-            InputEvent::None
+            return Some(match std::str::from_utf8(&bytes[..len]) {
+                Ok(s) => (s.chars().next().map_or(InputEvent::None, InputEvent::Char), len),
+                // Bad continuation: drop the lead byte and resync
+                Err(_) => (InputEvent::None, 1),
+            });
         }
+        _ => InputEvent::None,
+    };
+    Some((event, 1))
+}
+
+fn parse_escape(bytes: &[u8]) -> Option<(InputEvent, usize)> {
+    match bytes.get(1) {
+        None => return Some((InputEvent::Escape, 1)),
+        Some(b'[') => {}
+        Some(&b) if (0x20..=0x7e).contains(&b) => return Some((InputEvent::Alt(b as char), 2)),
+        Some(_) => return Some((InputEvent::Escape, 1)),
     }
+    // CSI: parameters up to a final byte in 0x40..=0x7e
+    let end = bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b))? + 2;
+    let event = match &bytes[2..=end] {
+        b"A" => InputEvent::Up,
+        b"B" => InputEvent::Down,
+        b"C" => InputEvent::Right,
+        b"D" => InputEvent::Left,
+        b"5~" => InputEvent::PageUp,
+        b"6~" => InputEvent::PageDown,
+        _ => InputEvent::None,
+    };
+    Some((event, end + 1))
 }
 
 impl Drop for InputReader {
@@ -111,3 +375,72 @@ impl Drop for InputReader {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The bytes the kernel would hand `read` for one event.
+    fn raw(type_: u16, code: u16, value: i32) -> Vec<u8> {
+        let ev = RawInputEvent {
+            time: libc::timeval { tv_sec: 0, tv_usec: 0 },
+            type_,
+            code,
+            value,
+        };
+        let ptr = &ev as *const RawInputEvent as *const u8;
+        unsafe { std::slice::from_raw_parts(ptr, RAW_EVENT_SIZE) }.to_vec()
+    }
+
+    /// Decode `events` the way `poll_evdev` does and collect what comes out.
+    fn feed(mouse: &mut MouseState, events: &[(u16, u16, i32)]) -> Vec<InputEvent> {
+        let bytes: Vec<u8> = events.iter().flat_map(|&(t, c, v)| raw(t, c, v)).collect();
+        let mut out = VecDeque::new();
+        for chunk in bytes.chunks_exact(RAW_EVENT_SIZE) {
+            let ev = RawInputEvent::from_bytes(chunk).unwrap();
+            mouse.apply(&ev, [(0, 0); 2], &mut out);
+        }
+        out.into()
+    }
+
+    #[test]
+    fn wheel_tick_becomes_scroll() {
+        let mut mouse = MouseState::new(100, 100);
+        let out = feed(&mut mouse, &[(EV_REL, REL_WHEEL, -1), (EV_SYN, SYN_REPORT, 0)]);
+        assert!(matches!(out.as_slice(), [InputEvent::Scroll { dx: 0, dy: -1 }]), "{out:?}");
+
+        let out = feed(&mut mouse, &[(EV_REL, REL_HWHEEL, 2), (EV_SYN, SYN_REPORT, 0)]);
+        assert!(matches!(out.as_slice(), [InputEvent::Scroll { dx: 2, dy: 0 }]), "{out:?}");
+    }
+
+    #[test]
+    fn right_and_middle_buttons_map_to_2_and_3() {
+        let mut mouse = MouseState::new(100, 100);
+        let out = feed(
+            &mut mouse,
+            &[(EV_KEY, BTN_RIGHT, 1), (EV_KEY, BTN_RIGHT, 0), (EV_KEY, BTN_MIDDLE, 1)],
+        );
+        assert!(matches!(
+            out.as_slice(),
+            [
+                InputEvent::Mouse { x: 50, y: 50, button: 2 },
+                InputEvent::Mouse { x: 50, y: 50, button: 3 }
+            ]
+        ), "{out:?}");
+    }
+
+    #[test]
+    fn relative_motion_is_clamped_and_reported_on_sync() {
+        let mut mouse = MouseState::new(100, 100);
+        let out = feed(
+            &mut mouse,
+            &[(EV_REL, REL_X, 500), (EV_REL, REL_Y, -500), (EV_SYN, SYN_REPORT, 0)],
+        );
+        assert!(matches!(out.as_slice(), [InputEvent::MouseMove { x: 99, y: 0 }]), "{out:?}");
+    }
+
+    #[test]
+    fn short_reads_are_not_decoded() {
+        assert!(RawInputEvent::from_bytes(&raw(EV_REL, REL_WHEEL, 1)[..RAW_EVENT_SIZE - 1]).is_none());
+    }
+}
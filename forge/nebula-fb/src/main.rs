@@ -10,8 +10,12 @@ mod scene;
 mod layout;
 mod brain_client;
 mod telemetry;
+mod profile;
+mod image;
 mod widgets;
 mod scenes;
+mod toast;
+mod services;
 
 use std::time::Instant;
 
@@ -37,7 +41,7 @@ fn main() {
     let text_renderer = text::TextRenderer::new();
 
     // Create input reader
-    let mut input_reader = match input::InputReader::new() {
+    let mut input_reader = match input::InputReader::new(width, height) {
         Ok(ir) => ir,
         Err(e) => {
             eprintln!("[nebula-fb] Warning: input init failed: {}", e);
@@ -51,6 +55,7 @@ fn main() {
     // Initialize scene manager with boot splash
     let splash = scenes::boot_splash::BootSplash::new(width, height);
     let mut scene_manager = scene::SceneManager::new(Box::new(splash));
+    let mut toasts = toast::Toasts::listen();
 
     // Main loop
     let mut last_frame = Instant::now();
@@ -73,6 +78,7 @@ fn main() {
 
         // Update
         scene_manager.update(dt);
+        toasts.update(dt);
 
         // Check if we should exit
         if scene_manager.is_empty() {
@@ -81,10 +87,14 @@ fn main() {
 
         // Draw
         scene_manager.draw(&mut render, &text_renderer);
+        toasts.draw(&mut render, &text_renderer);
 
-        // Blit to framebuffer
-        render.copy_to(framebuffer.back_buffer_mut());
-        framebuffer.present();
+        // Blit only what changed; idle frames skip the framebuffer entirely
+        if let Some(damage) = render.take_damage() {
+            if let Some(changed) = render.copy_changed(framebuffer.back_buffer_mut(), damage) {
+                framebuffer.present_region(changed);
+            }
+        }
 
         // Frame rate limiting
         let elapsed = now.elapsed();
@@ -0,0 +1,54 @@
+/// User profile from the setup wizard — name and interests, persisted across reboots.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+/// Where setup used to live; read once as a fallback and migrated.
+const LEGACY_SETUP_FILE: &str = "/tmp/aether_setup.json";
+/// Used when there's no `$HOME` to put the profile under.
+const SYSTEM_SETUP_FILE: &str = "/var/lib/aether/setup.json";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    #[serde(default)]
+    pub interests: Vec<String>,
+}
+
+/// `$HOME/.config/aether/setup.json`, or the system path without a home.
+pub fn setup_path() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) if !home.is_empty() => PathBuf::from(home).join(".config/aether/setup.json"),
+        _ => PathBuf::from(SYSTEM_SETUP_FILE),
+    }
+}
+
+/// Load a completed setup, migrating one left in /tmp by older builds.
+pub fn load() -> Option<Profile> {
+    if let Some(profile) = read(setup_path()) {
+        return Some(profile);
+    }
+    let profile = read(PathBuf::from(LEGACY_SETUP_FILE))?;
+    if let Err(e) = save(&profile) {
+        eprintln!("[nebula-fb] Could not migrate setup from {LEGACY_SETUP_FILE}: {e}");
+    }
+    Some(profile)
+}
+
+/// Write the profile, creating its directory if needed.
+pub fn save(profile: &Profile) -> Result<(), String> {
+    let path = setup_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("create {}: {e}", dir.display()))?;
+    }
+    let json = serde_json::to_string_pretty(profile).map_err(|e| format!("encode: {e}"))?;
+    std::fs::write(&path, json).map_err(|e| format!("write {}: {e}", path.display()))
+}
+
+/// A profile only counts as set up if it parses and has a name.
+fn read(path: PathBuf) -> Option<Profile> {
+    let data = std::fs::read_to_string(path).ok()?;
+    let profile: Profile = serde_json::from_str(&data).ok()?;
+    (!profile.name.trim().is_empty()).then_some(profile)
+}
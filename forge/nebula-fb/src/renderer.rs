@@ -1,31 +1,229 @@
 /// 2D rendering wrapper around tiny-skia.
 
 use tiny_skia::{
-    FillRule, LineCap, Paint, PathBuilder, Pixmap, Stroke, Transform,
+    FillRule, FilterQuality, LineCap, Mask, Paint, PathBuilder, Pixmap, PixmapPaint, Stroke, Transform,
 };
 
+use crate::image::Image;
 use crate::theme::Color;
 
+/// Extra pixels around each draw's bounds to cover anti-aliasing and strokes.
+const DAMAGE_PAD: f32 = 2.0;
+
+/// Pixel-aligned screen region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DirtyRect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl DirtyRect {
+    pub fn union(self, other: DirtyRect) -> DirtyRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x + self.w).max(other.x + other.w);
+        let bottom = (self.y + self.h).max(other.y + other.h);
+        DirtyRect { x, y, w: right - x, h: bottom - y }
+    }
+
+    pub fn area(&self) -> u32 {
+        self.w * self.h
+    }
+
+    /// Overlap of two rects; empty (zero-sized) when they don't meet.
+    pub fn intersect(self, other: DirtyRect) -> DirtyRect {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = (self.x + self.w).min(other.x + other.w).max(x);
+        let bottom = (self.y + self.h).min(other.y + other.h).max(y);
+        DirtyRect { x, y, w: right - x, h: bottom - y }
+    }
+}
+
+fn union_opt(a: Option<DirtyRect>, b: Option<DirtyRect>) -> Option<DirtyRect> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.union(b)),
+        (a, b) => a.or(b),
+    }
+}
+
 pub struct Renderer {
     pub pixmap: Pixmap,
+    /// Bounds touched by draws this frame.
+    damage: Option<DirtyRect>,
+    /// Bounds touched by draws last frame; those pixels revert to the
+    /// background when this frame clears.
+    prev_damage: Option<DirtyRect>,
+    clear_color: Option<Color>,
+    cleared: bool,
+    full_damage: bool,
+    /// Clip stack, each entry already intersected with the one below.
+    clips: Vec<DirtyRect>,
+    /// Mask reused for every clip, allocated on the first `push_clip`. Only
+    /// consulted while `clips` is non-empty.
+    mask: Option<Mask>,
+    /// The rect currently painted into `mask`.
+    mask_rect: Option<DirtyRect>,
+}
+
+/// Mask for the current clip, or `None` while nothing is clipped. Takes the
+/// fields rather than `&self` so it can be passed while `pixmap` is borrowed.
+fn clip_mask<'a>(clips: &[DirtyRect], mask: &'a Option<Mask>) -> Option<&'a Mask> {
+    if clips.is_empty() {
+        None
+    } else {
+        mask.as_ref()
+    }
 }
 
 impl Renderer {
     pub fn new(width: u32, height: u32) -> Self {
         Self {
             pixmap: Pixmap::new(width, height).expect("create pixmap"),
+            damage: None,
+            prev_damage: None,
+            clear_color: None,
+            cleared: false,
+            full_damage: true,
+            clips: Vec::new(),
+            mask: None,
+            mask_rect: None,
         }
     }
 
+    /// Restrict drawing to `x,y,w,h` (within any current clip) until `pop_clip`.
+    pub fn push_clip(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        let (pw, ph) = (self.width() as f32, self.height() as f32);
+        let x0 = x.floor().clamp(0.0, pw);
+        let y0 = y.floor().clamp(0.0, ph);
+        let x1 = (x + w).ceil().clamp(x0, pw);
+        let y1 = (y + h).ceil().clamp(y0, ph);
+        let rect = DirtyRect {
+            x: x0 as u32,
+            y: y0 as u32,
+            w: (x1 - x0) as u32,
+            h: (y1 - y0) as u32,
+        };
+        let clip = self.clip_rect().intersect(rect);
+        self.clips.push(clip);
+        self.rebuild_clip_mask();
+    }
+
+    pub fn pop_clip(&mut self) {
+        self.clips.pop();
+        self.rebuild_clip_mask();
+    }
+
+    /// Current clip in pixels; the full screen when nothing is pushed.
+    pub fn clip_rect(&self) -> DirtyRect {
+        self.clips
+            .last()
+            .copied()
+            .unwrap_or(DirtyRect { x: 0, y: 0, w: self.width(), h: self.height() })
+    }
+
+    /// Repaint the shared mask for the top clip. Clips are always rectangles,
+    /// so only the rows of the old and new rects are touched.
+    fn rebuild_clip_mask(&mut self) {
+        let Some(&clip) = self.clips.last() else {
+            return;
+        };
+        if self.mask_rect == Some(clip) {
+            return;
+        }
+        let mask = self
+            .mask
+            .get_or_insert_with(|| Mask::new(self.pixmap.width(), self.pixmap.height()).expect("create mask"));
+        let stride = mask.width() as usize;
+        let data = mask.data_mut();
+        let mut paint = |r: DirtyRect, value: u8| {
+            for y in r.y as usize..(r.y + r.h) as usize {
+                let row = y * stride;
+                data[row + r.x as usize..row + (r.x + r.w) as usize].fill(value);
+            }
+        };
+        if let Some(old) = self.mask_rect {
+            paint(old, 0);
+        }
+        paint(clip, 255);
+        self.mask_rect = Some(clip);
+    }
+
     pub fn clear(&mut self, color: Color) {
         self.pixmap.fill(color.to_skia());
+        if self.clear_color != Some(color) {
+            self.full_damage = true;
+            self.clear_color = Some(color);
+        }
+        self.cleared = true;
+    }
+
+    /// Record that a draw touched this area.
+    pub fn touch(&mut self, x: f32, y: f32, w: f32, h: f32) {
+        let (pw, ph) = (self.pixmap.width() as f32, self.pixmap.height() as f32);
+        let x0 = (x - DAMAGE_PAD).floor().clamp(0.0, pw);
+        let y0 = (y - DAMAGE_PAD).floor().clamp(0.0, ph);
+        let x1 = (x + w + DAMAGE_PAD).ceil().clamp(0.0, pw);
+        let y1 = (y + h + DAMAGE_PAD).ceil().clamp(0.0, ph);
+        if x1 <= x0 || y1 <= y0 {
+            return;
+        }
+        let rect = DirtyRect {
+            x: x0 as u32,
+            y: y0 as u32,
+            w: (x1 - x0) as u32,
+            h: (y1 - y0) as u32,
+        };
+        // Nothing outside the clip changed
+        let rect = rect.intersect(self.clip_rect());
+        if rect.area() == 0 {
+            return;
+        }
+        self.damage = union_opt(self.damage, Some(rect));
+    }
+
+    /// Region that may differ from the last frame, and reset for the next one.
+    pub fn take_damage(&mut self) -> Option<DirtyRect> {
+        let this = self.damage.take();
+        let region = if self.full_damage {
+            Some(DirtyRect { x: 0, y: 0, w: self.width(), h: self.height() })
+        } else if self.cleared {
+            union_opt(this, self.prev_damage)
+        } else {
+            this
+        };
+        self.prev_damage = this;
+        self.cleared = false;
+        self.full_damage = false;
+        region
     }
 
-    /// Copy pixmap data into a raw RGBA buffer.
-    pub fn copy_to(&self, dst: &mut [u8]) {
+    /// Copy the rows of `region` that changed into a raw RGBA buffer
+    /// (tightly packed, same size as the pixmap). Returns `region`
+    /// narrowed to the changed rows, or `None` if nothing changed.
+    pub fn copy_changed(&self, dst: &mut [u8], region: DirtyRect) -> Option<DirtyRect> {
         let src = self.pixmap.data();
-        let len = dst.len().min(src.len());
-        dst[..len].copy_from_slice(&src[..len]);
+        let row_bytes = self.width() as usize * 4;
+        let (x0, x1) = (region.x as usize * 4, (region.x + region.w) as usize * 4);
+        let mut changed: Option<(u32, u32)> = None;
+
+        for y in region.y..region.y + region.h {
+            let start = y as usize * row_bytes;
+            let (s, d) = (&src[start + x0..start + x1], &mut dst[start + x0..start + x1]);
+            if s != d {
+                d.copy_from_slice(s);
+                changed = Some(changed.map_or((y, y), |(first, _)| (first, y)));
+            }
+        }
+
+        changed.map(|(first, last)| DirtyRect {
+            x: region.x,
+            y: first,
+            w: region.w,
+            h: last - first + 1,
+        })
     }
 
     pub fn width(&self) -> u32 {
@@ -37,27 +235,34 @@ impl Renderer {
     }
 
     pub fn fill_rect(&mut self, x: f32, y: f32, w: f32, h: f32, color: Color) {
+        self.touch(x, y, w, h);
         let mut paint = Paint::default();
         paint.set_color(color.to_skia());
         paint.anti_alias = false;
 
-        let rect = tiny_skia::Rect::from_xywh(x, y, w, h);
-        if let Some(rect) = rect {
+        // A rect clipped to a rect is just the overlap; no mask needed
+        let c = self.clip_rect();
+        let (x0, y0) = (x.max(c.x as f32), y.max(c.y as f32));
+        let x1 = (x + w).min((c.x + c.w) as f32);
+        let y1 = (y + h).min((c.y + c.h) as f32);
+        if let Some(rect) = tiny_skia::Rect::from_ltrb(x0, y0, x1, y1) {
             self.pixmap.fill_rect(rect, &paint, Transform::identity(), None);
         }
     }
 
     pub fn fill_rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, radius: f32, color: Color) {
+        self.touch(x, y, w, h);
         let mut paint = Paint::default();
         paint.set_color(color.to_skia());
         paint.anti_alias = true;
 
         if let Some(path) = rounded_rect_path(x, y, w, h, radius) {
-            self.pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), None);
+            self.pixmap.fill_path(&path, &paint, FillRule::Winding, Transform::identity(), clip_mask(&self.clips, &self.mask));
         }
     }
 
     pub fn stroke_rounded_rect(&mut self, x: f32, y: f32, w: f32, h: f32, radius: f32, color: Color, width: f32) {
+        self.touch(x - width, y - width, w + width * 2.0, h + width * 2.0);
         let mut paint = Paint::default();
         paint.set_color(color.to_skia());
         paint.anti_alias = true;
@@ -66,11 +271,12 @@ impl Renderer {
         stroke.width = width;
 
         if let Some(path) = rounded_rect_path(x, y, w, h, radius) {
-            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), clip_mask(&self.clips, &self.mask));
         }
     }
 
     pub fn draw_line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, color: Color, width: f32) {
+        self.touch(x1.min(x2) - width, y1.min(y2) - width, (x2 - x1).abs() + width * 2.0, (y2 - y1).abs() + width * 2.0);
         let mut paint = Paint::default();
         paint.set_color(color.to_skia());
         paint.anti_alias = true;
@@ -83,7 +289,7 @@ impl Renderer {
         pb.move_to(x1, y1);
         pb.line_to(x2, y2);
         if let Some(path) = pb.finish() {
-            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), clip_mask(&self.clips, &self.mask));
         }
     }
 
@@ -92,6 +298,14 @@ impl Renderer {
         if points.len() < 2 {
             return;
         }
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
+        for &(x, y) in points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        self.touch(min_x - width, min_y - width, max_x - min_x + width * 2.0, max_y - min_y + width * 2.0);
         let mut paint = Paint::default();
         paint.set_color(color.to_skia());
         paint.anti_alias = true;
@@ -106,7 +320,7 @@ impl Renderer {
             pb.line_to(x, y);
         }
         if let Some(path) = pb.finish() {
-            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), None);
+            self.pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), clip_mask(&self.clips, &self.mask));
         }
     }
 
@@ -115,6 +329,29 @@ impl Renderer {
         self.fill_rounded_rect(x, y, w, h, h / 2.0, color);
     }
 
+    /// Composite an image at its natural size.
+    #[allow(dead_code)] // The splash logo is scaled; nothing else draws images yet
+    pub fn draw_image(&mut self, image: &Image, x: f32, y: f32) {
+        self.draw_image_scaled(image, x, y, image.width() as f32, image.height() as f32, 1.0);
+    }
+
+    /// Composite an image stretched to `w`x`h` (nearest-neighbor) at `opacity` 0.0–1.0.
+    pub fn draw_image_scaled(&mut self, image: &Image, x: f32, y: f32, w: f32, h: f32, opacity: f32) {
+        if w <= 0.0 || h <= 0.0 || opacity <= 0.0 {
+            return;
+        }
+        self.touch(x, y, w, h);
+        let paint = PixmapPaint {
+            opacity: opacity.min(1.0),
+            quality: FilterQuality::Nearest,
+            ..PixmapPaint::default()
+        };
+        let sx = w / image.width() as f32;
+        let sy = h / image.height() as f32;
+        let transform = Transform::from_row(sx, 0.0, 0.0, sy, x, y);
+        self.pixmap.draw_pixmap(0, 0, image.pixmap.as_ref(), &paint, transform, clip_mask(&self.clips, &self.mask));
+    }
+
     /// Horizontal gradient rect.
     pub fn fill_gradient_h(&mut self, x: f32, y: f32, w: f32, h: f32, from: Color, to: Color) {
         // Approximate with thin vertical strips
@@ -128,18 +365,81 @@ impl Renderer {
     }
 }
 
+/// Cubic control-point offset that makes a quarter curve a circular arc.
+const ARC_KAPPA: f32 = 0.552_284_8;
+
 fn rounded_rect_path(x: f32, y: f32, w: f32, h: f32, r: f32) -> Option<tiny_skia::Path> {
-    let r = r.min(w / 2.0).min(h / 2.0);
+    let r = r.min(w / 2.0).min(h / 2.0).max(0.0);
+    let k = r * (1.0 - ARC_KAPPA);
     let mut pb = PathBuilder::new();
     pb.move_to(x + r, y);
     pb.line_to(x + w - r, y);
-    pb.quad_to(x + w, y, x + w, y + r);
+    pb.cubic_to(x + w - k, y, x + w, y + k, x + w, y + r);
     pb.line_to(x + w, y + h - r);
-    pb.quad_to(x + w, y + h, x + w - r, y + h);
+    pb.cubic_to(x + w, y + h - k, x + w - k, y + h, x + w - r, y + h);
     pb.line_to(x + r, y + h);
-    pb.quad_to(x, y + h, x, y + h - r);
+    pb.cubic_to(x + k, y + h, x, y + h - k, x, y + h - r);
     pb.line_to(x, y + r);
-    pb.quad_to(x, y, x + r, y);
+    pb.cubic_to(x, y + k, x + k, y, x + r, y);
     pb.close();
     pb.finish()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alpha_at(r: &Renderer, x: u32, y: u32) -> u8 {
+        r.pixmap.pixel(x, y).unwrap().alpha()
+    }
+
+    #[test]
+    fn nested_clips_reuse_one_mask() {
+        let mut r = Renderer::new(8, 8);
+        r.push_clip(0.0, 0.0, 6.0, 6.0);
+        let mask = r.mask.as_ref().unwrap().data().as_ptr();
+        r.push_clip(2.0, 2.0, 8.0, 8.0);
+        assert_eq!(r.clip_rect(), DirtyRect { x: 2, y: 2, w: 4, h: 4 });
+        assert_eq!(r.mask.as_ref().unwrap().data().as_ptr(), mask);
+
+        // Only the intersection is painted in the mask
+        let data = r.mask.as_ref().unwrap().data();
+        assert_eq!(data[8 + 1], 0);
+        assert_eq!(data[2 * 8 + 2], 255);
+        assert_eq!(data[5 * 8 + 5], 255);
+        assert_eq!(data[6 * 8 + 6], 0);
+
+        r.pop_clip();
+        let data = r.mask.as_ref().unwrap().data();
+        assert_eq!(data[8 + 1], 255);
+        r.pop_clip();
+        assert!(clip_mask(&r.clips, &r.mask).is_none());
+    }
+
+    #[test]
+    fn clipped_draws_stay_inside_the_clip() {
+        let white = Color { r: 255, g: 255, b: 255, a: 255 };
+        let mut r = Renderer::new(8, 8);
+        r.push_clip(2.0, 2.0, 3.0, 3.0);
+        r.fill_rect(0.0, 0.0, 8.0, 8.0, white);
+        r.fill_rounded_rect(0.0, 0.0, 8.0, 8.0, 0.0, white);
+        r.pop_clip();
+        assert_eq!(alpha_at(&r, 1, 1), 0);
+        assert_eq!(alpha_at(&r, 2, 2), 255);
+        assert_eq!(alpha_at(&r, 4, 4), 255);
+        assert_eq!(alpha_at(&r, 5, 5), 0);
+    }
+
+    #[test]
+    fn images_draw_at_their_natural_size() {
+        let mut pixmap = tiny_skia::Pixmap::new(2, 2).unwrap();
+        pixmap.fill(tiny_skia::Color::WHITE);
+        let image = Image { pixmap };
+        let mut r = Renderer::new(8, 8);
+        r.draw_image(&image, 3.0, 3.0);
+        assert_eq!(alpha_at(&r, 2, 2), 0);
+        assert_eq!(alpha_at(&r, 3, 3), 255);
+        assert_eq!(alpha_at(&r, 4, 4), 255);
+        assert_eq!(alpha_at(&r, 5, 5), 0);
+    }
+}
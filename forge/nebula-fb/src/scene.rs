@@ -3,6 +3,7 @@
 use crate::input::InputEvent;
 use crate::renderer::Renderer;
 use crate::text::TextRenderer;
+use crate::theme;
 
 /// Transition instruction returned by scenes.
 pub enum Transition {
@@ -20,6 +21,12 @@ pub trait Scene {
     fn draw(&self, renderer: &mut Renderer, text: &TextRenderer);
     /// Handle an input event.
     fn handle_input(&mut self, event: InputEvent) -> Transition;
+    /// Modal scenes are drawn over the (dimmed) scene beneath them.
+    fn is_modal(&self) -> bool {
+        false
+    }
+    /// Called when a scene pushed over this one is popped.
+    fn resume(&mut self) {}
 }
 
 /// Manages a stack of scenes.
@@ -43,8 +50,14 @@ impl SceneManager {
         self.apply(transition);
     }
 
+    /// Draw the top scene, and under a modal every scene down to the first opaque one.
     pub fn draw(&self, renderer: &mut Renderer, text: &TextRenderer) {
-        if let Some(scene) = self.stack.last() {
+        let base = self.stack.iter().rposition(|s| !s.is_modal()).unwrap_or(0);
+        let (w, h) = (renderer.width() as f32, renderer.height() as f32);
+        for scene in self.stack.iter().skip(base) {
+            if scene.is_modal() {
+                renderer.fill_rect(0.0, 0.0, w, h, theme::SCRIM);
+            }
             scene.draw(renderer, text);
         }
     }
@@ -58,6 +71,7 @@ impl SceneManager {
         self.apply(transition);
     }
 
+    /// True once every scene, modal or not, has been popped.
     pub fn is_empty(&self) -> bool {
         self.stack.is_empty()
     }
@@ -72,6 +86,9 @@ impl SceneManager {
             }
             Transition::Pop => {
                 self.stack.pop();
+                if let Some(scene) = self.stack.last_mut() {
+                    scene.resume();
+                }
             }
         }
     }
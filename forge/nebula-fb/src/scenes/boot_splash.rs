@@ -1,51 +1,168 @@
-/// Boot splash — "AetherOS" fades in, then transitions to setup or dashboard.
+/// Boot splash — "AetherOS" fades in while the stack daemons come up, then
+/// transitions to setup or dashboard.
 
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::image::{self, Image};
 use crate::input::InputEvent;
+use crate::profile;
 use crate::renderer::Renderer;
 use crate::scene::{Scene, Transition};
+use crate::services::{self, Service};
 use crate::text::TextRenderer;
 use crate::theme;
+use crate::widgets::progress;
 
+/// Shortest the splash stays up, even with every service already running.
 const SPLASH_DURATION: f32 = 2.5;
 const FADE_IN_DURATION: f32 = 1.0;
-const SETUP_FILE: &str = "/tmp/aether_setup.json";
+const LOGO_SIZE: f32 = 96.0;
+/// Give up waiting on required services after this long and start degraded.
+const BOOT_TIMEOUT: f32 = 20.0;
+/// How long the degraded banner shows before moving on.
+const DEGRADED_HOLD: f32 = 3.0;
+/// Pause between health checks of a service that isn't up yet.
+const RETRY_INTERVAL: Duration = Duration::from_millis(500);
+/// How fast the bar catches up with readiness, in fractions per second.
+const PROGRESS_SPEED: f32 = 2.0;
+const BAR_WIDTH: f32 = 320.0;
 
 pub struct BootSplash {
     elapsed: f32,
     screen_width: u32,
     screen_height: u32,
+    logo: Option<Image>,
+    services: Vec<Service>,
+    ready: Vec<bool>,
+    /// Indices into `services` as each one answers its health check.
+    ready_rx: Receiver<usize>,
+    /// Tells the health-check threads to give up; set once the splash is gone.
+    stop: Arc<AtomicBool>,
+    /// Bar fill, easing towards the share of services that are up.
+    progress: f32,
+    /// Required services still down at the timeout, and when it hit.
+    degraded: Option<(Vec<&'static str>, f32)>,
+    skipped: bool,
 }
 
 impl BootSplash {
     pub fn new(screen_width: u32, screen_height: u32) -> Self {
+        let logo = Image::from_png(image::AETHER_LOGO_PNG)
+            .map_err(|e| eprintln!("[nebula-fb] Boot logo: {e}"))
+            .ok();
+        let services = services::boot_services();
+        let stop = Arc::new(AtomicBool::new(false));
+        let ready_rx = watch(&services, &stop);
         Self {
             elapsed: 0.0,
             screen_width,
             screen_height,
+            logo,
+            ready: vec![false; services.len()],
+            services,
+            ready_rx,
+            stop,
+            progress: 0.0,
+            degraded: None,
+            skipped: false,
+        }
+    }
+
+    /// The first service not up yet, required ones before optional ones.
+    fn waiting_on(&self) -> Option<&Service> {
+        let mut down = self.services.iter().zip(&self.ready).filter(|(_, up)| !**up).map(|(s, _)| s);
+        let first = down.clone().next();
+        down.find(|s| s.required).or(first)
+    }
+
+    fn required_ready(&self) -> bool {
+        self.services.iter().zip(&self.ready).all(|(s, up)| *up || !s.required)
+    }
+
+    fn next_scene(&self) -> Transition {
+        if Self::is_first_boot() {
+            Transition::Replace(Box::new(
+                super::setup::SetupWizard::new(self.screen_width, self.screen_height),
+            ))
+        } else {
+            Transition::Replace(Box::new(
+                super::dashboard::Dashboard::new(self.screen_width, self.screen_height),
+            ))
         }
     }
 
+    /// No valid saved setup, so the wizard has to run.
     fn is_first_boot() -> bool {
-        !std::path::Path::new(SETUP_FILE).exists()
+        profile::load().is_none()
+    }
+}
+
+/// Health-check each service on its own thread until it's up, the boot
+/// timeout passes or `stop` is set, sending its index once it answers.
+fn watch(services: &[Service], stop: &Arc<AtomicBool>) -> Receiver<usize> {
+    let (tx, rx) = mpsc::channel();
+    for (i, service) in services.iter().enumerate() {
+        let tx = tx.clone();
+        let target = service.target.clone();
+        let stop = Arc::clone(stop);
+        std::thread::spawn(move || {
+            let deadline = Instant::now() + Duration::from_secs_f32(BOOT_TIMEOUT);
+            while Instant::now() < deadline && !stop.load(Ordering::Relaxed) {
+                if services::is_up(&target) {
+                    let _ = tx.send(i);
+                    return;
+                }
+                std::thread::sleep(RETRY_INTERVAL);
+            }
+        });
+    }
+    rx
+}
+
+impl Drop for BootSplash {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
     }
 }
 
 impl Scene for BootSplash {
     fn update(&mut self, dt: f32) -> Transition {
         self.elapsed += dt;
-        if self.elapsed >= SPLASH_DURATION {
-            if Self::is_first_boot() {
-                Transition::Replace(Box::new(
-                    super::setup::SetupWizard::new(self.screen_width, self.screen_height),
-                ))
-            } else {
-                Transition::Replace(Box::new(
-                    super::dashboard::Dashboard::new(self.screen_width, self.screen_height),
-                ))
+        for i in self.ready_rx.try_iter() {
+            self.ready[i] = true;
+        }
+        let up = self.ready.iter().filter(|r| **r).count() as f32 / self.ready.len().max(1) as f32;
+        self.progress = (self.progress + PROGRESS_SPEED * dt).min(up);
+
+        if self.skipped {
+            return self.next_scene();
+        }
+        if let Some((_, at)) = self.degraded {
+            if self.elapsed >= at + DEGRADED_HOLD {
+                return self.next_scene();
             }
-        } else {
-            Transition::None
+            return Transition::None;
         }
+        if self.required_ready() {
+            // The bar fills before the fade-out starts
+            if self.elapsed >= SPLASH_DURATION && self.progress >= up {
+                return self.next_scene();
+            }
+        } else if self.elapsed >= BOOT_TIMEOUT {
+            let down: Vec<&'static str> = self
+                .services
+                .iter()
+                .zip(&self.ready)
+                .filter(|(s, up)| s.required && !**up)
+                .map(|(s, _)| s.name)
+                .collect();
+            eprintln!("[nebula-fb] Starting degraded: {} not responding", down.join(", "));
+            self.degraded = Some((down, self.elapsed));
+        }
+        Transition::None
     }
 
     fn draw(&self, renderer: &mut Renderer, text: &TextRenderer) {
@@ -61,6 +178,12 @@ impl Scene for BootSplash {
         );
         let cy = self.screen_height as f32 / 2.0;
 
+        // Logo above the title
+        if let Some(ref logo) = self.logo {
+            let x = (self.screen_width as f32 - LOGO_SIZE) / 2.0;
+            renderer.draw_image_scaled(logo, x, cy - 60.0 - LOGO_SIZE, LOGO_SIZE, LOGO_SIZE, alpha);
+        }
+
         // "AetherOS" — large centered title
         text.draw_centered(
             renderer,
@@ -72,7 +195,7 @@ impl Scene for BootSplash {
             title_color,
         );
 
-        // "Initializing..." below
+        // Service progress and what it's waiting on, below
         if self.elapsed > 0.5 {
             let sub_alpha = ((self.elapsed - 0.5) / FADE_IN_DURATION).clamp(0.0, 1.0);
             let c = theme::Color::rgba(
@@ -81,21 +204,45 @@ impl Scene for BootSplash {
                 theme::TEXT_MUTED.b,
                 (sub_alpha * 255.0) as u8,
             );
+            let status = match self.waiting_on() {
+                Some(service) if self.degraded.is_none() => format!("Waiting for {}...", service.name),
+                _ => "Initializing...".to_string(),
+            };
+            progress::draw_progress_animated(
+                renderer,
+                (self.screen_width as f32 - BAR_WIDTH) / 2.0,
+                cy + 30.0,
+                BAR_WIDTH,
+                6.0,
+                self.progress,
+                self.elapsed,
+            );
             text.draw_centered(
                 renderer,
-                "Initializing...",
+                &status,
                 0.0,
-                cy + 30.0,
+                cy + 48.0,
                 self.screen_width as f32,
                 theme::FONT_SIZE_BODY,
                 c,
             );
         }
+
+        // Degraded banner
+        if let Some((ref down, _)) = self.degraded {
+            let msg = format!("{} not responding \u{2014} starting in degraded mode", down.join(", "));
+            let w = text.measure(&msg, theme::FONT_SIZE_SMALL) + 40.0;
+            let x = (self.screen_width as f32 - w) / 2.0;
+            let y = cy + 90.0;
+            renderer.fill_rounded_rect(x, y, w, 32.0, 8.0, theme::CARD);
+            renderer.stroke_rounded_rect(x, y, w, 32.0, 8.0, theme::ACCENT_RED, 1.0);
+            text.draw_centered(renderer, &msg, x, y + 9.0, w, theme::FONT_SIZE_SMALL, theme::ACCENT_RED);
+        }
     }
 
     fn handle_input(&mut self, _event: InputEvent) -> Transition {
-        // Skip splash on any key
-        self.elapsed = SPLASH_DURATION;
+        // Skip splash, and the wait for services, on any key
+        self.skipped = true;
         Transition::None
     }
 }
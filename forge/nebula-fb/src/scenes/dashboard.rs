@@ -1,9 +1,13 @@
 /// Dashboard — generative card layout from brain server.
 /// Status bar (top), greeting + cards (middle), omnibar (bottom).
 
-use crate::brain_client;
+use std::cell::Cell;
+use std::sync::mpsc;
+
+use crate::brain_client::{self, BrainResponse, DashboardResponse};
 use crate::input::InputEvent;
 use crate::layout;
+use crate::profile;
 use crate::renderer::Renderer;
 use crate::scene::{Scene, Transition};
 use crate::telemetry;
@@ -13,9 +17,21 @@ use crate::widgets::card::{self, CardData};
 use crate::widgets::status_bar;
 use crate::widgets::text_input::{self, TextInputState};
 
-const SETUP_FILE: &str = "/tmp/aether_setup.json";
 const TELEMETRY_INTERVAL_SECS: f32 = 5.0;
 const DASHBOARD_REFRESH_SECS: f32 = 120.0;
+const RESPONSE_BOX_HEIGHT: f32 = 200.0;
+const RESPONSE_LINE_HEIGHT: f32 = 22.0;
+/// Response lines that fit in the box below its top padding.
+const RESPONSE_VISIBLE_LINES: usize = ((RESPONSE_BOX_HEIGHT - 24.0) / RESPONSE_LINE_HEIGHT) as usize;
+const WHEEL_LINES: i32 = 3;
+/// Omnibar entries that open Preferences instead of asking the brain.
+const PREFERENCES_COMMANDS: &[&str] = &["settings", "preferences", "prefs"];
+
+/// Result of a brain call made on a worker thread, tagged with its request id.
+enum BrainReply {
+    Dashboard(u64, Result<DashboardResponse, String>),
+    Query(u64, Result<BrainResponse, String>),
+}
 
 pub struct Dashboard {
     screen_width: u32,
@@ -32,7 +48,16 @@ pub struct Dashboard {
     user_name: String,
     user_interests: Vec<String>,
     response_text: Option<String>,
-    loading: bool,
+    /// First response line shown.
+    response_scroll: usize,
+    /// Wrapped line count of the response, measured at draw time.
+    response_lines: Cell<usize>,
+    brain_tx: mpsc::Sender<BrainReply>,
+    brain_rx: mpsc::Receiver<BrainReply>,
+    next_request: u64,
+    /// In-flight requests; replies with any other id are stale and dropped.
+    pending_refresh: Option<u64>,
+    pending_query: Option<u64>,
 }
 
 impl Dashboard {
@@ -45,17 +70,7 @@ impl Dashboard {
         let t = telemetry::read_telemetry();
         history.push(t.clone());
 
-        // Determine greeting from time of day
-        let hour = chrono::Local::now().hour();
-        let tod = if hour < 12 {
-            "morning"
-        } else if hour < 17 {
-            "afternoon"
-        } else {
-            "evening"
-        };
-
-        let greeting = format!("Good {}, {}.", tod, name);
+        let greeting = greeting_for(&name);
         let subtitle = "Here's what I found for you today.".to_string();
 
         // Default cards (will be replaced by brain response)
@@ -68,21 +83,30 @@ impl Dashboard {
                     cpu: t.cpu_percent,
                     mem: t.mem_used_pct(),
                 }),
-                temp: None,
-                desc: None,
-                wind: None,
+                lines: vec![],
+                weather: None,
+                series: vec![],
+                metric: None,
+                value: None,
+                label: None,
             },
+            chart_card("CPU History", "cpu", history.cpu_history()),
+            chart_card("Memory History", "mem", history.mem_pct_history()),
             CardData {
                 card_type: "text".to_string(),
                 title: "Welcome".to_string(),
                 body: Some("AetherOS is running. Ask me anything in the omnibar below.".to_string()),
                 metrics: None,
-                temp: None,
-                desc: None,
-                wind: None,
+                lines: vec![],
+                weather: None,
+                series: vec![],
+                metric: None,
+                value: None,
+                label: None,
             },
         ];
 
+        let (brain_tx, brain_rx) = mpsc::channel();
         let mut dash = Self {
             screen_width,
             screen_height,
@@ -98,66 +122,144 @@ impl Dashboard {
             user_name: name,
             user_interests: interests,
             response_text: None,
-            loading: false,
+            response_scroll: 0,
+            response_lines: Cell::new(0),
+            brain_tx,
+            brain_rx,
+            next_request: 0,
+            pending_refresh: None,
+            pending_query: None,
         };
 
-        // Try to fetch initial dashboard from brain (non-blocking attempt)
+        // Fetch the initial dashboard in the background
         dash.try_refresh_dashboard();
 
         dash
     }
 
+    fn request_id(&mut self) -> u64 {
+        self.next_request += 1;
+        self.next_request
+    }
+
+    fn loading(&self) -> bool {
+        self.pending_query.is_some()
+    }
+
+    /// Ask the brain for a fresh layout on a worker thread; `update` applies it.
     fn try_refresh_dashboard(&mut self) {
+        let id = self.request_id();
+        self.pending_refresh = Some(id);
+        self.last_dashboard_refresh = self.elapsed;
+
         let t = self.telemetry.latest().cloned().unwrap_or_default();
-        match brain_client::query_brain_dashboard(
-            &self.user_name,
-            &self.user_interests,
-            t.cpu_percent,
-            t.mem_used_pct(),
-            &t.uptime_str(),
-        ) {
-            Ok(resp) => {
-                if !resp.greeting.is_empty() {
-                    self.greeting = resp.greeting;
-                }
-                if !resp.subtitle.is_empty() {
-                    self.subtitle = resp.subtitle;
-                }
-                // Parse cards from JSON
-                let mut new_cards = Vec::new();
-                for card_val in &resp.cards {
-                    if let Ok(cd) = serde_json::from_value::<CardData>(card_val.clone()) {
-                        new_cards.push(cd);
+        let name = self.user_name.clone();
+        let interests = self.user_interests.clone();
+        let tx = self.brain_tx.clone();
+        std::thread::spawn(move || {
+            let result = brain_client::query_brain_dashboard(
+                &name,
+                &interests,
+                t.cpu_percent,
+                t.mem_used_pct(),
+                &t.uptime_str(),
+            );
+            let _ = tx.send(BrainReply::Dashboard(id, result));
+        });
+    }
+
+    fn apply_dashboard(&mut self, resp: DashboardResponse) {
+        if !resp.greeting.is_empty() {
+            self.greeting = resp.greeting;
+        }
+        if !resp.subtitle.is_empty() {
+            self.subtitle = resp.subtitle;
+        }
+        // Parse cards from JSON
+        let mut new_cards = Vec::new();
+        for card_val in &resp.cards {
+            if let Ok(cd) = serde_json::from_value::<CardData>(card_val.clone()) {
+                new_cards.push(cd);
+            }
+        }
+        if !new_cards.is_empty() {
+            self.cards = merge_cards(std::mem::take(&mut self.cards), new_cards);
+            self.selected_card = self.selected_card.min(self.cards.len() - 1);
+        }
+    }
+
+    /// Apply any replies that arrived since the last frame.
+    fn poll_brain(&mut self) {
+        while let Ok(reply) = self.brain_rx.try_recv() {
+            match reply {
+                BrainReply::Dashboard(id, result) if self.pending_refresh == Some(id) => {
+                    self.pending_refresh = None;
+                    match result {
+                        Ok(resp) => self.apply_dashboard(resp),
+                        Err(e) => eprintln!("[dashboard] Brain dashboard error: {}", e),
                     }
                 }
-                if !new_cards.is_empty() {
-                    self.cards = new_cards;
+                BrainReply::Query(id, result) if self.pending_query == Some(id) => {
+                    self.pending_query = None;
+                    self.response_scroll = 0;
+                    self.response_text = Some(match result {
+                        Ok(resp) => resp.text,
+                        Err(e) => format!("Error: {}", e),
+                    });
                 }
-            }
-            Err(e) => {
-                eprintln!("[dashboard] Brain dashboard error: {}", e);
+                _ => {}
             }
         }
-        self.last_dashboard_refresh = self.elapsed;
     }
 
-    fn submit_query(&mut self) {
+    fn max_response_scroll(&self) -> usize {
+        self.response_lines.get().saturating_sub(RESPONSE_VISIBLE_LINES)
+    }
+
+    fn scroll_response(&mut self, lines: i32) {
+        let target = self.response_scroll as i64 + lines as i64;
+        self.response_scroll = target.clamp(0, self.max_response_scroll() as i64) as usize;
+    }
+
+    fn open_preferences(&mut self) -> Transition {
+        self.omnibar.clear();
+        Transition::Push(Box::new(super::preferences::Preferences::new(
+            self.screen_width,
+            self.screen_height,
+        )))
+    }
+
+    /// Index of the card under a screen position, if cards are showing.
+    fn card_at(&self, x: i32, y: i32) -> Option<usize> {
+        if self.response_text.is_some() || self.loading() {
+            return None;
+        }
+        let card_top = theme::STATUS_BAR_HEIGHT + 90;
+        let card_bottom = self.screen_height - theme::OMNIBAR_HEIGHT - 20;
+        let slots = layout::card_grid(self.screen_width, card_top, card_bottom, self.cards.len());
+        let (x, y) = (x as f32, y as f32);
+        slots
+            .iter()
+            .position(|s| x >= s.x && x < s.x + s.w && y >= s.y && y < s.y + s.h)
+    }
+
+    fn submit_query(&mut self) -> Transition {
         let query = self.omnibar.take_text();
         if query.is_empty() {
-            return;
+            return Transition::None;
         }
-
-        self.loading = true;
-        match brain_client::query_brain(&query) {
-            Ok(resp) => {
-                self.response_text = Some(resp.text);
-                self.loading = false;
-            }
-            Err(e) => {
-                self.response_text = Some(format!("Error: {}", e));
-                self.loading = false;
-            }
+        if PREFERENCES_COMMANDS.contains(&query.trim().to_lowercase().as_str()) {
+            return self.open_preferences();
         }
+
+        let id = self.request_id();
+        self.pending_query = Some(id);
+        self.response_text = None;
+        let tx = self.brain_tx.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(BrainReply::Query(id, brain_client::query_brain(&query)));
+        });
+        Transition::None
     }
 }
 
@@ -166,6 +268,7 @@ use chrono::Timelike;
 impl Scene for Dashboard {
     fn update(&mut self, dt: f32) -> Transition {
         self.elapsed += dt;
+        self.poll_brain();
 
         // Refresh telemetry periodically
         if self.elapsed - self.last_telemetry >= TELEMETRY_INTERVAL_SECS {
@@ -173,13 +276,18 @@ impl Scene for Dashboard {
             self.telemetry.push(t.clone());
             self.last_telemetry = self.elapsed;
 
-            // Update system card metrics
+            // Update system card metrics and live charts
             for card in &mut self.cards {
-                if card.card_type == "system" {
-                    card.metrics = Some(card::CardMetrics {
-                        cpu: t.cpu_percent,
-                        mem: t.mem_used_pct(),
-                    });
+                match (card.card_type.as_str(), card.metric.as_deref()) {
+                    ("system", _) => {
+                        card.metrics = Some(card::CardMetrics {
+                            cpu: t.cpu_percent,
+                            mem: t.mem_used_pct(),
+                        });
+                    }
+                    ("chart", Some("cpu")) => card.series = self.telemetry.cpu_history(),
+                    ("chart", Some("mem")) => card.series = self.telemetry.mem_pct_history(),
+                    _ => {}
                 }
             }
         }
@@ -207,7 +315,7 @@ impl Scene for Dashboard {
             &status_bar::StatusBarData {
                 cpu_pct: t.cpu_percent,
                 mem_pct: t.mem_used_pct(),
-                net_status: t.ip_addr.clone(),
+                net_up: t.net_up(),
                 time_str,
             },
             w,
@@ -239,42 +347,53 @@ impl Scene for Dashboard {
         // If we have a response, show it instead of cards
         if let Some(ref resp) = self.response_text {
             let resp_y = card_top as f32 + 16.0;
+            let box_x = theme::CONTENT_MARGIN as f32;
             let max_w = w as f32 - theme::CONTENT_MARGIN as f32 * 2.0;
-            renderer.fill_rounded_rect(
-                theme::CONTENT_MARGIN as f32,
-                resp_y - 8.0,
-                max_w,
-                200.0,
-                theme::CARD_RADIUS,
-                theme::CARD,
-            );
+            renderer.fill_rounded_rect(box_x, resp_y - 8.0, max_w, RESPONSE_BOX_HEIGHT, theme::CARD_RADIUS, theme::CARD);
             renderer.stroke_rounded_rect(
-                theme::CONTENT_MARGIN as f32,
+                box_x,
                 resp_y - 8.0,
                 max_w,
-                200.0,
+                RESPONSE_BOX_HEIGHT,
                 theme::CARD_RADIUS,
                 theme::CARD_BORDER,
                 1.0,
             );
-            text.draw_wrapped(
-                renderer,
-                resp,
-                theme::CONTENT_MARGIN as f32 + 16.0,
-                resp_y + 8.0,
-                max_w - 32.0,
-                theme::FONT_SIZE_BODY,
-                22.0,
-                theme::TEXT_PRIMARY,
-            );
-        } else if self.loading {
+
+            let lines = text.wrap(resp, theme::FONT_SIZE_BODY, max_w - 40.0);
+            self.response_lines.set(lines.len());
+            let scroll = self.response_scroll.min(self.max_response_scroll());
+
+            // Keep long responses inside the box
+            renderer.push_clip(box_x, resp_y, max_w, RESPONSE_BOX_HEIGHT - 16.0);
+            for (i, line) in lines.iter().skip(scroll).take(RESPONSE_VISIBLE_LINES + 1).enumerate() {
+                let ly = resp_y + 8.0 + i as f32 * RESPONSE_LINE_HEIGHT;
+                text.draw(renderer, line, box_x + 16.0, ly, theme::FONT_SIZE_BODY, theme::TEXT_PRIMARY);
+            }
+            renderer.pop_clip();
+
+            // Scrollbar when the response overflows
+            if lines.len() > RESPONSE_VISIBLE_LINES {
+                let track_x = box_x + max_w - 10.0;
+                let track_y = resp_y;
+                let track_h = RESPONSE_BOX_HEIGHT - 16.0;
+                let thumb_h = (track_h * RESPONSE_VISIBLE_LINES as f32 / lines.len() as f32).max(16.0);
+                let max_scroll = self.max_response_scroll().max(1) as f32;
+                let thumb_y = track_y + (track_h - thumb_h) * scroll as f32 / max_scroll;
+                renderer.fill_rounded_rect(track_x, track_y, 4.0, track_h, 2.0, theme::SURFACE);
+                renderer.fill_rounded_rect(track_x, thumb_y, 4.0, thumb_h, 2.0, theme::TEXT_MUTED);
+            }
+        } else if self.loading() {
+            let cy = (card_top + card_bottom) as f32 / 2.0;
+            draw_spinner(renderer, w as f32 / 2.0, cy - 36.0, self.elapsed);
+            text.draw_centered(renderer, "Thinking...", 0.0, cy, w as f32, theme::FONT_SIZE_BODY, theme::TEXT_MUTED);
             text.draw_centered(
                 renderer,
-                "Thinking...",
+                "Esc to cancel",
                 0.0,
-                (card_top + card_bottom) as f32 / 2.0,
+                cy + 28.0,
                 w as f32,
-                theme::FONT_SIZE_BODY,
+                theme::FONT_SIZE_SMALL,
                 theme::TEXT_MUTED,
             );
         } else {
@@ -299,6 +418,12 @@ impl Scene for Dashboard {
 
     fn handle_input(&mut self, event: InputEvent) -> Transition {
         match event {
+            InputEvent::Char('?') if self.omnibar.text.is_empty() => {
+                return Transition::Push(Box::new(super::help::HelpOverlay::new(
+                    self.screen_width,
+                    self.screen_height,
+                )));
+            }
             InputEvent::Char(ch) => {
                 self.omnibar.insert_char(ch);
                 self.response_text = None; // Clear response on new input
@@ -307,7 +432,10 @@ impl Scene for Dashboard {
                 self.omnibar.backspace();
             }
             InputEvent::Enter => {
-                self.submit_query();
+                return self.submit_query();
+            }
+            InputEvent::Ctrl('p') => {
+                return self.open_preferences();
             }
             InputEvent::Left => {
                 self.omnibar.move_left();
@@ -315,6 +443,14 @@ impl Scene for Dashboard {
             InputEvent::Right => {
                 self.omnibar.move_right();
             }
+            InputEvent::Up if self.response_text.is_some() => self.scroll_response(-1),
+            InputEvent::Down if self.response_text.is_some() => self.scroll_response(1),
+            InputEvent::PageUp => self.scroll_response(-(RESPONSE_VISIBLE_LINES as i32)),
+            InputEvent::PageDown => self.scroll_response(RESPONSE_VISIBLE_LINES as i32),
+            InputEvent::Scroll { dy, .. } if self.response_text.is_some() => {
+                // Wheel away from the user scrolls back toward the top
+                self.scroll_response(-dy * WHEEL_LINES);
+            }
             InputEvent::Up => {
                 if self.selected_card > 0 {
                     self.selected_card -= 1;
@@ -329,37 +465,164 @@ impl Scene for Dashboard {
                 self.response_text = None; // Clear response, show cards again
             }
             InputEvent::Escape => {
+                // Drop an in-flight query; its reply will be ignored
+                self.pending_query = None;
+                self.response_text = None;
+            }
+            InputEvent::Ctrl('u') => {
+                self.omnibar.clear();
+            }
+            InputEvent::Ctrl('l') => {
                 self.response_text = None;
             }
+            InputEvent::Scroll { dx, dy } => {
+                // Wheel down or right moves to later cards
+                let steps = dx - dy;
+                let last = self.cards.len().saturating_sub(1);
+                self.selected_card = if steps < 0 {
+                    self.selected_card.saturating_sub(steps.unsigned_abs() as usize)
+                } else {
+                    (self.selected_card + steps as usize).min(last)
+                };
+            }
+            InputEvent::MouseMove { x, y } => {
+                if let Some(i) = self.card_at(x, y) {
+                    self.selected_card = i;
+                }
+            }
+            InputEvent::Mouse { x, y, button } => {
+                if let Some(i) = self.card_at(x, y) {
+                    self.selected_card = i;
+                    // Right-click: offer a follow-up question about the card
+                    if button == 2 {
+                        self.omnibar.clear();
+                        for ch in format!("Tell me more about {}", self.cards[i].title).chars() {
+                            self.omnibar.insert_char(ch);
+                        }
+                    }
+                }
+            }
+            InputEvent::Alt('b') => {
+                self.omnibar.move_word_left();
+            }
+            InputEvent::Alt('f') => {
+                self.omnibar.move_word_right();
+            }
             _ => {}
         }
         Transition::None
     }
+
+    /// Pick up edits made in Preferences.
+    fn resume(&mut self) {
+        let (name, interests) = load_setup();
+        if name != self.user_name || interests != self.user_interests {
+            self.greeting = greeting_for(&name);
+            self.user_name = name;
+            self.user_interests = interests;
+            self.try_refresh_dashboard();
+        }
+    }
 }
 
-fn load_setup() -> (String, Vec<String>) {
-    match std::fs::read_to_string(SETUP_FILE) {
-        Ok(data) => {
-            if let Ok(v) = serde_json::from_str::<serde_json::Value>(&data) {
-                let name = v
-                    .get("name")
-                    .and_then(|n| n.as_str())
-                    .unwrap_or("User")
-                    .to_string();
-                let interests: Vec<String> = v
-                    .get("interests")
-                    .and_then(|i| i.as_array())
-                    .map(|arr| {
-                        arr.iter()
-                            .filter_map(|v| v.as_str().map(|s| s.to_string()))
-                            .collect()
-                    })
-                    .unwrap_or_default();
-                (name, interests)
-            } else {
-                ("User".to_string(), vec![])
-            }
+/// Lay the brain's cards into the slots of the previous ones, keeping local
+/// chart cards (those following a telemetry `metric`) where they were. Extra
+/// server cards go at the end; unused slots are dropped.
+fn merge_cards(old: Vec<CardData>, server: Vec<CardData>) -> Vec<CardData> {
+    let mut server = server.into_iter().filter(|c| c.metric.is_none());
+    let mut merged = Vec::new();
+    for card in old {
+        if card.metric.is_some() {
+            merged.push(card);
+        } else if let Some(next) = server.next() {
+            merged.push(next);
         }
-        Err(_) => ("User".to_string(), vec![]),
+    }
+    merged.extend(server);
+    merged
+}
+
+/// A chart card that follows one telemetry metric.
+fn chart_card(title: &str, metric: &str, series: Vec<f64>) -> CardData {
+    CardData {
+        card_type: "chart".to_string(),
+        title: title.to_string(),
+        body: None,
+        metrics: None,
+        lines: vec![],
+        weather: None,
+        series,
+        metric: Some(metric.to_string()),
+        value: None,
+        label: None,
+    }
+}
+
+/// Ring of dots with a bright head that circles once a second.
+fn draw_spinner(renderer: &mut Renderer, cx: f32, cy: f32, time: f32) {
+    const DOTS: usize = 8;
+    let radius = 14.0;
+    let head = (time * DOTS as f32) as usize % DOTS;
+    for i in 0..DOTS {
+        let angle = i as f32 / DOTS as f32 * std::f32::consts::TAU;
+        let (x, y) = (cx + radius * angle.cos(), cy + radius * angle.sin());
+        // Fade out behind the head
+        let age = (head + DOTS - i) % DOTS;
+        let alpha = 255 - (age * 220 / DOTS) as u8;
+        let c = theme::ACCENT_BLUE;
+        renderer.fill_rounded_rect(x - 3.0, y - 3.0, 6.0, 6.0, 3.0, theme::Color::rgba(c.r, c.g, c.b, alpha));
+    }
+}
+
+/// "Good morning, Name." from the local time of day.
+fn greeting_for(name: &str) -> String {
+    let hour = chrono::Local::now().hour();
+    let tod = if hour < 12 {
+        "morning"
+    } else if hour < 17 {
+        "afternoon"
+    } else {
+        "evening"
+    };
+    format!("Good {}, {}.", tod, name)
+}
+
+fn load_setup() -> (String, Vec<String>) {
+    match profile::load() {
+        Some(p) => (p.name, p.interests),
+        None => ("User".to_string(), vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_card(title: &str) -> CardData {
+        serde_json::from_value(serde_json::json!({ "type": "text", "title": title })).unwrap()
+    }
+
+    fn titles(cards: &[CardData]) -> Vec<&str> {
+        cards.iter().map(|c| c.title.as_str()).collect()
+    }
+
+    #[test]
+    fn server_cards_fill_in_around_local_charts() {
+        let old = vec![
+            text_card("System"),
+            chart_card("CPU History", "cpu", vec![]),
+            chart_card("Memory History", "mem", vec![]),
+            text_card("Welcome"),
+        ];
+        let server = vec![text_card("Weather"), text_card("News"), text_card("Tip")];
+        let merged = merge_cards(old, server);
+        assert_eq!(titles(&merged), ["Weather", "CPU History", "Memory History", "News", "Tip"]);
+    }
+
+    #[test]
+    fn fewer_server_cards_drop_the_old_ones() {
+        let old = vec![text_card("System"), chart_card("CPU History", "cpu", vec![]), text_card("Welcome")];
+        let merged = merge_cards(old, vec![text_card("Weather")]);
+        assert_eq!(titles(&merged), ["Weather", "CPU History"]);
     }
 }
@@ -0,0 +1,68 @@
+/// Help overlay — modal panel listing the dashboard's keys; any key dismisses it.
+
+use crate::input::InputEvent;
+use crate::renderer::Renderer;
+use crate::scene::{Scene, Transition};
+use crate::text::TextRenderer;
+use crate::theme;
+
+const KEYS: &[(&str, &str)] = &[
+    ("Enter", "Ask the brain"),
+    ("Up / Down", "Select a card"),
+    ("Esc / Tab", "Back to the cards"),
+    ("Ctrl+U", "Clear the omnibar"),
+    ("Ctrl+L", "Clear the response"),
+    ("Alt+B / Alt+F", "Move by word"),
+    ("Right-click", "Ask about a card"),
+    ("Ctrl+P / settings", "Edit name and interests"),
+    ("?", "This help"),
+];
+
+pub struct HelpOverlay {
+    screen_width: u32,
+    screen_height: u32,
+}
+
+impl HelpOverlay {
+    pub fn new(screen_width: u32, screen_height: u32) -> Self {
+        Self {
+            screen_width,
+            screen_height,
+        }
+    }
+}
+
+impl Scene for HelpOverlay {
+    fn update(&mut self, _dt: f32) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, renderer: &mut Renderer, text: &TextRenderer) {
+        let row_h = 28.0;
+        let panel_w = 480.0f32.min(self.screen_width as f32 - 40.0);
+        let panel_h = 80.0 + KEYS.len() as f32 * row_h;
+        let x = (self.screen_width as f32 - panel_w) / 2.0;
+        let y = (self.screen_height as f32 - panel_h) / 2.0;
+
+        renderer.fill_rounded_rect(x, y, panel_w, panel_h, theme::CARD_RADIUS, theme::SURFACE);
+        renderer.stroke_rounded_rect(x, y, panel_w, panel_h, theme::CARD_RADIUS, theme::CARD_BORDER, 1.0);
+
+        text.draw(renderer, "Keyboard shortcuts", x + 24.0, y + 20.0, theme::FONT_SIZE_HEADING, theme::TEXT_PRIMARY);
+        for (i, (key, action)) in KEYS.iter().enumerate() {
+            let ry = y + 64.0 + i as f32 * row_h;
+            text.draw(renderer, key, x + 24.0, ry, theme::FONT_SIZE_BODY, theme::ACCENT_BLUE);
+            text.draw(renderer, action, x + 200.0, ry, theme::FONT_SIZE_BODY, theme::TEXT_SECONDARY);
+        }
+    }
+
+    fn handle_input(&mut self, event: InputEvent) -> Transition {
+        match event {
+            InputEvent::None | InputEvent::MouseMove { .. } | InputEvent::Scroll { .. } => Transition::None,
+            _ => Transition::Pop,
+        }
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
@@ -1,3 +1,5 @@
 pub mod boot_splash;
 pub mod setup;
 pub mod dashboard;
+pub mod help;
+pub mod preferences;
@@ -0,0 +1,126 @@
+/// Preferences — modal panel to edit the name and interests saved at setup.
+
+use crate::input::InputEvent;
+use crate::profile::{self, Profile};
+use crate::renderer::Renderer;
+use crate::scene::{Scene, Transition};
+use crate::text::TextRenderer;
+use crate::theme;
+use crate::widgets::chip_grid::{ChipGrid, INTEREST_OPTIONS};
+use crate::widgets::text_input::TextInputState;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    Name,
+    Interests,
+}
+
+pub struct Preferences {
+    screen_width: u32,
+    screen_height: u32,
+    section: Section,
+    name: TextInputState,
+    interests: ChipGrid,
+}
+
+impl Preferences {
+    pub fn new(screen_width: u32, screen_height: u32) -> Self {
+        let current = profile::load().unwrap_or_default();
+        let mut name = TextInputState::new("Your name");
+        for ch in current.name.chars() {
+            name.insert_char(ch);
+        }
+        Self {
+            screen_width,
+            screen_height,
+            section: Section::Name,
+            name,
+            interests: ChipGrid::new(INTEREST_OPTIONS).with_selected(&current.interests),
+        }
+    }
+
+    fn save(&self) {
+        let profile = Profile {
+            name: self.name.text.trim().to_string(),
+            interests: self.interests.selected_labels(),
+        };
+        if let Err(e) = profile::save(&profile) {
+            eprintln!("[nebula-fb] Failed to save preferences: {e}");
+        }
+    }
+}
+
+impl Scene for Preferences {
+    fn update(&mut self, _dt: f32) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, renderer: &mut Renderer, text: &TextRenderer) {
+        let panel_w = 780.0f32.min(self.screen_width as f32 - 40.0);
+        let panel_h = 440.0;
+        let x = (self.screen_width as f32 - panel_w) / 2.0;
+        let y = (self.screen_height as f32 - panel_h) / 2.0;
+        let cx = self.screen_width as f32 / 2.0;
+
+        renderer.fill_rounded_rect(x, y, panel_w, panel_h, theme::CARD_RADIUS, theme::SURFACE);
+        renderer.stroke_rounded_rect(x, y, panel_w, panel_h, theme::CARD_RADIUS, theme::CARD_BORDER, 1.0);
+        text.draw(renderer, "Preferences", x + 24.0, y + 20.0, theme::FONT_SIZE_HEADING, theme::TEXT_PRIMARY);
+
+        // Name field
+        let name_focused = self.section == Section::Name;
+        text.draw(renderer, "Name", x + 24.0, y + 72.0, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
+        let (box_x, box_y, box_w, box_h) = (x + 24.0, y + 96.0, 400.0, 44.0);
+        renderer.fill_rounded_rect(box_x, box_y, box_w, box_h, 8.0, theme::BG);
+        let border = if name_focused { theme::ACCENT_BLUE } else { theme::CARD_BORDER };
+        renderer.stroke_rounded_rect(box_x, box_y, box_w, box_h, 8.0, border, if name_focused { 2.0 } else { 1.0 });
+        if self.name.text.is_empty() {
+            text.draw(renderer, &self.name.placeholder, box_x + 16.0, box_y + 12.0, theme::FONT_SIZE_BODY, theme::TEXT_MUTED);
+        } else {
+            text.draw(renderer, &self.name.text, box_x + 16.0, box_y + 12.0, theme::FONT_SIZE_BODY, theme::TEXT_PRIMARY);
+        }
+        if name_focused {
+            let cursor_x = box_x + 16.0 + text.measure(&self.name.text[..self.name.cursor], theme::FONT_SIZE_BODY);
+            renderer.fill_rect(cursor_x, box_y + 10.0, 2.0, 24.0, theme::ACCENT_BLUE);
+        }
+
+        // Interests
+        text.draw(renderer, "Interests", x + 24.0, y + 164.0, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
+        self.interests.draw(renderer, text, cx, y + 192.0, self.section == Section::Interests);
+
+        let hint = match self.section {
+            Section::Name => "Next: Enter/Tab  |  Cancel: Esc",
+            Section::Interests => "Navigate: Arrow keys  |  Select: Enter/Space  |  Save: Tab  |  Back: Esc",
+        };
+        text.draw_centered(renderer, hint, x, y + panel_h - 36.0, panel_w, theme::FONT_SIZE_SMALL, theme::TEXT_MUTED);
+    }
+
+    fn handle_input(&mut self, event: InputEvent) -> Transition {
+        match self.section {
+            Section::Name => match event {
+                InputEvent::Char(ch) if self.name.text.len() < 32 => self.name.insert_char(ch),
+                InputEvent::Backspace => self.name.backspace(),
+                InputEvent::Left => self.name.move_left(),
+                InputEvent::Right => self.name.move_right(),
+                InputEvent::Enter | InputEvent::Tab if !self.name.text.trim().is_empty() => {
+                    self.section = Section::Interests;
+                }
+                InputEvent::Escape => return Transition::Pop,
+                _ => {}
+            },
+            Section::Interests => match event {
+                ref e if self.interests.handle_input(e) => {}
+                InputEvent::Tab => {
+                    self.save();
+                    return Transition::Pop;
+                }
+                InputEvent::Escape => self.section = Section::Name,
+                _ => {}
+            },
+        }
+        Transition::None
+    }
+
+    fn is_modal(&self) -> bool {
+        true
+    }
+}
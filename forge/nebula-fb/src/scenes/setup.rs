@@ -4,28 +4,15 @@
 /// Step 3: Animated progress "Setting up your experience..."
 
 use crate::input::InputEvent;
+use crate::profile::{self, Profile};
 use crate::renderer::Renderer;
 use crate::scene::{Scene, Transition};
 use crate::text::TextRenderer;
 use crate::theme;
 use crate::widgets::button;
+use crate::widgets::chip_grid::{ChipGrid, INTEREST_OPTIONS};
 use crate::widgets::progress;
 
-const SETUP_FILE: &str = "/tmp/aether_setup.json";
-
-const INTEREST_OPTIONS: &[&str] = &[
-    "Technology",
-    "Weather",
-    "Science",
-    "Programming",
-    "News",
-    "Finance",
-    "Music",
-    "Space",
-    "AI",
-    "Health",
-];
-
 #[derive(Clone, Copy, PartialEq)]
 enum Step {
     Name,
@@ -39,8 +26,7 @@ pub struct SetupWizard {
     step: Step,
     name: String,
     cursor: usize,
-    selected_interests: Vec<bool>,
-    interest_cursor: usize,
+    interests: ChipGrid,
     finish_elapsed: f32,
     finish_duration: f32,
 }
@@ -53,28 +39,19 @@ impl SetupWizard {
             step: Step::Name,
             name: String::new(),
             cursor: 0,
-            selected_interests: vec![false; INTEREST_OPTIONS.len()],
-            interest_cursor: 0,
+            interests: ChipGrid::new(INTEREST_OPTIONS),
             finish_elapsed: 0.0,
             finish_duration: 3.0,
         }
     }
 
     fn save_setup(&self) {
-        let interests: Vec<&str> = INTEREST_OPTIONS
-            .iter()
-            .zip(self.selected_interests.iter())
-            .filter(|(_, &sel)| sel)
-            .map(|(&name, _)| name)
-            .collect();
-
-        let data = serde_json::json!({
-            "name": self.name,
-            "interests": interests,
-        });
-
-        if let Ok(json) = serde_json::to_string_pretty(&data) {
-            let _ = std::fs::write(SETUP_FILE, json);
+        let profile = Profile {
+            name: self.name.clone(),
+            interests: self.interests.selected_labels(),
+        };
+        if let Err(e) = profile::save(&profile) {
+            eprintln!("[nebula-fb] Failed to save setup: {e}");
         }
     }
 }
@@ -133,32 +110,10 @@ impl Scene for SetupWizard {
                 text.draw_centered(renderer, "Select topics to personalize your experience.", 0.0, 250.0, w, theme::FONT_SIZE_BODY, theme::TEXT_SECONDARY);
 
                 // Chip grid
-                let grid_w = 700.0;
-                let start_x = cx - grid_w / 2.0;
-                let mut chip_x = start_x;
-                let mut chip_y = 320.0;
-                let gap = 12.0;
-
-                for (i, &label) in INTEREST_OPTIONS.iter().enumerate() {
-                    let is_selected = self.selected_interests[i];
-                    let is_cursor = i == self.interest_cursor;
-
-                    let (cw, ch) = button::draw_chip(renderer, text, label, chip_x, chip_y, is_selected);
-
-                    // Cursor indicator
-                    if is_cursor {
-                        renderer.stroke_rounded_rect(chip_x - 2.0, chip_y - 2.0, cw + 4.0, ch + 4.0, (ch + 4.0) / 2.0, theme::ACCENT_BLUE, 1.5);
-                    }
-
-                    chip_x += cw + gap;
-                    if chip_x + 100.0 > start_x + grid_w {
-                        chip_x = start_x;
-                        chip_y += ch + gap;
-                    }
-                }
+                let chip_y = self.interests.draw(renderer, text, cx, 320.0, true);
 
                 // Continue button
-                let any_selected = self.selected_interests.iter().any(|&s| s);
+                let any_selected = self.interests.any_selected();
                 let btn_y = chip_y + 60.0;
                 let btn_label = "Continue";
                 let btn_w = text.measure(btn_label, theme::FONT_SIZE_BODY) + 24.0;
@@ -259,32 +214,9 @@ impl Scene for SetupWizard {
                 _ => {}
             },
             Step::Interests => match event {
-                InputEvent::Left => {
-                    if self.interest_cursor > 0 {
-                        self.interest_cursor -= 1;
-                    }
-                }
-                InputEvent::Right => {
-                    if self.interest_cursor < INTEREST_OPTIONS.len() - 1 {
-                        self.interest_cursor += 1;
-                    }
-                }
-                InputEvent::Up => {
-                    if self.interest_cursor >= 5 {
-                        self.interest_cursor -= 5;
-                    }
-                }
-                InputEvent::Down => {
-                    if self.interest_cursor + 5 < INTEREST_OPTIONS.len() {
-                        self.interest_cursor += 5;
-                    }
-                }
-                InputEvent::Enter | InputEvent::Char(' ') => {
-                    self.selected_interests[self.interest_cursor] =
-                        !self.selected_interests[self.interest_cursor];
-                }
+                ref e if self.interests.handle_input(e) => {}
                 InputEvent::Tab => {
-                    if self.selected_interests.iter().any(|&s| s) {
+                    if self.interests.any_selected() {
                         self.step = Step::Finishing;
                     }
                 }
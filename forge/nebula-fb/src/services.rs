@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use aether_common::health::{self, Target};
+
+use crate::brain_client;
+
+/// A daemon slower than this to answer its health check isn't up yet.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(800);
+
+#[derive(Clone, Debug)]
+pub struct Service {
+    pub name: &'static str,
+    pub target: Target,
+    /// Whether the shell waits for it; NEBULA_BOOT_OPTIONAL (comma-separated
+    /// names) lists the ones it doesn't.
+    pub required: bool,
+}
+
+/// aetherd, aurorad and cfcd, in the order they start, at the addresses the
+/// daemons themselves read from the environment.
+pub fn boot_services() -> Vec<Service> {
+    let optional = std::env::var("NEBULA_BOOT_OPTIONAL").unwrap_or_default();
+    let required = |name: &str| !optional.split(',').any(|o| o.trim() == name);
+
+    [
+        ("aetherd", Target::aetherd()),
+        ("aurorad", Target::from_addr(&brain_client::aurorad_addr())),
+        ("cfcd", Target::cfcd()),
+    ]
+    .into_iter()
+    .map(|(name, target)| Service {
+        name,
+        target,
+        required: required(name),
+    })
+    .collect()
+}
+
+/// Whether `target` answers GET `/v0/health` with `"ok": true`. Blocks for
+/// up to a couple of `PROBE_TIMEOUT`s, so call it off the render thread.
+pub fn is_up(target: &Target) -> bool {
+    health::is_up(target, PROBE_TIMEOUT)
+}
@@ -10,12 +10,27 @@ pub struct SysTelemetry {
     pub mem_avail_mb: u64,
     pub uptime_secs: u64,
     pub num_procs: u32,
+    /// Display text: "addr (iface)", "iface up" without an address, or "no network".
     pub ip_addr: String,
+    /// Active interface, if any is up.
+    pub net: Option<NetInterface>,
     pub kernel: String,
     pub cores: u32,
 }
 
+/// An interface whose operstate is up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetInterface {
+    pub name: String,
+    /// First IPv4 address, if one is assigned.
+    pub addr: Option<String>,
+}
+
 impl SysTelemetry {
+    pub fn net_up(&self) -> bool {
+        self.net.is_some()
+    }
+
     pub fn mem_used_pct(&self) -> f64 {
         if self.mem_total_mb > 0 {
             let used = self.mem_total_mb.saturating_sub(self.mem_avail_mb);
@@ -121,7 +136,12 @@ pub fn read_telemetry() -> SysTelemetry {
             .count() as u32;
     }
 
-    t.ip_addr = read_ip_addr();
+    t.net = read_active_interface();
+    t.ip_addr = match &t.net {
+        Some(NetInterface { name, addr: Some(addr) }) => format!("{} ({})", addr, name),
+        Some(NetInterface { name, addr: None }) => format!("{} up", name),
+        None => "no network".to_string(),
+    };
     t
 }
 
@@ -152,24 +172,33 @@ fn read_cpu_percent() -> f64 {
     0.0
 }
 
-fn read_ip_addr() -> String {
-    if let Ok(operstate) = fs::read_to_string("/sys/class/net/eth0/operstate") {
-        if operstate.trim() == "up" {
-            if let Ok(fib) = fs::read_to_string("/proc/net/fib_trie") {
-                for line in fib.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.starts_with("10.")
-                        || trimmed.starts_with("192.168.")
-                        || trimmed.starts_with("172.")
-                    {
-                        if !trimmed.contains('/') {
-                            return trimmed.to_string();
-                        }
-                    }
-                }
-            }
-            return "eth0 up".to_string();
-        }
-    }
-    "no network".to_string()
+/// First non-loopback interface that is up, preferring one with an address.
+fn read_active_interface() -> Option<NetInterface> {
+    let mut up: Vec<String> = fs::read_dir("/sys/class/net")
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name != "lo")
+        .filter(|name| {
+            fs::read_to_string(format!("/sys/class/net/{}/operstate", name))
+                .map(|s| s.trim() == "up")
+                .unwrap_or(false)
+        })
+        .collect();
+    up.sort();
+
+    let addrs = aether_common::netif::ipv4_addrs();
+    let addr_of = |name: &str| {
+        addrs
+            .iter()
+            .find(|(iface, _)| iface == name)
+            .map(|(_, addr)| addr.to_string())
+    };
+    let name = up
+        .iter()
+        .find(|name| addr_of(name).is_some())
+        .or_else(|| up.first())?
+        .clone();
+    let addr = addr_of(&name);
+    Some(NetInterface { name, addr })
 }
@@ -1,31 +1,68 @@
-/// Text rendering with fontdue — embedded Inter font, layout + render, word wrap.
+/// Text rendering with fontdue — embedded Inter font, glyph cache, layout + render, word wrap.
 
-use fontdue::{Font, FontSettings};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use fontdue::{Font, FontSettings, Metrics};
 
 use crate::renderer::Renderer;
 use crate::theme::Color;
 
 static INTER_TTF: &[u8] = include_bytes!("../assets/Inter-Regular.ttf");
 
+type CachedGlyph = (Metrics, Vec<u8>);
+
+/// Cached glyphs before the cache is dropped and rebuilt.
+const GLYPH_CACHE_MAX: usize = 2048;
+
 pub struct TextRenderer {
     font: Font,
+    /// Rasterized glyphs keyed by (char, size bits).
+    glyphs: RefCell<HashMap<(char, u32), CachedGlyph>>,
 }
 
 impl TextRenderer {
     pub fn new() -> Self {
         let font = Font::from_bytes(INTER_TTF, FontSettings::default())
             .expect("load Inter font");
-        Self { font }
+        Self {
+            font,
+            glyphs: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Advance for `ch`, including kerning against the previous char.
+    fn advance(&self, prev: Option<char>, ch: char, size: f32) -> f32 {
+        let kern = prev
+            .and_then(|p| self.font.horizontal_kern(p, ch, size))
+            .unwrap_or(0.0);
+        self.font.metrics(ch, size).advance_width + kern
     }
 
     /// Render a single line of text at (x, y) with the given size and color.
     /// Returns the width of the rendered text in pixels.
     pub fn draw(&self, renderer: &mut Renderer, text: &str, x: f32, y: f32, size: f32, color: Color) -> f32 {
         let pw = renderer.pixmap.width() as i32;
-        let ph = renderer.pixmap.height() as i32;
+        // Glyph pixels outside the clip are skipped
+        let clip = renderer.clip_rect();
+        let (cx0, cy0) = (clip.x as i32, clip.y as i32);
+        let (cx1, cy1) = (cx0 + clip.w as i32, cy0 + clip.h as i32);
+        let mut glyphs = self.glyphs.borrow_mut();
+        if glyphs.len() > GLYPH_CACHE_MAX {
+            glyphs.clear();
+        }
+
         let mut cursor_x = x;
+        let mut prev = None;
         for ch in text.chars() {
-            let (metrics, bitmap) = self.font.rasterize(ch, size);
+            if let Some(p) = prev {
+                cursor_x += self.font.horizontal_kern(p, ch, size).unwrap_or(0.0);
+            }
+            prev = Some(ch);
+            let (metrics, bitmap) = glyphs
+                .entry((ch, size.to_bits()))
+                .or_insert_with(|| self.font.rasterize(ch, size));
+            let metrics = *metrics;
             if bitmap.is_empty() {
                 cursor_x += metrics.advance_width;
                 continue;
@@ -45,7 +82,7 @@ impl TextRenderer {
                     }
                     let px = gx + col as i32;
                     let py = gy + row as i32;
-                    if px < 0 || py < 0 || px >= pw || py >= ph {
+                    if px < cx0 || py < cy0 || px >= cx1 || py >= cy1 {
                         continue;
                     }
                     let idx = (py as usize * pw as usize + px as usize) * 4;
@@ -63,15 +100,17 @@ impl TextRenderer {
 
             cursor_x += metrics.advance_width;
         }
+        renderer.touch(x, y, cursor_x - x, size * 1.25);
         cursor_x - x
     }
 
     /// Measure the width of text at a given font size.
     pub fn measure(&self, text: &str, size: f32) -> f32 {
         let mut w = 0.0f32;
+        let mut prev = None;
         for ch in text.chars() {
-            let (metrics, _) = self.font.rasterize(ch, size);
-            w += metrics.advance_width;
+            w += self.advance(prev, ch, size);
+            prev = Some(ch);
         }
         w
     }
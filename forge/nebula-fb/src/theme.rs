@@ -1,6 +1,6 @@
 /// AetherOS dark theme — GitHub-dark inspired.
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub struct Color {
     pub r: u8,
     pub g: u8,
@@ -34,6 +34,8 @@ pub const BG: Color = Color::rgb(0x0D, 0x11, 0x17);
 pub const SURFACE: Color = Color::rgb(0x16, 0x1B, 0x22);
 pub const CARD: Color = Color::rgb(0x1C, 0x21, 0x28);
 pub const CARD_BORDER: Color = Color::rgb(0x30, 0x36, 0x3D);
+/// Dims whatever is under a modal scene.
+pub const SCRIM: Color = Color::rgba(0x00, 0x00, 0x00, 0xA0);
 
 // Text
 pub const TEXT_PRIMARY: Color = Color::rgb(0xE6, 0xED, 0xF3);
@@ -0,0 +1,94 @@
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use crate::brain_client::{self, Notification};
+use crate::renderer::Renderer;
+use crate::text::TextRenderer;
+use crate::theme::{self, Color};
+
+/// How often aurorad is asked for new notifications.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Seconds a toast stays up, including its fade.
+const TOAST_SECONDS: f32 = 6.0;
+const FADE_SECONDS: f32 = 0.5;
+/// Toasts shown at once; the rest wait their turn.
+const MAX_VISIBLE: usize = 3;
+const TOAST_WIDTH: f32 = 340.0;
+
+/// Notifications from aurorad, shown briefly over any scene.
+pub struct Toasts {
+    rx: Receiver<Notification>,
+    /// Each toast with the seconds it has been shown.
+    shown: Vec<(Notification, f32)>,
+    queued: Vec<Notification>,
+}
+
+impl Toasts {
+    /// Start polling aurorad in the background.
+    pub fn listen() -> Self {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut after = None;
+            loop {
+                if let Ok((latest, notifications)) = brain_client::fetch_notifications(after) {
+                    // A restarted aurorad numbers from 1 again; follow it down
+                    after = Some(latest);
+                    for n in notifications {
+                        if tx.send(n).is_err() {
+                            return;
+                        }
+                    }
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Self { rx, shown: Vec::new(), queued: Vec::new() }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.queued.extend(self.rx.try_iter());
+        for (_, age) in &mut self.shown {
+            *age += dt;
+        }
+        self.shown.retain(|(_, age)| *age < TOAST_SECONDS);
+        while self.shown.len() < MAX_VISIBLE && !self.queued.is_empty() {
+            self.shown.push((self.queued.remove(0), 0.0));
+        }
+    }
+
+    /// Stack the toasts down the right edge, below the status bar.
+    pub fn draw(&self, renderer: &mut Renderer, text: &TextRenderer) {
+        let pad = theme::CARD_PADDING as f32;
+        let x = renderer.width() as f32 - TOAST_WIDTH - theme::CONTENT_MARGIN as f32;
+        let mut y = theme::STATUS_BAR_HEIGHT as f32 + 12.0;
+        for (n, age) in &self.shown {
+            let alpha = ((TOAST_SECONDS - age) / FADE_SECONDS).clamp(0.0, 1.0);
+            let fade = |c: Color| Color::rgba(c.r, c.g, c.b, (c.a as f32 * alpha) as u8);
+            let accent = match n.priority.as_str() {
+                "urgent" => theme::ACCENT_RED,
+                "low" => theme::TEXT_MUTED,
+                _ => theme::ACCENT_BLUE,
+            };
+
+            let body: Vec<String> = n
+                .lines
+                .iter()
+                .flat_map(|l| text.wrap(l, theme::FONT_SIZE_SMALL, TOAST_WIDTH - pad * 2.0))
+                .take(3)
+                .collect();
+            let h = pad * 2.0 + theme::FONT_SIZE_BODY + body.len() as f32 * (theme::FONT_SIZE_SMALL + 4.0);
+
+            renderer.fill_rounded_rect(x, y, TOAST_WIDTH, h, theme::CARD_RADIUS, fade(theme::CARD));
+            renderer.stroke_rounded_rect(x, y, TOAST_WIDTH, h, theme::CARD_RADIUS, fade(theme::CARD_BORDER), 1.0);
+            renderer.fill_rect(x, y + theme::CARD_RADIUS, 3.0, h - theme::CARD_RADIUS * 2.0, fade(accent));
+
+            text.draw(renderer, &n.title, x + pad, y + pad, theme::FONT_SIZE_BODY, fade(theme::TEXT_PRIMARY));
+            let mut line_y = y + pad + theme::FONT_SIZE_BODY + 4.0;
+            for line in &body {
+                text.draw(renderer, line, x + pad, line_y, theme::FONT_SIZE_SMALL, fade(theme::TEXT_SECONDARY));
+                line_y += theme::FONT_SIZE_SMALL + 4.0;
+            }
+            y += h + 8.0;
+        }
+    }
+}
@@ -1,28 +1,46 @@
 /// Card widget — rounded rect with title, body, optional metrics/progress bars.
 
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
 use crate::renderer::Renderer;
 use crate::text::TextRenderer;
-use crate::theme;
+use crate::theme::{self, Color};
+use crate::widgets::progress;
 
 /// Data for a card from the dashboard JSON.
-#[derive(Clone, Debug, serde::Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct CardData {
     #[serde(rename = "type")]
     pub card_type: String,
+    #[serde(default)]
     pub title: String,
     #[serde(default)]
     pub body: Option<String>,
     #[serde(default)]
     pub metrics: Option<CardMetrics>,
+    /// Raw rows, shown when a card has no structured fields for its type.
     #[serde(default)]
-    pub temp: Option<String>,
+    pub lines: Vec<String>,
+    /// Structured fields of a "weather" card.
+    #[serde(flatten)]
+    pub weather: Option<Box<Weather>>,
+    /// Points for a "chart" card, oldest first.
     #[serde(default)]
-    pub desc: Option<String>,
+    pub series: Vec<f64>,
+    /// Live telemetry a "chart" card follows: "cpu" or "mem".
     #[serde(default)]
-    pub wind: Option<String>,
+    pub metric: Option<String>,
+    /// Fill of a "progress" card, 0 to 1:
+    /// `{"type":"progress","value":0.42,"label":"Downloading"}`.
+    #[serde(default)]
+    pub value: Option<f64>,
+    /// What a "progress" card is measuring.
+    #[serde(default)]
+    pub label: Option<String>,
 }
 
-#[derive(Clone, Debug, serde::Deserialize)]
+#[derive(Clone, Debug, Deserialize)]
 pub struct CardMetrics {
     #[serde(default)]
     pub cpu: f64,
@@ -30,6 +48,90 @@ pub struct CardMetrics {
     pub mem: f64,
 }
 
+/// Structured weather: `{"temp":"18°C","condition":"Partly cloudy",
+/// "high":21,"low":12,"wind":"10 km/h NW","icon":"partly_cloudy","forecast":[…]}`.
+/// Only `temp` is required.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Weather {
+    #[serde(deserialize_with = "text_or_number")]
+    pub temp: String,
+    #[serde(default, alias = "desc")]
+    pub condition: String,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub high: Option<String>,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub low: Option<String>,
+    #[serde(default)]
+    pub wind: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub forecast: Vec<Forecast>,
+}
+
+/// One day of a weather forecast.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Forecast {
+    pub day: String,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub high: Option<String>,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub low: Option<String>,
+    #[serde(default, alias = "condition")]
+    pub icon: Option<String>,
+}
+
+/// Brain replies give temperatures as text ("18°C") or bare numbers (18).
+fn text_or_number<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    match serde_json::Value::deserialize(d)? {
+        serde_json::Value::String(s) => Ok(s),
+        serde_json::Value::Number(n) => Ok(format!("{:.0}\u{00b0}", n.as_f64().unwrap_or(0.0))),
+        other => Err(D::Error::custom(format!("expected text or a number, got {}", other))),
+    }
+}
+
+fn opt_text_or_number<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    match serde_json::Value::deserialize(d)? {
+        serde_json::Value::Null => Ok(None),
+        v => text_or_number(v).map(Some).map_err(D::Error::custom),
+    }
+}
+
+/// Sky conditions an icon is drawn for.
+#[derive(Clone, Copy, PartialEq)]
+enum Sky {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Rain,
+    Snow,
+    Storm,
+    Fog,
+}
+
+impl Sky {
+    /// From an icon name or condition text ("rain", "Partly cloudy").
+    fn parse(sky: &str) -> Sky {
+        let sky = sky.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| sky.contains(w));
+        if has(&["thunder", "storm", "lightning"]) {
+            Sky::Storm
+        } else if has(&["snow", "sleet", "hail", "ice"]) {
+            Sky::Snow
+        } else if has(&["rain", "drizzle", "shower"]) {
+            Sky::Rain
+        } else if has(&["fog", "mist", "haze", "smoke"]) {
+            Sky::Fog
+        } else if has(&["partly", "partial", "broken", "scattered"]) {
+            Sky::PartlyCloudy
+        } else if has(&["cloud", "overcast"]) {
+            Sky::Cloudy
+        } else {
+            Sky::Clear
+        }
+    }
+}
+
 /// Draw a card at the given position and size.
 pub fn draw_card(
     renderer: &mut Renderer,
@@ -57,6 +159,7 @@ pub fn draw_card(
         "weather" => theme::ACCENT_BLUE,
         "alert" => theme::ACCENT_RED,
         "tip" => theme::ACCENT_YELLOW,
+        "chart" => theme::ACCENT_BLUE,
         _ => theme::TEXT_PRIMARY,
     };
     text.draw(renderer, &data.title, x + pad, y + pad, theme::FONT_SIZE_BODY, title_color);
@@ -68,6 +171,9 @@ pub fn draw_card(
     let content_y = sep_y + 8.0;
     let content_w = w - pad * 2.0;
 
+    // Content stays inside the card's padding
+    renderer.push_clip(x + pad, content_y, content_w, y + h - pad - content_y);
+
     match data.card_type.as_str() {
         "system" => {
             if let Some(ref metrics) = data.metrics {
@@ -75,21 +181,20 @@ pub fn draw_card(
                 draw_metric_bar(renderer, text, "Mem", metrics.mem, x + pad, content_y + 28.0, content_w);
             }
         }
-        "weather" => {
-            let mut cy = content_y;
-            if let Some(ref temp) = data.temp {
-                if let Some(ref desc) = data.desc {
-                    text.draw(renderer, &format!("{}  {}", temp, desc), x + pad, cy, theme::FONT_SIZE_BODY, theme::TEXT_PRIMARY);
-                    cy += 22.0;
-                } else {
-                    text.draw(renderer, temp, x + pad, cy, theme::FONT_SIZE_BODY, theme::TEXT_PRIMARY);
-                    cy += 22.0;
-                }
-            }
-            if let Some(ref wind) = data.wind {
-                text.draw(renderer, &format!("Wind: {}", wind), x + pad, cy, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
-            }
+        "weather" => match data.weather {
+            Some(ref weather) => draw_weather(renderer, text, weather, x + pad, content_y, content_w),
+            // Older replies only send text rows
+            None => draw_lines(renderer, text, &data.lines, x + pad, content_y),
+        },
+        "chart" => {
+            let content_h = y + h - pad - content_y;
+            draw_chart_card(renderer, text, &data.series, x + pad, content_y, content_w, content_h);
+        }
+        "progress" => {
+            let value = data.value.unwrap_or(0.0).clamp(0.0, 1.0) as f32;
+            draw_progress_card(renderer, text, data.label.as_deref(), value, x + pad, content_y, content_w);
         }
+        _ if data.body.is_none() => draw_lines(renderer, text, &data.lines, x + pad, content_y),
         _ => {
             if let Some(ref body) = data.body {
                 text.draw_wrapped(
@@ -105,6 +210,182 @@ pub fn draw_card(
             }
         }
     }
+    renderer.pop_clip();
+}
+
+fn draw_lines(renderer: &mut Renderer, text: &TextRenderer, lines: &[String], x: f32, y: f32) {
+    for (i, line) in lines.iter().enumerate() {
+        text.draw(renderer, line, x, y + i as f32 * 18.0, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
+    }
+}
+
+/// A big temperature beside the sky icon and condition, highs, lows and
+/// wind below, then a row of forecast days.
+fn draw_weather(renderer: &mut Renderer, text: &TextRenderer, weather: &Weather, x: f32, y: f32, w: f32) {
+    let sky = Sky::parse(weather.icon.as_deref().unwrap_or(&weather.condition));
+    draw_sky_icon(renderer, sky, x + 22.0, y + 20.0, 40.0);
+
+    let tx = x + 56.0;
+    let temp_w = text.draw(renderer, &weather.temp, tx, y, theme::FONT_SIZE_TITLE, theme::TEXT_PRIMARY);
+    if !weather.condition.is_empty() {
+        let cx = tx + temp_w + 12.0;
+        text.draw(renderer, &weather.condition, cx, y + 10.0, theme::FONT_SIZE_BODY, theme::TEXT_SECONDARY);
+    }
+
+    let range = match (&weather.high, &weather.low) {
+        (Some(h), Some(l)) => Some(format!("H {}  L {}", h, l)),
+        (Some(h), None) => Some(format!("H {}", h)),
+        (None, Some(l)) => Some(format!("L {}", l)),
+        (None, None) => None,
+    };
+    let wind = weather.wind.as_ref().map(|wind| format!("Wind {}", wind));
+    let details: Vec<String> = range.into_iter().chain(wind).collect();
+    let mut cy = y + theme::FONT_SIZE_TITLE + 12.0;
+    if !details.is_empty() {
+        text.draw(renderer, &details.join("  \u{00b7}  "), tx, cy, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
+        cy += 24.0;
+    }
+
+    if weather.forecast.is_empty() {
+        return;
+    }
+    renderer.draw_line(x, cy, x + w, cy, theme::CARD_BORDER, 1.0);
+    cy += 8.0;
+    // As many days as fit at a readable width
+    let slot = 64.0;
+    let days = ((w / slot) as usize).max(1);
+    for (i, day) in weather.forecast.iter().take(days).enumerate() {
+        let dx = x + i as f32 * slot;
+        text.draw_centered(renderer, &day.day, dx, cy, slot, theme::FONT_SIZE_TINY, theme::TEXT_MUTED);
+        let sky = day.icon.as_deref().map_or(Sky::Clear, Sky::parse);
+        draw_sky_icon(renderer, sky, dx + slot / 2.0, cy + 26.0, 20.0);
+        let temps = match (&day.high, &day.low) {
+            (Some(h), Some(l)) => format!("{}/{}", h, l),
+            (Some(t), None) | (None, Some(t)) => t.clone(),
+            (None, None) => String::new(),
+        };
+        text.draw_centered(renderer, &temps, dx, cy + 40.0, slot, theme::FONT_SIZE_TINY, theme::TEXT_PRIMARY);
+    }
+}
+
+/// A small drawn icon for `sky`, centred on `cx,cy`, about `size` across.
+fn draw_sky_icon(renderer: &mut Renderer, sky: Sky, cx: f32, cy: f32, size: f32) {
+    let sun = theme::ACCENT_YELLOW;
+    let cloud = theme::TEXT_SECONDARY;
+    let s = size / 2.0;
+
+    let draw_sun = |renderer: &mut Renderer, cx: f32, cy: f32, r: f32| {
+        renderer.fill_rounded_rect(cx - r, cy - r, r * 2.0, r * 2.0, r, sun);
+        for i in 0..8 {
+            let a = i as f32 * std::f32::consts::FRAC_PI_4;
+            let (dx, dy) = (a.cos(), a.sin());
+            renderer.draw_line(cx + dx * r * 1.4, cy + dy * r * 1.4, cx + dx * r * 1.9, cy + dy * r * 1.9, sun, 2.0);
+        }
+    };
+    let draw_cloud = |renderer: &mut Renderer, cx: f32, cy: f32, color: Color| {
+        renderer.fill_rounded_rect(cx - s * 0.9, cy - s * 0.1, s * 1.8, s * 0.7, s * 0.35, color);
+        renderer.fill_rounded_rect(cx - s * 0.55, cy - s * 0.5, s * 0.8, s * 0.8, s * 0.4, color);
+        renderer.fill_rounded_rect(cx - s * 0.05, cy - s * 0.65, s * 0.8, s * 0.8, s * 0.4, color);
+    };
+
+    match sky {
+        Sky::Clear => draw_sun(renderer, cx, cy, s * 0.5),
+        Sky::PartlyCloudy => {
+            draw_sun(renderer, cx - s * 0.3, cy - s * 0.3, s * 0.35);
+            draw_cloud(renderer, cx + s * 0.1, cy + s * 0.2, cloud);
+        }
+        Sky::Cloudy => draw_cloud(renderer, cx, cy, cloud),
+        Sky::Rain | Sky::Snow | Sky::Storm => {
+            draw_cloud(renderer, cx, cy - s * 0.3, cloud);
+            let below = cy + s * 0.5;
+            for i in 0..3 {
+                let dx = cx + (i as f32 - 1.0) * s * 0.5;
+                match sky {
+                    Sky::Rain => renderer.draw_line(dx, below, dx - s * 0.15, below + s * 0.4, theme::ACCENT_BLUE, 2.0),
+                    Sky::Snow => renderer.fill_rounded_rect(dx - 2.0, below + s * 0.1, 4.0, 4.0, 2.0, theme::TEXT_PRIMARY),
+                    _ => {}
+                }
+            }
+            if sky == Sky::Storm {
+                let bolt = [
+                    (cx + s * 0.1, below - s * 0.1),
+                    (cx - s * 0.15, below + s * 0.25),
+                    (cx + s * 0.1, below + s * 0.25),
+                    (cx - s * 0.1, below + s * 0.6),
+                ];
+                renderer.draw_polyline(&bolt, sun, 2.0);
+            }
+        }
+        Sky::Fog => {
+            for i in 0..3 {
+                let ly = cy - s * 0.4 + i as f32 * s * 0.4;
+                renderer.draw_line(cx - s * 0.8, ly, cx + s * 0.8, ly, cloud, 2.0);
+            }
+        }
+    }
+}
+
+/// Plot `series` as a polyline scaled to the rect, with min/max labels on the left.
+pub fn draw_chart_card(
+    renderer: &mut Renderer,
+    text: &TextRenderer,
+    series: &[f64],
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+) {
+    if series.len() < 2 || w <= 0.0 || h <= 0.0 {
+        text.draw(renderer, "Collecting data...", x, y, theme::FONT_SIZE_SMALL, theme::TEXT_MUTED);
+        return;
+    }
+
+    let min = series.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = series.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    // Keep a flat series off the axis
+    let (lo, hi) = if max - min < 1.0 { (min - 0.5, max + 0.5) } else { (min, max) };
+
+    // Axis labels
+    let label_w = 40.0;
+    text.draw(renderer, &format!("{:.0}", hi), x, y, theme::FONT_SIZE_SMALL, theme::TEXT_MUTED);
+    text.draw(renderer, &format!("{:.0}", lo), x, y + h - theme::FONT_SIZE_SMALL, theme::FONT_SIZE_SMALL, theme::TEXT_MUTED);
+
+    let px = x + label_w;
+    let pw = w - label_w;
+    if pw <= 0.0 {
+        return;
+    }
+    renderer.draw_line(px, y, px, y + h, theme::CARD_BORDER, 1.0);
+    renderer.draw_line(px, y + h, px + pw, y + h, theme::CARD_BORDER, 1.0);
+
+    let step = pw / (series.len() - 1) as f32;
+    let point = |i: usize, v: f64| {
+        let t = ((v - lo) / (hi - lo)).clamp(0.0, 1.0) as f32;
+        (px + i as f32 * step, y + h - t * h)
+    };
+    let points: Vec<(f32, f32)> = series.iter().enumerate().map(|(i, &v)| point(i, v)).collect();
+    renderer.draw_polyline(&points, theme::ACCENT_BLUE, 2.0);
+}
+
+/// The label, then a bar with the percentage beside it.
+fn draw_progress_card(
+    renderer: &mut Renderer,
+    text: &TextRenderer,
+    label: Option<&str>,
+    value: f32,
+    x: f32,
+    y: f32,
+    w: f32,
+) {
+    let mut by = y;
+    if let Some(label) = label {
+        text.draw(renderer, label, x, y, theme::FONT_SIZE_SMALL, theme::TEXT_SECONDARY);
+        by += 24.0;
+    }
+    let pct_w = 50.0;
+    progress::draw_progress_bar(renderer, x, by, w - pct_w, 12.0, value);
+    let pct = format!("{:.0}%", value * 100.0);
+    text.draw(renderer, &pct, x + w - pct_w + 8.0, by - 2.0, theme::FONT_SIZE_SMALL, theme::TEXT_PRIMARY);
 }
 
 fn draw_metric_bar(
@@ -0,0 +1,118 @@
+/// Chip grid — a wrapping grid of toggleable chips with a keyboard cursor.
+
+use crate::input::InputEvent;
+use crate::renderer::Renderer;
+use crate::text::TextRenderer;
+use crate::theme;
+use crate::widgets::button;
+
+/// Topics offered at setup and in preferences.
+pub const INTEREST_OPTIONS: &[&str] = &[
+    "Technology",
+    "Weather",
+    "Science",
+    "Programming",
+    "News",
+    "Finance",
+    "Music",
+    "Space",
+    "AI",
+    "Health",
+];
+
+const GRID_WIDTH: f32 = 700.0;
+const GAP: f32 = 12.0;
+/// Chips per row, for Up/Down navigation.
+const ROW_LEN: usize = 5;
+
+pub struct ChipGrid {
+    options: &'static [&'static str],
+    selected: Vec<bool>,
+    cursor: usize,
+}
+
+impl ChipGrid {
+    pub fn new(options: &'static [&'static str]) -> Self {
+        Self {
+            options,
+            selected: vec![false; options.len()],
+            cursor: 0,
+        }
+    }
+
+    /// Pre-select the options named in `labels`.
+    pub fn with_selected(mut self, labels: &[String]) -> Self {
+        for (opt, sel) in self.options.iter().zip(self.selected.iter_mut()) {
+            *sel = labels.iter().any(|l| l == opt);
+        }
+        self
+    }
+
+    pub fn any_selected(&self) -> bool {
+        self.selected.iter().any(|&s| s)
+    }
+
+    pub fn selected_labels(&self) -> Vec<String> {
+        self.options
+            .iter()
+            .zip(self.selected.iter())
+            .filter(|(_, &sel)| sel)
+            .map(|(&name, _)| name.to_string())
+            .collect()
+    }
+
+    /// Arrow keys move the cursor, Enter/Space toggle. Returns whether the event was used.
+    pub fn handle_input(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::Left => {
+                if self.cursor > 0 {
+                    self.cursor -= 1;
+                }
+            }
+            InputEvent::Right => {
+                if self.cursor + 1 < self.options.len() {
+                    self.cursor += 1;
+                }
+            }
+            InputEvent::Up => {
+                if self.cursor >= ROW_LEN {
+                    self.cursor -= ROW_LEN;
+                }
+            }
+            InputEvent::Down => {
+                if self.cursor + ROW_LEN < self.options.len() {
+                    self.cursor += ROW_LEN;
+                }
+            }
+            InputEvent::Enter | InputEvent::Char(' ') => {
+                self.selected[self.cursor] = !self.selected[self.cursor];
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Draw centred on `cx` starting at `y`. `focused` shows the cursor ring.
+    /// Returns the y of the last row.
+    pub fn draw(&self, renderer: &mut Renderer, text: &TextRenderer, cx: f32, y: f32, focused: bool) -> f32 {
+        let start_x = cx - GRID_WIDTH / 2.0;
+        let mut chip_x = start_x;
+        let mut chip_y = y;
+
+        for (i, &label) in self.options.iter().enumerate() {
+            let (cw, ch) = button::draw_chip(renderer, text, label, chip_x, chip_y, self.selected[i]);
+
+            // Cursor indicator
+            if focused && i == self.cursor {
+                renderer.stroke_rounded_rect(chip_x - 2.0, chip_y - 2.0, cw + 4.0, ch + 4.0, (ch + 4.0) / 2.0, theme::ACCENT_BLUE, 1.5);
+            }
+
+            chip_x += cw + GAP;
+            if chip_x + 100.0 > start_x + GRID_WIDTH {
+                chip_x = start_x;
+                chip_y += ch + GAP;
+            }
+        }
+        chip_y
+    }
+}
@@ -4,3 +4,4 @@ pub mod text_input;
 pub mod button;
 pub mod chart;
 pub mod progress;
+pub mod chip_grid;
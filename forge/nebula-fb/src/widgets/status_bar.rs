@@ -7,7 +7,7 @@ use crate::theme;
 pub struct StatusBarData {
     pub cpu_pct: f64,
     pub mem_pct: f64,
-    pub net_status: String,
+    pub net_up: bool,
     pub time_str: String,
 }
 
@@ -38,12 +38,12 @@ pub fn draw_status_bar(
     let right_x = width as f32 - 12.0;
 
     // Net indicator
-    let net_icon = if data.net_status.contains("10.") || data.net_status.contains("up") {
+    let net_icon = if data.net_up {
         "\u{25B2}"
     } else {
         "\u{25BC}"
     };
-    let net_color = if data.net_status.contains("10.") || data.net_status.contains("up") {
+    let net_color = if data.net_up {
         theme::ACCENT_GREEN
     } else {
         theme::ACCENT_RED
@@ -40,9 +40,13 @@ impl TextInputState {
 
     pub fn take_text(&mut self) -> String {
         let text = self.text.clone();
+        self.clear();
+        text
+    }
+
+    pub fn clear(&mut self) {
         self.text.clear();
         self.cursor = 0;
-        text
     }
 
     pub fn move_left(&mut self) {
@@ -55,6 +59,19 @@ impl TextInputState {
         }
     }
 
+    /// Move to the start of the previous word (Alt+B).
+    pub fn move_word_left(&mut self) {
+        let before = self.text[..self.cursor].trim_end();
+        self.cursor = before.rfind(' ').map(|i| i + 1).unwrap_or(0);
+    }
+
+    /// Move past the end of the next word (Alt+F).
+    pub fn move_word_right(&mut self) {
+        let after = &self.text[self.cursor..];
+        let skip = after.len() - after.trim_start().len();
+        self.cursor += after[skip..].find(' ').map(|i| skip + i).unwrap_or(after.len());
+    }
+
     pub fn move_right(&mut self) {
         if self.cursor < self.text.len() {
             self.cursor = self.text[self.cursor..]
@@ -1,7 +1,10 @@
-use std::io::{Read, Write};
-use std::net::TcpStream;
-use std::os::unix::net::UnixStream;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use aether_common::health::Target;
+
+use crate::conn;
 
 /// Aurora/aurorad connection status.
 #[derive(Default, Clone)]
@@ -13,6 +16,8 @@ pub struct AuroraStatus {
     pub error: f64,
     pub gate_stats: Vec<String>,
     pub latency_ms: u64,
+    /// Required daemons aurorad reports down, from `/v0/health/all`.
+    pub down: Vec<String>,
 }
 
 /// Structured prediction insight from CFC-JEPA.
@@ -37,103 +42,81 @@ pub struct IntrospectData {
     pub mean_prediction_error: f64,
 }
 
+/// A notification raised through aurorad's `/v0/notify`: the brain's
+/// widget shape plus a priority.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    #[serde(default)]
+    pub lines: Vec<String>,
+    #[serde(default)]
+    pub priority: String,
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct NotificationsResponse {
+    latest: u64,
+    #[serde(default)]
+    notifications: Vec<Notification>,
+}
+
 /// How to reach aurorad.
-fn aurorad_addr() -> AuroraAddr {
+fn aurorad_addr() -> Target {
     if let Ok(port) = std::env::var("AURORAD_TCP_PORT") {
         if let Ok(p) = port.parse::<u16>() {
-            return AuroraAddr::Tcp(format!("127.0.0.1:{}", p));
+            return Target::Tcp(format!("127.0.0.1:{}", p));
         }
     }
     if let Ok(host) = std::env::var("AURORAD_HOST") {
-        return AuroraAddr::Tcp(host);
+        return Target::Tcp(host);
     }
     let sock = std::env::var("AURORAD_SOCKET")
         .unwrap_or_else(|_| "/tmp/aurorad.sock".to_string());
-    AuroraAddr::Unix(sock)
-}
-
-enum AuroraAddr {
-    Unix(String),
-    Tcp(String),
-}
-
-fn http_get(addr: &AuroraAddr, path: &str) -> Result<String, String> {
-    let request =
-        format!("GET {path} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n");
-
-    match addr {
-        AuroraAddr::Unix(sock) => {
-            let mut stream = UnixStream::connect(sock).map_err(|e| e.to_string())?;
-            stream.set_read_timeout(Some(Duration::from_secs(2))).ok();
-            stream
-                .write_all(request.as_bytes())
-                .map_err(|e| e.to_string())?;
-            let mut resp = String::new();
-            stream
-                .read_to_string(&mut resp)
-                .map_err(|e| e.to_string())?;
-            extract_body(&resp)
-        }
-        AuroraAddr::Tcp(host) => {
-            let mut stream = TcpStream::connect(host).map_err(|e| e.to_string())?;
-            stream.set_read_timeout(Some(Duration::from_secs(2))).ok();
-            stream
-                .write_all(request.as_bytes())
-                .map_err(|e| e.to_string())?;
-            let mut resp = String::new();
-            stream
-                .read_to_string(&mut resp)
-                .map_err(|e| e.to_string())?;
-            extract_body(&resp)
-        }
-    }
+    Target::Unix(sock)
 }
 
-fn http_post(addr: &AuroraAddr, path: &str, body: &str) -> Result<String, String> {
-    let request = format!(
-        "POST {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
-        body.len()
-    );
-
-    match addr {
-        AuroraAddr::Unix(sock) => {
-            let mut stream = UnixStream::connect(sock).map_err(|e| e.to_string())?;
-            stream.set_read_timeout(Some(Duration::from_secs(5))).ok();
-            stream
-                .write_all(request.as_bytes())
-                .map_err(|e| e.to_string())?;
-            let mut resp = String::new();
-            stream
-                .read_to_string(&mut resp)
-                .map_err(|e| e.to_string())?;
-            extract_body(&resp)
-        }
-        AuroraAddr::Tcp(host) => {
-            let mut stream = TcpStream::connect(host).map_err(|e| e.to_string())?;
-            stream.set_read_timeout(Some(Duration::from_secs(5))).ok();
-            stream
-                .write_all(request.as_bytes())
-                .map_err(|e| e.to_string())?;
-            let mut resp = String::new();
-            stream
-                .read_to_string(&mut resp)
-                .map_err(|e| e.to_string())?;
-            extract_body(&resp)
-        }
-    }
+fn http_get(addr: &Target, path: &str) -> Result<String, String> {
+    conn::request(addr, "GET", path, "", Duration::from_secs(2), || {
+        conn::connect(addr).map_err(|e| e.to_string())
+    })
 }
 
-fn extract_body(resp: &str) -> Result<String, String> {
-    if let Some(idx) = resp.find("\r\n\r\n") {
-        Ok(resp[idx + 4..].to_string())
-    } else {
-        Ok(resp.to_string())
-    }
+fn http_post(addr: &Target, path: &str, body: &str) -> Result<String, String> {
+    conn::request(addr, "POST", path, body, Duration::from_secs(5), || {
+        conn::connect(addr).map_err(|e| e.to_string())
+    })
 }
 
-/// Check aurorad health.
+/// Check the stack's health through aurorad's `/v0/health/all`, falling
+/// back to aurorad's own `/v0/health` on versions without it.
 pub fn check_health() -> AuroraStatus {
     let addr = aurorad_addr();
+    let start = Instant::now();
+    // aurorad probes each daemon before answering
+    let all = conn::request(&addr, "GET", "/v0/health/all", "", Duration::from_secs(5), || {
+        conn::connect(&addr).map_err(|e| e.to_string())
+    });
+    let Ok(body) = all else {
+        return AuroraStatus::default();
+    };
+    let parsed = serde_json::from_str::<serde_json::Value>(&body).ok();
+    if let Some(components) = parsed.as_ref().and_then(|v| v.get("components")).and_then(|c| c.as_object()) {
+        let is = |c: &serde_json::Value, key: &str| c.get(key).and_then(|v| v.as_bool()) == Some(true);
+        let down = components
+            .iter()
+            .filter(|(_, c)| is(c, "required") && !is(c, "ok"))
+            .map(|(name, _)| name.clone())
+            .collect();
+        return AuroraStatus {
+            connected: true,
+            latency_ms: start.elapsed().as_millis() as u64,
+            down,
+            ..Default::default()
+        };
+    }
+
     match http_get(&addr, "/v0/health") {
         Ok(body) => {
             if let Ok(v) = serde_json::from_str::<serde_json::Value>(&body) {
@@ -150,6 +133,19 @@ pub fn check_health() -> AuroraStatus {
     }
 }
 
+/// Notifications newer than `after`, and the latest id to pass next time.
+/// Without `after`, only the latest id.
+pub fn fetch_notifications(after: Option<u64>) -> Result<(u64, Vec<Notification>), String> {
+    let addr = aurorad_addr();
+    let path = match after {
+        Some(id) => format!("/v0/notifications?after={}", id),
+        None => "/v0/notifications".to_string(),
+    };
+    let resp = http_get(&addr, &path)?;
+    let parsed: NotificationsResponse = serde_json::from_str(&resp).map_err(|e| e.to_string())?;
+    Ok((parsed.latest, parsed.notifications))
+}
+
 /// Query predict endpoint and parse structured response.
 pub fn query_prediction() -> Result<PredictionInsight, String> {
     let addr = aurorad_addr();
@@ -0,0 +1,89 @@
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::brain_client;
+use crate::feed::{FeedItem, FeedSource, Priority};
+use crate::widgets;
+
+/// Minutes without input that count as being away, unless `/away on <mins>`.
+pub const DEFAULT_IDLE_MINS: u64 = 10;
+/// Longest idle threshold `/away on <mins>` accepts: a week.
+pub const MAX_IDLE_MINS: u64 = 7 * 24 * 60;
+/// Most cards described to the brain in one summary; the newest are kept.
+const MAX_SUMMARY_CARDS: usize = 30;
+/// Titles listed in the fallback card when the brain can't be reached.
+const FALLBACK_TITLES: usize = 8;
+
+/// A stretch without input that the user has just come back from.
+pub struct Away {
+    /// First feed id that arrived after the last input.
+    pub since_id: u64,
+    pub duration: Duration,
+}
+
+/// Watches for input to notice idle episodes, each reported once on return.
+pub struct AwayTracker {
+    last_input: Instant,
+    /// The feed's next id at the last input.
+    mark: u64,
+    away: bool,
+}
+
+impl AwayTracker {
+    pub fn new(next_feed_id: u64) -> Self {
+        Self { last_input: Instant::now(), mark: next_feed_id, away: false }
+    }
+
+    /// Start an episode once there has been no input for `idle`.
+    pub fn tick(&mut self, idle: Duration) {
+        if !self.away && self.last_input.elapsed() >= idle {
+            self.away = true;
+        }
+    }
+
+    /// Note a key or click. Returns the episode it ended, if there was one.
+    pub fn input(&mut self, next_feed_id: u64) -> Option<Away> {
+        let ended = self.away.then(|| Away { since_id: self.mark, duration: self.last_input.elapsed() });
+        self.last_input = Instant::now();
+        self.mark = next_feed_id;
+        self.away = false;
+        ended
+    }
+}
+
+/// Ask the brain to sum up `cards` in the background and send a single
+/// "While you were away" card to `tx`, or a plain list if the brain fails.
+pub fn summarize(cards: Vec<&FeedItem>, away: &Away, tx: mpsc::Sender<FeedItem>) {
+    let start = cards.len().saturating_sub(MAX_SUMMARY_CARDS);
+    let lines: Vec<String> = cards[start..]
+        .iter()
+        .map(|c| match c.body.first().map(|b| b.trim()).filter(|b| !b.is_empty()) {
+            Some(first) => format!("- [{}] {}: {}", c.source.label(), c.title, widgets::truncate(first, 80)),
+            None => format!("- [{}] {}", c.source.label(), c.title),
+        })
+        .collect();
+    let titles: Vec<String> = cards.iter().rev().take(FALLBACK_TITLES).map(|c| format!("  {}", c.title)).collect();
+    let count = cards.len();
+    let minutes = (away.duration.as_secs() / 60).max(1);
+    let prompt = format!(
+        "I was away from the terminal for {minutes} minutes. In a few short bullet points, most important \
+         first, summarize what happened from these feed cards (oldest first). Skip routine noise.\n{}",
+        lines.join("\n")
+    );
+
+    std::thread::spawn(move || {
+        let title = "While you were away".to_string();
+        let card = match brain_client::query_brain(&prompt) {
+            Ok(resp) if !resp.text.trim().is_empty() => FeedItem::new(FeedSource::Brain, Priority::Normal, title)
+                .with_body(resp.text.lines().map(|l| l.to_string()).collect())
+                .with_markdown(),
+            _ => {
+                let plural = if count == 1 { "" } else { "s" };
+                let mut body = vec![format!("{count} card{plural} arrived in {minutes} min; newest:")];
+                body.extend(titles);
+                FeedItem::new(FeedSource::System, Priority::Normal, title).with_body(body)
+            }
+        };
+        let _ = tx.send(card);
+    });
+}
@@ -1,18 +1,29 @@
-use std::io::{Read, Write};
-use std::net::TcpStream;
-use std::os::unix::net::UnixStream;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::sync::mpsc;
 use std::time::Duration;
 
 use serde::Deserialize;
 
+use aether_common::health::Target;
+
+use crate::conn;
+use crate::feed::{Progress, Weather};
+
 /// Widget from brain response.
 #[derive(Clone, Debug, Deserialize)]
 pub struct Widget {
     #[serde(rename = "type")]
     pub widget_type: String,
+    #[serde(default)]
     pub title: String,
     #[serde(default)]
     pub lines: Vec<String>,
+    /// Set when a weather widget carries structured fields.
+    #[serde(flatten)]
+    pub weather: Option<Box<Weather>>,
+    /// Set when a progress widget carries its `value`.
+    #[serde(flatten)]
+    pub progress: Option<Box<Progress>>,
 }
 
 /// Brain response from the brain server.
@@ -27,6 +38,88 @@ pub struct BrainResponse {
     pub latency_ms: u64,
     #[serde(default)]
     pub error: Option<String>,
+    /// Optional reasoning/chain-of-thought, kept apart from the answer text.
+    #[serde(default)]
+    pub reasoning: Option<String>,
+}
+
+/// Incremental output from `query_brain_streaming`.
+#[derive(Clone, Debug)]
+pub enum BrainStreamEvent {
+    /// More answer text, to append to what arrived so far.
+    Delta(String),
+    /// The complete response; always the last event.
+    Done(BrainResponse),
+}
+
+/// How often to retry connecting to aurorad, which may not be up yet at boot.
+/// Only the connect is retried: once connected, an error body is final.
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    pub base_delay_ms: u64,
+    pub max_delay_ms: u64,
+    /// Environment variable that overrides `attempts`.
+    pub attempts_env: &'static str,
+}
+
+/// Interactive queries. `NEBULA_BRAIN_RETRIES` overrides the attempt count.
+pub const QUERY_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 4,
+    base_delay_ms: 250,
+    max_delay_ms: 4000,
+    attempts_env: "NEBULA_BRAIN_RETRIES",
+};
+
+/// Proactive checks run on a timer anyway, so give up sooner.
+/// `NEBULA_PROACTIVE_RETRIES` overrides the attempt count.
+pub const PROACTIVE_RETRY: RetryPolicy = RetryPolicy {
+    attempts: 2,
+    base_delay_ms: 500,
+    max_delay_ms: 2000,
+    attempts_env: "NEBULA_PROACTIVE_RETRIES",
+};
+
+impl RetryPolicy {
+    fn with_env_override(self) -> Self {
+        let attempts = std::env::var(self.attempts_env)
+            .ok()
+            .and_then(|v| v.parse::<u32>().ok())
+            .unwrap_or(self.attempts);
+        Self { attempts: attempts.max(1), ..self }
+    }
+
+    /// Exponential backoff for the given (0-based) retry, capped, with up to
+    /// 50% jitter so clients started together don't retry in lockstep.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exp = self.base_delay_ms.saturating_mul(1u64 << retry.min(16));
+        let capped = exp.min(self.max_delay_ms);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as u64)
+            .unwrap_or(0);
+        let jitter = if capped > 1 { nanos % (capped / 2).max(1) } else { 0 };
+        Duration::from_millis(capped - jitter)
+    }
+}
+
+/// Run `connect` until it succeeds or the policy's attempts run out.
+fn connect_with_retry<T>(policy: RetryPolicy, mut connect: impl FnMut() -> std::io::Result<T>) -> Result<T, String> {
+    let policy = policy.with_env_override();
+    let mut attempt = 1;
+    loop {
+        match connect() {
+            Ok(stream) => return Ok(stream),
+            Err(e) if attempt >= policy.attempts => {
+                let plural = if attempt == 1 { "" } else { "s" };
+                return Err(format!("connect: {e} (after {attempt} attempt{plural})"));
+            }
+            Err(_) => {
+                std::thread::sleep(policy.delay(attempt - 1));
+                attempt += 1;
+            }
+        }
+    }
 }
 
 /// How to reach aurorad (which forwards brain queries).
@@ -51,45 +144,116 @@ pub fn query_brain(input: &str) -> Result<BrainResponse, String> {
     });
     let body_str = body.to_string();
 
+    let target = Target::from_addr(&addr);
+    let resp_body = conn::request(&target, "POST", "/v0/jobs", &body_str, Duration::from_secs(90), || {
+        connect_with_retry(QUERY_RETRY, || conn::connect(&target))
+    })?;
+
+    parse_brain_body(resp_body)
+}
+
+/// Stream a brain query via aurorad's `brain_stream` job. aurorad relays
+/// newline-delimited JSON from the brain: `{"delta":"..."}` lines, then a
+/// final `{"done":true, ...}` carrying the full `BrainResponse` fields.
+/// If the server answers with a plain JSON body instead, it is sent as a
+/// single `Done`.
+pub fn query_brain_streaming(input: &str, tx: &mpsc::Sender<BrainStreamEvent>) -> Result<(), String> {
+    let addr = aurorad_addr();
+    let body = serde_json::json!({
+        "job_type": "brain_stream",
+        "input": input
+    });
+    let body_str = body.to_string();
+
     let request = format!(
         "POST /v0/jobs HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
         body_str.len(), body_str
     );
 
-    // Try TCP connection to aurorad
-    let resp_body = if addr.contains(':') && !addr.starts_with('/') {
-        let mut stream = TcpStream::connect(&addr).map_err(|e| format!("connect: {e}"))?;
-        stream.set_read_timeout(Some(Duration::from_secs(90))).ok();
-        stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
-        stream.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
-
-        let mut resp = Vec::new();
-        let mut buf = [0u8; 4096];
-        loop {
-            match stream.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => resp.extend_from_slice(&buf[..n]),
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock
-                    || e.kind() == std::io::ErrorKind::TimedOut => break,
-                Err(e) => return Err(format!("read: {e}")),
-            }
+    let target = Target::from_addr(&addr);
+    let mut stream = connect_with_retry(QUERY_RETRY, || conn::connect(&target))?;
+    stream.set_timeouts(Duration::from_secs(90), Duration::from_secs(5));
+    stream.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
+    let mut reader = BufReader::new(stream);
+
+    // Headers: only the framing, content type and request ID matter
+    let mut ndjson = false;
+    let mut chunked = false;
+    let mut request_id = None;
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => return Err("connection closed before headers".to_string()),
+            Ok(_) => {}
+            Err(e) => return Err(format!("read: {e}")),
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
         }
-        let resp_str = String::from_utf8_lossy(&resp).to_string();
-        extract_body(&resp_str)
-    } else {
-        let mut stream = UnixStream::connect(&addr).map_err(|e| format!("connect: {e}"))?;
-        stream.set_read_timeout(Some(Duration::from_secs(90))).ok();
-        stream.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
-        let mut resp = String::new();
-        stream.read_to_string(&mut resp).map_err(|e| format!("read: {e}"))?;
-        extract_body(&resp)
-    };
+        let lower = line.to_ascii_lowercase();
+        if lower.starts_with("content-type:") && lower.contains("ndjson") {
+            ndjson = true;
+        }
+        if lower.starts_with("transfer-encoding:") && lower.contains("chunked") {
+            chunked = true;
+        }
+        if lower.starts_with("x-request-id:") {
+            request_id = Some(line["x-request-id:".len()..].trim().to_string());
+        }
+    }
+
+    if !ndjson {
+        let mut rest = Vec::new();
+        reader.read_to_end(&mut rest).map_err(|e| format!("read: {e}"))?;
+        if chunked {
+            rest = crate::http::dechunk(&rest).0;
+        }
+        let body = String::from_utf8_lossy(&rest).to_string();
+        let _ = tx.send(BrainStreamEvent::Done(parse_brain_body(body)?));
+        return Ok(());
+    }
+
+    for line in reader.lines() {
+        let line = line.map_err(|e| format!("read: {e}"))?;
+        let Ok(event) = serde_json::from_str::<serde_json::Value>(&line) else {
+            continue;
+        };
+        if let Some(delta) = event.get("delta").and_then(|d| d.as_str()) {
+            let _ = tx.send(BrainStreamEvent::Delta(delta.to_string()));
+        } else if event.get("done").and_then(|d| d.as_bool()).unwrap_or(false) {
+            let resp = match serde_json::from_value::<BrainResponse>(event.clone()) {
+                Ok(resp) if resp.ok => resp,
+                Ok(resp) => return Err(with_request_id(resp.error.unwrap_or(resp.text), request_id.as_deref())),
+                Err(e) => return Err(with_request_id(format!("parse: {e}"), request_id.as_deref())),
+            };
+            let _ = tx.send(BrainStreamEvent::Done(resp));
+            return Ok(());
+        }
+    }
+    Err(with_request_id("stream ended before the response was done".to_string(), request_id.as_deref()))
+}
+
+/// `error` tagged with the ID aurorad logged the request under, so it can
+/// be grepped for across the aurorad, cfcd and brain logs.
+fn with_request_id(error: String, request_id: Option<&str>) -> String {
+    match request_id {
+        Some(id) => format!("{error} (request {id})"),
+        None => error,
+    }
+}
 
+/// Turn an aurorad `/v0/jobs` body (or a bare brain body) into a response.
+fn parse_brain_body(resp_body: String) -> Result<BrainResponse, String> {
     // Parse the aurorad job response — brain result is nested in "result"
     if let Ok(job_resp) = serde_json::from_str::<serde_json::Value>(&resp_body) {
+        let request_id = job_resp.get("request_id").and_then(|id| id.as_str());
         if let Some(result) = job_resp.get("result") {
             // The brain response is inside the "result" field
             if let Ok(brain) = serde_json::from_value::<BrainResponse>(result.clone()) {
+                if !brain.ok {
+                    return Err(with_request_id(brain.error.unwrap_or(brain.text), request_id));
+                }
                 return Ok(brain);
             }
             // If it has a "text" field directly
@@ -97,12 +261,16 @@ pub fn query_brain(input: &str) -> Result<BrainResponse, String> {
                 let widgets: Vec<Widget> = result.get("widgets")
                     .and_then(|w| serde_json::from_value(w.clone()).ok())
                     .unwrap_or_default();
+                let reasoning = result.get("reasoning")
+                    .and_then(|r| r.as_str())
+                    .map(|r| r.to_string());
                 return Ok(BrainResponse {
                     ok: true,
                     text: text.to_string(),
                     widgets,
                     latency_ms: 0,
                     error: None,
+                    reasoning,
                 });
             }
             // Raw result
@@ -113,11 +281,12 @@ pub fn query_brain(input: &str) -> Result<BrainResponse, String> {
                 widgets: vec![],
                 latency_ms: 0,
                 error: None,
+                reasoning: None,
             });
         }
         // Check for error at job level
         if let Some(err) = job_resp.get("error").and_then(|e| e.as_str()) {
-            return Err(err.to_string());
+            return Err(with_request_id(err.to_string(), request_id));
         }
     }
 
@@ -130,6 +299,7 @@ pub fn query_brain(input: &str) -> Result<BrainResponse, String> {
             widgets: vec![],
             latency_ms: 0,
             error: None,
+            reasoning: None,
         }),
     }
 }
@@ -205,38 +375,10 @@ pub fn query_brain_proactive(context: &ProactiveContext) -> Result<ProactiveResp
     });
     let body_str = body.to_string();
 
-    let request = format!(
-        "POST /v0/jobs HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        body_str.len(), body_str
-    );
-
-    let resp_body = if addr.contains(':') && !addr.starts_with('/') {
-        let mut stream = TcpStream::connect(&addr).map_err(|e| format!("connect: {e}"))?;
-        stream.set_read_timeout(Some(Duration::from_secs(45))).ok();
-        stream.set_write_timeout(Some(Duration::from_secs(5))).ok();
-        stream.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
-
-        let mut resp = Vec::new();
-        let mut buf = [0u8; 4096];
-        loop {
-            match stream.read(&mut buf) {
-                Ok(0) => break,
-                Ok(n) => resp.extend_from_slice(&buf[..n]),
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock
-                    || e.kind() == std::io::ErrorKind::TimedOut => break,
-                Err(e) => return Err(format!("read: {e}")),
-            }
-        }
-        let resp_str = String::from_utf8_lossy(&resp).to_string();
-        extract_body(&resp_str)
-    } else {
-        let mut stream = UnixStream::connect(&addr).map_err(|e| format!("connect: {e}"))?;
-        stream.set_read_timeout(Some(Duration::from_secs(45))).ok();
-        stream.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
-        let mut resp = String::new();
-        stream.read_to_string(&mut resp).map_err(|e| format!("read: {e}"))?;
-        extract_body(&resp)
-    };
+    let target = Target::from_addr(&addr);
+    let resp_body = conn::request(&target, "POST", "/v0/jobs", &body_str, Duration::from_secs(45), || {
+        connect_with_retry(PROACTIVE_RETRY, || conn::connect(&target))
+    })?;
 
     // Parse the aurorad job response — proactive result is nested in "result"
     if let Ok(job_resp) = serde_json::from_str::<serde_json::Value>(&resp_body) {
@@ -254,10 +396,55 @@ pub fn query_brain_proactive(context: &ProactiveContext) -> Result<ProactiveResp
     }
 }
 
-fn extract_body(resp: &str) -> String {
-    if let Some(idx) = resp.find("\r\n\r\n") {
-        resp[idx + 4..].to_string()
-    } else {
-        resp.to_string()
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(result: serde_json::Value) -> String {
+        serde_json::json!({ "ok": true, "result": result }).to_string()
+    }
+
+    #[test]
+    fn each_policy_reads_its_own_attempts_knob() {
+        std::env::set_var("NEBULA_BRAIN_RETRIES", "9");
+        assert_eq!(QUERY_RETRY.with_env_override().attempts, 9);
+        assert_eq!(PROACTIVE_RETRY.with_env_override().attempts, PROACTIVE_RETRY.attempts);
+        std::env::remove_var("NEBULA_BRAIN_RETRIES");
+    }
+
+    #[test]
+    fn reasoning_is_kept_apart_from_text() {
+        let body = job(serde_json::json!({
+            "ok": true,
+            "text": "It's 18C.",
+            "reasoning": "Looked up the forecast.\nPicked the current hour.",
+        }));
+        let resp = parse_brain_body(body).unwrap();
+        assert_eq!(resp.text, "It's 18C.");
+        assert_eq!(resp.reasoning.as_deref(), Some("Looked up the forecast.\nPicked the current hour."));
+    }
+
+    #[test]
+    fn reasoning_is_optional() {
+        let resp = parse_brain_body(job(serde_json::json!({ "ok": true, "text": "Hi" }))).unwrap();
+        assert_eq!(resp.text, "Hi");
+        assert_eq!(resp.reasoning, None);
+    }
+
+    #[test]
+    fn empty_reasoning_parses_as_empty() {
+        let body = job(serde_json::json!({ "ok": true, "text": "Hi", "reasoning": "" }));
+        let resp = parse_brain_body(body).unwrap();
+        assert_eq!(resp.reasoning.as_deref(), Some(""));
+        assert_eq!(resp.reasoning.as_deref().map(|r| r.lines().count()), Some(0));
+    }
+
+    #[test]
+    fn reasoning_without_ok_still_parses() {
+        // Older brains leave out "ok"; the text fallback keeps reasoning too
+        let body = job(serde_json::json!({ "text": "Hi", "reasoning": "because" }));
+        let resp = parse_brain_body(body).unwrap();
+        assert_eq!(resp.text, "Hi");
+        assert_eq!(resp.reasoning.as_deref(), Some("because"));
     }
 }
@@ -0,0 +1,18 @@
+use std::io::Write;
+
+use aether_common::base64;
+
+/// Copy `text` to the clipboard. Uses wl-copy or xclip when a display server
+/// is around, otherwise an OSC 52 escape so the terminal on the other end of
+/// a serial console or SSH session takes it. Returns how it was copied.
+pub fn copy(text: &str) -> Result<&'static str, String> {
+    if let Some(tool) = aether_common::clipboard::copy(text) {
+        return Ok(tool);
+    }
+
+    let mut out = std::io::stdout();
+    write!(out, "\x1b]52;c;{}\x07", base64::encode(text.as_bytes()))
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("write OSC 52: {e}"))?;
+    Ok("OSC 52")
+}
@@ -1,5 +1,18 @@
 use crate::telemetry::SysTelemetry;
-use std::process::Command;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read};
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// A foreground `!` command blocks the UI, so it is killed after this long.
+/// Background `&!` tasks have their own, longer timeout.
+const SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+/// Output kept from a foreground `!` command before it is killed. Also the
+/// longest single line any shell command may print before it is killed.
+const SHELL_MAX_BYTES: usize = 64 * 1024;
 
 /// Help text for local commands.
 pub fn help_text() -> String {
@@ -18,6 +31,20 @@ pub fn help_text() -> String {
         "",
         "  Local commands:",
         "    sysinfo     System telemetry dashboard",
+        "    history cpu CPU usage chart (also: history mem)",
+        "    /filter     Hide a source: /filter brain off (also: on, clear)",
+        "    /theme      Color theme: /theme light (dark, high-contrast, light, colorblind)",
+        "    /group off  Show every card separately (/group on folds runs again)",
+        "    /proactive  Polling intervals: /proactive interval brain 300 (also world, cooldown)",
+        "    /away on    Summarize what arrived after 10 idle min (/away on 30, /away off)",
+        "    /export     Save the feed as Markdown: /export session.md",
+        "    /alias      Define a shortcut: /alias w=&weather in Tokyo",
+        "    /unalias w  Remove a shortcut (bare /alias lists them)",
+        "    /mute       Silence an alert: /mute HighCpu 1h (no time: until /unmute)",
+        "    /unmute     Unsilence: /unmute HighCpu, /unmute all (bare /mute lists)",
+        "    /shutdown   Power off, after confirming (also: /reboot; /cancel stops it)",
+        "    learn on    Resume world-model learning (also: learn off)",
+        "    save weights  Persist world-model weights",
         "    help        This help screen",
         "    clear       Clear output",
         "    exit        Exit Nebula",
@@ -27,21 +54,154 @@ pub fn help_text() -> String {
         "    !ls -la     Any command prefixed with !",
         "",
         "  Navigation:
-    Tab         Switch panels (Input/Feed/Sidebar)
+    Tab         Complete command/history (switch panels when input is empty)
     Esc         Return to input
+    Alt+\u{2190}\u{2192}      Move by word (also Ctrl); Ctrl+W deletes a word
     \u{2191}\u{2193}          History (input) / Select card (feed)
     j/k         Select card (in feed panel)
-    Enter       Collapse/expand card (in feed)
-    d           Dismiss card (in feed)
+    Enter       Collapse/expand card or group (in feed)
+    r           Show/hide reasoning (in feed)
+    d           Dismiss card or group (in feed)
+    u           Undo last dismiss (in feed)
+    p           Pin/unpin card (in feed)
+    y           Copy card to clipboard (in feed)
+    j/k         Select task (in sidebar)
+    x           Cancel task (in sidebar)
     PgUp/PgDn   Scroll",
         "",
     ]
     .join("\n")
 }
 
+/// Local commands offered by Tab completion.
+const LOCAL_COMMANDS: &[&str] = &[
+    "help",
+    "clear",
+    "sysinfo",
+    "tasks",
+    "learn on",
+    "learn off",
+    "save weights",
+    "history cpu",
+    "history mem",
+    "/group",
+    "/away",
+    "/proactive interval",
+    "/theme",
+    "/mute",
+    "/unmute",
+    "/shutdown",
+    "/reboot",
+    "/cancel",
+    "exit",
+];
+
+/// Candidates for a Tab in the omni-bar and which one is filled in.
+pub struct Completion {
+    pub candidates: Vec<String>,
+    /// `None` until the first cycle; the input holds their common prefix until then.
+    pub index: Option<usize>,
+}
+
+impl Completion {
+    /// Step to the next candidate, wrapping around.
+    pub fn next(&mut self) -> &str {
+        let next = self.index.map_or(0, |i| (i + 1) % self.candidates.len());
+        self.index = Some(next);
+        &self.candidates[next]
+    }
+}
+
+/// Local commands then history entries (newest first) starting with `prefix`.
+pub fn completions(prefix: &str, history: &[String]) -> Vec<String> {
+    let lower = prefix.to_lowercase();
+    let mut out: Vec<String> = LOCAL_COMMANDS
+        .iter()
+        .filter(|c| c.starts_with(&lower) && **c != lower)
+        .map(|c| c.to_string())
+        .collect();
+    for entry in history.iter().rev() {
+        if entry.starts_with(prefix) && entry != prefix && !out.contains(entry) {
+            out.push(entry.clone());
+        }
+    }
+    out
+}
+
+/// Longest prefix shared by every candidate.
+pub fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut len = first.len();
+    for c in &candidates[1..] {
+        len = first
+            .char_indices()
+            .zip(c.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8())
+            .min(len);
+    }
+    first[..len].to_string()
+}
+
+/// Expansions applied to one line before giving up, so `a=b` / `b=a` can't loop.
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Replace a leading alias token with its expansion, repeatedly, up to
+/// `MAX_ALIAS_DEPTH` times.
+pub fn expand_alias(line: &str, aliases: &BTreeMap<String, String>) -> String {
+    let mut line = line.to_string();
+    for _ in 0..MAX_ALIAS_DEPTH {
+        let (token, rest) = match line.split_once(char::is_whitespace) {
+            Some((token, rest)) => (token, rest.trim_start()),
+            None => (line.as_str(), ""),
+        };
+        let Some(expansion) = aliases.get(token) else {
+            break;
+        };
+        line = if rest.is_empty() {
+            expansion.clone()
+        } else {
+            format!("{} {}", expansion, rest)
+        };
+    }
+    line
+}
+
+/// The arguments after `name` when `line`'s first word is exactly `name`,
+/// so `/filterx` isn't taken for `/filter`.
+pub fn command_args<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let line = line.trim_start();
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    (word == name).then(|| rest.trim())
+}
+
+/// Parse a duration like `90s`, `15m`, `1h` or `2d`; a bare number is minutes.
+/// `None` for zero, an unknown unit, or a value too large to count in seconds.
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (num, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(i) => text.split_at(i),
+        None => (text, "m"),
+    };
+    let n: u64 = num.parse().ok()?;
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    let secs = n.checked_mul(scale)?;
+    (secs > 0).then(|| Duration::from_secs(secs))
+}
+
 /// System info formatted text.
 pub fn sysinfo_text(t: &SysTelemetry) -> String {
     let mem_used = t.mem_total_mb.saturating_sub(t.mem_avail_mb);
+    let disk_used = t.disk_total_mb.saturating_sub(t.disk_avail_mb);
     format!(
         "\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550} AETHER SYSTEM INFO \u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\n\
          Kernel:   {}\n\
@@ -49,6 +209,9 @@ pub fn sysinfo_text(t: &SysTelemetry) -> String {
          Uptime:   {}s\n\
          CPU:      {:.1}%\n\
          Memory:   {}/{}MB ({:.0}%)\n\
+         Swap:     {}/{}MB ({:.0}%)\n\
+         Load:     {:.2} {:.2} {:.2}\n\
+         Disk:     {}/{}MB ({:.0}%)\n\
          Procs:    {}\n\
          Network:  {}\n\
          \u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}\u{2550}",
@@ -59,30 +222,246 @@ pub fn sysinfo_text(t: &SysTelemetry) -> String {
         mem_used,
         t.mem_total_mb,
         if t.mem_total_mb > 0 { (mem_used as f64 / t.mem_total_mb as f64) * 100.0 } else { 0.0 },
+        t.swap_total_mb.saturating_sub(t.swap_free_mb),
+        t.swap_total_mb,
+        t.swap_used_pct(),
+        t.load_avg[0],
+        t.load_avg[1],
+        t.load_avg[2],
+        disk_used,
+        t.disk_total_mb,
+        if t.disk_total_mb > 0 { (disk_used as f64 / t.disk_total_mb as f64) * 100.0 } else { 0.0 },
         t.num_procs,
         t.ip_addr,
     )
 }
 
-/// Run a shell command and return output.
+/// Run a command line through `/bin/sh -c` (so pipes, redirects and globs
+/// work) and return stdout followed by stderr. Stops at `SHELL_MAX_BYTES` of
+/// output or after `SHELL_TIMEOUT`, noting which in the result.
 pub fn run_shell(cmd: &str) -> String {
-    match Command::new("/bin/sh").args(["-c", cmd]).output() {
-        Ok(output) => {
-            let mut result = String::new();
-            if !output.stdout.is_empty() {
-                result.push_str(&String::from_utf8_lossy(&output.stdout));
+    let cancel = Arc::new(AtomicBool::new(false));
+    let timer = Arc::clone(&cancel);
+    std::thread::spawn(move || {
+        std::thread::sleep(SHELL_TIMEOUT);
+        timer.store(true, Ordering::Relaxed);
+    });
+
+    let mut out = Vec::new();
+    let mut bytes = 0;
+    let mut capped = false;
+    let rest = run_shell_lines(cmd, &cancel, |line| {
+        if capped {
+            return;
+        }
+        bytes += line.len() + 1;
+        if bytes > SHELL_MAX_BYTES {
+            capped = true;
+            cancel.store(true, Ordering::Relaxed);
+        } else {
+            out.push(line);
+        }
+    });
+    match rest {
+        Some(mut rest) if !rest.is_empty() => {
+            // stderr counts against the same cap
+            let mut end = SHELL_MAX_BYTES.saturating_sub(bytes).min(rest.len());
+            while !rest.is_char_boundary(end) {
+                end -= 1;
             }
-            if !output.stderr.is_empty() {
-                if !result.is_empty() {
-                    result.push('\n');
+            rest.truncate(end);
+            out.push(rest);
+        }
+        Some(_) => {}
+        None if capped => out.push(format!("[output cut at {} KiB]", SHELL_MAX_BYTES / 1024)),
+        None => out.push(format!("[killed after {}s]", SHELL_TIMEOUT.as_secs())),
+    }
+    out.join("\n").trim_end().to_string()
+}
+
+/// Run a shell command, handing each stdout line to `on_line` as it arrives
+/// and killing the child if `cancel` is set. Returns the stderr output (or a
+/// failure message), or `None` when cancelled. Only the first
+/// `SHELL_MAX_BYTES` of stderr are kept, and a stdout line longer than that
+/// is cut there and the child killed, so output without newlines can't fill
+/// memory.
+///
+/// The shell leads its own process group, so cancelling kills whatever it
+/// started too (`sleep 60 | cat`, `foo &`), not just `sh`.
+pub fn run_shell_lines(cmd: &str, cancel: &AtomicBool, mut on_line: impl FnMut(String)) -> Option<String> {
+    let mut child = match Command::new("/bin/sh")
+        .args(["-c", cmd])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return Some(format!("Failed to execute: {}", e)),
+    };
+
+    // Read both pipes on their own threads so a chatty child can't fill one and block
+    let (line_tx, line_rx) = mpsc::channel();
+    let overflowed = Arc::new(AtomicBool::new(false));
+    if let Some(stdout) = child.stdout.take() {
+        let overflowed = Arc::clone(&overflowed);
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            let mut buf = Vec::new();
+            while matches!((&mut reader).take(SHELL_MAX_BYTES as u64 + 1).read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+                let too_long = buf.len() > SHELL_MAX_BYTES;
+                buf.truncate(SHELL_MAX_BYTES);
+                let line = String::from_utf8_lossy(&buf).trim_end_matches(['\n', '\r']).to_string();
+                if line_tx.send(line).is_err() {
+                    break;
                 }
-                result.push_str(&String::from_utf8_lossy(&output.stderr));
+                if too_long {
+                    overflowed.store(true, Ordering::Relaxed);
+                    break;
+                }
+                buf.clear();
             }
-            if result.is_empty() && !output.status.success() {
-                result = format!("Command failed with exit code {}", output.status.code().unwrap_or(-1));
+        });
+    }
+    let stderr = child.stderr.take().map(|mut pipe| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            let _ = (&mut pipe).take(SHELL_MAX_BYTES as u64).read_to_end(&mut buf);
+            // Keep draining so the child doesn't block on a full pipe
+            let _ = std::io::copy(&mut pipe, &mut std::io::sink());
+            buf
+        })
+    });
+
+    let kill = |child: &mut std::process::Child| {
+        // The group id is the shell's pid
+        unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+        let _ = child.kill();
+        let _ = child.wait();
+    };
+    let mut saw_output = false;
+    let status = loop {
+        if cancel.load(Ordering::Relaxed) {
+            kill(&mut child);
+            return None;
+        }
+        if overflowed.load(Ordering::Relaxed) {
+            kill(&mut child);
+            break None;
+        }
+        match line_rx.recv_timeout(Duration::from_millis(50)) {
+            Ok(line) => {
+                saw_output = true;
+                on_line(line);
+                continue;
             }
-            result.trim_end().to_string()
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            // stdout closed; keep polling for the exit status without spinning
+            Err(mpsc::RecvTimeoutError::Disconnected) => std::thread::sleep(Duration::from_millis(50)),
+        }
+        match child.try_wait() {
+            Ok(Some(status)) => break Some(status),
+            Ok(None) => {}
+            Err(e) => return Some(format!("Failed to wait: {}", e)),
+        }
+    };
+    // The output thread may still hold lines read just before exit
+    for line in line_rx.try_iter() {
+        saw_output = true;
+        on_line(line);
+    }
+    // Checked after an exit too: the shell may have died writing to the
+    // pipe the reader dropped at the cut, leaving the rest of its group
+    if overflowed.load(Ordering::Relaxed) {
+        if status.is_some() {
+            kill(&mut child);
         }
-        Err(e) => format!("Failed to execute: {}", e),
+        return (!cancel.load(Ordering::Relaxed))
+            .then(|| format!("[line cut at {} KiB, command stopped]", SHELL_MAX_BYTES / 1024));
+    }
+    let status = status?;
+
+    let stderr = stderr.and_then(|h| h.join().ok()).unwrap_or_default();
+    let mut result = String::from_utf8_lossy(&stderr).trim_end().to_string();
+    if result.is_empty() && !saw_output && !status.success() {
+        result = format!("Command failed with exit code {}", status.code().unwrap_or(-1));
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_args_match_the_whole_word() {
+        assert_eq!(command_args("/filter brain off", "/filter"), Some("brain off"));
+        assert_eq!(command_args("/filter", "/filter"), Some(""));
+        assert_eq!(command_args("  /away   on 15 ", "/away"), Some("on 15"));
+        assert_eq!(command_args("/filterx", "/filter"), None);
+        assert_eq!(command_args("/awayfoo on", "/away"), None);
+        assert_eq!(command_args("/unmute all", "/mute"), None);
+    }
+
+    /// Whether `pid` has exited (gone, or a zombie waiting to be reaped).
+    fn exited(pid: &str) -> bool {
+        match std::fs::read_to_string(format!("/proc/{pid}/stat")) {
+            Ok(stat) => stat.rsplit_once(") ").is_some_and(|(_, rest)| rest.starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    #[test]
+    fn cancel_kills_the_whole_process_group() {
+        let cancel = AtomicBool::new(false);
+        let mut pid = String::new();
+        let rest = run_shell_lines("sleep 30 & echo $!; wait", &cancel, |line| {
+            pid = line;
+            cancel.store(true, Ordering::Relaxed);
+        });
+        assert_eq!(rest, None);
+        let deadline = std::time::Instant::now() + Duration::from_secs(2);
+        while !exited(&pid) && std::time::Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        assert!(exited(&pid), "background sleep {pid} outlived the cancel");
+    }
+
+    #[test]
+    fn stderr_is_capped() {
+        let cancel = AtomicBool::new(false);
+        let cmd = format!("head -c {} /dev/zero | tr '\\0' x >&2", SHELL_MAX_BYTES * 4);
+        let rest = run_shell_lines(&cmd, &cancel, |_| {}).unwrap();
+        assert_eq!(rest.len(), SHELL_MAX_BYTES);
+    }
+
+    #[test]
+    fn endless_line_is_cut_and_the_command_stopped() {
+        let cancel = AtomicBool::new(false);
+        let mut lines = Vec::new();
+        let started = std::time::Instant::now();
+        let rest = run_shell_lines("cat /dev/zero", &cancel, |line| lines.push(line));
+        assert!(started.elapsed() < Duration::from_secs(5), "took {:?}", started.elapsed());
+        assert_eq!(rest.as_deref(), Some("[line cut at 64 KiB, command stopped]"));
+        assert_eq!(lines.concat().len(), SHELL_MAX_BYTES);
+
+        assert!(run_shell("cat /dev/zero").ends_with("[output cut at 64 KiB]"));
+    }
+
+    #[test]
+    fn parse_duration_scales_by_unit() {
+        assert_eq!(parse_duration("90s"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration("15"), Some(Duration::from_secs(900)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(2 * 86400)));
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("5w"), None);
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert_eq!(parse_duration(&format!("{}s", u64::MAX)), Some(Duration::from_secs(u64::MAX)));
+        assert_eq!(parse_duration(&format!("{}m", u64::MAX)), None);
+        assert_eq!(parse_duration(&format!("{}d", u64::MAX / 86400 + 1)), None);
     }
 }
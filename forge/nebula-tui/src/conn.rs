@@ -0,0 +1,130 @@
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use aether_common::health::Target;
+
+/// Open a connection to `target`.
+pub fn connect(target: &Target) -> io::Result<Conn> {
+    match target {
+        Target::Unix(path) => UnixStream::connect(path).map(Conn::Unix),
+        Target::Tcp(host) => TcpStream::connect(host).map(Conn::Tcp),
+    }
+}
+
+pub enum Conn {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl Conn {
+    pub fn set_timeouts(&self, read: Duration, write: Duration) {
+        match self {
+            Conn::Unix(s) => {
+                s.set_read_timeout(Some(read)).ok();
+                s.set_write_timeout(Some(write)).ok();
+            }
+            Conn::Tcp(s) => {
+                s.set_read_timeout(Some(read)).ok();
+                s.set_write_timeout(Some(write)).ok();
+            }
+        }
+    }
+}
+
+impl Read for Conn {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Conn::Unix(s) => s.read(buf),
+            Conn::Tcp(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Conn {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Conn::Unix(s) => s.write(buf),
+            Conn::Tcp(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Conn::Unix(s) => s.flush(),
+            Conn::Tcp(s) => s.flush(),
+        }
+    }
+}
+
+/// At most one idle keep-alive connection per target. Requests running
+/// at the same time simply open their own; whichever finishes first is kept.
+static IDLE: Mutex<Vec<(Target, Conn)>> = Mutex::new(Vec::new());
+
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn take_idle(target: &Target) -> Option<Conn> {
+    let mut idle = IDLE.lock().ok()?;
+    let pos = idle.iter().position(|(t, _)| t == target)?;
+    Some(idle.swap_remove(pos).1)
+}
+
+fn put_idle(target: &Target, conn: Conn) {
+    if let Ok(mut idle) = IDLE.lock() {
+        if !idle.iter().any(|(t, _)| t == target) {
+            idle.push((target.clone(), conn));
+        }
+    }
+}
+
+/// Send one request over a pooled keep-alive connection and return the
+/// response body. If the pooled connection turns out to be closed, the
+/// request is resent on a fresh one from `connect`. A response that can't
+/// be framed, or that closes the connection, just isn't pooled.
+pub fn request(
+    target: &Target,
+    method: &str,
+    path: &str,
+    body: &str,
+    read_timeout: Duration,
+    connect: impl FnOnce() -> Result<Conn, String>,
+) -> Result<String, String> {
+    let request = if method == "GET" {
+        format!("{method} {path} HTTP/1.1\r\nHost: localhost\r\n\r\n")
+    } else {
+        format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        )
+    };
+
+    if let Some(mut conn) = take_idle(target) {
+        match exchange(&mut conn, &request, read_timeout) {
+            Ok((body, reusable)) => {
+                if reusable {
+                    put_idle(target, conn);
+                }
+                return Ok(body);
+            }
+            // Closed while idle, so the request never ran: reconnect and resend
+            Err(e) if e == crate::http::CLOSED_BEFORE_RESPONSE || e.starts_with("write:") => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut conn = connect()?;
+    let (body, reusable) = exchange(&mut conn, &request, read_timeout)?;
+    if reusable {
+        put_idle(target, conn);
+    }
+    Ok(body)
+}
+
+/// Write the request and read back (body, whether the connection is reusable).
+fn exchange(conn: &mut Conn, request: &str, read_timeout: Duration) -> Result<(String, bool), String> {
+    conn.set_timeouts(read_timeout, WRITE_TIMEOUT);
+    conn.write_all(request.as_bytes()).map_err(|e| format!("write: {e}"))?;
+    crate::http::read_response_reusable(conn)
+}
@@ -1,8 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Write;
 use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
 
+use crate::feed::FeedSource;
+use crate::telemetry::AlertKind;
+
 /// Tracks user session context for smarter proactive intelligence.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SessionContext {
@@ -14,6 +18,25 @@ pub struct SessionContext {
     pub query_count: u32,
     /// Last few queries (up to 10).
     pub recent_queries: Vec<String>,
+    /// Feed sources hidden with `/filter`.
+    #[serde(default)]
+    pub hidden_sources: HashSet<FeedSource>,
+    /// Show every card on its own instead of grouping runs (`/group off`).
+    #[serde(default)]
+    pub flat_feed: bool,
+    /// Color theme picked with `/theme`.
+    #[serde(default)]
+    pub theme: Option<String>,
+    /// Command aliases defined with `/alias`.
+    #[serde(default)]
+    pub aliases: BTreeMap<String, String>,
+    /// Alert kinds silenced with `/mute`: unix-seconds expiry, or `None` for good.
+    #[serde(default)]
+    pub muted_alerts: BTreeMap<AlertKind, Option<u64>>,
+    /// Idle minutes before a "While you were away" summary, set with `/away on`.
+    /// `None` leaves summaries off.
+    #[serde(default)]
+    pub away_summary_mins: Option<u64>,
     /// Session start time (not serialized — set on load).
     #[serde(skip)]
     pub session_start: Option<Instant>,
@@ -24,6 +47,12 @@ pub struct SessionContext {
 
 const SESSION_FILE: &str = "/tmp/aether_session.json";
 const SAVE_INTERVAL_SECS: u64 = 60;
+/// Omni-bar command history, one entry per line, oldest first.
+const HISTORY_FILE: &str = "/tmp/aether_history";
+/// Entries of command history kept; `NEBULA_HISTORY_SIZE` overrides.
+const DEFAULT_HISTORY_SIZE: usize = 500;
+/// Commands containing these are kept out of the history file.
+const SECRET_MARKERS: &[&str] = &["password", "passwd", "token", "secret", "api_key"];
 
 /// Stop words that don't count as topics.
 const STOP_WORDS: &[&str] = &[
@@ -51,6 +80,12 @@ impl SessionContext {
             dismissed_categories: Vec::new(),
             query_count: 0,
             recent_queries: Vec::new(),
+            hidden_sources: HashSet::new(),
+            flat_feed: false,
+            theme: None,
+            aliases: BTreeMap::new(),
+            muted_alerts: BTreeMap::new(),
+            away_summary_mins: None,
             session_start: Some(Instant::now()),
             last_save: None,
         }
@@ -80,7 +115,7 @@ impl SessionContext {
     }
 
     /// Force save to disk.
-    fn save(&mut self) {
+    pub fn save(&mut self) {
         if let Ok(data) = serde_json::to_string(self) {
             let _ = std::fs::write(SESSION_FILE, data);
         }
@@ -131,3 +166,36 @@ impl SessionContext {
         entries.into_iter().take(n).map(|(k, _)| k.clone()).collect()
     }
 }
+
+/// Maximum command history length.
+pub fn history_size() -> usize {
+    std::env::var("NEBULA_HISTORY_SIZE")
+        .ok()
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(DEFAULT_HISTORY_SIZE)
+}
+
+/// Load saved command history, keeping the newest `max` entries and
+/// rewriting the file if it had grown past that.
+pub fn load_history(max: usize) -> Vec<String> {
+    let Ok(data) = std::fs::read_to_string(HISTORY_FILE) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<String> = data.lines().filter(|l| !l.is_empty()).map(String::from).collect();
+    if entries.len() > max {
+        entries.drain(..entries.len() - max);
+        let _ = std::fs::write(HISTORY_FILE, entries.join("\n") + "\n");
+    }
+    entries
+}
+
+/// Append a command to the history file, unless it looks like it holds a secret.
+pub fn append_history(entry: &str) {
+    let lower = entry.to_lowercase();
+    if SECRET_MARKERS.iter().any(|m| lower.contains(m)) {
+        return;
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(HISTORY_FILE) {
+        let _ = writeln!(file, "{}", entry);
+    }
+}
@@ -0,0 +1,79 @@
+use crate::feed::{FeedItem, FeedSource};
+
+/// Render feed items as a Markdown transcript: user prompts as quotes,
+/// everything else as prose, widgets as fenced blocks.
+pub fn to_markdown(items: &[&FeedItem]) -> String {
+    let mut out = String::from("# AetherOS session\n\n");
+    out.push_str(&format!("_Exported {}_\n", format_time(chrono::Local::now().timestamp() as u64)));
+
+    for item in items {
+        out.push('\n');
+        let when = format_time(item.timestamp);
+        if item.source == FeedSource::User {
+            let prompt = item.title.strip_prefix("> ").unwrap_or(&item.title);
+            out.push_str(&format!("**You** \u{00b7} {}\n\n> {}\n", when, prompt));
+            continue;
+        }
+
+        out.push_str(&format!("### {} \u{00b7} {} \u{00b7} {}\n", item.title, item.source.label(), when));
+        if !item.body.is_empty() {
+            // Markdown joins adjacent lines, so keep line breaks explicit
+            out.push('\n');
+            for line in &item.body {
+                if line.trim().is_empty() {
+                    out.push('\n');
+                } else {
+                    out.push_str(line);
+                    out.push_str("  \n");
+                }
+            }
+        }
+        if let Some(widget) = &item.widget {
+            out.push_str(&format!("\n**{}**\n\n```\n", widget.title));
+            for line in &widget.lines {
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str("```\n");
+        }
+        if !item.reasoning.is_empty() {
+            out.push_str("\n<details><summary>Reasoning</summary>\n\n");
+            for line in &item.reasoning {
+                out.push_str(line);
+                out.push('\n');
+            }
+            out.push_str("\n</details>\n");
+        }
+    }
+    out
+}
+
+/// Write an export, resolving a relative path under `$HOME` and falling
+/// back to /tmp if that can't be written. Returns the path and byte count.
+pub fn write_export(name: &str, contents: &str) -> Result<(String, usize), String> {
+    let path = std::path::Path::new(name);
+    let mut candidates = Vec::new();
+    if path.is_absolute() {
+        candidates.push(path.to_path_buf());
+    } else {
+        if let Some(home) = std::env::var_os("HOME") {
+            candidates.push(std::path::Path::new(&home).join(path));
+        }
+        candidates.push(std::path::Path::new("/tmp").join(path));
+    }
+
+    let mut last_err = String::new();
+    for candidate in candidates {
+        match std::fs::write(&candidate, contents) {
+            Ok(()) => return Ok((candidate.display().to_string(), contents.len())),
+            Err(e) => last_err = format!("{}: {}", candidate.display(), e),
+        }
+    }
+    Err(last_err)
+}
+
+fn format_time(unix_secs: u64) -> String {
+    chrono::DateTime::from_timestamp(unix_secs as i64, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
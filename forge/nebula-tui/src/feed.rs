@@ -1,15 +1,47 @@
-use std::time::Instant;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::ui::BlockColor;
 
+/// Most recent non-dismissed items kept when the feed is saved.
+const PERSIST_MAX_ITEMS: usize = 50;
+/// Where the feed used to live; read once as a fallback and migrated.
+const LEGACY_FEED_FILE: &str = "/tmp/aether_feed.json";
+/// Used when there's no `$HOME` to put the feed under.
+const SYSTEM_FEED_FILE: &str = "/var/lib/aether/feed.json";
+/// Shortest run of similar cards that gets folded under a group header.
+const MIN_GROUP_SIZE: usize = 3;
+
+/// `$HOME/.local/state/aether/feed.json`, or the system path without a home.
+/// Unlike /tmp, both survive a reboot.
+pub fn feed_path() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) if !home.is_empty() => PathBuf::from(home).join(".local/state/aether/feed.json"),
+        _ => PathBuf::from(SYSTEM_FEED_FILE),
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
 /// Source of a feed item.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum FeedSource {
     System,
     Brain,
     WorldModel,
     User,
     Task,
+    /// Posted to aurorad's `/v0/notify` by a daemon or facet.
+    Notification,
 }
 
 impl FeedSource {
@@ -21,6 +53,7 @@ impl FeedSource {
             FeedSource::WorldModel => "W",
             FeedSource::User => "U",
             FeedSource::Task => "T",
+            FeedSource::Notification => "N",
         }
     }
 
@@ -31,6 +64,32 @@ impl FeedSource {
             FeedSource::WorldModel => "World Model",
             FeedSource::User => "User",
             FeedSource::Task => "Task",
+            FeedSource::Notification => "Notification",
+        }
+    }
+
+    /// Parse a user-typed source name (as used by `/filter`).
+    pub fn parse(name: &str) -> Option<FeedSource> {
+        match name {
+            "system" => Some(FeedSource::System),
+            "brain" => Some(FeedSource::Brain),
+            "world" | "worldmodel" => Some(FeedSource::WorldModel),
+            "user" => Some(FeedSource::User),
+            "task" | "tasks" => Some(FeedSource::Task),
+            "notification" | "notifications" | "notify" => Some(FeedSource::Notification),
+            _ => None,
+        }
+    }
+
+    /// Plural noun for a group header: "3 System alerts".
+    pub fn group_noun(&self) -> &'static str {
+        match self {
+            FeedSource::System => "alerts",
+            FeedSource::Brain => "insights",
+            FeedSource::WorldModel => "updates",
+            FeedSource::User => "messages",
+            FeedSource::Task => "task updates",
+            FeedSource::Notification => "notifications",
         }
     }
 
@@ -41,12 +100,13 @@ impl FeedSource {
             FeedSource::WorldModel => BlockColor::Cyan,
             FeedSource::User => BlockColor::Blue,
             FeedSource::Task => BlockColor::White,
+            FeedSource::Notification => BlockColor::Magenta,
         }
     }
 }
 
 /// Priority level for feed items.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Priority {
     Urgent = 0,
     Normal = 1,
@@ -54,30 +114,151 @@ pub enum Priority {
 }
 
 /// Widget data embedded in a feed item.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct WidgetData {
     pub widget_type: String,
     pub title: String,
     pub lines: Vec<String>,
     pub color: BlockColor,
+    /// Structured fields of a "weather" widget; `lines` is shown without them.
+    #[serde(default)]
+    pub weather: Option<Box<Weather>>,
+    /// Fill of a "progress" widget.
+    #[serde(default)]
+    pub progress: Option<Box<Progress>>,
+}
+
+/// A "progress" widget's fill: `{"type":"progress","value":0.42,
+/// "label":"Downloading"}`. `value` runs from 0 to 1.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Progress {
+    pub value: f64,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl Progress {
+    /// The last percentage in a line of command output ("42%", "(7.5%)"),
+    /// as curl, wget and rsync print. Only words starting with a digit
+    /// (sizes, rates, times) or an ETA may follow it, so a report row like
+    /// df's `/dev/sda1 50G 45G 5G 90% /` isn't taken for progress.
+    pub fn from_output(line: &str) -> Option<Progress> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (at, pct) = words.iter().enumerate().rev().find_map(|(i, word)| {
+            let pct = word.trim_matches(|c: char| !c.is_ascii_digit() && c != '.' && c != '%');
+            let pct = pct.strip_suffix('%')?.parse::<f64>().ok()?;
+            (0.0..=100.0).contains(&pct).then_some((i, pct))
+        })?;
+        let trailing_ok = words[at + 1..]
+            .iter()
+            .all(|w| w.starts_with(|c: char| c.is_ascii_digit()) || w.eq_ignore_ascii_case("eta"));
+        trailing_ok.then(|| Progress { value: pct / 100.0, label: None })
+    }
+}
+
+/// Structured weather from the brain, alongside a widget's `lines`:
+/// `{"temp":"18°C","condition":"Partly cloudy","high":21,"low":12,
+/// "wind":"10 km/h NW","icon":"partly_cloudy","forecast":[…]}`.
+/// Only `temp` is required.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Weather {
+    #[serde(deserialize_with = "text_or_number")]
+    pub temp: String,
+    #[serde(default, alias = "desc")]
+    pub condition: String,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub high: Option<String>,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub low: Option<String>,
+    #[serde(default)]
+    pub wind: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub forecast: Vec<Forecast>,
+}
+
+/// One day of a weather forecast.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Forecast {
+    pub day: String,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub high: Option<String>,
+    #[serde(default, deserialize_with = "opt_text_or_number")]
+    pub low: Option<String>,
+    #[serde(default, alias = "condition")]
+    pub icon: Option<String>,
+}
+
+impl Weather {
+    pub fn glyph(&self) -> &'static str {
+        sky_glyph(self.icon.as_deref().unwrap_or(&self.condition))
+    }
+}
+
+/// A one-cell symbol for an icon name or condition ("rain", "Partly cloudy").
+pub fn sky_glyph(sky: &str) -> &'static str {
+    let sky = sky.to_lowercase();
+    let has = |words: &[&str]| words.iter().any(|w| sky.contains(w));
+    if has(&["thunder", "storm", "lightning"]) {
+        "\u{03df}"
+    } else if has(&["snow", "sleet", "hail", "ice"]) {
+        "\u{2744}"
+    } else if has(&["rain", "drizzle", "shower"]) {
+        "\u{2602}"
+    } else if has(&["fog", "mist", "haze", "smoke"]) {
+        "\u{2261}"
+    } else if has(&["cloud", "overcast"]) {
+        "\u{2601}"
+    } else if has(&["clear", "sun", "fair"]) {
+        "\u{2600}"
+    } else {
+        "\u{00b0}"
+    }
+}
+
+/// Brain replies give temperatures as text ("18°C") or bare numbers (18).
+fn text_or_number<'de, D: Deserializer<'de>>(d: D) -> Result<String, D::Error> {
+    match serde_json::Value::deserialize(d)? {
+        serde_json::Value::String(s) => Ok(s),
+        serde_json::Value::Number(n) => Ok(format!("{:.0}\u{00b0}", n.as_f64().unwrap_or(0.0))),
+        other => Err(D::Error::custom(format!("expected text or a number, got {}", other))),
+    }
+}
+
+fn opt_text_or_number<'de, D: Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    match serde_json::Value::deserialize(d)? {
+        serde_json::Value::Null => Ok(None),
+        v => text_or_number(v).map(Some).map_err(D::Error::custom),
+    }
 }
 
 /// A single item in the feed.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct FeedItem {
     pub id: u64,
     pub source: FeedSource,
     pub priority: Priority,
     pub title: String,
     pub body: Vec<String>,
+    /// Reasoning behind the body, hidden unless `reasoning_expanded`.
+    pub reasoning: Vec<String>,
+    pub reasoning_expanded: bool,
     pub widget: Option<WidgetData>,
-    pub timestamp: Instant,
+    /// Creation time in UNIX seconds, so ages survive a restart.
+    pub timestamp: u64,
     pub seen: bool,
     pub stale_after_secs: Option<u64>,
     pub collapsed: bool,
     pub dismissed: bool,
+    /// Pinned items are never evicted, expired or auto-replaced.
+    #[serde(default)]
+    pub pinned: bool,
     /// If set, a new item from this source auto-replaces the previous one.
     pub replaces_source: Option<FeedSource>,
+    /// Render the body as Markdown, as brain replies are written.
+    #[serde(default)]
+    pub markdown: bool,
 }
 
 impl FeedItem {
@@ -88,13 +269,17 @@ impl FeedItem {
             priority,
             title,
             body: Vec::new(),
+            reasoning: Vec::new(),
+            reasoning_expanded: false,
             widget: None,
-            timestamp: Instant::now(),
+            timestamp: now_unix(),
             seen: false,
             stale_after_secs: None,
             collapsed: false,
             dismissed: false,
+            pinned: false,
             replaces_source: None,
+            markdown: false,
         }
     }
 
@@ -103,6 +288,11 @@ impl FeedItem {
         self
     }
 
+    pub fn with_reasoning(mut self, lines: Vec<String>) -> Self {
+        self.reasoning = lines;
+        self
+    }
+
     pub fn with_widget(mut self, widget: WidgetData) -> Self {
         self.widget = Some(widget);
         self
@@ -118,10 +308,23 @@ impl FeedItem {
         self
     }
 
-    /// Whether this item has expired.
+    pub fn with_markdown(mut self) -> Self {
+        self.markdown = true;
+        self
+    }
+
+    /// Seconds since the item was created.
+    pub fn age_secs(&self) -> u64 {
+        now_unix().saturating_sub(self.timestamp)
+    }
+
+    /// Whether this item has expired. Pinned items never do.
     pub fn is_stale(&self) -> bool {
+        if self.pinned {
+            return false;
+        }
         if let Some(secs) = self.stale_after_secs {
-            self.timestamp.elapsed().as_secs() >= secs
+            self.age_secs() >= secs
         } else {
             false
         }
@@ -129,15 +332,17 @@ impl FeedItem {
 
     /// Human-readable relative timestamp.
     pub fn age_str(&self) -> String {
-        let secs = self.timestamp.elapsed().as_secs();
+        let secs = self.age_secs();
         if secs < 5 {
             "just now".to_string()
         } else if secs < 60 {
             format!("{}s ago", secs)
         } else if secs < 3600 {
             format!("{}m ago", secs / 60)
-        } else {
+        } else if secs < 86400 {
             format!("{}h ago", secs / 3600)
+        } else {
+            format!("{}d ago", secs / 86400)
         }
     }
 }
@@ -158,31 +363,86 @@ impl FeedStore {
         }
     }
 
+    /// Load a saved feed, falling back to one left in /tmp by older builds,
+    /// and drop anything that went stale while we were away. A missing or
+    /// corrupt file gives an empty store.
+    pub fn load_from(path: &Path, max_items: usize) -> Self {
+        let mut store = Self::new(max_items);
+        let read = |p: &Path| -> Option<Vec<FeedItem>> {
+            serde_json::from_str(&std::fs::read_to_string(p).ok()?).ok()
+        };
+        let items = read(path).or_else(|| read(Path::new(LEGACY_FEED_FILE))).unwrap_or_default();
+        store.items = items
+            .into_iter()
+            .filter(|i| !i.dismissed && !i.is_stale())
+            .collect();
+        if store.items.len() > max_items {
+            let excess = store.items.len() - max_items;
+            store.items.drain(..excess);
+        }
+        store.next_id = store.items.iter().map(|i| i.id).max().unwrap_or(0) + 1;
+        store
+    }
+
+    /// Save the most recent non-dismissed items (plus any pinned) to disk.
+    /// The file is replaced whole, so a crash mid-save keeps the last copy.
+    pub fn save_to(&self, path: &Path) {
+        let kept: Vec<&FeedItem> = self.items.iter().filter(|i| !i.dismissed).collect();
+        let start = kept.len().saturating_sub(PERSIST_MAX_ITEMS);
+        let kept: Vec<&FeedItem> = kept
+            .into_iter()
+            .enumerate()
+            .filter(|(idx, i)| *idx >= start || i.pinned)
+            .map(|(_, i)| i)
+            .collect();
+        let Ok(data) = serde_json::to_string(&kept) else { return };
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let tmp = path.with_extension("json.tmp");
+        if std::fs::write(&tmp, data).is_ok() {
+            let _ = std::fs::rename(&tmp, path);
+        }
+    }
+
     /// Push a new item, assigning it an ID. Handles auto-replacement.
-    pub fn push(&mut self, mut item: FeedItem) {
+    /// Returns the assigned ID.
+    pub fn push(&mut self, mut item: FeedItem) -> u64 {
         // Handle replacement: dismiss the most recent item from the same source
         if let Some(ref replace_source) = item.replaces_source {
             for existing in self.items.iter_mut().rev() {
-                if &existing.source == replace_source && !existing.dismissed {
+                if &existing.source == replace_source && !existing.dismissed && !existing.pinned {
                     existing.dismissed = true;
                     break;
                 }
             }
         }
 
-        item.id = self.next_id;
+        let id = self.next_id;
+        item.id = id;
         self.next_id += 1;
         self.items.push(item);
 
         // Cap total items
         if self.items.len() > self.max_items {
-            // Remove oldest dismissed items first, then oldest items
+            // Remove oldest dismissed items first, then oldest unpinned items
             if let Some(pos) = self.items.iter().position(|i| i.dismissed) {
                 self.items.remove(pos);
-            } else {
-                self.items.remove(0);
+            } else if let Some(pos) = self.items.iter().position(|i| !i.pinned) {
+                self.items.remove(pos);
             }
         }
+        id
+    }
+
+    /// The id the next pushed item will get.
+    pub fn next_id(&self) -> u64 {
+        self.next_id
+    }
+
+    /// Visible items pushed at or after id `since`, oldest first.
+    pub fn visible_since(&self, since: u64) -> Vec<&FeedItem> {
+        self.visible_items().into_iter().filter(|i| i.id >= since).collect()
     }
 
     /// Get visible (non-dismissed, non-stale) items in chronological order.
@@ -193,6 +453,14 @@ impl FeedStore {
             .collect()
     }
 
+    /// Visible items, minus any from hidden sources.
+    pub fn visible_items_filtered(&self, hidden: &HashSet<FeedSource>) -> Vec<&FeedItem> {
+        self.items
+            .iter()
+            .filter(|i| !i.dismissed && !i.is_stale() && !hidden.contains(&i.source))
+            .collect()
+    }
+
     /// Mark an item as seen.
     pub fn mark_seen(&mut self, id: u64) {
         if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
@@ -200,6 +468,25 @@ impl FeedStore {
         }
     }
 
+    /// Replace an item's body, e.g. as a streamed response grows.
+    pub fn set_body(&mut self, id: u64, lines: Vec<String>) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+            item.body = lines;
+        }
+    }
+
+    /// Replace an item's widget, e.g. as a task's progress moves.
+    pub fn set_widget(&mut self, id: u64, widget: WidgetData) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+            item.widget = Some(widget);
+        }
+    }
+
+    /// Drop an item outright, without leaving it dismissed.
+    pub fn remove(&mut self, id: u64) {
+        self.items.retain(|i| i.id != id);
+    }
+
     /// Toggle collapsed state for an item.
     pub fn toggle_collapse(&mut self, id: u64) {
         if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
@@ -207,6 +494,13 @@ impl FeedStore {
         }
     }
 
+    /// Toggle the reasoning section for an item.
+    pub fn toggle_reasoning(&mut self, id: u64) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+            item.reasoning_expanded = !item.reasoning_expanded;
+        }
+    }
+
     /// Dismiss an item (hide from view).
     pub fn dismiss(&mut self, id: u64) {
         if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
@@ -214,6 +508,13 @@ impl FeedStore {
         }
     }
 
+    /// Restore a dismissed item.
+    pub fn undismiss(&mut self, id: u64) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+            item.dismissed = false;
+        }
+    }
+
     /// Count of unseen, non-dismissed items.
     pub fn unseen_count(&self) -> usize {
         self.items
@@ -230,10 +531,17 @@ impl FeedStore {
             .count()
     }
 
-    /// Prune stale items by marking them dismissed.
+    /// Toggle pinned state for an item.
+    pub fn toggle_pin(&mut self, id: u64) {
+        if let Some(item) = self.items.iter_mut().find(|i| i.id == id) {
+            item.pinned = !item.pinned;
+        }
+    }
+
+    /// Prune stale items by marking them dismissed. Pinned items are kept.
     pub fn prune_stale(&mut self) {
         for item in &mut self.items {
-            if item.is_stale() && !item.dismissed {
+            if item.is_stale() && !item.dismissed && !item.pinned {
                 item.dismissed = true;
             }
         }
@@ -249,3 +557,121 @@ impl FeedStore {
         self.visible_items().len()
     }
 }
+
+/// One selectable row of the feed.
+pub enum FeedEntry<'a> {
+    Item(&'a FeedItem),
+    /// Header for a run of cards with the same source and priority, keyed by
+    /// the first card's id. When expanded, its cards follow as `Item`s.
+    Group {
+        key: u64,
+        items: Vec<&'a FeedItem>,
+        expanded: bool,
+    },
+}
+
+impl<'a> FeedEntry<'a> {
+    /// The card behind this row, if it isn't a group header.
+    pub fn item(&self) -> Option<&'a FeedItem> {
+        match self {
+            FeedEntry::Item(item) => Some(item),
+            FeedEntry::Group { .. } => None,
+        }
+    }
+
+    /// Ids of every card this row stands for.
+    pub fn ids(&self) -> Vec<u64> {
+        match self {
+            FeedEntry::Item(item) => vec![item.id],
+            FeedEntry::Group { items, .. } => items.iter().map(|i| i.id).collect(),
+        }
+    }
+}
+
+/// Every item as its own row, for the flat feed.
+pub fn flat_entries(items: Vec<&FeedItem>) -> Vec<FeedEntry<'_>> {
+    items.into_iter().map(FeedEntry::Item).collect()
+}
+
+/// Fold runs of `MIN_GROUP_SIZE` or more consecutive items that share a
+/// source and priority under a group header. Groups whose key is in
+/// `expanded` list their items after the header. Pinned items never group.
+pub fn group_entries<'a>(items: Vec<&'a FeedItem>, expanded: &HashSet<u64>) -> Vec<FeedEntry<'a>> {
+    let groupable = |a: &FeedItem, b: &FeedItem| {
+        a.source == b.source && a.priority == b.priority && !a.pinned && !b.pinned
+    };
+
+    let mut entries = Vec::new();
+    let mut start = 0;
+    while start < items.len() {
+        let mut end = start + 1;
+        while end < items.len() && groupable(items[start], items[end]) {
+            end += 1;
+        }
+        let run = &items[start..end];
+        if run.len() >= MIN_GROUP_SIZE {
+            let key = run[0].id;
+            let is_expanded = expanded.contains(&key);
+            entries.push(FeedEntry::Group { key, items: run.to_vec(), expanded: is_expanded });
+            if is_expanded {
+                entries.extend(run.iter().map(|i| FeedEntry::Item(i)));
+            }
+        } else {
+            entries.extend(run.iter().map(|i| FeedEntry::Item(i)));
+        }
+        start = end;
+    }
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_feed_reloads_from_a_new_directory() {
+        let dir = std::env::temp_dir().join(format!("aether_feed_test_{}", std::process::id()));
+        let path = dir.join("state/feed.json");
+        let mut store = FeedStore::new(10);
+        store.push(FeedItem::new(FeedSource::System, Priority::Normal, "kept".to_string()));
+        store.save_to(&path);
+
+        let loaded = FeedStore::load_from(&path, 10);
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(loaded.items.iter().map(|i| i.title.as_str()).collect::<Vec<_>>(), ["kept"]);
+        assert!(!path.with_extension("json.tmp").exists());
+    }
+
+    #[test]
+    fn progress_comes_from_progress_shaped_lines() {
+        let pct = |line: &str| Progress::from_output(line).map(|p| (p.value * 1000.0).round() / 10.0);
+        assert_eq!(pct("42%"), Some(42.0));
+        assert_eq!(pct("[#####     ] (7.5%)"), Some(7.5));
+        // rsync and wget put rates and times after the percentage
+        assert_eq!(pct("  1,234,567  42%  1.20MB/s    0:00:10"), Some(42.0));
+        assert_eq!(pct("  50K .......... .......... 12% 1.2M 3s"), Some(12.0));
+        assert_eq!(pct("Downloading 63% ETA 0:12"), Some(63.0));
+        // Report rows that happen to contain a percentage aren't progress
+        assert_eq!(pct("/dev/sda1        50G   45G  5.0G  90% /"), None);
+        assert_eq!(pct("Use% 95% of quota used"), None);
+        assert_eq!(pct("no percentage here"), None);
+        assert_eq!(pct("150%"), None);
+    }
+
+    #[test]
+    fn age_str_switches_units_at_each_boundary() {
+        let mut item = FeedItem::new(FeedSource::System, Priority::Normal, "aged".to_string());
+        let mut age = |secs: u64| {
+            item.timestamp = now_unix() - secs;
+            item.age_str()
+        };
+        assert_eq!(age(4), "just now");
+        assert_eq!(age(5), "5s ago");
+        assert_eq!(age(59), "59s ago");
+        assert_eq!(age(60), "1m ago");
+        assert_eq!(age(59 * 60 + 59), "59m ago");
+        assert_eq!(age(60 * 60), "1h ago");
+        assert_eq!(age(23 * 3600 + 3599), "23h ago");
+        assert_eq!(age(24 * 3600), "1d ago");
+    }
+}
@@ -0,0 +1,195 @@
+use std::io::Read;
+
+/// Error from `read_response_reusable` when the peer closed without replying,
+/// as a stale keep-alive connection does.
+pub const CLOSED_BEFORE_RESPONSE: &str = "connection closed before response";
+
+/// Read an HTTP response and return its decoded body, plus whether the
+/// connection can carry another request: the body was framed and complete,
+/// and the server didn't send `Connection: close`. Stops as soon as the body
+/// is complete per `Content-Length` or chunked framing, so keep-alive servers
+/// don't leave us waiting for EOF. A connection closed before any bytes is an
+/// error, and so is a timeout or close that cuts off the headers or a framed
+/// body; an unframed body ends with whatever has arrived.
+pub fn read_response_reusable(stream: &mut impl Read) -> Result<(String, bool), String> {
+    let mut resp = Vec::new();
+    let mut buf = [0u8; 4096];
+    let complete = loop {
+        match stream.read(&mut buf) {
+            Ok(0) if resp.is_empty() => return Err(CLOSED_BEFORE_RESPONSE.to_string()),
+            Ok(0) => break false,
+            Ok(n) => resp.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock
+                || e.kind() == std::io::ErrorKind::TimedOut => break false,
+            Err(e) => return Err(format!("read: {e}")),
+        }
+        if let Some((_, true)) = decode_body(&resp) {
+            break true;
+        }
+    };
+    if !complete {
+        match decode_body(&resp) {
+            None => return Err("response headers cut off".to_string()),
+            Some((body, _)) if is_framed(&resp) => {
+                return Err(format!("response body cut off after {} bytes", body.len()))
+            }
+            Some(_) => {}
+        }
+    }
+    let reusable = complete && !server_closes(&resp);
+    Ok((extract_body(&resp), reusable))
+}
+
+/// Whether the response headers carry `Connection: close`.
+fn server_closes(resp: &[u8]) -> bool {
+    let end = resp.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(resp.len());
+    String::from_utf8_lossy(&resp[..end])
+        .to_ascii_lowercase()
+        .lines()
+        .skip(1)
+        .any(|l| l.replace(' ', "") == "connection:close")
+}
+
+/// Whether the headers frame the body with `Content-Length` or chunking,
+/// rather than leaving it to run until the connection closes.
+fn is_framed(resp: &[u8]) -> bool {
+    let end = resp.windows(4).position(|w| w == b"\r\n\r\n").unwrap_or(resp.len());
+    String::from_utf8_lossy(&resp[..end])
+        .to_ascii_lowercase()
+        .lines()
+        .skip(1)
+        .any(|l| l.starts_with("content-length:") || (l.starts_with("transfer-encoding:") && l.contains("chunked")))
+}
+
+/// Body of a raw HTTP response, de-chunked or cut to `Content-Length`.
+/// Without a header block the whole input is returned.
+pub fn extract_body(resp: &[u8]) -> String {
+    match decode_body(resp) {
+        Some((body, _)) => String::from_utf8_lossy(&body).to_string(),
+        None => String::from_utf8_lossy(resp).to_string(),
+    }
+}
+
+/// Decode the body after the headers. Returns the body so far and whether
+/// it is complete, or `None` if the headers haven't fully arrived.
+fn decode_body(resp: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let header_end = resp.windows(4).position(|w| w == b"\r\n\r\n")?;
+    let headers = String::from_utf8_lossy(&resp[..header_end]).to_ascii_lowercase();
+    let body = &resp[header_end + 4..];
+
+    let header = |name: &str| {
+        headers
+            .lines()
+            .skip(1)
+            .find_map(|l| l.strip_prefix(name).map(|v| v.trim().to_string()))
+    };
+
+    if header("transfer-encoding:").is_some_and(|v| v.contains("chunked")) {
+        return Some(dechunk(body));
+    }
+    if let Some(len) = header("content-length:").and_then(|v| v.parse::<usize>().ok()) {
+        let take = len.min(body.len());
+        return Some((body[..take].to_vec(), body.len() >= len));
+    }
+    // No framing: the body runs until the connection closes
+    Some((body.to_vec(), false))
+}
+
+/// Decode `Transfer-Encoding: chunked`. Returns the data so far and whether
+/// the terminating zero-length chunk was seen.
+pub fn dechunk(mut data: &[u8]) -> (Vec<u8>, bool) {
+    let mut out = Vec::new();
+    loop {
+        let Some(line_end) = data.windows(2).position(|w| w == b"\r\n") else {
+            return (out, false);
+        };
+        // Chunk size in hex, optionally followed by ";extensions"
+        let size_line = String::from_utf8_lossy(&data[..line_end]);
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let Ok(size) = usize::from_str_radix(size_hex, 16) else {
+            return (out, false);
+        };
+        data = &data[line_end + 2..];
+        if size == 0 {
+            return (out, true);
+        }
+        if data.len() < size {
+            out.extend_from_slice(data);
+            return (out, false);
+        }
+        out.extend_from_slice(&data[..size]);
+        data = data.get(size + 2..).unwrap_or(&[]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out `chunks` one read at a time, then times out like a socket
+    /// with a read timeout would.
+    struct Stalling {
+        chunks: Vec<&'static [u8]>,
+    }
+
+    impl Read for Stalling {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.chunks.is_empty() {
+                return Err(std::io::ErrorKind::WouldBlock.into());
+            }
+            let chunk = self.chunks.remove(0);
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Ok(chunk.len())
+        }
+    }
+
+    fn read(chunks: &[&'static [u8]]) -> Result<(String, bool), String> {
+        read_response_reusable(&mut Stalling { chunks: chunks.to_vec() })
+    }
+
+    #[test]
+    fn complete_content_length_body_is_returned() {
+        let resp = read(&[b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhel", b"lo"]);
+        assert_eq!(resp, Ok(("hello".to_string(), true)));
+    }
+
+    #[test]
+    fn short_content_length_body_is_an_error() {
+        let err = read(&[b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhel"]).unwrap_err();
+        assert!(err.contains("cut off after 3 bytes"), "{err}");
+    }
+
+    #[test]
+    fn chunked_body_without_terminator_is_an_error() {
+        let err = read(&[b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n"]).unwrap_err();
+        assert!(err.contains("cut off"), "{err}");
+    }
+
+    #[test]
+    fn complete_chunked_body_is_decoded() {
+        let resp = read(&[b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nhel\r\n2\r\nlo\r\n0\r\n\r\n"]);
+        assert_eq!(resp, Ok(("hello".to_string(), true)));
+    }
+
+    #[test]
+    fn cut_off_headers_are_an_error() {
+        assert!(read(&[b"HTTP/1.1 200 OK\r\nContent-Le"]).is_err());
+    }
+
+    #[test]
+    fn unframed_body_ends_with_what_arrived() {
+        let resp = read(&[b"HTTP/1.1 200 OK\r\n\r\npartial"]);
+        assert_eq!(resp, Ok(("partial".to_string(), false)));
+    }
+
+    #[test]
+    fn close_before_any_bytes_is_reported() {
+        struct Closed;
+        impl Read for Closed {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Ok(0)
+            }
+        }
+        assert_eq!(read_response_reusable(&mut Closed), Err(CLOSED_BEFORE_RESPONSE.to_string()));
+    }
+}
@@ -18,17 +18,30 @@ pub enum AppAction {
     CursorRight,
     CursorHome,
     CursorEnd,
+    CursorWordLeft,
+    CursorWordRight,
+    DeleteWordBackward,
     HistoryUp,
     HistoryDown,
+    Complete,
 
     // Feed panel
     FeedSelectPrev,
     FeedSelectNext,
     FeedToggleCollapse,
+    FeedToggleReasoning,
     FeedDismiss,
+    FeedUndoDismiss,
+    FeedTogglePin,
+    FeedCopy,
     FeedPageUp,
     FeedPageDown,
 
+    // Sidebar panel
+    TaskSelectPrev,
+    TaskSelectNext,
+    TaskCancel,
+
     // Global scrolling (works from input panel too)
     PageUp,
     PageDown,
@@ -42,12 +55,21 @@ pub enum AppAction {
 }
 
 /// Route a key event to an action based on the active panel.
-pub fn route(key: KeyEvent, panel: &ActivePanel, thinking: bool) -> AppAction {
+pub fn route(key: KeyEvent, panel: &ActivePanel, thinking: bool, input_empty: bool) -> AppAction {
     // Global: Ctrl+C always quits
     if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
         return AppAction::Quit;
     }
 
+    // Ctrl+W deletes a word while typing; otherwise it's the world-model shortcut
+    if key.modifiers.contains(KeyModifiers::CONTROL)
+        && key.code == KeyCode::Char('w')
+        && *panel == ActivePanel::Input
+        && !input_empty
+    {
+        return if thinking { AppAction::Noop } else { AppAction::DeleteWordBackward };
+    }
+
     // Global shortcuts
     if key.modifiers.contains(KeyModifiers::CONTROL) {
         match key.code {
@@ -58,15 +80,21 @@ pub fn route(key: KeyEvent, panel: &ActivePanel, thinking: bool) -> AppAction {
     }
 
     match panel {
-        ActivePanel::Input => route_input(key, thinking),
+        ActivePanel::Input => route_input(key, thinking, input_empty),
         ActivePanel::Feed => route_feed(key),
         ActivePanel::Sidebar => route_sidebar(key),
     }
 }
 
-fn route_input(key: KeyEvent, thinking: bool) -> AppAction {
+fn route_input(key: KeyEvent, thinking: bool, input_empty: bool) -> AppAction {
+    let word = key.modifiers.intersects(KeyModifiers::ALT | KeyModifiers::CONTROL);
     match key.code {
-        KeyCode::Tab => AppAction::SwitchPanel,
+        KeyCode::Left if word => AppAction::CursorWordLeft,
+        KeyCode::Right if word => AppAction::CursorWordRight,
+        KeyCode::Backspace if word && !thinking => AppAction::DeleteWordBackward,
+        // Tab completes what's typed; with nothing typed it switches panels
+        KeyCode::Tab if input_empty || thinking => AppAction::SwitchPanel,
+        KeyCode::Tab => AppAction::Complete,
         KeyCode::Enter if !thinking => AppAction::Submit,
         KeyCode::Backspace if !thinking => AppAction::Backspace,
         KeyCode::Delete if !thinking => AppAction::Delete,
@@ -90,7 +118,11 @@ fn route_feed(key: KeyEvent) -> AppAction {
         KeyCode::Up | KeyCode::Char('k') => AppAction::FeedSelectPrev,
         KeyCode::Down | KeyCode::Char('j') => AppAction::FeedSelectNext,
         KeyCode::Enter => AppAction::FeedToggleCollapse,
+        KeyCode::Char('r') => AppAction::FeedToggleReasoning,
         KeyCode::Char('d') => AppAction::FeedDismiss,
+        KeyCode::Char('u') => AppAction::FeedUndoDismiss,
+        KeyCode::Char('p') => AppAction::FeedTogglePin,
+        KeyCode::Char('y') => AppAction::FeedCopy,
         KeyCode::PageUp => AppAction::FeedPageUp,
         KeyCode::PageDown => AppAction::FeedPageDown,
         _ => AppAction::Noop,
@@ -101,6 +133,111 @@ fn route_sidebar(key: KeyEvent) -> AppAction {
     match key.code {
         KeyCode::Tab => AppAction::SwitchPanel,
         KeyCode::Esc => AppAction::ReturnToInput,
+        KeyCode::Up | KeyCode::Char('k') => AppAction::TaskSelectPrev,
+        KeyCode::Down | KeyCode::Char('j') => AppAction::TaskSelectNext,
+        KeyCode::Char('x') => AppAction::TaskCancel,
         _ => AppAction::Noop,
     }
 }
+
+/// Characters that make up a word for word motion; anything else
+/// (spaces, punctuation) separates words. CJK ideographs count as letters.
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Byte index of the char boundary before `cursor`.
+pub fn prev_char(s: &str, cursor: usize) -> usize {
+    s[..cursor].char_indices().next_back().map(|(i, _)| i).unwrap_or(0)
+}
+
+/// Byte index of the char boundary after `cursor`.
+pub fn next_char(s: &str, cursor: usize) -> usize {
+    s[cursor..].chars().next().map(|c| cursor + c.len_utf8()).unwrap_or(s.len())
+}
+
+/// Start of the word at or before `cursor`: skip separators, then the word.
+pub fn prev_word(s: &str, cursor: usize) -> usize {
+    let mut chars = s[..cursor].char_indices().rev().peekable();
+    while chars.next_if(|&(_, c)| !is_word_char(c)).is_some() {}
+    let mut start = chars.peek().map(|&(i, c)| i + c.len_utf8()).unwrap_or(0);
+    while let Some((i, _)) = chars.next_if(|&(_, c)| is_word_char(c)) {
+        start = i;
+    }
+    start
+}
+
+/// End of the word at or after `cursor`: skip separators, then the word.
+pub fn next_word(s: &str, cursor: usize) -> usize {
+    let mut chars = s[cursor..].char_indices().peekable();
+    while chars.next_if(|&(_, c)| !is_word_char(c)).is_some() {}
+    while chars.next_if(|&(_, c)| is_word_char(c)).is_some() {}
+    chars.peek().map(|&(i, _)| cursor + i).unwrap_or(s.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Every stop `step` makes from `start` until it stops moving.
+    fn stops(s: &str, start: usize, step: fn(&str, usize) -> usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut cursor = start;
+        loop {
+            let next = step(s, cursor);
+            if next == cursor {
+                return out;
+            }
+            assert!(s.is_char_boundary(next), "{next} splits a char in {s:?}");
+            out.push(next);
+            cursor = next;
+        }
+    }
+
+    #[test]
+    fn punctuation_runs_separate_words() {
+        let s = "foo, bar...baz";
+        assert_eq!(stops(s, 0, next_word), [3, 8, 14]);
+        assert_eq!(stops(s, s.len(), prev_word), [11, 5, 0]);
+        // From inside a word, motion goes to that word's own ends
+        assert_eq!(next_word(s, 6), 8);
+        assert_eq!(prev_word(s, 6), 5);
+        // Underscores join words
+        assert_eq!(next_word("snake_case x", 0), 10);
+    }
+
+    #[test]
+    fn cjk_runs_are_words() {
+        // 你好 is bytes 0..6, 世界 is 7..13
+        let s = "你好 世界";
+        assert_eq!(stops(s, 0, next_word), [6, 13]);
+        assert_eq!(stops(s, s.len(), prev_word), [7, 0]);
+        assert_eq!(next_word("说：好", 0), 3);
+    }
+
+    #[test]
+    fn motion_lands_on_multibyte_boundaries() {
+        // é is bytes 3..5
+        let s = "café au";
+        assert_eq!(stops(s, 0, next_word), [5, 8]);
+        assert_eq!(stops(s, s.len(), prev_word), [6, 0]);
+        assert_eq!(prev_char(s, 5), 3);
+        assert_eq!(next_char(s, 3), 5);
+        assert_eq!(prev_char(s, 0), 0);
+        assert_eq!(next_char(s, s.len()), s.len());
+
+        // An emoji is a separator, four bytes wide
+        let s = "hi🙂there";
+        assert_eq!(stops(s, 0, next_word), [2, 11]);
+        assert_eq!(stops(s, s.len(), prev_word), [6, 0]);
+    }
+
+    #[test]
+    fn ctrl_w_deletes_a_word_only_while_typing() {
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        let action = |panel, input_empty| route(ctrl_w, &panel, false, input_empty);
+        assert!(matches!(action(ActivePanel::Input, false), AppAction::DeleteWordBackward));
+        assert!(matches!(action(ActivePanel::Input, true), AppAction::TriggerWorldModel));
+        assert!(matches!(action(ActivePanel::Feed, false), AppAction::TriggerWorldModel));
+    }
+}
@@ -0,0 +1,202 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+/// A URL or absolute path found in text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Link {
+    /// Byte range of the link in the searched text.
+    pub range: Range<usize>,
+    /// What opening it means: the URL itself, or a file:// URL for a path.
+    pub url: String,
+}
+
+const SCHEMES: [&str; 3] = ["https://", "http://", "file://"];
+
+/// Punctuation that ends a sentence or closes a quote rather than a link.
+const TRAILING: [char; 10] = ['.', ',', ';', ':', '!', '?', '"', '\'', '`', '>'];
+
+/// Bodies whose links are remembered before the cache starts over.
+const FIND_CACHE_MAX: usize = 256;
+
+thread_local! {
+    /// Links per body text, so redrawing the feed doesn't stat every
+    /// `/word` in it again each frame.
+    static FOUND: RefCell<HashMap<String, Vec<Link>>> = RefCell::new(HashMap::new());
+}
+
+/// URLs and absolute paths in `text`, in order. Quotes and brackets around
+/// them and trailing sentence punctuation are left out of the link. Results
+/// are cached per text, so a path created later isn't picked up until the
+/// cache fills and starts over.
+pub fn find(text: &str) -> Vec<Link> {
+    if let Some(links) = FOUND.with(|found| found.borrow().get(text).cloned()) {
+        return links;
+    }
+    let links = scan(text);
+    FOUND.with(|found| {
+        let mut found = found.borrow_mut();
+        if found.len() >= FIND_CACHE_MAX {
+            found.clear();
+        }
+        found.insert(text.to_string(), links.clone());
+    });
+    links
+}
+
+fn scan(text: &str) -> Vec<Link> {
+    let mut links = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (c.is_whitespace(), start) {
+            (false, None) => start = Some(i),
+            (true, Some(s)) => {
+                links.extend(classify(&text[s..i], s));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    links
+}
+
+/// The link in one whitespace-free `word` found at byte `offset`, if any.
+fn classify(word: &str, offset: usize) -> Option<Link> {
+    let mut lead = word.len() - word.trim_start_matches(['(', '[', '<', '"', '\'', '`']).len();
+    // A URL glued to other text, as in a Markdown `[label](https://...)`
+    if let Some(at) = SCHEMES.iter().filter_map(|s| word[lead..].find(s)).min() {
+        lead += at;
+    }
+    let mut token = &word[lead..];
+    loop {
+        let trimmed = token.trim_end_matches(TRAILING);
+        // A closing bracket stays only if the link opened one, as in wiki URLs
+        let trimmed = match trimmed.strip_suffix([')', ']']) {
+            Some(t) if t.matches(['(', '[']).count() <= t.matches([')', ']']).count() => t,
+            _ => trimmed,
+        };
+        if trimmed.len() == token.len() {
+            break;
+        }
+        token = trimmed;
+    }
+    if token.chars().any(char::is_control) {
+        return None;
+    }
+
+    let url = if SCHEMES.iter().any(|s| token.len() > s.len() && token.starts_with(s)) {
+        token.to_string()
+    } else if is_path(token) {
+        format!("file://{}", token)
+    } else {
+        return None;
+    };
+    Some(Link { range: offset + lead..offset + lead + token.len(), url })
+}
+
+/// `/etc/fstab` or an existing `/tmp`, but not a command like `/help`.
+fn is_path(token: &str) -> bool {
+    let Some(rest) = token.strip_prefix('/') else {
+        return false;
+    };
+    !rest.is_empty() && !rest.starts_with('/') && (rest.contains('/') || Path::new(token).exists())
+}
+
+/// Whether to emit OSC 8 escapes. The Linux console prints their payload as
+/// text, so they're off there; NEBULA_HYPERLINKS=0 or 1 overrides.
+pub fn enabled() -> bool {
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| match std::env::var("NEBULA_HYPERLINKS").as_deref() {
+        Ok("0") => false,
+        Ok(_) => true,
+        Err(_) => std::env::var("TERM").is_ok_and(|t| t != "linux" && t != "dumb"),
+    })
+}
+
+/// `text` as an OSC 8 hyperlink to `url`.
+pub fn osc8(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{url}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Open a link with the desktop's handler for it.
+pub fn open(url: &str) -> Result<(), String> {
+    let mut child = Command::new("xdg-open")
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("xdg-open: {e}"))?;
+    // Reap it off the UI thread
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (linked text, url) for each link in `text`.
+    fn links(text: &str) -> Vec<(&str, String)> {
+        find(text).into_iter().map(|l| (&text[l.range], l.url)).collect()
+    }
+
+    fn url(s: &str) -> (&str, String) {
+        (s, s.to_string())
+    }
+
+    #[test]
+    fn urls_drop_trailing_punctuation_and_quotes() {
+        assert_eq!(links("see https://example.com/a."), [url("https://example.com/a")]);
+        assert_eq!(links("(http://x.org/b), or \"file:///etc/hosts\"!"), [url("http://x.org/b"), url("file:///etc/hosts")]);
+        assert_eq!(links("`https://a.io/?q=1`;"), [url("https://a.io/?q=1")]);
+        assert_eq!(links("<https://a.io/x>"), [url("https://a.io/x")]);
+        // A bare scheme isn't a link
+        assert!(links("https:// and http://").is_empty());
+    }
+
+    #[test]
+    fn brackets_stay_when_the_link_opened_them() {
+        let wiki = "https://en.wikipedia.org/wiki/Rust_(programming_language)";
+        assert_eq!(links(&format!("({wiki})")), [url(wiki)]);
+        assert_eq!(links("[docs](https://docs.rs/x)"), [url("https://docs.rs/x")]);
+    }
+
+    #[test]
+    fn absolute_paths_become_file_urls() {
+        assert_eq!(links("edit /etc/fstab, then"), [("/etc/fstab", "file:///etc/fstab".to_string())]);
+        // One segment counts only if it exists, so commands aren't links
+        assert_eq!(links("/tmp"), [("/tmp", "file:///tmp".to_string())]);
+        assert!(links("try /help or /aether_no_such_dir").is_empty());
+        assert!(links("// comment and relative/path").is_empty());
+    }
+
+    #[test]
+    fn ranges_are_byte_offsets_into_multibyte_text() {
+        let text = "café → https://ex.com/ü ok";
+        let found = find(text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(&text[found[0].range.clone()], "https://ex.com/ü");
+    }
+
+    #[test]
+    fn control_characters_are_never_linked() {
+        assert!(links("https://evil.com/\x1b]8;;x").is_empty());
+    }
+
+    #[test]
+    fn cached_results_match_a_fresh_scan() {
+        let text = "one https://a.io two /etc/fstab";
+        assert_eq!(find(text), scan(text));
+        assert_eq!(find(text), scan(text));
+        assert!(FOUND.with(|found| found.borrow().contains_key(text)));
+    }
+
+    #[test]
+    fn osc8_wraps_text_in_a_hyperlink() {
+        assert_eq!(osc8("https://a.io", "a"), "\x1b]8;;https://a.io\x1b\\a\x1b]8;;\x1b\\");
+    }
+}
@@ -1,32 +1,56 @@
 mod aurora_client;
+mod away;
 mod brain_client;
+mod clipboard;
 mod commands;
+mod conn;
 mod context;
+mod export;
 mod feed;
+mod http;
 mod input;
+mod links;
+mod markdown;
+mod power;
 mod proactive;
 mod tasks;
 mod telemetry;
+mod theme;
 mod ui;
 mod widgets;
 
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashSet};
 use std::io::{self, Write};
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 use crossterm::{
     cursor,
-    event::{self, Event},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, MouseButton, MouseEvent, MouseEventKind},
     terminal::{self, disable_raw_mode, enable_raw_mode, Clear, ClearType},
     ExecutableCommand,
 };
 use ratatui::prelude::*;
 use ratatui::{TerminalOptions, Viewport};
 
-use feed::{FeedItem, FeedSource, FeedStore, Priority, WidgetData};
+use feed::{FeedEntry, FeedItem, FeedSource, FeedStore, Priority, WidgetData};
 use input::AppAction;
+use power::{PowerAction, PowerState};
+use telemetry::AlertKind;
 use ui::ActivePanel;
 
+/// Seconds between telemetry refreshes (and history samples).
+const TELEMETRY_INTERVAL_SECS: u64 = 2;
+/// How often the status bar re-checks the daemons behind aurorad.
+const STACK_HEALTH_INTERVAL_SECS: u64 = 15;
+/// How often the feed is saved, so a crash loses at most this much.
+const FEED_SAVE_INTERVAL_SECS: u64 = 30;
+/// Repeats of the same CFC-JEPA control command inside this window are ignored.
+const CONTROL_DEBOUNCE: Duration = Duration::from_secs(3);
+/// How long a status bar toast stays up.
+const TOAST_SECS: u64 = 2;
+
 /// Application state.
 pub struct App {
     /// Current input in the omni-bar.
@@ -39,26 +63,46 @@ pub struct App {
     pub feed_scroll: u16,
     /// System telemetry snapshot.
     pub telemetry: telemetry::SysTelemetry,
-    /// Aurora AI status.
-    pub aurora: aurora_client::AuroraStatus,
+    /// CPU jiffies from the previous telemetry read.
+    pub cpu_sampler: telemetry::CpuSampler,
+    /// Aurora AI and stack status; `None` until the first check returns.
+    pub aurora: Option<aurora_client::AuroraStatus>,
+    /// Receiver for background health checks.
+    pub health_rx: mpsc::Receiver<aurora_client::AuroraStatus>,
+    /// Sender for health checks (cloned into background threads).
+    pub health_tx: mpsc::Sender<aurora_client::AuroraStatus>,
     /// Whether we should quit.
     pub quit: bool,
-    /// Command history.
+    /// Command history, restored from the last session.
     pub history: Vec<String>,
+    /// Most entries `history` keeps.
+    pub history_max: usize,
     /// Current position in history.
     pub history_pos: Option<usize>,
     /// Whether a brain query is in progress.
     pub thinking: bool,
     /// Thinking animation frame counter.
     pub thinking_frame: u8,
-    /// Receiver for brain responses.
-    pub brain_rx: mpsc::Receiver<brain_client::BrainResponse>,
-    /// Sender for brain responses (cloned into threads).
-    pub brain_tx: mpsc::Sender<brain_client::BrainResponse>,
+    /// Receiver for streamed brain output.
+    pub brain_rx: mpsc::Receiver<brain_client::BrainStreamEvent>,
+    /// Sender for streamed brain output (cloned into threads).
+    pub brain_tx: mpsc::Sender<brain_client::BrainStreamEvent>,
+    /// Live card growing from brain deltas, replaced once the response is done.
+    pub streaming_card: Option<u64>,
+    /// Text streamed so far for `streaming_card`.
+    pub streaming_text: String,
+    /// Tab completion in progress, cleared by any other key.
+    pub completion: Option<commands::Completion>,
     /// Which panel currently has focus.
     pub active_panel: ActivePanel,
-    /// Selected feed item index (within visible items).
+    /// Selected feed row (within `feed_entries`).
     pub selected_feed_item: Option<usize>,
+    /// Fold runs of similar cards under group headers.
+    pub group_feed: bool,
+    /// Groups the user has expanded, by group key.
+    pub expanded_groups: HashSet<u64>,
+    /// Card positions from the last draw, for mouse clicks.
+    pub feed_layout: RefCell<ui::FeedLayout>,
     /// Receiver for proactive feed items from background sources.
     pub proactive_rx: mpsc::Receiver<FeedItem>,
     /// Sender for proactive feed items (cloned into background threads).
@@ -67,18 +111,36 @@ pub struct App {
     pub proactive: proactive::ProactiveEngine,
     /// Background task manager.
     pub task_manager: tasks::TaskManager,
+    /// Highlighted row in the sidebar task list.
+    pub selected_task: usize,
     /// Session context for proactive intelligence.
     pub session: context::SessionContext,
+    /// Feed sources hidden by `/filter`.
+    pub hidden_sources: HashSet<FeedSource>,
+    /// Command aliases, expanded on submit.
+    pub aliases: BTreeMap<String, String>,
+    /// Most recently dismissed card (or group of cards), for single-level undo.
+    pub last_dismissed: Vec<u64>,
+    /// Brief status bar message and when it was raised.
+    pub toast: Option<(String, Instant)>,
+    /// Kind of the last CFC-JEPA control sent (see `control_kind`) and when,
+    /// for debouncing.
+    pub last_control: Option<(&'static str, Instant)>,
+    /// Shutdown or reboot being confirmed or counting down.
+    pub power: Option<PowerState>,
+    /// Idle episodes, for `/away` summaries.
+    pub away: away::AwayTracker,
 }
 
 impl App {
     fn new() -> Self {
         let (brain_tx, brain_rx) = mpsc::channel();
         let (proactive_tx, proactive_rx) = mpsc::channel();
+        let (health_tx, health_rx) = mpsc::channel();
 
-        let mut feed = FeedStore::new(200);
+        let mut feed = FeedStore::load_from(&feed::feed_path(), 200);
 
-        // Welcome card
+        // Welcome card (first run only; otherwise the restored feed shows)
         let welcome = FeedItem::new(
             FeedSource::System,
             Priority::Normal,
@@ -89,9 +151,15 @@ impl App {
             "Type anything. The OS understands you.".to_string(),
             "Use !cmd for shell, &query for background, Tab to navigate.".to_string(),
         ]);
-        feed.push(welcome);
+        if feed.visible_count() == 0 {
+            feed.push(welcome);
+        }
 
-        let proactive_engine = proactive::ProactiveEngine::new(proactive_tx.clone());
+        let proactive_engine = proactive::ProactiveEngine::new(
+            proactive_tx.clone(),
+            telemetry::Thresholds::load(),
+            proactive::Intervals::load(),
+        );
 
         let mut app = Self {
             input: String::new(),
@@ -99,24 +167,48 @@ impl App {
             feed,
             feed_scroll: 0,
             telemetry: telemetry::SysTelemetry::default(),
-            aurora: aurora_client::AuroraStatus::default(),
+            cpu_sampler: telemetry::CpuSampler::new(),
+            aurora: None,
+            health_rx,
+            health_tx,
             quit: false,
-            history: Vec::new(),
+            history: context::load_history(context::history_size()),
+            history_max: context::history_size(),
             history_pos: None,
             thinking: false,
             thinking_frame: 0,
             brain_rx,
             brain_tx,
+            streaming_card: None,
+            streaming_text: String::new(),
+            completion: None,
             active_panel: ActivePanel::Input,
             selected_feed_item: None,
+            group_feed: true,
+            expanded_groups: HashSet::new(),
+            feed_layout: RefCell::new(ui::FeedLayout::default()),
             proactive_rx,
             proactive_tx,
             proactive: proactive_engine,
             task_manager: tasks::TaskManager::new(),
+            selected_task: 0,
             session: context::SessionContext::load(),
+            hidden_sources: HashSet::new(),
+            aliases: BTreeMap::new(),
+            last_dismissed: Vec::new(),
+            toast: None,
+            last_control: None,
+            power: None,
+            away: away::AwayTracker::new(0),
         };
-        app.telemetry = telemetry::read_telemetry();
-        app.aurora = aurora_client::check_health();
+        app.hidden_sources = app.session.hidden_sources.clone();
+        app.group_feed = !app.session.flat_feed;
+        theme::init(app.session.theme.as_deref());
+        app.aliases = app.session.aliases.clone();
+        app.proactive.set_muted(app.session.muted_alerts.clone());
+        app.telemetry = telemetry::read_telemetry(&mut app.cpu_sampler);
+        app.refresh_health();
+        app.away = away::AwayTracker::new(app.feed.next_id());
 
         // Initial system health card
         app.push_system_health_card();
@@ -124,6 +216,14 @@ impl App {
         app
     }
 
+    /// Re-check the stack's health off the UI thread; the result lands in `aurora`.
+    fn refresh_health(&self) {
+        let tx = self.health_tx.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(aurora_client::check_health());
+        });
+    }
+
     fn push_system_health_card(&mut self) {
         let t = &self.telemetry;
         let mem_used = t.mem_total_mb.saturating_sub(t.mem_avail_mb);
@@ -154,6 +254,29 @@ impl App {
         self.feed.push(card);
     }
 
+    /// Push a full-card chart of the retained CPU or memory history.
+    fn push_history_card(&mut self, metric: &str) {
+        let (label, values, color) = match metric {
+            "mem" => ("Memory", self.proactive.mem_pct_history(), ui::BlockColor::Magenta),
+            _ => ("CPU", self.proactive.cpu_history(), ui::BlockColor::Cyan),
+        };
+
+        let card = FeedItem::new(
+            FeedSource::System,
+            Priority::Normal,
+            format!("{} History", label),
+        )
+        .with_widget(WidgetData {
+            widget_type: "history".to_string(),
+            title: format!("{} %", label),
+            lines: widgets::history_chart(&values, 40, 6, TELEMETRY_INTERVAL_SECS),
+            color,
+            weather: None,
+            progress: None,
+        });
+        self.feed.push(card);
+    }
+
     fn push_brain_response(&mut self, resp: brain_client::BrainResponse) {
         let mut body: Vec<String> = Vec::new();
         if !resp.text.is_empty() {
@@ -162,27 +285,36 @@ impl App {
             }
         }
 
+        let reasoning: Vec<String> = resp.reasoning
+            .as_deref()
+            .map(|r| r.lines().map(|l| l.to_string()).collect())
+            .unwrap_or_default();
+
         let mut card = FeedItem::new(
             FeedSource::Brain,
             Priority::Normal,
             "Brain Response".to_string(),
         )
-        .with_body(body);
+        .with_body(body)
+        .with_reasoning(reasoning)
+        .with_markdown();
 
         // Add first widget to the main card
         if let Some(first_widget) = resp.widgets.first() {
-            let color = widget_color(&first_widget.widget_type);
+            let color = ui::BlockColor::for_widget(&first_widget.widget_type);
             card = card.with_widget(WidgetData {
                 widget_type: first_widget.widget_type.clone(),
                 title: first_widget.title.clone(),
                 lines: first_widget.lines.clone(),
+                weather: first_widget.weather.clone(),
+                progress: first_widget.progress.clone(),
                 color,
             });
         }
 
         // Additional widgets as separate cards
         for widget in resp.widgets.iter().skip(1) {
-            let color = widget_color(&widget.widget_type);
+            let color = ui::BlockColor::for_widget(&widget.widget_type);
             let widget_card = FeedItem::new(
                 FeedSource::Brain,
                 Priority::Normal,
@@ -192,6 +324,8 @@ impl App {
                 widget_type: widget.widget_type.clone(),
                 title: widget.title.clone(),
                 lines: widget.lines.clone(),
+                weather: widget.weather.clone(),
+                progress: widget.progress.clone(),
                 color,
             });
             self.feed.push(widget_card);
@@ -206,21 +340,38 @@ impl App {
     }
 
     fn submit_command(&mut self) {
-        let cmd = self.input.trim().to_string();
-        if cmd.is_empty() {
+        let typed = self.input.trim().to_string();
+        if typed.is_empty() {
             return;
         }
 
         // Echo user input as a card
-        let user_card = FeedItem::new(FeedSource::User, Priority::Normal, format!("> {}", cmd));
+        let user_card = FeedItem::new(FeedSource::User, Priority::Normal, format!("> {}", typed));
         self.feed.push(user_card);
 
-        self.history.push(cmd.clone());
+        if self.history.last() != Some(&typed) {
+            self.history.push(typed.clone());
+            context::append_history(&typed);
+            if self.history.len() > self.history_max {
+                self.history.remove(0);
+            }
+        }
         self.history_pos = None;
         self.input.clear();
         self.cursor = 0;
         self.feed_scroll = 0;
 
+        // Alias management is never itself alias-expanded
+        if let Some(args) = commands::command_args(&typed, "/alias") {
+            self.handle_alias_command(args);
+            return;
+        }
+        if let Some(name) = commands::command_args(&typed, "/unalias") {
+            self.handle_unalias_command(name);
+            return;
+        }
+        let cmd = commands::expand_alias(&typed, &self.aliases);
+
         // Local-only commands
         let lower = cmd.to_lowercase();
         match lower.as_str() {
@@ -241,7 +392,7 @@ impl App {
                 return;
             }
             "sysinfo" => {
-                self.telemetry = telemetry::read_telemetry();
+                self.telemetry = telemetry::read_telemetry(&mut self.cpu_sampler);
                 let result = commands::sysinfo_text(&self.telemetry);
                 let card = FeedItem::new(
                     FeedSource::System,
@@ -252,12 +403,20 @@ impl App {
                 self.feed.push(card);
                 return;
             }
+            "history cpu" | "history mem" => {
+                self.push_history_card(&lower[8..]);
+                return;
+            }
+            "learn on" | "learn off" | "save weights" => {
+                self.send_world_model_control(&lower);
+                return;
+            }
             "tasks" => {
                 let summary = self.task_manager.summary();
                 let active = self.task_manager.active_tasks();
                 let mut body = vec![summary];
-                for (name, elapsed) in active {
-                    body.push(format!("  {} ({}s)", name, elapsed));
+                for task in active {
+                    body.push(format!("  {} ({}s)", task.name, task.elapsed_secs()));
                 }
                 let card = FeedItem::new(
                     FeedSource::System,
@@ -271,6 +430,73 @@ impl App {
             _ => {}
         }
 
+        // Markdown transcript: /export session.md
+        if let Some(args) = commands::command_args(&cmd, "/export") {
+            let name = match args {
+                "" => "aether-session.md",
+                name => name,
+            };
+            let markdown = export::to_markdown(&self.visible_feed());
+            let body = match export::write_export(name, &markdown) {
+                Ok((path, bytes)) => vec![format!("Wrote {} bytes to {}", bytes, path)],
+                Err(e) => vec![format!("Export failed: {}", e)],
+            };
+            let card = FeedItem::new(FeedSource::System, Priority::Low, "Export".to_string())
+                .with_body(body);
+            self.feed.push(card);
+            return;
+        }
+
+        // Feed filter: /filter brain off
+        if let Some(args) = commands::command_args(&lower, "/filter") {
+            self.handle_filter_command(args);
+            return;
+        }
+
+        // Color theme: /theme light
+        if let Some(args) = commands::command_args(&lower, "/theme") {
+            self.handle_theme_command(args);
+            return;
+        }
+
+        // Idle summaries: /away on 15
+        if let Some(args) = commands::command_args(&lower, "/away") {
+            self.handle_away_command(args);
+            return;
+        }
+
+        // Polling intervals: /proactive interval brain 300
+        if let Some(args) = commands::command_args(&lower, "/proactive") {
+            self.handle_proactive_command(args);
+            return;
+        }
+
+        // Card grouping: /group off
+        if let Some(args) = commands::command_args(&lower, "/group") {
+            self.handle_group_command(args);
+            return;
+        }
+
+        // Alert mutes: /mute highcpu 1h, /unmute all
+        if let Some(args) = commands::command_args(&lower, "/unmute") {
+            self.handle_unmute_command(args);
+            return;
+        }
+        if let Some(args) = commands::command_args(&lower, "/mute") {
+            self.handle_mute_command(args);
+            return;
+        }
+
+        // Power: /shutdown, /reboot (asked twice, then a short undo window)
+        if let Some(action) = PowerAction::parse(lower.trim()) {
+            self.handle_power_command(action, lower.trim());
+            return;
+        }
+        if lower.trim() == "/cancel" {
+            self.cancel_power();
+            return;
+        }
+
         // Background task with & prefix
         if cmd.starts_with('&') {
             let query = cmd[1..].trim();
@@ -343,18 +569,464 @@ impl App {
         let tx = self.brain_tx.clone();
         let input_str = cmd;
         std::thread::spawn(move || {
-            let result = match brain_client::query_brain(&input_str) {
-                Ok(resp) => resp,
-                Err(e) => brain_client::BrainResponse {
+            if let Err(e) = brain_client::query_brain_streaming(&input_str, &tx) {
+                let _ = tx.send(brain_client::BrainStreamEvent::Done(brain_client::BrainResponse {
                     ok: false,
                     text: format!("Brain error: {}", e),
                     widgets: vec![],
                     latency_ms: 0,
                     error: Some(e),
-                },
+                    reasoning: None,
+                }));
+            }
+        });
+    }
+
+    /// Grow the live brain card on each delta; swap in the full card when done.
+    fn handle_brain_event(&mut self, event: brain_client::BrainStreamEvent) {
+        match event {
+            brain_client::BrainStreamEvent::Delta(delta) => {
+                self.streaming_text.push_str(&delta);
+                let body: Vec<String> = self.streaming_text.lines().map(|l| l.to_string()).collect();
+                match self.streaming_card {
+                    Some(id) => self.feed.set_body(id, body),
+                    None => {
+                        let card = FeedItem::new(
+                            FeedSource::Brain,
+                            Priority::Normal,
+                            "Brain Response".to_string(),
+                        )
+                        .with_body(body)
+                        .with_markdown();
+                        self.streaming_card = Some(self.feed.push(card));
+                    }
+                }
+            }
+            brain_client::BrainStreamEvent::Done(resp) => {
+                self.thinking = false;
+                if let Some(id) = self.streaming_card.take() {
+                    self.feed.remove(id);
+                }
+                self.streaming_text.clear();
+                self.push_brain_response(resp);
+            }
+        }
+    }
+
+    /// Send a learning toggle or weight save to CFC-JEPA on a background
+    /// thread; the result card arrives through the proactive channel.
+    fn send_world_model_control(&mut self, cmd: &str) {
+        let kind = control_kind(cmd);
+        if let Some((last, at)) = &self.last_control {
+            if *last == kind && at.elapsed() < CONTROL_DEBOUNCE {
+                let card = FeedItem::new(
+                    FeedSource::WorldModel,
+                    Priority::Low,
+                    "Already sent".to_string(),
+                )
+                .with_body(vec![format!("A {} was just sent; wait a moment before sending another.", kind)]);
+                self.feed.push(card);
+                return;
+            }
+        }
+        self.last_control = Some((kind, Instant::now()));
+
+        let tx = self.proactive_tx.clone();
+        let cmd = cmd.to_string();
+        std::thread::spawn(move || {
+            let (title, resp) = match cmd.as_str() {
+                "learn on" => ("Learning Enabled", aurora_client::set_learning(true)),
+                "learn off" => ("Learning Disabled", aurora_client::set_learning(false)),
+                _ => ("Weights Saved", aurora_client::save_weights()),
             };
-            let _ = tx.send(result);
+            let _ = tx.send(world_model_control_card(title, &resp));
+        });
+    }
+
+    /// Visible feed items with the active source filter applied.
+    pub fn visible_feed(&self) -> Vec<&FeedItem> {
+        self.feed.visible_items_filtered(&self.hidden_sources)
+    }
+
+    /// Rows of the feed as drawn: visible items, grouped unless `/group off`.
+    pub fn feed_entries(&self) -> Vec<FeedEntry<'_>> {
+        let visible = self.visible_feed();
+        if self.group_feed {
+            feed::group_entries(visible, &self.expanded_groups)
+        } else {
+            feed::flat_entries(visible)
+        }
+    }
+
+    /// Id of the selected card, unless a group header is selected.
+    fn selected_item_id(&self) -> Option<u64> {
+        let idx = self.selected_feed_item?;
+        self.feed_entries().get(idx)?.item().map(|i| i.id)
+    }
+
+    /// Keep the feed selection on a row that still exists.
+    fn clamp_selected_feed_item(&mut self) {
+        let count = self.feed_entries().len();
+        self.selected_feed_item = match self.selected_feed_item {
+            Some(idx) if count > 0 => Some(idx.min(count - 1)),
+            _ => None,
+        };
+    }
+
+    /// `/group on|off` folds runs of similar cards; bare `/group` reports.
+    fn handle_group_command(&mut self, args: &str) {
+        let body = match args {
+            "" => None,
+            "on" => {
+                self.group_feed = true;
+                None
+            }
+            "off" => {
+                self.group_feed = false;
+                None
+            }
+            _ => Some("Usage: /group on|off".to_string()),
+        };
+        self.session.flat_feed = !self.group_feed;
+        self.clamp_selected_feed_item();
+
+        let body = body.unwrap_or_else(|| {
+            if self.group_feed {
+                "Grouping runs of similar cards (Enter expands a group).".to_string()
+            } else {
+                "Showing every card separately.".to_string()
+            }
+        });
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Feed Grouping".to_string())
+            .with_body(vec![body]);
+        self.feed.push(card);
+    }
+
+    /// `/theme <name>` switches color theme; bare `/theme` lists them.
+    fn handle_theme_command(&mut self, args: &str) {
+        let body = if args.is_empty() {
+            vec![
+                format!("Current theme: {}", theme::current().name),
+                format!("Built-in: {}", theme::BUILTIN_THEMES.join(", ")),
+            ]
+        } else {
+            match theme::by_name(args) {
+                Some(t) => {
+                    let msg = format!("Switched to {}.", t.name);
+                    self.session.theme = Some(t.name.clone());
+                    theme::set(t);
+                    vec![msg]
+                }
+                None => vec![format!(
+                    "Unknown theme '{}'. Built-in: {}",
+                    args,
+                    theme::BUILTIN_THEMES.join(", ")
+                )],
+            }
+        };
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Theme".to_string())
+            .with_body(body);
+        self.feed.push(card);
+    }
+
+    /// `/filter <source> on|off`, `/filter clear`, or bare `/filter` for status.
+    fn handle_filter_command(&mut self, args: &str) {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let msg = match parts.as_slice() {
+            [] => None,
+            ["clear"] | ["all", "on"] => {
+                self.hidden_sources.clear();
+                None
+            }
+            [name, state @ ("on" | "off")] => match FeedSource::parse(name) {
+                Some(source) => {
+                    if *state == "off" {
+                        self.hidden_sources.insert(source);
+                    } else {
+                        self.hidden_sources.remove(&source);
+                    }
+                    None
+                }
+                None => Some(format!(
+                    "Unknown source '{}'. Use system, brain, world, user, task or notification.",
+                    name
+                )),
+            },
+            _ => Some("Usage: /filter <source> on|off, or /filter clear".to_string()),
+        };
+
+        self.session.hidden_sources = self.hidden_sources.clone();
+        self.clamp_selected_feed_item();
+
+        let body = msg.unwrap_or_else(|| {
+            if self.hidden_sources.is_empty() {
+                "Showing all sources.".to_string()
+            } else {
+                format!("Hidden: {}", self.hidden_labels().join(", "))
+            }
         });
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Feed Filter".to_string())
+            .with_body(vec![body]);
+        self.feed.push(card);
+    }
+
+    /// A key or click: if it ends an idle episode and summaries are on, ask
+    /// the brain what happened in the feed meanwhile.
+    fn note_input(&mut self) {
+        let Some(away) = self.away.input(self.feed.next_id()) else {
+            return;
+        };
+        if self.session.away_summary_mins.is_none() {
+            return;
+        }
+        let cards = self.feed.visible_since(away.since_id);
+        if !cards.is_empty() {
+            away::summarize(cards, &away, self.proactive_tx.clone());
+        }
+    }
+
+    /// `/away on [minutes]` turns on idle summaries, `/away off` stops them.
+    fn handle_away_command(&mut self, args: &str) {
+        let mut words = args.split_whitespace();
+        let body = match (words.next(), words.next()) {
+            (None, _) => None,
+            (Some("on"), None) => {
+                self.session.away_summary_mins = Some(away::DEFAULT_IDLE_MINS);
+                None
+            }
+            (Some("on"), Some(mins)) => match mins.parse::<u64>() {
+                Ok(mins) if (1..=away::MAX_IDLE_MINS).contains(&mins) => {
+                    self.session.away_summary_mins = Some(mins);
+                    None
+                }
+                _ => Some(format!("Minutes must be a whole number from 1 to {}.", away::MAX_IDLE_MINS)),
+            },
+            (Some("off"), None) => {
+                self.session.away_summary_mins = None;
+                None
+            }
+            _ => Some("Usage: /away on [minutes] | /away off".to_string()),
+        };
+
+        let body = body.unwrap_or_else(|| match self.session.away_summary_mins {
+            Some(mins) => format!("After {} min without input, you'll get a summary of what arrived.", mins),
+            None => "Idle summaries are off (/away on to enable).".to_string(),
+        });
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Away Summaries".to_string())
+            .with_body(vec![body]);
+        self.feed.push(card);
+    }
+
+    /// `/proactive interval <world|brain|cooldown> <secs>` changes how often the
+    /// proactive engine polls; bare `/proactive` shows the current intervals.
+    fn handle_proactive_command(&mut self, args: &str) {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let result = match parts.as_slice() {
+            [] | ["interval"] => Ok(()),
+            ["interval", name, value] => match parse_interval(value) {
+                Some(secs) => self.proactive.set_interval(name, secs),
+                None => Err(format!("Bad interval '{}'. Use seconds, or e.g. 5m.", value)),
+            },
+            _ => Err("Usage: /proactive interval <world|brain|cooldown> <seconds>".to_string()),
+        };
+
+        let i = self.proactive.intervals();
+        let mut body = vec![
+            format!("World model checks every {}s", i.world_model_secs),
+            format!("Brain insights every {}s", i.brain_secs),
+            format!("Same alert at most every {}s", i.cooldown_secs),
+        ];
+        if let Err(e) = result {
+            body.insert(0, e);
+        }
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Proactive Intervals".to_string())
+            .with_body(body);
+        self.feed.push(card);
+    }
+
+    /// `/mute <kind> [duration]` silences an alert kind; bare `/mute` lists mutes.
+    fn handle_mute_command(&mut self, args: &str) {
+        let parts: Vec<&str> = args.split_whitespace().collect();
+        let body = match parts.as_slice() {
+            [] => self.muted_lines(),
+            [name, rest @ ..] if rest.len() <= 1 => match (AlertKind::parse(name), rest.first()) {
+                (None, _) => vec![format!("Unknown alert '{}'. Kinds: {}", name, alert_kind_names())],
+                (Some(kind), None) => {
+                    self.proactive.mute(kind.clone());
+                    vec![format!("Muted {} until /unmute.", kind.label())]
+                }
+                (Some(kind), Some(dur)) => match commands::parse_duration(dur) {
+                    Some(d) => {
+                        self.proactive.snooze(kind.clone(), d);
+                        vec![format!("Muted {} for {}.", kind.label(), dur)]
+                    }
+                    None => vec![format!("Bad duration '{}'. Use e.g. 30m, 1h, 2d.", dur)],
+                },
+            },
+            _ => vec!["Usage: /mute <kind> [duration], e.g. /mute HighCpu 1h".to_string()],
+        };
+        self.session.muted_alerts = self.proactive.muted().clone();
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Alert Mutes".to_string())
+            .with_body(body);
+        self.feed.push(card);
+    }
+
+    /// First `/shutdown` asks for confirmation; repeating it within
+    /// `CONFIRM_WINDOW` starts the `UNDO_WINDOW` countdown.
+    fn handle_power_command(&mut self, action: PowerAction, typed: &str) {
+        let now = Instant::now();
+        let (priority, title, body) = match self.power {
+            Some(PowerState::Pending { action: pending, .. }) => (
+                Priority::Normal,
+                format!("{} already pending", pending.progressive()),
+                vec!["Type /cancel to stop it.".to_string()],
+            ),
+            Some(PowerState::Confirming { action: asked, until }) if asked == action && now < until => {
+                self.power = Some(PowerState::Pending { action, at: now + power::UNDO_WINDOW });
+                (
+                    Priority::Urgent,
+                    format!("{} in {}s", action.progressive(), power::UNDO_WINDOW.as_secs()),
+                    vec!["Type /cancel to stop.".to_string()],
+                )
+            }
+            _ => {
+                self.power = Some(PowerState::Confirming { action, until: now + power::CONFIRM_WINDOW });
+                (
+                    Priority::Urgent,
+                    format!("{} AetherOS?", action.verb()),
+                    vec![
+                        format!("Type {} again within {}s to confirm.", typed, power::CONFIRM_WINDOW.as_secs()),
+                        "Running programs will be stopped.".to_string(),
+                    ],
+                )
+            }
+        };
+        let card = FeedItem::new(FeedSource::System, priority, title).with_body(body);
+        self.feed.push(card);
+    }
+
+    fn cancel_power(&mut self) {
+        let body = match self.power.take() {
+            Some(PowerState::Pending { action, .. } | PowerState::Confirming { action, .. }) => {
+                format!("{} cancelled.", action.verb())
+            }
+            None => "Nothing to cancel.".to_string(),
+        };
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Power".to_string())
+            .with_body(vec![body]);
+        self.feed.push(card);
+    }
+
+    /// Run a pending power action once its undo window is over, and let
+    /// unanswered confirmations lapse.
+    fn tick_power(&mut self) {
+        let now = Instant::now();
+        match self.power {
+            Some(PowerState::Confirming { until, .. }) if now >= until => self.power = None,
+            Some(PowerState::Pending { action, at }) if now >= at => {
+                self.power = None;
+                self.feed.save_to(&feed::feed_path());
+                self.session.save();
+                if let Err(e) = power::perform(action) {
+                    let card = FeedItem::new(
+                        FeedSource::System,
+                        Priority::Urgent,
+                        format!("{} failed", action.verb()),
+                    )
+                    .with_body(vec![e]);
+                    self.feed.push(card);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// `/unmute <kind>` or `/unmute all`.
+    fn handle_unmute_command(&mut self, args: &str) {
+        let body = match args {
+            "" => self.muted_lines(),
+            "all" => {
+                self.proactive.unmute_all();
+                vec!["All alerts unmuted.".to_string()]
+            }
+            name => match AlertKind::parse(name) {
+                Some(kind) if self.proactive.unmute(&kind) => vec![format!("Unmuted {}.", kind.label())],
+                Some(kind) => vec![format!("{} wasn't muted.", kind.label())],
+                None => vec![format!("Unknown alert '{}'. Kinds: {}", name, alert_kind_names())],
+            },
+        };
+        self.session.muted_alerts = self.proactive.muted().clone();
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Alert Mutes".to_string())
+            .with_body(body);
+        self.feed.push(card);
+    }
+
+    /// One line per muted kind with its remaining time.
+    fn muted_lines(&self) -> Vec<String> {
+        let muted = self.proactive.muted();
+        if muted.is_empty() {
+            return vec!["No alerts muted. Kinds: ".to_string() + &alert_kind_names()];
+        }
+        let now = proactive::unix_now();
+        muted
+            .iter()
+            .map(|(kind, until)| match until {
+                Some(t) => format!("{:?}: {}m left", kind, t.saturating_sub(now).div_ceil(60)),
+                None => format!("{:?}: until /unmute", kind),
+            })
+            .collect()
+    }
+
+    /// `/alias name=expansion` defines a shortcut; bare `/alias` lists them.
+    fn handle_alias_command(&mut self, args: &str) {
+        let body = if args.is_empty() {
+            if self.aliases.is_empty() {
+                vec!["No aliases. Define one with /alias w=&weather in Tokyo".to_string()]
+            } else {
+                self.aliases
+                    .iter()
+                    .map(|(name, expansion)| format!("{} = {}", name, expansion))
+                    .collect()
+            }
+        } else {
+            match args.split_once('=') {
+                Some((name, expansion))
+                    if !name.trim().is_empty()
+                        && !name.trim().contains(char::is_whitespace)
+                        && !expansion.trim().is_empty() =>
+                {
+                    let (name, expansion) = (name.trim(), expansion.trim());
+                    self.aliases.insert(name.to_string(), expansion.to_string());
+                    self.session.aliases = self.aliases.clone();
+                    vec![format!("{} = {}", name, expansion)]
+                }
+                _ => vec!["Usage: /alias name=expansion (name must be one word)".to_string()],
+            }
+        };
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Aliases".to_string())
+            .with_body(body);
+        self.feed.push(card);
+    }
+
+    /// `/unalias name` removes a shortcut.
+    fn handle_unalias_command(&mut self, name: &str) {
+        let body = if self.aliases.remove(name).is_some() {
+            self.session.aliases = self.aliases.clone();
+            format!("Removed alias '{}'.", name)
+        } else {
+            format!("No alias named '{}'.", name)
+        };
+        let card = FeedItem::new(FeedSource::System, Priority::Low, "Aliases".to_string())
+            .with_body(vec![body]);
+        self.feed.push(card);
+    }
+
+    /// Labels of hidden sources, sorted for stable display.
+    fn hidden_labels(&self) -> Vec<&'static str> {
+        let mut labels: Vec<&'static str> =
+            self.hidden_sources.iter().map(|s| s.label()).collect();
+        labels.sort();
+        labels
     }
 
     /// Cycle to the next panel.
@@ -365,7 +1037,7 @@ impl App {
             ActivePanel::Sidebar => ActivePanel::Input,
         };
         if self.active_panel == ActivePanel::Feed {
-            let count = self.feed.visible_items().len();
+            let count = self.feed_entries().len();
             self.selected_feed_item = if count > 0 { Some(count - 1) } else { None };
         }
     }
@@ -380,72 +1052,221 @@ impl App {
 
     fn feed_select_next(&mut self) {
         if let Some(idx) = self.selected_feed_item {
-            let max = self.feed.visible_items().len().saturating_sub(1);
+            let max = self.feed_entries().len().saturating_sub(1);
             if idx < max {
                 self.selected_feed_item = Some(idx + 1);
             }
         }
     }
 
+    /// Enter collapses a card, or expands/folds a selected group.
     fn feed_toggle_collapse(&mut self) {
-        if let Some(idx) = self.selected_feed_item {
-            let visible = self.feed.visible_items();
-            if let Some(item) = visible.get(idx) {
-                let id = item.id;
-                self.feed.toggle_collapse(id);
+        let Some(idx) = self.selected_feed_item else {
+            return;
+        };
+        let (item_id, group_key) = match self.feed_entries().get(idx) {
+            Some(FeedEntry::Item(item)) => (Some(item.id), None),
+            Some(FeedEntry::Group { key, .. }) => (None, Some(*key)),
+            None => return,
+        };
+        if let Some(id) = item_id {
+            self.feed.toggle_collapse(id);
+        }
+        if let Some(key) = group_key {
+            if !self.expanded_groups.remove(&key) {
+                self.expanded_groups.insert(key);
             }
         }
     }
 
+    fn feed_toggle_reasoning(&mut self) {
+        if let Some(id) = self.selected_item_id() {
+            self.feed.toggle_reasoning(id);
+        }
+    }
+
+    /// Dismiss the selected card, or every card in a selected group.
     fn feed_dismiss(&mut self) {
-        if let Some(idx) = self.selected_feed_item {
-            let visible = self.feed.visible_items();
-            if let Some(item) = visible.get(idx) {
-                let id = item.id;
-                self.feed.dismiss(id);
-                let new_count = self.feed.visible_items().len();
-                if new_count == 0 {
-                    self.selected_feed_item = None;
-                } else if idx >= new_count {
-                    self.selected_feed_item = Some(new_count - 1);
+        let Some(idx) = self.selected_feed_item else {
+            return;
+        };
+        let Some(ids) = self.feed_entries().get(idx).map(|e| e.ids()) else {
+            return;
+        };
+        for &id in &ids {
+            self.feed.dismiss(id);
+        }
+        self.last_dismissed = ids;
+        let new_count = self.feed_entries().len();
+        if new_count == 0 {
+            self.selected_feed_item = None;
+        } else if idx >= new_count {
+            self.selected_feed_item = Some(new_count - 1);
+        }
+    }
+
+    fn feed_toggle_pin(&mut self) {
+        if let Some(id) = self.selected_item_id() {
+            self.feed.toggle_pin(id);
+        }
+    }
+
+    /// Copy the selected card's title and body to the clipboard.
+    fn feed_copy(&mut self) {
+        let Some(idx) = self.selected_feed_item else {
+            return;
+        };
+        let entries = self.feed_entries();
+        let Some(item) = entries.get(idx).and_then(|e| e.item()) else {
+            return;
+        };
+        let mut text = item.title.clone();
+        for line in &item.body {
+            text.push('\n');
+            text.push_str(line);
+        }
+        let msg = match clipboard::copy(&text) {
+            Ok(via) => format!("Copied ({})", via),
+            Err(e) => format!("Copy failed: {}", e),
+        };
+        self.toast = Some((msg, Instant::now()));
+    }
+
+    /// Current toast message, if it hasn't expired.
+    pub fn active_toast(&self) -> Option<&str> {
+        self.toast
+            .as_ref()
+            .filter(|(_, at)| at.elapsed() < Duration::from_secs(TOAST_SECS))
+            .map(|(msg, _)| msg.as_str())
+    }
+
+    fn feed_undo_dismiss(&mut self) {
+        let ids = std::mem::take(&mut self.last_dismissed);
+        let Some(&first) = ids.first() else {
+            return;
+        };
+        for &id in &ids {
+            self.feed.undismiss(id);
+        }
+        if let Some(idx) = self.feed_entries().iter().position(|e| e.ids().contains(&first)) {
+            self.selected_feed_item = Some(idx);
+        }
+    }
+
+    /// Cancel the task highlighted in the sidebar.
+    fn cancel_selected_task(&mut self) {
+        let id = self
+            .task_manager
+            .active_tasks()
+            .get(self.selected_task)
+            .map(|t| t.id);
+        if let Some(card) = id.and_then(|id| self.task_manager.cancel(id, &mut self.feed)) {
+            self.feed.push(card);
+        }
+        self.clamp_selected_task();
+    }
+
+    /// Keep the task highlight on a row that still exists.
+    fn clamp_selected_task(&mut self) {
+        let count = self.task_manager.active_count();
+        self.selected_task = self.selected_task.min(count.saturating_sub(1));
+    }
+
+    /// Tab: fill a lone match, extend to the common prefix of several, then
+    /// cycle through them on repeated presses.
+    fn complete_input(&mut self) {
+        if let Some(completion) = self.completion.as_mut() {
+            self.input = completion.next().to_string();
+            self.cursor = self.input.len();
+            return;
+        }
+
+        let candidates = commands::completions(&self.input, &self.history);
+        match candidates.len() {
+            0 => {}
+            1 => {
+                self.input = candidates[0].clone();
+                self.cursor = self.input.len();
+            }
+            _ => {
+                let prefix = commands::common_prefix(&candidates);
+                if prefix.len() > self.input.len() {
+                    self.input = prefix;
+                    self.cursor = self.input.len();
+                }
+                self.completion = Some(commands::Completion { candidates, index: None });
+            }
+        }
+    }
+
+    /// Wheel scrolls the feed; a left click selects the card under it, and
+    /// opens a link if it landed on one.
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        match mouse.kind {
+            MouseEventKind::ScrollUp => self.feed_scroll = self.feed_scroll.saturating_add(3),
+            MouseEventKind::ScrollDown => self.feed_scroll = self.feed_scroll.saturating_sub(3),
+            MouseEventKind::Down(MouseButton::Left) => {
+                let link = self.feed_layout.borrow().link_at(mouse.column, mouse.row).map(str::to_string);
+                if let Some(url) = link {
+                    self.open_link(&url);
+                }
+                let hit = self.feed_layout.borrow().card_at(mouse.column, mouse.row);
+                if let Some(idx) = hit {
+                    self.completion = None;
+                    self.active_panel = ActivePanel::Feed;
+                    self.selected_feed_item = Some(idx);
                 }
             }
+            _ => {}
+        }
+    }
+
+    /// Open a URL or file from the feed with the desktop's handler.
+    fn open_link(&mut self, url: &str) {
+        if let Err(e) = links::open(url) {
+            let card = FeedItem::new(FeedSource::System, Priority::Normal, "Can't open link".to_string())
+                .with_body(vec![url.to_string(), e]);
+            self.feed.push(card);
         }
     }
 
     /// Handle an action from the input router.
     fn handle_action(&mut self, action: AppAction) {
+        if !matches!(action, AppAction::Complete | AppAction::Noop) {
+            self.completion = None;
+        }
         match action {
+            AppAction::Complete => self.complete_input(),
             AppAction::Quit => self.quit = true,
             AppAction::SwitchPanel => self.cycle_panel(),
             AppAction::ReturnToInput => self.active_panel = ActivePanel::Input,
 
+            // `cursor` is a byte index, always kept on a char boundary
             AppAction::TypeChar(c) => {
                 self.input.insert(self.cursor, c);
-                self.cursor += 1;
+                self.cursor += c.len_utf8();
             }
             AppAction::Backspace => {
                 if self.cursor > 0 {
-                    self.cursor -= 1;
+                    self.cursor = input::prev_char(&self.input, self.cursor);
                     self.input.remove(self.cursor);
                 }
             }
+            AppAction::DeleteWordBackward => {
+                let start = input::prev_word(&self.input, self.cursor);
+                self.input.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
             AppAction::Delete => {
                 if self.cursor < self.input.len() {
                     self.input.remove(self.cursor);
                 }
             }
             AppAction::Submit => self.submit_command(),
-            AppAction::CursorLeft => {
-                if self.cursor > 0 {
-                    self.cursor -= 1;
-                }
-            }
-            AppAction::CursorRight => {
-                if self.cursor < self.input.len() {
-                    self.cursor += 1;
-                }
-            }
+            AppAction::CursorLeft => self.cursor = input::prev_char(&self.input, self.cursor),
+            AppAction::CursorRight => self.cursor = input::next_char(&self.input, self.cursor),
+            AppAction::CursorWordLeft => self.cursor = input::prev_word(&self.input, self.cursor),
+            AppAction::CursorWordRight => self.cursor = input::next_word(&self.input, self.cursor),
             AppAction::CursorHome => self.cursor = 0,
             AppAction::CursorEnd => self.cursor = self.input.len(),
             AppAction::HistoryUp => {
@@ -478,7 +1299,11 @@ impl App {
             AppAction::FeedSelectPrev => self.feed_select_prev(),
             AppAction::FeedSelectNext => self.feed_select_next(),
             AppAction::FeedToggleCollapse => self.feed_toggle_collapse(),
+            AppAction::FeedToggleReasoning => self.feed_toggle_reasoning(),
             AppAction::FeedDismiss => self.feed_dismiss(),
+            AppAction::FeedUndoDismiss => self.feed_undo_dismiss(),
+            AppAction::FeedTogglePin => self.feed_toggle_pin(),
+            AppAction::FeedCopy => self.feed_copy(),
             AppAction::FeedPageUp => {
                 self.feed_scroll = self.feed_scroll.saturating_add(10);
             }
@@ -486,6 +1311,15 @@ impl App {
                 self.feed_scroll = self.feed_scroll.saturating_sub(10);
             }
 
+            AppAction::TaskSelectPrev => {
+                self.selected_task = self.selected_task.saturating_sub(1);
+            }
+            AppAction::TaskSelectNext => {
+                self.selected_task += 1;
+                self.clamp_selected_task();
+            }
+            AppAction::TaskCancel => self.cancel_selected_task(),
+
             AppAction::PageUp => {
                 self.feed_scroll = self.feed_scroll.saturating_add(10);
             }
@@ -494,7 +1328,7 @@ impl App {
             }
 
             AppAction::TriggerSysinfo => {
-                self.telemetry = telemetry::read_telemetry();
+                self.telemetry = telemetry::read_telemetry(&mut self.cpu_sampler);
                 let result = commands::sysinfo_text(&self.telemetry);
                 let card = FeedItem::new(
                     FeedSource::System,
@@ -532,6 +1366,11 @@ impl App {
                                 data.total_updates,
                                 data.mean_prediction_error
                             ),
+                            format!(
+                                "Type 'learn {}' to {} learning, 'save weights' to persist.",
+                                if data.learning_enabled { "off" } else { "on" },
+                                if data.learning_enabled { "pause" } else { "resume" },
+                            ),
                         ]);
                         self.feed.push(card);
                     }
@@ -552,16 +1391,42 @@ impl App {
     }
 }
 
-fn widget_color(widget_type: &str) -> ui::BlockColor {
-    match widget_type {
-        "weather" => ui::BlockColor::Yellow,
-        "system" => ui::BlockColor::Green,
-        "file" => ui::BlockColor::Blue,
-        "table" => ui::BlockColor::Cyan,
-        _ => ui::BlockColor::White,
+/// What a CFC-JEPA control command does, for debouncing: "learn on" and
+/// "learn off" are both a learning toggle, so flipping back and forth is
+/// held off too.
+fn control_kind(cmd: &str) -> &'static str {
+    if cmd.starts_with("learn") {
+        "learning toggle"
+    } else {
+        "weight save"
     }
 }
 
+/// Result card for a CFC-JEPA control command, showing the returned status.
+fn world_model_control_card(title: &str, resp: &str) -> FeedItem {
+    if let Some(err) = resp.strip_prefix("Error: ") {
+        return FeedItem::new(
+            FeedSource::WorldModel,
+            Priority::Normal,
+            "World Model Control Failed".to_string(),
+        )
+        .with_body(vec![format!("{}: {}", title, err)]);
+    }
+
+    // Show a JSON object's fields one per line; anything else verbatim
+    let body = match serde_json::from_str::<serde_json::Value>(resp) {
+        Ok(serde_json::Value::Object(map)) => map
+            .iter()
+            .map(|(k, v)| match v {
+                serde_json::Value::String(s) => format!("{}: {}", k, s),
+                other => format!("{}: {}", k, other),
+            })
+            .collect(),
+        _ => resp.lines().map(|l| l.to_string()).collect(),
+    };
+    FeedItem::new(FeedSource::WorldModel, Priority::Normal, title.to_string()).with_body(body)
+}
+
 /// Try to get terminal size, with fallback for serial consoles.
 fn get_terminal_size() -> (u16, u16) {
     if let Ok((w, h)) = terminal::size() {
@@ -587,6 +1452,7 @@ fn main() -> io::Result<()> {
     let mut stdout = io::stdout();
     stdout.execute(Clear(ClearType::All))?;
     stdout.execute(cursor::MoveTo(0, 0))?;
+    stdout.execute(EnableMouseCapture)?;
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::with_options(
@@ -601,49 +1467,65 @@ fn main() -> io::Result<()> {
     let mut last_tick = Instant::now();
     let mut telemetry_interval = Instant::now();
     let mut health_card_interval = Instant::now();
+    let mut feed_save_interval = Instant::now();
+    let mut stack_health_interval = Instant::now();
 
     loop {
         // Render
-        terminal.draw(|f| ui::draw(f, &app))?;
+        let frame = terminal.draw(|f| ui::draw(f, &app))?;
+        ui::write_hyperlinks(&mut io::stdout(), frame.buffer, &app.feed_layout.borrow())?;
 
         // Input
         let timeout = tick_rate.saturating_sub(last_tick.elapsed());
         if event::poll(timeout)? {
-            if let Event::Key(key) = event::read()? {
-                let action = input::route(key, &app.active_panel, app.thinking);
-                app.handle_action(action);
+            match event::read()? {
+                Event::Key(key) => {
+                    app.note_input();
+                    let action = input::route(key, &app.active_panel, app.thinking, app.input.is_empty());
+                    app.handle_action(action);
+                }
+                Event::Mouse(mouse) => {
+                    app.note_input();
+                    app.handle_mouse(mouse);
+                }
+                _ => {}
             }
         }
 
         // Brain response check
         if app.thinking {
-            if let Ok(resp) = app.brain_rx.try_recv() {
-                app.thinking = false;
-                app.push_brain_response(resp);
-            } else {
-                app.thinking_frame = app.thinking_frame.wrapping_add(1);
+            app.thinking_frame = app.thinking_frame.wrapping_add(1);
+            while let Ok(event) = app.brain_rx.try_recv() {
+                app.handle_brain_event(event);
             }
         }
 
+        while let Ok(status) = app.health_rx.try_recv() {
+            app.aurora = Some(status);
+        }
+
         // Proactive feed items
         while let Ok(item) = app.proactive_rx.try_recv() {
             app.feed.push(item);
         }
 
-        // Background task completions
-        let task_items = app.task_manager.tick();
-        for item in task_items {
-            app.feed.push(item);
-        }
+        // Background task output and completions
+        app.task_manager.tick(&mut app.feed);
+        app.clamp_selected_task();
 
         // Periodic tick
         if last_tick.elapsed() >= tick_rate {
             last_tick = Instant::now();
+            app.tick_power();
+            if let Some(mins) = app.session.away_summary_mins {
+                // A saved session may predate the `/away on` limit
+                app.away.tick(Duration::from_secs(mins.min(away::MAX_IDLE_MINS) * 60));
+            }
         }
 
         // Telemetry refresh every 2 seconds
-        if telemetry_interval.elapsed() >= Duration::from_secs(2) {
-            app.telemetry = telemetry::read_telemetry();
+        if telemetry_interval.elapsed() >= Duration::from_secs(TELEMETRY_INTERVAL_SECS) {
+            app.telemetry = telemetry::read_telemetry(&mut app.cpu_sampler);
             // Feed task + session context into proactive engine
             let (active, completed) = app.task_manager.counts();
             app.proactive.set_task_counts(active, completed);
@@ -652,6 +1534,12 @@ fn main() -> io::Result<()> {
             telemetry_interval = Instant::now();
         }
 
+        // Stack health for the status bar; aurorad caches it between polls
+        if stack_health_interval.elapsed() >= Duration::from_secs(STACK_HEALTH_INTERVAL_SECS) {
+            app.refresh_health();
+            stack_health_interval = Instant::now();
+        }
+
         // System health card every 30 seconds
         if health_card_interval.elapsed() >= Duration::from_secs(30) {
             app.push_system_health_card();
@@ -661,18 +1549,50 @@ fn main() -> io::Result<()> {
         // Prune stale feed items
         app.feed.prune_stale();
 
-        // Periodic session context save
+        // Periodic session context and feed saves
         app.session.maybe_save();
+        if feed_save_interval.elapsed() >= Duration::from_secs(FEED_SAVE_INTERVAL_SECS) {
+            app.feed.save_to(&feed::feed_path());
+            feed_save_interval = Instant::now();
+        }
 
         if app.quit {
+            app.feed.save_to(&feed::feed_path());
+            app.session.save();
             break;
         }
     }
 
     disable_raw_mode()?;
     let mut stdout = io::stdout();
+    stdout.execute(DisableMouseCapture)?;
     stdout.execute(Clear(ClearType::All))?;
     stdout.execute(cursor::MoveTo(0, 0))?;
     stdout.flush()?;
     Ok(())
 }
+
+/// Seconds for `/proactive interval`: a bare number, or a duration like `5m`.
+fn parse_interval(value: &str) -> Option<u64> {
+    value.parse().ok().or_else(|| commands::parse_duration(value).map(|d| d.as_secs()))
+}
+
+/// Alert kind names as `/mute` accepts them.
+fn alert_kind_names() -> String {
+    AlertKind::ALL
+        .iter()
+        .map(|k| format!("{:?}", k))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn learn_on_and_off_share_a_debounce_kind() {
+        assert_eq!(control_kind("learn on"), control_kind("learn off"));
+        assert_ne!(control_kind("learn on"), control_kind("save weights"));
+    }
+}
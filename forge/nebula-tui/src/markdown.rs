@@ -0,0 +1,152 @@
+use std::ops::Range;
+
+use aether_common::highlight::{Highlighter, Token};
+
+/// What a rendered line is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    Heading,
+    /// A line inside a fenced code block, shown verbatim.
+    Code,
+}
+
+/// Inline styling over part of a line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Inline {
+    Bold,
+    Italic,
+    Code,
+    /// Highlighted source in a fenced block.
+    Syntax(Token),
+}
+
+/// One source line with its Markdown markers taken out.
+#[derive(Clone, Debug)]
+pub struct MdLine {
+    pub kind: Kind,
+    /// The text to show, with any list bullet or indent in front.
+    pub text: String,
+    /// Styling as byte ranges of `text`. Ranges may nest.
+    pub spans: Vec<(Range<usize>, Inline)>,
+}
+
+/// Render the subset of Markdown brain replies use: `#` headers, `-`/`*`/`+`
+/// and numbered lists, ``` fences, and **bold**, *italic* and `code`.
+/// Anything else, including unmatched markers, stays as plain text. Fenced
+/// code is highlighted when its info string names a known language.
+pub fn parse(lines: &[String]) -> Vec<MdLine> {
+    let mut out = Vec::new();
+    // Inside a fence, with its language's highlighter if there is one
+    let mut code: Option<Option<Highlighter>> = None;
+    for line in lines {
+        let trimmed = line.trim_start();
+        if let Some(info) = trimmed.strip_prefix("```") {
+            code = match code {
+                Some(_) => None,
+                None => Some(info.split_whitespace().next().and_then(Highlighter::for_name)),
+            };
+            continue;
+        }
+        if let Some(highlighter) = &mut code {
+            let text = line.replace('\t', "    ");
+            let spans = match highlighter {
+                Some(h) => h.line(&text).into_iter().map(|(r, t)| (r, Inline::Syntax(t))).collect(),
+                None => Vec::new(),
+            };
+            out.push(MdLine { kind: Kind::Code, text, spans });
+            continue;
+        }
+
+        let level = trimmed.bytes().take_while(|&b| b == b'#').count();
+        if (1..=6).contains(&level) && trimmed[level..].starts_with(' ') {
+            out.push(inline_line(Kind::Heading, String::new(), trimmed[level..].trim()));
+            continue;
+        }
+
+        // Two spaces of source indent per nesting level
+        let indent = "  ".repeat((line.len() - trimmed.len()) / 2);
+        if let Some(rest) = trimmed.strip_prefix(['-', '*', '+']).and_then(|r| r.strip_prefix(' ')) {
+            out.push(inline_line(Kind::Text, format!("{indent}\u{2022} "), rest));
+            continue;
+        }
+        let digits = trimmed.bytes().take_while(u8::is_ascii_digit).count();
+        if digits > 0 && trimmed[digits..].starts_with(". ") {
+            let (number, rest) = trimmed.split_at(digits + 2);
+            out.push(inline_line(Kind::Text, format!("{indent}{number}"), rest));
+            continue;
+        }
+
+        out.push(inline_line(Kind::Text, String::new(), line));
+    }
+    out
+}
+
+fn inline_line(kind: Kind, prefix: String, source: &str) -> MdLine {
+    let mut line = MdLine { kind, text: prefix, spans: Vec::new() };
+    inline(source, &mut line);
+    line
+}
+
+/// Append `source` to `line.text`, turning emphasis markers into spans.
+fn inline(source: &str, line: &mut MdLine) {
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        if c == '\\' && rest[1..].starts_with(['*', '_', '`', '\\', '#']) {
+            line.text.push_str(&rest[1..2]);
+            rest = &rest[2..];
+            continue;
+        }
+        if let Some((inner, after)) = delimited(rest, "`") {
+            let start = line.text.len();
+            line.text.push_str(inner);
+            line.spans.push((start..line.text.len(), Inline::Code));
+            rest = after;
+            continue;
+        }
+        // snake_case_words aren't emphasis
+        let emphasis = if c == '_' && !at_word_start(source, rest) {
+            None
+        } else {
+            delimited(rest, "**")
+                .or_else(|| delimited(rest, "__"))
+                .map(|(i, a)| (i, a, Inline::Bold))
+                .or_else(|| delimited(rest, "*").or_else(|| delimited(rest, "_")).map(|(i, a)| (i, a, Inline::Italic)))
+        };
+        if let Some((inner, after, kind)) = emphasis {
+            let start = line.text.len();
+            inline(inner, line);
+            line.spans.push((start..line.text.len(), kind));
+            rest = after;
+            continue;
+        }
+        line.text.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+}
+
+/// If `s` opens with `marker` and closes it later, the text between and
+/// what follows the closing marker. Emphasis must hug its text: `* a *`
+/// isn't italic, and a single `*` doesn't close on half of a `**`.
+fn delimited<'a>(s: &'a str, marker: &str) -> Option<(&'a str, &'a str)> {
+    let body = s.strip_prefix(marker)?;
+    if marker != "`" && (body.starts_with(char::is_whitespace) || body.starts_with(marker)) {
+        return None;
+    }
+    let mut from = 0;
+    loop {
+        let end = from + body[from..].find(marker)?;
+        let inner = &body[..end];
+        let doubled = marker.len() == 1 && body[end + 1..].starts_with(marker);
+        if !inner.is_empty() && (marker == "`" || !inner.ends_with(char::is_whitespace)) && !doubled {
+            return Some((inner, &body[end + marker.len()..]));
+        }
+        from = end + marker.len() + usize::from(doubled);
+    }
+}
+
+/// Whether `rest`, a suffix of `source`, starts where a word can begin.
+fn at_word_start(source: &str, rest: &str) -> bool {
+    let before = &source[..source.len() - rest.len()];
+    !before.chars().next_back().is_some_and(char::is_alphanumeric)
+}
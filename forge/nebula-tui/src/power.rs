@@ -0,0 +1,93 @@
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long a confirmed power action waits, so `/cancel` can still stop it.
+pub const UNDO_WINDOW: Duration = Duration::from_secs(3);
+
+/// How long a confirmation card stays valid.
+pub const CONFIRM_WINDOW: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerAction {
+    Shutdown,
+    Reboot,
+}
+
+impl PowerAction {
+    pub fn parse(cmd: &str) -> Option<Self> {
+        match cmd {
+            "/shutdown" | "/poweroff" => Some(Self::Shutdown),
+            "/reboot" | "/restart" => Some(Self::Reboot),
+            _ => None,
+        }
+    }
+
+    /// "Shut down", "Reboot"
+    pub fn verb(self) -> &'static str {
+        match self {
+            Self::Shutdown => "Shut down",
+            Self::Reboot => "Reboot",
+        }
+    }
+
+    /// "Shutting down", "Rebooting"
+    pub fn progressive(self) -> &'static str {
+        match self {
+            Self::Shutdown => "Shutting down",
+            Self::Reboot => "Rebooting",
+        }
+    }
+
+    /// The command to run when we can't do it ourselves.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Shutdown => "systemctl poweroff",
+            Self::Reboot => "systemctl reboot",
+        }
+    }
+}
+
+/// Where a power action stands.
+#[derive(Clone, Copy, Debug)]
+pub enum PowerState {
+    /// Asked once; repeating the command before `until` confirms.
+    Confirming { action: PowerAction, until: Instant },
+    /// Confirmed; runs at `at` unless cancelled.
+    Pending { action: PowerAction, at: Instant },
+}
+
+/// Sync disks and power off or reboot: reboot(2) when we hold CAP_SYS_BOOT,
+/// else systemctl, else the busybox applet that signals init. On failure,
+/// the error says what to run by hand.
+pub fn perform(action: PowerAction) -> Result<(), String> {
+    // SAFETY: sync(2) takes no arguments and cannot fail
+    unsafe { libc::sync() };
+
+    let cmd = match action {
+        PowerAction::Shutdown => libc::RB_POWER_OFF,
+        PowerAction::Reboot => libc::RB_AUTOBOOT,
+    };
+    // SAFETY: reboot(2) takes no pointers; it only returns when refused
+    if unsafe { libc::reboot(cmd) } == 0 {
+        return Ok(());
+    }
+    let err = std::io::Error::last_os_error();
+
+    let applet = match action {
+        PowerAction::Shutdown => "poweroff",
+        PowerAction::Reboot => "reboot",
+    };
+    let (systemctl, arg) = action.command().split_once(' ').unwrap_or((action.command(), ""));
+    for (program, args) in [(systemctl, vec![arg]), (applet, vec![])] {
+        let ran = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        if ran.is_ok_and(|s| s.success()) {
+            return Ok(());
+        }
+    }
+    Err(format!("Not permitted ({err}). Run as root: {}", action.command()))
+}
@@ -1,31 +1,111 @@
-use std::collections::{HashMap, VecDeque};
-use std::sync::mpsc;
-use std::time::{Duration, Instant};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
 
 use crate::aurora_client;
 use crate::brain_client;
 use crate::feed::{FeedItem, FeedSource, Priority, WidgetData};
-use crate::telemetry::{AlertKind, SysTelemetry, TelemetryHistory};
+use crate::telemetry::{AlertKind, SysTelemetry, TelemetryHistory, Thresholds};
 use crate::ui::BlockColor;
 
+const INTERVALS_FILE: &str = "/tmp/aether_proactive.json";
+
+/// How often the proactive engine polls, in seconds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Intervals {
+    /// World-model (cfcd) prediction checks.
+    pub world_model_secs: u64,
+    /// Brain proactive insight requests.
+    pub brain_secs: u64,
+    /// Quiet time before the same alert kind can post again.
+    pub cooldown_secs: u64,
+}
+
+impl Default for Intervals {
+    fn default() -> Self {
+        Self { world_model_secs: 15, brain_secs: 120, cooldown_secs: 60 }
+    }
+}
+
+impl Intervals {
+    /// Names accepted by `set`, with the shortest interval each allows so a
+    /// typo can't flood cfcd or the brain.
+    pub const MINIMUMS: [(&'static str, u64); 3] = [("world", 5), ("brain", 30), ("cooldown", 5)];
+
+    /// Load overrides from disk, raising any below its minimum. Missing keys
+    /// keep their defaults; a missing or corrupt file gives the defaults.
+    pub fn load() -> Self {
+        let mut intervals: Self = std::fs::read_to_string(INTERVALS_FILE)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        for (name, min) in Self::MINIMUMS {
+            let secs = intervals.get_mut(name).expect("every minimum names an interval");
+            *secs = (*secs).max(min);
+        }
+        intervals
+    }
+
+    pub fn save(&self) {
+        if let Ok(data) = serde_json::to_string_pretty(self) {
+            let _ = std::fs::write(INTERVALS_FILE, data);
+        }
+    }
+
+    /// Set the interval `name` (world, brain or cooldown) to `secs`.
+    pub fn set(&mut self, name: &str, secs: u64) -> Result<(), String> {
+        let Some((_, min)) = Self::MINIMUMS.iter().find(|(n, _)| *n == name) else {
+            return Err(format!("Unknown interval '{}'. Use world, brain or cooldown.", name));
+        };
+        if secs < *min {
+            return Err(format!("The {} interval can't be under {}s.", name, min));
+        }
+        *self.get_mut(name).expect("every minimum names an interval") = secs;
+        Ok(())
+    }
+
+    fn get_mut(&mut self, name: &str) -> Option<&mut u64> {
+        match name {
+            "world" => Some(&mut self.world_model_secs),
+            "brain" => Some(&mut self.brain_secs),
+            "cooldown" => Some(&mut self.cooldown_secs),
+            _ => None,
+        }
+    }
+}
+
 /// The proactive engine generates feed items from background monitoring.
 pub struct ProactiveEngine {
     pub telemetry_history: TelemetryHistory,
     feed_tx: mpsc::Sender<FeedItem>,
     /// Cooldowns: prevent the same alert kind from firing too frequently.
     cooldowns: HashMap<AlertKind, Instant>,
-    cooldown_duration: Duration,
+    /// Polling intervals and the alert cooldown.
+    intervals: Intervals,
+    /// Muted alert kinds: unix-seconds expiry, or `None` until unmuted.
+    muted: BTreeMap<AlertKind, Option<u64>>,
     /// World model polling state.
-    world_model_interval: Duration,
     last_world_model_check: Instant,
     prediction_errors: VecDeque<f64>,
     last_world_model_card: Instant,
     world_model_cooldown: Duration,
     /// Track if cfcd is reachable.
     cfcd_available: Option<bool>,
+    /// Prediction error from each background world-model query, or `None`
+    /// when cfcd didn't answer. Drained in `tick`.
+    world_model_tx: mpsc::Sender<Option<f64>>,
+    world_model_rx: mpsc::Receiver<Option<f64>>,
     /// Brain proactive polling state.
-    brain_proactive_interval: Duration,
     last_brain_proactive: Instant,
+    /// Notification polling state.
+    notify_interval: Duration,
+    last_notify_check: Instant,
+    /// Last notification id seen; `u64::MAX` until the first poll answers.
+    notify_after: Arc<AtomicU64>,
     /// Recent alert labels for brain context.
     recent_alert_labels: VecDeque<String>,
     /// Last user query for brain context.
@@ -40,20 +120,25 @@ pub struct ProactiveEngine {
 }
 
 impl ProactiveEngine {
-    pub fn new(feed_tx: mpsc::Sender<FeedItem>) -> Self {
+    pub fn new(feed_tx: mpsc::Sender<FeedItem>, thresholds: Thresholds, intervals: Intervals) -> Self {
+        let (world_model_tx, world_model_rx) = mpsc::channel();
         Self {
-            telemetry_history: TelemetryHistory::new(30),
+            telemetry_history: TelemetryHistory::with_thresholds(30, thresholds),
             feed_tx,
             cooldowns: HashMap::new(),
-            cooldown_duration: Duration::from_secs(60),
-            world_model_interval: Duration::from_secs(15),
+            intervals,
+            muted: BTreeMap::new(),
             last_world_model_check: Instant::now(),
             prediction_errors: VecDeque::new(),
             last_world_model_card: Instant::now(),
             world_model_cooldown: Duration::from_secs(60),
             cfcd_available: None,
-            brain_proactive_interval: Duration::from_secs(120),
+            world_model_tx,
+            world_model_rx,
             last_brain_proactive: Instant::now(),
+            notify_interval: Duration::from_secs(3),
+            last_notify_check: Instant::now(),
+            notify_after: Arc::new(AtomicU64::new(u64::MAX)),
             recent_alert_labels: VecDeque::new(),
             last_user_query: String::new(),
             session_start: Instant::now(),
@@ -66,12 +151,18 @@ impl ProactiveEngine {
     /// Called every telemetry refresh (2s). Updates history and checks for alerts.
     pub fn tick(&mut self, telemetry: &SysTelemetry) {
         self.telemetry_history.push(telemetry.clone());
+        self.drain_world_model();
 
         // Check telemetry thresholds
+        // History and trends still update for muted kinds; they just don't post cards
         let alerts = self.telemetry_history.check_thresholds();
+        self.prune_muted();
         for alert in alerts {
+            if self.muted.contains_key(&alert.kind) {
+                continue;
+            }
             if let Some(last) = self.cooldowns.get(&alert.kind) {
-                if last.elapsed() < self.cooldown_duration {
+                if last.elapsed() < Duration::from_secs(self.intervals.cooldown_secs) {
                     continue;
                 }
             }
@@ -94,17 +185,73 @@ impl ProactiveEngine {
             let _ = self.feed_tx.send(card);
         }
 
-        // World model check (every 15s, non-blocking via thread)
-        if self.last_world_model_check.elapsed() >= self.world_model_interval {
+        // World model check (every world_model_secs, non-blocking via thread)
+        if self.last_world_model_check.elapsed() >= Duration::from_secs(self.intervals.world_model_secs) {
             self.last_world_model_check = Instant::now();
             self.check_world_model();
         }
 
-        // Brain proactive check (every 120s, non-blocking via thread)
-        if self.last_brain_proactive.elapsed() >= self.brain_proactive_interval {
+        // Brain proactive check (every brain_secs, non-blocking via thread)
+        if self.last_brain_proactive.elapsed() >= Duration::from_secs(self.intervals.brain_secs) {
             self.last_brain_proactive = Instant::now();
             self.check_brain_proactive(telemetry);
         }
+
+        // Notifications (every 3s, non-blocking via thread)
+        if self.last_notify_check.elapsed() >= self.notify_interval {
+            self.last_notify_check = Instant::now();
+            self.check_notifications();
+        }
+    }
+
+    /// Alert thresholds, also used to color the status bar and sidebar.
+    pub fn thresholds(&self) -> &Thresholds {
+        self.telemetry_history.thresholds()
+    }
+
+    pub fn intervals(&self) -> &Intervals {
+        &self.intervals
+    }
+
+    /// Change an interval at runtime and save it for next time.
+    pub fn set_interval(&mut self, name: &str, secs: u64) -> Result<(), String> {
+        self.intervals.set(name, secs)?;
+        self.intervals.save();
+        Ok(())
+    }
+
+    /// Silence an alert kind for `duration`.
+    pub fn snooze(&mut self, kind: AlertKind, duration: Duration) {
+        self.muted.insert(kind, Some(unix_now() + duration.as_secs()));
+    }
+
+    /// Silence an alert kind until it's unmuted.
+    pub fn mute(&mut self, kind: AlertKind) {
+        self.muted.insert(kind, None);
+    }
+
+    pub fn unmute(&mut self, kind: &AlertKind) -> bool {
+        self.muted.remove(kind).is_some()
+    }
+
+    pub fn unmute_all(&mut self) {
+        self.muted.clear();
+    }
+
+    /// Muted kinds and their expiries, for display and persistence.
+    pub fn muted(&self) -> &BTreeMap<AlertKind, Option<u64>> {
+        &self.muted
+    }
+
+    /// Restore mutes saved with the session; expired snoozes are dropped.
+    pub fn set_muted(&mut self, muted: BTreeMap<AlertKind, Option<u64>>) {
+        self.muted = muted;
+        self.prune_muted();
+    }
+
+    fn prune_muted(&mut self) {
+        let now = unix_now();
+        self.muted.retain(|_, until| until.is_none_or(|t| t > now));
     }
 
     /// Query the world model in a background thread.
@@ -116,9 +263,12 @@ impl ProactiveEngine {
 
         // Clone what we need for the thread
         let feed_tx = tx;
+        let result_tx = self.world_model_tx.clone();
 
         std::thread::spawn(move || {
-            match aurora_client::query_prediction() {
+            let result = aurora_client::query_prediction();
+            let _ = result_tx.send(result.as_ref().ok().map(|i| i.prediction_error));
+            match result {
                 Ok(insight) => {
                     // Determine if this is interesting enough to show
                     let error = insight.prediction_error;
@@ -203,22 +353,20 @@ impl ProactiveEngine {
                 }
             }
         });
+    }
 
-        // Try to get a synchronous quick check for tracking
-        match aurora_client::query_prediction() {
-            Ok(insight) => {
-                self.prediction_errors.push_back(insight.prediction_error);
-                if self.prediction_errors.len() > 20 {
-                    self.prediction_errors.pop_front();
-                }
-                self.cfcd_available = Some(true);
-            }
-            Err(_) => {
-                if self.cfcd_available == Some(true) {
-                    self.cfcd_available = Some(false);
-                } else if self.cfcd_available.is_none() {
-                    self.cfcd_available = Some(false);
+    /// Fold finished world-model queries into the error history and availability.
+    fn drain_world_model(&mut self) {
+        while let Ok(reading) = self.world_model_rx.try_recv() {
+            match reading {
+                Some(error) => {
+                    self.prediction_errors.push_back(error);
+                    if self.prediction_errors.len() > 20 {
+                        self.prediction_errors.pop_front();
+                    }
+                    self.cfcd_available = Some(true);
                 }
+                None => self.cfcd_available = Some(false),
             }
         }
     }
@@ -269,15 +417,7 @@ impl ProactiveEngine {
         // World model context from prediction errors
         let wm_ctx = if !self.prediction_errors.is_empty() {
             let last_error = *self.prediction_errors.back().unwrap_or(&0.0);
-            let trend = if self.prediction_errors.len() >= 3 {
-                let recent: f64 = self.prediction_errors.iter().rev().take(3).sum::<f64>() / 3.0;
-                let older: f64 = self.prediction_errors.iter().take(3).sum::<f64>() / 3.0;
-                if recent > older * 1.2 { "rising".to_string() }
-                else if recent < older * 0.8 { "falling".to_string() }
-                else { "stable".to_string() }
-            } else {
-                "unknown".to_string()
-            };
+            let trend = self.prediction_error_trend().to_string();
             Some(brain_client::WorldModelContext {
                 prediction_error: last_error,
                 trend,
@@ -338,19 +478,18 @@ impl ProactiveEngine {
                         priority,
                         title.to_string(),
                     )
-                    .with_body(resp.text.lines().map(|l| l.to_string()).collect());
+                    .with_body(resp.text.lines().map(|l| l.to_string()).collect())
+                    .with_markdown();
 
                     // Add widgets if present
                     if let Some(first_widget) = resp.widgets.first() {
-                        let color = match first_widget.widget_type.as_str() {
-                            "weather" => BlockColor::Yellow,
-                            "system" => BlockColor::Green,
-                            _ => BlockColor::Cyan,
-                        };
+                        let color = BlockColor::for_widget(&first_widget.widget_type);
                         card = card.with_widget(WidgetData {
                             widget_type: first_widget.widget_type.clone(),
                             title: first_widget.title.clone(),
                             lines: first_widget.lines.clone(),
+                            weather: first_widget.weather.clone(),
+                            progress: first_widget.progress.clone(),
                             color,
                         });
                     }
@@ -362,6 +501,36 @@ impl ProactiveEngine {
         });
     }
 
+    /// Turn notifications posted to aurorad since the last poll into cards.
+    fn check_notifications(&self) {
+        let feed_tx = self.feed_tx.clone();
+        let seen = Arc::clone(&self.notify_after);
+        std::thread::spawn(move || {
+            let after = match seen.load(Ordering::Relaxed) {
+                u64::MAX => None,
+                id => Some(id),
+            };
+            let Ok((latest, notifications)) = aurora_client::fetch_notifications(after) else {
+                return;
+            };
+            // A restarted aurorad numbers from 1 again; follow it down
+            seen.store(latest, Ordering::Relaxed);
+            for n in notifications {
+                let priority = match n.priority.as_str() {
+                    "urgent" => Priority::Urgent,
+                    "low" => Priority::Low,
+                    _ => Priority::Normal,
+                };
+                let mut body = n.lines;
+                if let Some(source) = n.source {
+                    body.push(format!("from {}", source));
+                }
+                let card = FeedItem::new(FeedSource::Notification, priority, n.title).with_body(body);
+                let _ = feed_tx.send(card);
+            }
+        });
+    }
+
     /// Get CPU history for sparkline rendering.
     pub fn cpu_history(&self) -> Vec<f64> {
         self.telemetry_history.cpu_history()
@@ -371,4 +540,34 @@ impl ProactiveEngine {
     pub fn mem_pct_history(&self) -> Vec<f64> {
         self.telemetry_history.mem_pct_history()
     }
+
+    /// Recent world-model prediction errors, oldest first.
+    pub fn prediction_error_history(&self) -> Vec<f64> {
+        self.prediction_errors.iter().copied().collect()
+    }
+
+    /// Whether cfcd answered the last world-model query.
+    pub fn cfcd_available(&self) -> bool {
+        self.cfcd_available == Some(true)
+    }
+
+    /// Compare the newest and oldest three prediction errors:
+    /// "rising", "falling", "stable", or "unknown" with too few samples.
+    pub fn prediction_error_trend(&self) -> &'static str {
+        if self.prediction_errors.len() < 3 {
+            return "unknown";
+        }
+        let recent: f64 = self.prediction_errors.iter().rev().take(3).sum::<f64>() / 3.0;
+        let older: f64 = self.prediction_errors.iter().take(3).sum::<f64>() / 3.0;
+        if recent > older * 1.2 { "rising" }
+        else if recent < older * 0.8 { "falling" }
+        else { "stable" }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
@@ -1,11 +1,22 @@
-use std::sync::mpsc;
-use std::time::Instant;
+use std::collections::{HashSet, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::{Duration, Instant};
 
 use crate::brain_client;
 use crate::commands;
-use crate::feed::{FeedItem, FeedSource, Priority, WidgetData};
+use crate::feed::{FeedItem, FeedSource, FeedStore, Priority, Progress, WidgetData};
 use crate::ui::BlockColor;
 
+/// How long a task may run before it is cancelled; `NEBULA_TASK_TIMEOUT` overrides (seconds).
+const DEFAULT_TASK_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Fraction of the timeout after which the sidebar warns.
+const TIMEOUT_WARN_FRACTION: f32 = 0.8;
+
+/// Output lines kept per streaming task; older lines are dropped.
+const MAX_TASK_LINES: usize = 500;
+
 /// Status of a background task.
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
@@ -13,6 +24,8 @@ pub enum TaskStatus {
     Running,
     Completed(String),
     Failed(String),
+    Cancelled,
+    TimedOut,
 }
 
 /// A background task being tracked.
@@ -21,6 +34,25 @@ pub struct BackgroundTask {
     pub name: String,
     pub status: TaskStatus,
     pub started: Instant,
+    /// Set to ask the worker thread to stop.
+    pub cancel: Arc<AtomicBool>,
+    /// Cancelled by `tick` once it has run this long.
+    pub timeout: Duration,
+    /// Feed card showing streamed output while the task runs.
+    pub live_card: Option<u64>,
+    /// Most recent streamed output lines.
+    pub output: VecDeque<String>,
+}
+
+impl BackgroundTask {
+    pub fn elapsed_secs(&self) -> u64 {
+        self.started.elapsed().as_secs()
+    }
+
+    /// Whether the task has used most of its timeout.
+    pub fn near_timeout(&self) -> bool {
+        self.started.elapsed().as_secs_f32() >= self.timeout.as_secs_f32() * TIMEOUT_WARN_FRACTION
+    }
 }
 
 /// Update message from a background task thread.
@@ -33,6 +65,11 @@ pub enum TaskUpdate {
         id: u64,
         error: String,
     },
+    /// A line of output from a still-running task.
+    Progress {
+        id: u64,
+        line: String,
+    },
 }
 
 /// Manages background tasks and their completion.
@@ -42,6 +79,7 @@ pub struct TaskManager {
     task_rx: mpsc::Receiver<TaskUpdate>,
     task_tx: mpsc::Sender<TaskUpdate>,
     max_tasks: usize,
+    timeout: Duration,
 }
 
 impl TaskManager {
@@ -53,6 +91,11 @@ impl TaskManager {
             task_rx: rx,
             task_tx: tx,
             max_tasks: 10,
+            timeout: std::env::var("NEBULA_TASK_TIMEOUT")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .map(Duration::from_secs)
+                .unwrap_or(DEFAULT_TASK_TIMEOUT),
         }
     }
 
@@ -73,13 +116,23 @@ impl TaskManager {
             },
             status: TaskStatus::Running,
             started: Instant::now(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            timeout: self.timeout,
+            live_card: None,
+            output: VecDeque::new(),
         };
+        let cancel = task.cancel.clone();
         self.tasks.push(task);
 
         let tx = self.task_tx.clone();
         let input = query.to_string();
         std::thread::spawn(move || {
-            match brain_client::query_brain(&input) {
+            // The HTTP read can't be interrupted, so a cancelled query just drops its result
+            let result = brain_client::query_brain(&input);
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            match result {
                 Ok(resp) => {
                     let mut body: Vec<String> = Vec::new();
                     if !resp.text.is_empty() {
@@ -97,20 +150,23 @@ impl TaskManager {
                             input
                         }),
                     )
-                    .with_body(body);
+                    .with_body(body)
+                    .with_reasoning(
+                        resp.reasoning
+                            .as_deref()
+                            .map(|r| r.lines().map(|l| l.to_string()).collect())
+                            .unwrap_or_default(),
+                    )
+                    .with_markdown();
 
                     if let Some(w) = resp.widgets.first() {
-                        let color = match w.widget_type.as_str() {
-                            "weather" => BlockColor::Yellow,
-                            "system" => BlockColor::Green,
-                            "file" => BlockColor::Blue,
-                            "table" => BlockColor::Cyan,
-                            _ => BlockColor::White,
-                        };
+                        let color = BlockColor::for_widget(&w.widget_type);
                         card = card.with_widget(WidgetData {
                             widget_type: w.widget_type.clone(),
                             title: w.title.clone(),
                             lines: w.lines.clone(),
+                            weather: w.weather.clone(),
+                            progress: w.progress.clone(),
                             color,
                         });
                     }
@@ -153,13 +209,38 @@ impl TaskManager {
             },
             status: TaskStatus::Running,
             started: Instant::now(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            timeout: self.timeout,
+            live_card: None,
+            output: VecDeque::new(),
         };
+        let cancel = task.cancel.clone();
         self.tasks.push(task);
 
         let tx = self.task_tx.clone();
         let shell_cmd = cmd.to_string();
         std::thread::spawn(move || {
-            let result = commands::run_shell(&shell_cmd);
+            // The worker keeps its own capped copy for the final card
+            let mut lines = VecDeque::new();
+            let mut dropped = 0usize;
+            let stderr = commands::run_shell_lines(&shell_cmd, &cancel, |line| {
+                let _ = tx.send(TaskUpdate::Progress { id, line: line.clone() });
+                lines.push_back(line);
+                if lines.len() > MAX_TASK_LINES {
+                    lines.pop_front();
+                    dropped += 1;
+                }
+            });
+            let Some(stderr) = stderr else {
+                return;
+            };
+
+            let mut body: Vec<String> = Vec::new();
+            if dropped > 0 {
+                body.push(format!("[{} earlier lines dropped]", dropped));
+            }
+            body.extend(lines);
+            body.extend(stderr.lines().map(|l| l.to_string()));
             let card = FeedItem::new(
                 FeedSource::Task,
                 Priority::Normal,
@@ -169,7 +250,7 @@ impl TaskManager {
                     shell_cmd
                 }),
             )
-            .with_body(result.lines().map(|l| l.to_string()).collect());
+            .with_body(body);
 
             let _ = tx.send(TaskUpdate::Complete {
                 id,
@@ -180,20 +261,70 @@ impl TaskManager {
         Some(id)
     }
 
-    /// Check for completed tasks and return feed items.
-    pub fn tick(&mut self) -> Vec<FeedItem> {
-        let mut items = Vec::new();
+    /// Cancel a running task: the worker is signalled and its result discarded.
+    pub fn cancel(&mut self, id: u64, feed: &mut FeedStore) -> Option<FeedItem> {
+        self.stop(id, TaskStatus::Cancelled, "Task cancelled", feed)
+    }
+
+    /// Signal a running task's worker to stop, drop its live output card and
+    /// return the card announcing it.
+    fn stop(&mut self, id: u64, status: TaskStatus, title: &str, feed: &mut FeedStore) -> Option<FeedItem> {
+        let task = self
+            .tasks
+            .iter_mut()
+            .find(|t| t.id == id && matches!(t.status, TaskStatus::Running))?;
+        task.cancel.store(true, Ordering::Relaxed);
+        task.status = status;
+        task.output.clear();
+        if let Some(card) = task.live_card.take() {
+            feed.remove(card);
+        }
+        Some(
+            FeedItem::new(FeedSource::Task, Priority::Normal, title.to_string())
+                .with_body(vec![format!("{} ({}s)", task.name, task.elapsed_secs())]),
+        )
+    }
+
+    /// Apply task updates to the feed: grow live output cards, and post
+    /// completions, failures and timeouts.
+    pub fn tick(&mut self, feed: &mut FeedStore) {
+        let mut grown = HashSet::new();
         while let Ok(update) = self.task_rx.try_recv() {
+            let id = match &update {
+                TaskUpdate::Complete { id, .. }
+                | TaskUpdate::Failed { id, .. }
+                | TaskUpdate::Progress { id, .. } => *id,
+            };
+            let Some(task) = self
+                .tasks
+                .iter_mut()
+                .find(|t| t.id == id && matches!(t.status, TaskStatus::Running))
+            else {
+                continue;
+            };
             match update {
-                TaskUpdate::Complete { id, feed_item } => {
-                    if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-                        task.status = TaskStatus::Completed("done".to_string());
+                TaskUpdate::Progress { line, .. } => {
+                    task.output.push_back(line);
+                    if task.output.len() > MAX_TASK_LINES {
+                        task.output.pop_front();
+                    }
+                    grown.insert(id);
+                }
+                TaskUpdate::Complete { feed_item, .. } => {
+                    task.status = TaskStatus::Completed("done".to_string());
+                    task.output.clear();
+                    grown.remove(&id);
+                    if let Some(card) = task.live_card.take() {
+                        feed.remove(card);
                     }
-                    items.push(feed_item);
+                    feed.push(feed_item);
                 }
-                TaskUpdate::Failed { id, error } => {
-                    if let Some(task) = self.tasks.iter_mut().find(|t| t.id == id) {
-                        task.status = TaskStatus::Failed(error.clone());
+                TaskUpdate::Failed { error, .. } => {
+                    task.status = TaskStatus::Failed(error.clone());
+                    task.output.clear();
+                    grown.remove(&id);
+                    if let Some(card) = task.live_card.take() {
+                        feed.remove(card);
                     }
                     let card = FeedItem::new(
                         FeedSource::Task,
@@ -201,11 +332,57 @@ impl TaskManager {
                         "Task Failed".to_string(),
                     )
                     .with_body(vec![error]);
-                    items.push(card);
+                    feed.push(card);
+                }
+            }
+        }
+
+        // One body update per task per tick, however many lines arrived
+        for task in self.tasks.iter_mut().filter(|t| grown.contains(&t.id)) {
+            let body: Vec<String> = task.output.iter().cloned().collect();
+            // Output whose latest line reports progress gets a bar, moved in place
+            let progress = task.output.back().and_then(|l| Progress::from_output(l));
+            let bar = progress.map(|p| WidgetData {
+                widget_type: "progress".to_string(),
+                title: "Progress".to_string(),
+                lines: Vec::new(),
+                color: BlockColor::for_widget("progress"),
+                weather: None,
+                progress: Some(Box::new(p)),
+            });
+            match task.live_card {
+                Some(card) => {
+                    feed.set_body(card, body);
+                    if let Some(bar) = bar {
+                        feed.set_widget(card, bar);
+                    }
+                }
+                None => {
+                    let mut card = FeedItem::new(
+                        FeedSource::Task,
+                        Priority::Normal,
+                        format!("Running: {}", task.name),
+                    )
+                    .with_body(body);
+                    if let Some(bar) = bar {
+                        card = card.with_widget(bar);
+                    }
+                    task.live_card = Some(feed.push(card));
                 }
             }
         }
-        items
+
+        let expired: Vec<u64> = self
+            .tasks
+            .iter()
+            .filter(|t| matches!(t.status, TaskStatus::Running) && t.started.elapsed() >= t.timeout)
+            .map(|t| t.id)
+            .collect();
+        for id in expired {
+            if let Some(card) = self.stop(id, TaskStatus::TimedOut, "Task timed out", feed) {
+                feed.push(card);
+            }
+        }
     }
 
     /// Count of currently running tasks.
@@ -230,11 +407,10 @@ impl TaskManager {
     }
 
     /// Get active tasks for sidebar display.
-    pub fn active_tasks(&self) -> Vec<(&str, u64)> {
+    pub fn active_tasks(&self) -> Vec<&BackgroundTask> {
         self.tasks
             .iter()
             .filter(|t| matches!(t.status, TaskStatus::Running))
-            .map(|t| (t.name.as_str(), t.started.elapsed().as_secs()))
             .collect()
     }
 
@@ -1,6 +1,8 @@
 use std::collections::VecDeque;
 use std::fs;
 
+use serde::{Deserialize, Serialize};
+
 use crate::feed::Priority;
 
 #[derive(Default, Clone)]
@@ -8,19 +10,68 @@ pub struct SysTelemetry {
     pub cpu_percent: f64,
     pub mem_total_mb: u64,
     pub mem_avail_mb: u64,
+    pub swap_total_mb: u64,
+    pub swap_free_mb: u64,
+    /// 1, 5 and 15 minute load averages.
+    pub load_avg: [f64; 3],
+    /// Battery charge, when the machine has one.
+    pub battery_pct: Option<u8>,
+    /// Whether the battery is charging (or full), when known.
+    pub charging: Option<bool>,
+    /// (zone type, degrees C) for each readable thermal zone.
+    pub temps: Vec<(String, f64)>,
+    pub disk_total_mb: u64,
+    pub disk_avail_mb: u64,
     pub uptime_secs: u64,
     pub num_procs: u32,
+    /// Display text: "addr (iface)", "iface up" without an address, or "no network".
     pub ip_addr: String,
+    /// Active interface, if any is up.
+    pub net: Option<NetInterface>,
     pub kernel: String,
     pub cores: u32,
 }
 
+/// An interface whose operstate is up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetInterface {
+    pub name: String,
+    /// First IPv4 address, if one is assigned.
+    pub addr: Option<String>,
+}
+
+impl SysTelemetry {
+    pub fn net_up(&self) -> bool {
+        self.net.is_some()
+    }
+
+    /// Hottest thermal zone, if any were readable.
+    pub fn hottest_zone(&self) -> Option<&(String, f64)> {
+        self.temps.iter().max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// Percentage of swap in use; 0 when there is no swap.
+    pub fn swap_used_pct(&self) -> f64 {
+        if self.swap_total_mb > 0 {
+            let used = self.swap_total_mb.saturating_sub(self.swap_free_mb);
+            (used as f64 / self.swap_total_mb as f64) * 100.0
+        } else {
+            0.0
+        }
+    }
+}
+
 /// Kinds of telemetry alerts.
-#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum AlertKind {
     HighCpu,
     HighMemory,
     LowMemory,
+    HighSwap,
+    LowDisk,
+    LowBattery,
+    CriticalBattery,
+    HighTemperature,
     NetworkDown,
     NetworkUp,
     ProcessSpike,
@@ -28,11 +79,39 @@ pub enum AlertKind {
 }
 
 impl AlertKind {
+    pub const ALL: &'static [AlertKind] = &[
+        AlertKind::HighCpu,
+        AlertKind::HighMemory,
+        AlertKind::LowMemory,
+        AlertKind::HighSwap,
+        AlertKind::LowDisk,
+        AlertKind::LowBattery,
+        AlertKind::CriticalBattery,
+        AlertKind::HighTemperature,
+        AlertKind::NetworkDown,
+        AlertKind::NetworkUp,
+        AlertKind::ProcessSpike,
+        AlertKind::UptimeMilestone,
+    ];
+
+    /// Parse a user-typed kind name (as used by `/mute`), ignoring case.
+    pub fn parse(name: &str) -> Option<AlertKind> {
+        Self::ALL
+            .iter()
+            .find(|k| format!("{:?}", k).eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
     pub fn label(&self) -> &'static str {
         match self {
             AlertKind::HighCpu => "High CPU Usage",
             AlertKind::HighMemory => "Memory Spike",
             AlertKind::LowMemory => "Low Memory",
+            AlertKind::HighSwap => "High Swap Usage",
+            AlertKind::LowDisk => "Low Disk Space",
+            AlertKind::LowBattery => "Low Battery",
+            AlertKind::CriticalBattery => "Battery Critical",
+            AlertKind::HighTemperature => "High Temperature",
             AlertKind::NetworkDown => "Network Down",
             AlertKind::NetworkUp => "Network Connected",
             AlertKind::ProcessSpike => "Process Spike",
@@ -48,24 +127,96 @@ pub struct TelemetryAlert {
     pub priority: Priority,
 }
 
+const THRESHOLDS_FILE: &str = "/tmp/aether_thresholds.json";
+
+/// Alert thresholds used by `check_thresholds`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    /// Sustained CPU average (over 3 readings) that counts as high.
+    pub high_cpu_pct: f64,
+    /// Available memory percentage below which memory is low.
+    pub low_mem_pct: f64,
+    /// Jump in memory usage percentage within one tick.
+    pub mem_spike_delta: f64,
+    /// Jump in process count within one tick.
+    pub proc_spike_delta: u32,
+    /// Swap usage percentage that signals memory pressure.
+    pub high_swap_pct: f64,
+    /// Battery percentage below which a discharging battery is low.
+    pub low_battery_pct: u8,
+    /// Battery percentage for the one-shot critical warning.
+    pub critical_battery_pct: u8,
+    /// Battery percentage below which the status bar shows it as a warning.
+    pub warn_battery_pct: u8,
+    /// Degrees C at which any thermal zone counts as too hot.
+    pub high_temp_c: f64,
+    /// Degrees C at which the sidebar shows a zone as a warning.
+    pub warn_temp_c: f64,
+    /// Free space percentage on / below which the disk is low.
+    pub low_disk_pct: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            high_cpu_pct: 80.0,
+            low_mem_pct: 15.0,
+            mem_spike_delta: 20.0,
+            proc_spike_delta: 20,
+            high_swap_pct: 50.0,
+            low_battery_pct: 10,
+            critical_battery_pct: 5,
+            warn_battery_pct: 25,
+            high_temp_c: 85.0,
+            warn_temp_c: 70.0,
+            low_disk_pct: 10.0,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Load overrides from disk. Missing keys keep their defaults; a missing
+    /// or corrupt file gives the defaults.
+    pub fn load() -> Self {
+        fs::read_to_string(THRESHOLDS_FILE)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+}
+
 /// Keeps a rolling window of telemetry snapshots for trend detection.
 pub struct TelemetryHistory {
     snapshots: VecDeque<SysTelemetry>,
     max_snapshots: usize,
     prev_network_up: Option<bool>,
     reported_milestones: Vec<u64>,
+    /// Critical battery already reported this discharge; cleared on charge.
+    critical_battery_reported: bool,
+    thresholds: Thresholds,
 }
 
 impl TelemetryHistory {
     pub fn new(max_snapshots: usize) -> Self {
+        Self::with_thresholds(max_snapshots, Thresholds::default())
+    }
+
+    pub fn with_thresholds(max_snapshots: usize, thresholds: Thresholds) -> Self {
         Self {
             snapshots: VecDeque::new(),
             max_snapshots,
             prev_network_up: None,
             reported_milestones: Vec::new(),
+            critical_battery_reported: false,
+            thresholds,
         }
     }
 
+    pub fn thresholds(&self) -> &Thresholds {
+        &self.thresholds
+    }
+
     /// Record a new telemetry snapshot.
     pub fn push(&mut self, snapshot: SysTelemetry) {
         self.snapshots.push_back(snapshot);
@@ -123,8 +274,10 @@ impl TelemetryHistory {
             None => return alerts,
         };
 
-        // High CPU: sustained >80% over last 3 readings
-        if self.snapshots.len() >= 3 && self.avg_cpu(3) > 80.0 {
+        let th = self.thresholds.clone();
+
+        // High CPU: sustained above threshold over last 3 readings
+        if self.snapshots.len() >= 3 && self.avg_cpu(3) > th.high_cpu_pct {
             alerts.push(TelemetryAlert {
                 kind: AlertKind::HighCpu,
                 message: format!(
@@ -136,11 +289,11 @@ impl TelemetryHistory {
             });
         }
 
-        // Low memory: available < 15%
+        // Low memory: available below threshold
         if latest.mem_total_mb > 0 {
             let avail_pct =
                 (latest.mem_avail_mb as f64 / latest.mem_total_mb as f64) * 100.0;
-            if avail_pct < 15.0 {
+            if avail_pct < th.low_mem_pct {
                 alerts.push(TelemetryAlert {
                     kind: AlertKind::LowMemory,
                     message: format!(
@@ -151,7 +304,7 @@ impl TelemetryHistory {
                 });
             }
 
-            // Memory spike: usage jumped 20%+ in one tick
+            // Memory spike: usage jumped by more than the delta in one tick
             if self.snapshots.len() >= 2 {
                 let prev = &self.snapshots[self.snapshots.len() - 2];
                 let prev_used_pct = if prev.mem_total_mb > 0 {
@@ -164,7 +317,7 @@ impl TelemetryHistory {
                     let used = latest.mem_total_mb.saturating_sub(latest.mem_avail_mb);
                     (used as f64 / latest.mem_total_mb as f64) * 100.0
                 };
-                if curr_used_pct - prev_used_pct > 20.0 {
+                if curr_used_pct - prev_used_pct > th.mem_spike_delta {
                     alerts.push(TelemetryAlert {
                         kind: AlertKind::HighMemory,
                         message: format!(
@@ -177,11 +330,72 @@ impl TelemetryHistory {
             }
         }
 
+        // High swap: memory pressure even when MemAvailable looks fine
+        if latest.swap_total_mb > 0 && latest.swap_used_pct() > th.high_swap_pct {
+            let used = latest.swap_total_mb.saturating_sub(latest.swap_free_mb);
+            alerts.push(TelemetryAlert {
+                kind: AlertKind::HighSwap,
+                message: format!(
+                    "Swap at {:.0}% ({}MB / {}MB), the system is under memory pressure",
+                    latest.swap_used_pct(),
+                    used,
+                    latest.swap_total_mb
+                ),
+                priority: Priority::Normal,
+            });
+        }
+
+        // Low disk: free space on / below the threshold
+        if latest.disk_total_mb > 0 {
+            let avail_pct =
+                (latest.disk_avail_mb as f64 / latest.disk_total_mb as f64) * 100.0;
+            if avail_pct < th.low_disk_pct {
+                alerts.push(TelemetryAlert {
+                    kind: AlertKind::LowDisk,
+                    message: format!(
+                        "Only {:.0}% disk space free on / ({}MB / {}MB)",
+                        avail_pct, latest.disk_avail_mb, latest.disk_total_mb
+                    ),
+                    priority: Priority::Urgent,
+                });
+            }
+        }
+
+        // High temperature: any zone over the threshold
+        if let Some((zone, temp)) = latest.hottest_zone() {
+            if *temp >= th.high_temp_c {
+                alerts.push(TelemetryAlert {
+                    kind: AlertKind::HighTemperature,
+                    message: format!("{} at {:.0}\u{b0}C, the system may throttle", zone, temp),
+                    priority: Priority::Urgent,
+                });
+            }
+        }
+
+        // Battery: low while discharging, plus a one-shot critical warning
+        if let (Some(pct), Some(charging)) = (latest.battery_pct, latest.charging) {
+            if charging {
+                self.critical_battery_reported = false;
+            } else if pct < th.critical_battery_pct {
+                if !self.critical_battery_reported {
+                    self.critical_battery_reported = true;
+                    alerts.push(TelemetryAlert {
+                        kind: AlertKind::CriticalBattery,
+                        message: format!("Battery at {}%, plug in now or save your work", pct),
+                        priority: Priority::Urgent,
+                    });
+                }
+            } else if pct < th.low_battery_pct {
+                alerts.push(TelemetryAlert {
+                    kind: AlertKind::LowBattery,
+                    message: format!("Battery at {}% and discharging", pct),
+                    priority: Priority::Urgent,
+                });
+            }
+        }
+
         // Network state change
-        let net_up = latest.ip_addr.starts_with("10.")
-            || latest.ip_addr.starts_with("192.")
-            || latest.ip_addr.starts_with("172.")
-            || latest.ip_addr.contains("up");
+        let net_up = latest.net_up();
 
         if let Some(prev_up) = self.prev_network_up {
             if !prev_up && net_up {
@@ -203,7 +417,7 @@ impl TelemetryHistory {
         // Process count spike
         if self.snapshots.len() >= 2 {
             let prev = &self.snapshots[self.snapshots.len() - 2];
-            if latest.num_procs > prev.num_procs + 20 {
+            if latest.num_procs > prev.num_procs + th.proc_spike_delta {
                 alerts.push(TelemetryAlert {
                     kind: AlertKind::ProcessSpike,
                     message: format!(
@@ -238,7 +452,43 @@ impl TelemetryHistory {
     }
 }
 
-pub fn read_telemetry() -> SysTelemetry {
+/// Previous `/proc/stat` jiffies, so CPU usage can be taken over an interval
+/// instead of averaged since boot.
+#[derive(Default)]
+pub struct CpuSampler {
+    prev: Option<(u64, u64)>,
+}
+
+impl CpuSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the aggregate `cpu` line from `/proc/stat` and return usage since
+    /// the previous call. The first call only primes the sampler and returns 0.
+    pub fn sample_line(&mut self, line: &str) -> f64 {
+        let (total, idle) = match parse_cpu_jiffies(line) {
+            Some(j) => j,
+            None => return 0.0,
+        };
+        let pct = match self.prev {
+            Some((prev_total, prev_idle)) => {
+                let d_total = total.saturating_sub(prev_total);
+                let d_idle = idle.saturating_sub(prev_idle);
+                if d_total > 0 {
+                    (d_total.saturating_sub(d_idle) as f64 / d_total as f64) * 100.0
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+        self.prev = Some((total, idle));
+        pct
+    }
+}
+
+pub fn read_telemetry(cpu: &mut CpuSampler) -> SysTelemetry {
     let mut t = SysTelemetry::default();
 
     // Kernel version
@@ -264,10 +514,35 @@ pub fn read_telemetry() -> SysTelemetry {
                 t.mem_total_mb = parse_kb(line) / 1024;
             } else if line.starts_with("MemAvailable:") {
                 t.mem_avail_mb = parse_kb(line) / 1024;
+            } else if line.starts_with("SwapTotal:") {
+                t.swap_total_mb = parse_kb(line) / 1024;
+            } else if line.starts_with("SwapFree:") {
+                t.swap_free_mb = parse_kb(line) / 1024;
             }
         }
     }
 
+    // Load average
+    if let Ok(loadavg) = fs::read_to_string("/proc/loadavg") {
+        for (slot, val) in t.load_avg.iter_mut().zip(loadavg.split_whitespace()) {
+            *slot = val.parse().unwrap_or(0.0);
+        }
+    }
+
+    // Battery (absent on servers and VMs)
+    if let Some((pct, charging)) = read_battery() {
+        t.battery_pct = Some(pct);
+        t.charging = charging;
+    }
+
+    // Thermal zones
+    t.temps = read_temps();
+
+    // Disk (root filesystem)
+    let (disk_total, disk_avail) = read_disk_mb("/");
+    t.disk_total_mb = disk_total;
+    t.disk_avail_mb = disk_avail;
+
     // CPU count
     if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
         t.cores = cpuinfo
@@ -276,8 +551,8 @@ pub fn read_telemetry() -> SysTelemetry {
             .count() as u32;
     }
 
-    // CPU usage (simplified: from /proc/stat)
-    t.cpu_percent = read_cpu_percent();
+    // CPU usage since the previous reading
+    t.cpu_percent = read_cpu_percent(cpu);
 
     // Process count
     if let Ok(entries) = fs::read_dir("/proc") {
@@ -292,8 +567,13 @@ pub fn read_telemetry() -> SysTelemetry {
             .count() as u32;
     }
 
-    // IP address
-    t.ip_addr = read_ip_addr();
+    // Active interface and address
+    t.net = read_active_interface();
+    t.ip_addr = match &t.net {
+        Some(NetInterface { name, addr: Some(addr) }) => format!("{} ({})", addr, name),
+        Some(NetInterface { name, addr: None }) => format!("{} up", name),
+        None => "no network".to_string(),
+    };
 
     t
 }
@@ -305,44 +585,213 @@ fn parse_kb(line: &str) -> u64 {
         .unwrap_or(0)
 }
 
-fn read_cpu_percent() -> f64 {
+fn read_cpu_percent(cpu: &mut CpuSampler) -> f64 {
     if let Ok(stat) = fs::read_to_string("/proc/stat") {
         if let Some(line) = stat.lines().next() {
-            let vals: Vec<u64> = line
-                .split_whitespace()
-                .skip(1)
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            if vals.len() >= 4 {
-                let idle = vals[3];
-                let total: u64 = vals.iter().sum();
-                if total > 0 {
-                    return ((total - idle) as f64 / total as f64) * 100.0;
-                }
-            }
+            return cpu.sample_line(line);
         }
     }
     0.0
 }
 
-fn read_ip_addr() -> String {
-    if let Ok(operstate) = fs::read_to_string("/sys/class/net/eth0/operstate") {
-        if operstate.trim() == "up" {
-            if let Ok(fib) = fs::read_to_string("/proc/net/fib_trie") {
-                for line in fib.lines() {
-                    let trimmed = line.trim();
-                    if trimmed.starts_with("10.")
-                        || trimmed.starts_with("192.168.")
-                        || trimmed.starts_with("172.")
-                    {
-                        if !trimmed.contains('/') {
-                            return trimmed.to_string();
-                        }
-                    }
-                }
-            }
-            return "eth0 up".to_string();
+/// Parse a `/proc/stat` cpu line into (total, idle) jiffies.
+/// Idle includes iowait, which is time the CPU had nothing to run.
+fn parse_cpu_jiffies(line: &str) -> Option<(u64, u64)> {
+    let vals: Vec<u64> = line
+        .split_whitespace()
+        .skip(1)
+        .filter_map(|s| s.parse().ok())
+        .collect();
+    if vals.len() < 4 {
+        return None;
+    }
+    let idle = vals[3] + vals.get(4).copied().unwrap_or(0);
+    let total: u64 = vals.iter().sum();
+    Some((total, idle))
+}
+
+/// Total and available MB for the filesystem at `path`.
+/// Returns zeros if statvfs fails (e.g. in a minimal chroot).
+fn read_disk_mb(path: &str) -> (u64, u64) {
+    let c_path = match std::ffi::CString::new(path) {
+        Ok(p) => p,
+        Err(_) => return (0, 0),
+    };
+    let mut st: libc::statvfs = unsafe { std::mem::zeroed() };
+    let rc = unsafe { libc::statvfs(c_path.as_ptr(), &mut st) };
+    if rc != 0 {
+        return (0, 0);
+    }
+    let frsize = st.f_frsize as u64;
+    let total = st.f_blocks as u64 * frsize / (1024 * 1024);
+    let avail = st.f_bavail as u64 * frsize / (1024 * 1024);
+    (total, avail)
+}
+
+/// Readable thermal zones as (type, degrees C); empty when there are none.
+fn read_temps() -> Vec<(String, f64)> {
+    let Ok(entries) = fs::read_dir("/sys/class/thermal") else {
+        return Vec::new();
+    };
+    let mut zones: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name().to_string_lossy().starts_with("thermal_zone"))
+        .map(|e| e.path())
+        .collect();
+    zones.sort();
+    zones
+        .iter()
+        .filter_map(|zone| {
+            // Millidegrees; some zones error on read when the sensor is off
+            let milli: f64 = fs::read_to_string(zone.join("temp")).ok()?.trim().parse().ok()?;
+            let kind = fs::read_to_string(zone.join("type"))
+                .map(|t| t.trim().to_string())
+                .unwrap_or_else(|_| zone.file_name().unwrap_or_default().to_string_lossy().to_string());
+            Some((kind, milli / 1000.0))
+        })
+        .collect()
+}
+
+/// Charge percentage and charging state of the first battery, if any.
+fn read_battery() -> Option<(u8, Option<bool>)> {
+    let mut supplies: Vec<_> = fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    supplies.sort();
+    let bat = supplies.into_iter().find(|p| {
+        fs::read_to_string(p.join("type"))
+            .map(|t| t.trim() == "Battery")
+            .unwrap_or(false)
+    })?;
+    let pct: u8 = fs::read_to_string(bat.join("capacity"))
+        .ok()?
+        .trim()
+        .parse::<u32>()
+        .ok()?
+        .min(100) as u8;
+    let charging = fs::read_to_string(bat.join("status"))
+        .ok()
+        .and_then(|s| match s.trim() {
+            "Charging" | "Full" => Some(true),
+            "Discharging" => Some(false),
+            _ => None,
+        });
+    Some((pct, charging))
+}
+
+/// First non-loopback interface that is up, preferring one with an address.
+fn read_active_interface() -> Option<NetInterface> {
+    let mut up: Vec<String> = fs::read_dir("/sys/class/net")
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .filter(|name| name != "lo")
+        .filter(|name| {
+            fs::read_to_string(format!("/sys/class/net/{}/operstate", name))
+                .map(|s| s.trim() == "up")
+                .unwrap_or(false)
+        })
+        .collect();
+    up.sort();
+
+    let addrs = aether_common::netif::ipv4_addrs();
+    let addr_of = |name: &str| {
+        addrs
+            .iter()
+            .find(|(iface, _)| iface == name)
+            .map(|(_, addr)| addr.to_string())
+    };
+    let name = up
+        .iter()
+        .find(|name| addr_of(name).is_some())
+        .or_else(|| up.first())?
+        .clone();
+    let addr = addr_of(&name);
+    Some(NetInterface { name, addr })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_percent_comes_from_the_delta_between_samples() {
+        let mut cpu = CpuSampler::new();
+        // user nice system idle iowait irq softirq
+        assert_eq!(cpu.sample_line("cpu  1000 0 500 8000 500 0 0"), 0.0);
+        // +300 busy, +100 idle (+50 idle, +50 iowait) out of +400
+        let pct = cpu.sample_line("cpu  1200 0 600 8050 550 0 0");
+        assert!((pct - 75.0).abs() < 1e-9, "got {pct}");
+    }
+
+    fn snapshot() -> SysTelemetry {
+        SysTelemetry {
+            mem_total_mb: 1000,
+            mem_avail_mb: 500,
+            disk_total_mb: 1000,
+            disk_avail_mb: 500,
+            num_procs: 100,
+            ..Default::default()
+        }
+    }
+
+    fn kinds(thresholds: Thresholds, snapshots: &[SysTelemetry]) -> Vec<AlertKind> {
+        let mut history = TelemetryHistory::with_thresholds(10, thresholds);
+        let mut kinds = Vec::new();
+        for s in snapshots {
+            history.push(s.clone());
+            kinds.extend(history.check_thresholds().into_iter().map(|a| a.kind));
         }
+        kinds
+    }
+
+    #[test]
+    fn low_memory_respects_the_threshold() {
+        let low = SysTelemetry { mem_avail_mb: 120, ..snapshot() };
+        assert!(kinds(Thresholds::default(), std::slice::from_ref(&low)).contains(&AlertKind::LowMemory));
+        let relaxed = Thresholds { low_mem_pct: 5.0, ..Thresholds::default() };
+        assert!(!kinds(relaxed, &[low]).contains(&AlertKind::LowMemory));
+    }
+
+    #[test]
+    fn high_cpu_respects_the_threshold() {
+        let busy = SysTelemetry { cpu_percent: 90.0, ..snapshot() };
+        let three = [busy.clone(), busy.clone(), busy];
+        assert!(kinds(Thresholds::default(), &three).contains(&AlertKind::HighCpu));
+        let relaxed = Thresholds { high_cpu_pct: 95.0, ..Thresholds::default() };
+        assert!(!kinds(relaxed, &three).contains(&AlertKind::HighCpu));
+    }
+
+    #[test]
+    fn process_spike_respects_the_threshold() {
+        let spike = [snapshot(), SysTelemetry { num_procs: 130, ..snapshot() }];
+        assert!(kinds(Thresholds::default(), &spike).contains(&AlertKind::ProcessSpike));
+        let relaxed = Thresholds { proc_spike_delta: 50, ..Thresholds::default() };
+        assert!(!kinds(relaxed, &spike).contains(&AlertKind::ProcessSpike));
+    }
+
+    #[test]
+    fn low_disk_respects_the_threshold() {
+        let full = SysTelemetry { disk_avail_mb: 80, ..snapshot() };
+        assert!(kinds(Thresholds::default(), std::slice::from_ref(&full)).contains(&AlertKind::LowDisk));
+        let relaxed = Thresholds { low_disk_pct: 5.0, ..Thresholds::default() };
+        assert!(!kinds(relaxed, &[full]).contains(&AlertKind::LowDisk));
+    }
+
+    #[test]
+    fn missing_threshold_keys_keep_their_defaults() {
+        let th: Thresholds = serde_json::from_str(r#"{"low_disk_pct": 3.0}"#).unwrap();
+        assert_eq!(th.low_disk_pct, 3.0);
+        assert_eq!(th.low_mem_pct, Thresholds::default().low_mem_pct);
+    }
+
+    #[test]
+    fn cpu_percent_ignores_bad_lines() {
+        let mut cpu = CpuSampler::new();
+        assert_eq!(cpu.sample_line("cpu  1 2"), 0.0);
+        cpu.sample_line("cpu  100 0 0 100 0");
+        assert_eq!(cpu.sample_line("cpu  100 0 0 100 0"), 0.0);
     }
-    "no network".to_string()
 }
@@ -0,0 +1,267 @@
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::ui::BlockColor;
+
+/// Optional custom theme, read at startup. Any field it leaves out comes
+/// from its `base` built-in theme.
+const THEME_FILE: &str = "/tmp/aether_theme.json";
+
+/// Built-in themes, as `/theme` accepts them.
+pub const BUILTIN_THEMES: &[&str] = &["dark", "high-contrast", "light", "colorblind"];
+
+static ACTIVE: RwLock<Option<Arc<Theme>>> = RwLock::new(None);
+
+/// Semantic colors the UI draws with.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    /// Focused borders, selection and the prompt.
+    pub accent: Color,
+    pub ok: Color,
+    pub warn: Color,
+    pub urgent: Color,
+    /// Secondary text and unfocused borders.
+    pub dim: Color,
+    pub text: Color,
+    /// Text on colored status bar badges.
+    pub badge_text: Color,
+    /// How each `BlockColor` (feed sources, widget boxes) is rendered.
+    pub cyan: Color,
+    pub green: Color,
+    pub yellow: Color,
+    pub blue: Color,
+    pub red: Color,
+    pub white: Color,
+    pub dark_gray: Color,
+    pub magenta: Color,
+    /// Also mark state with shapes (✓/✗, ↑/↓, !) so it never rests on hue alone.
+    pub glyphs: bool,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            name: "dark".to_string(),
+            accent: Color::Cyan,
+            ok: Color::Green,
+            warn: Color::Yellow,
+            urgent: Color::Red,
+            dim: Color::DarkGray,
+            text: Color::White,
+            badge_text: Color::Black,
+            cyan: Color::Cyan,
+            green: Color::Green,
+            yellow: Color::Yellow,
+            blue: Color::LightBlue,
+            red: Color::Red,
+            white: Color::White,
+            dark_gray: Color::DarkGray,
+            magenta: Color::Magenta,
+            glyphs: false,
+        }
+    }
+
+    /// Bright colors and no dark gray, for dim screens and low vision.
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast".to_string(),
+            accent: Color::LightCyan,
+            ok: Color::LightGreen,
+            warn: Color::LightYellow,
+            urgent: Color::LightRed,
+            dim: Color::Gray,
+            text: Color::White,
+            badge_text: Color::Black,
+            cyan: Color::LightCyan,
+            green: Color::LightGreen,
+            yellow: Color::LightYellow,
+            blue: Color::LightBlue,
+            red: Color::LightRed,
+            white: Color::White,
+            dark_gray: Color::Gray,
+            magenta: Color::LightMagenta,
+            glyphs: false,
+        }
+    }
+
+    /// For terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            name: "light".to_string(),
+            accent: Color::Blue,
+            ok: Color::Rgb(0, 128, 0),
+            warn: Color::Rgb(176, 112, 0),
+            urgent: Color::Rgb(192, 0, 0),
+            dim: Color::Rgb(110, 110, 110),
+            text: Color::Black,
+            badge_text: Color::White,
+            cyan: Color::Rgb(0, 120, 140),
+            green: Color::Rgb(0, 128, 0),
+            yellow: Color::Rgb(176, 112, 0),
+            blue: Color::Blue,
+            red: Color::Rgb(192, 0, 0),
+            white: Color::Black,
+            dark_gray: Color::Rgb(110, 110, 110),
+            magenta: Color::Rgb(140, 0, 140),
+            glyphs: false,
+        }
+    }
+
+    /// Blue/orange instead of green/red (Okabe-Ito colors), plus glyphs,
+    /// for red-green color blindness.
+    pub fn colorblind() -> Self {
+        Self {
+            name: "colorblind".to_string(),
+            accent: Color::Rgb(86, 180, 233),
+            ok: Color::Rgb(86, 180, 233),
+            warn: Color::Rgb(240, 228, 66),
+            urgent: Color::Rgb(230, 159, 0),
+            dim: Color::DarkGray,
+            text: Color::White,
+            badge_text: Color::Black,
+            cyan: Color::Rgb(86, 180, 233),
+            green: Color::Rgb(0, 158, 115),
+            yellow: Color::Rgb(240, 228, 66),
+            blue: Color::Rgb(0, 114, 178),
+            red: Color::Rgb(230, 159, 0),
+            white: Color::White,
+            dark_gray: Color::DarkGray,
+            magenta: Color::Rgb(204, 121, 167),
+            glyphs: true,
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "high-contrast" | "contrast" => Some(Self::high_contrast()),
+            "light" => Some(Self::light()),
+            "colorblind" | "deuteranopia" | "protanopia" => Some(Self::colorblind()),
+            _ => None,
+        }
+    }
+
+    pub fn block(&self, color: &BlockColor) -> Color {
+        match color {
+            BlockColor::Cyan => self.cyan,
+            BlockColor::Green => self.green,
+            BlockColor::Yellow => self.yellow,
+            BlockColor::Blue => self.blue,
+            BlockColor::Red => self.red,
+            BlockColor::White => self.white,
+            BlockColor::DarkGray => self.dark_gray,
+            BlockColor::Magenta => self.magenta,
+        }
+    }
+
+    /// `glyph` when this theme marks state with shapes, otherwise "".
+    pub fn mark(&self, glyph: &'static str) -> &'static str {
+        if self.glyphs { glyph } else { "" }
+    }
+
+    /// `urgent` above `urgent_at`, `warn` above `warn_at`, otherwise `ok`.
+    pub fn level(&self, value: f64, warn_at: f64, urgent_at: f64) -> Color {
+        if value > urgent_at {
+            self.urgent
+        } else if value > warn_at {
+            self.warn
+        } else {
+            self.ok
+        }
+    }
+}
+
+/// A theme file: every field optional, colors as names ("cyan",
+/// "lightred") or "#rrggbb".
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ThemeFile {
+    name: Option<String>,
+    base: Option<String>,
+    accent: Option<String>,
+    ok: Option<String>,
+    warn: Option<String>,
+    urgent: Option<String>,
+    dim: Option<String>,
+    text: Option<String>,
+    badge_text: Option<String>,
+    cyan: Option<String>,
+    green: Option<String>,
+    yellow: Option<String>,
+    blue: Option<String>,
+    red: Option<String>,
+    white: Option<String>,
+    dark_gray: Option<String>,
+    magenta: Option<String>,
+    glyphs: Option<bool>,
+}
+
+/// Read `THEME_FILE`, if present and valid. Unparseable colors keep the
+/// base theme's value.
+fn load_file() -> Option<Theme> {
+    let data = std::fs::read_to_string(THEME_FILE).ok()?;
+    let file: ThemeFile = serde_json::from_str(&data).ok()?;
+    let mut theme = file.base.as_deref().and_then(Theme::builtin).unwrap_or_else(Theme::dark);
+    theme.name = file.name.unwrap_or_else(|| "custom".to_string());
+    theme.glyphs = file.glyphs.unwrap_or(theme.glyphs);
+
+    let fields = [
+        (&mut theme.accent, file.accent),
+        (&mut theme.ok, file.ok),
+        (&mut theme.warn, file.warn),
+        (&mut theme.urgent, file.urgent),
+        (&mut theme.dim, file.dim),
+        (&mut theme.text, file.text),
+        (&mut theme.badge_text, file.badge_text),
+        (&mut theme.cyan, file.cyan),
+        (&mut theme.green, file.green),
+        (&mut theme.yellow, file.yellow),
+        (&mut theme.blue, file.blue),
+        (&mut theme.red, file.red),
+        (&mut theme.white, file.white),
+        (&mut theme.dark_gray, file.dark_gray),
+        (&mut theme.magenta, file.magenta),
+    ];
+    for (field, value) in fields {
+        if let Some(color) = value.and_then(|v| Color::from_str(&v).ok()) {
+            *field = color;
+        }
+    }
+    Some(theme)
+}
+
+/// A built-in theme, or the theme file's theme if `name` matches it.
+pub fn by_name(name: &str) -> Option<Theme> {
+    Theme::builtin(name).or_else(|| load_file().filter(|t| t.name.eq_ignore_ascii_case(name)))
+}
+
+/// Pick the startup theme: `NEBULA_THEME`, then the one saved with the
+/// session, then the theme file, then dark.
+pub fn init(saved: Option<&str>) {
+    let theme = std::env::var("NEBULA_THEME")
+        .ok()
+        .and_then(|name| by_name(&name))
+        .or_else(|| saved.and_then(by_name))
+        .or_else(load_file)
+        .unwrap_or_else(Theme::dark);
+    set(theme);
+}
+
+pub fn set(theme: Theme) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(Arc::new(theme));
+    }
+}
+
+/// The active theme.
+pub fn current() -> Arc<Theme> {
+    ACTIVE
+        .read()
+        .ok()
+        .and_then(|active| active.clone())
+        .unwrap_or_else(|| Arc::new(Theme::dark()))
+}
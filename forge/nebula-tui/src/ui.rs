@@ -2,13 +2,20 @@ use ratatui::{
     prelude::*,
     widgets::{Block, Borders, Paragraph, Wrap},
 };
-
-use crate::feed::{FeedItem, Priority};
+use aether_common::highlight::Token;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::ops::Range;
+
+use crate::feed::{FeedEntry, FeedItem, Priority};
+use crate::links;
+use crate::markdown;
+use crate::theme::{self, Theme};
 use crate::widgets;
 use crate::App;
 
 /// Color identifiers for styled output.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum BlockColor {
     Cyan,
     Green,
@@ -21,20 +28,103 @@ pub enum BlockColor {
 }
 
 impl BlockColor {
+    /// The color a brain widget of `widget_type` is drawn in.
+    pub fn for_widget(widget_type: &str) -> Self {
+        match widget_type {
+            "weather" => BlockColor::Yellow,
+            "system" => BlockColor::Green,
+            "file" => BlockColor::Blue,
+            "table" => BlockColor::Cyan,
+            "history" => BlockColor::Cyan,
+            "progress" => BlockColor::Green,
+            _ => BlockColor::White,
+        }
+    }
+
+    /// This color in the active theme.
     pub fn to_color(&self) -> Color {
-        match self {
-            BlockColor::Cyan => Color::Cyan,
-            BlockColor::Green => Color::Green,
-            BlockColor::Yellow => Color::Yellow,
-            BlockColor::Blue => Color::LightBlue,
-            BlockColor::Red => Color::Red,
-            BlockColor::White => Color::White,
-            BlockColor::DarkGray => Color::DarkGray,
-            BlockColor::Magenta => Color::Magenta,
+        theme::current().block(self)
+    }
+}
+
+/// Where each feed card landed on screen in the last draw, for mouse hit-testing.
+#[derive(Default)]
+pub struct FeedLayout {
+    /// Feed panel area including its border.
+    pub area: Rect,
+    /// (first row, row after last, visible item index) for each card on screen.
+    pub cards: Vec<(u16, u16, usize)>,
+    /// Link pieces on screen, for OSC 8 and clicking.
+    pub links: Vec<FeedLink>,
+}
+
+/// One line's worth of a link, at its screen cell.
+#[derive(Clone, Debug)]
+pub struct FeedLink {
+    pub x: u16,
+    pub y: u16,
+    pub text: String,
+    pub url: String,
+}
+
+impl FeedLayout {
+    /// Visible item index of the card drawn at screen position (x, y).
+    pub fn card_at(&self, x: u16, y: u16) -> Option<usize> {
+        if !self.area.contains(Position::new(x, y)) {
+            return None;
         }
+        self.cards
+            .iter()
+            .find(|(top, bottom, _)| (*top..*bottom).contains(&y))
+            .map(|(_, _, idx)| *idx)
+    }
+
+    /// URL of the link drawn at screen position (x, y).
+    pub fn link_at(&self, x: u16, y: u16) -> Option<&str> {
+        self.links
+            .iter()
+            .find(|l| l.y == y && (l.x..l.x + Span::raw(&l.text).width() as u16).contains(&x))
+            .map(|l| l.url.as_str())
     }
 }
 
+/// Re-emit the feed's links over the drawn frame as OSC 8 hyperlinks, in
+/// the cells' own style. ratatui can't carry the escapes in a cell, so this
+/// goes straight to the terminal after each draw. Links that something else
+/// was drawn over are left alone.
+pub fn write_hyperlinks(out: &mut impl Write, buffer: &Buffer, layout: &FeedLayout) -> io::Result<()> {
+    use crossterm::cursor::{MoveTo, RestorePosition, SavePosition};
+    use crossterm::queue;
+    use crossterm::style::{Attribute, Print, SetAttribute, SetBackgroundColor, SetForegroundColor};
+
+    if layout.links.is_empty() || !links::enabled() {
+        return Ok(());
+    }
+    queue!(out, SavePosition)?;
+    for link in &layout.links {
+        let width = Span::raw(&link.text).width() as u16;
+        let cells: Vec<_> = (0..width).filter_map(|i| buffer.cell((link.x + i, link.y))).collect();
+        let shown: String = cells.iter().map(|c| c.symbol()).collect();
+        let Some(cell) = cells.first().filter(|_| shown == link.text) else {
+            continue;
+        };
+        queue!(out, MoveTo(link.x, link.y), SetForegroundColor(cell.fg.into()), SetBackgroundColor(cell.bg.into()))?;
+        for (modifier, attribute) in [
+            (Modifier::BOLD, Attribute::Bold),
+            (Modifier::DIM, Attribute::Dim),
+            (Modifier::ITALIC, Attribute::Italic),
+            (Modifier::UNDERLINED, Attribute::Underlined),
+        ] {
+            if cell.modifier.contains(modifier) {
+                queue!(out, SetAttribute(attribute))?;
+            }
+        }
+        queue!(out, Print(links::osc8(&link.url, &link.text)), SetAttribute(Attribute::Reset))?;
+    }
+    queue!(out, RestorePosition)?;
+    out.flush()
+}
+
 /// Which panel has focus.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ActivePanel {
@@ -44,6 +134,7 @@ pub enum ActivePanel {
 }
 
 pub fn draw(f: &mut Frame, app: &App) {
+    let theme = theme::current();
     let size = f.area();
     let show_sidebar = size.width >= 60;
 
@@ -57,7 +148,7 @@ pub fn draw(f: &mut Frame, app: &App) {
         ])
         .split(size);
 
-    draw_status_bar(f, main_chunks[0], app);
+    draw_status_bar(f, main_chunks[0], app, &theme);
 
     if show_sidebar {
         let body_chunks = Layout::default()
@@ -68,16 +159,16 @@ pub fn draw(f: &mut Frame, app: &App) {
             ])
             .split(main_chunks[1]);
 
-        draw_sidebar(f, body_chunks[0], app);
-        draw_feed(f, body_chunks[1], app);
+        draw_sidebar(f, body_chunks[0], app, &theme);
+        draw_feed(f, body_chunks[1], app, &theme);
     } else {
-        draw_feed(f, main_chunks[1], app);
+        draw_feed(f, main_chunks[1], app, &theme);
     }
 
-    draw_input(f, main_chunks[2], app);
+    draw_input(f, main_chunks[2], app, &theme);
 }
 
-fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
+fn draw_status_bar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let uptime = app.telemetry.uptime_secs;
     let up_str = if uptime >= 3600 {
         format!("{}h{}m", uptime / 3600, (uptime % 3600) / 60)
@@ -96,58 +187,123 @@ fn draw_status_bar(f: &mut Frame, area: Rect, app: &App) {
     };
 
     let brain_status = if app.thinking {
-        Span::styled(" thinking ", Style::default().fg(Color::Black).bg(Color::Yellow).bold())
+        Span::styled(" thinking ", Style::default().fg(theme.badge_text).bg(theme.warn).bold())
     } else {
-        Span::styled(" ready ", Style::default().fg(Color::Black).bg(Color::Green))
+        Span::styled(
+            format!(" {}ready ", theme.mark("\u{2713} ")),
+            Style::default().fg(theme.badge_text).bg(theme.ok),
+        )
     };
 
-    let net_indicator = if app.telemetry.ip_addr.starts_with("10.")
-        || app.telemetry.ip_addr.starts_with("192.")
-        || app.telemetry.ip_addr.starts_with("172.")
-        || app.telemetry.ip_addr.contains("up")
-    {
-        Span::styled(" NET ", Style::default().fg(Color::Black).bg(Color::Green))
+    let net_indicator = if app.telemetry.net_up() {
+        Span::styled(
+            format!(" NET{} ", theme.mark(" \u{2713}")),
+            Style::default().fg(theme.badge_text).bg(theme.ok),
+        )
     } else {
-        Span::styled(" NET ", Style::default().fg(Color::Black).bg(Color::Red))
+        Span::styled(
+            format!(" NET{} ", theme.mark(" \u{2717}")),
+            Style::default().fg(theme.badge_text).bg(theme.urgent),
+        )
+    };
+
+    // Only problems get a badge: aurorad unreachable, or daemons behind it down
+    let stack_span = match &app.aurora {
+        Some(status) if !status.connected => Span::styled(
+            format!(" AURORA{} ", theme.mark(" \u{2717}")),
+            Style::default().fg(theme.badge_text).bg(theme.urgent),
+        ),
+        Some(status) if !status.down.is_empty() => Span::styled(
+            format!(" DOWN: {} ", status.down.join(",")),
+            Style::default().fg(theme.badge_text).bg(theme.urgent),
+        ),
+        _ => Span::raw(""),
+    };
+
+    let battery_span = match app.telemetry.battery_pct {
+        Some(pct) => {
+            let glyph = if app.telemetry.charging == Some(true) { "\u{26A1}" } else { "\u{25AE}" };
+            let th = app.proactive.thresholds();
+            let color = if pct < th.low_battery_pct {
+                theme.urgent
+            } else if pct < th.warn_battery_pct {
+                theme.warn
+            } else {
+                theme.dim
+            };
+            let low = if pct < th.low_battery_pct { theme.mark("!") } else { "" };
+            Span::styled(format!("{}{}%{} ", glyph, pct, low), Style::default().fg(color))
+        }
+        None => Span::raw(""),
     };
 
     let unseen = app.feed.unseen_count();
     let urgent = app.feed.unseen_urgent_count();
     let alert_span = if urgent > 0 {
         Span::styled(
-            format!(" {} ", urgent),
-            Style::default().fg(Color::Black).bg(Color::Red).bold(),
+            format!(" {}{} ", theme.mark("! "), urgent),
+            Style::default().fg(theme.badge_text).bg(theme.urgent).bold(),
         )
     } else if unseen > 0 {
         Span::styled(
             format!(" {} ", unseen),
-            Style::default().fg(Color::Black).bg(Color::Yellow),
+            Style::default().fg(theme.badge_text).bg(theme.warn),
         )
     } else {
         Span::raw("")
     };
 
+    let filter_span = if app.hidden_sources.is_empty() {
+        Span::raw("")
+    } else {
+        Span::styled(
+            format!(" FILTER -{} ", app.hidden_sources.len()),
+            Style::default().fg(theme.badge_text).bg(theme.magenta),
+        )
+    };
+
+    let mute_span = match app.proactive.muted().len() {
+        0 => Span::raw(""),
+        n => Span::styled(
+            format!(" MUTE {} ", n),
+            Style::default().fg(theme.badge_text).bg(theme.dim),
+        ),
+    };
+
+    let toast_span = match app.active_toast() {
+        Some(msg) => Span::styled(
+            format!(" {} ", msg),
+            Style::default().fg(theme.badge_text).bg(theme.accent),
+        ),
+        None => Span::raw(""),
+    };
+
     let header = Paragraph::new(Line::from(vec![
         Span::styled(
             " AETHER OS ",
-            Style::default().fg(Color::Black).bg(Color::Cyan).bold(),
+            Style::default().fg(theme.badge_text).bg(theme.accent).bold(),
         ),
         Span::raw(" "),
         brain_status,
         Span::raw(" "),
         net_indicator,
+        stack_span,
         Span::styled(
             format!(" Up:{} CPU:{:.0}% Mem:{:.0}% ", up_str, cpu, mem_pct),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         ),
+        battery_span,
+        filter_span,
+        mute_span,
         alert_span,
+        toast_span,
     ]));
     f.render_widget(header, area);
 }
 
-fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
+fn draw_sidebar(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let is_focused = app.active_panel == ActivePanel::Sidebar;
-    let border_color = if is_focused { Color::Cyan } else { Color::DarkGray };
+    let border_color = if is_focused { theme.accent } else { theme.dim };
 
     let mut lines: Vec<Line> = Vec::new();
 
@@ -162,16 +318,16 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
     };
     lines.push(Line::from(Span::styled(
         format!(" Up: {}", up_str),
-        Style::default().fg(Color::White).bold(),
+        Style::default().fg(theme.text).bold(),
     )));
     lines.push(Line::from(""));
 
     // CPU bar + sparkline
     let cpu = app.telemetry.cpu_percent;
-    let cpu_color = if cpu > 80.0 { Color::Red } else if cpu > 50.0 { Color::Yellow } else { Color::Green };
+    let cpu_color = theme.level(cpu, 50.0, 80.0);
     let cpu_bar = widgets::mini_bar(cpu, 100.0, 10);
     lines.push(Line::from(vec![
-        Span::styled(" CPU ", Style::default().fg(Color::White)),
+        Span::styled(" CPU ", Style::default().fg(theme.text)),
         Span::styled(cpu_bar, Style::default().fg(cpu_color)),
     ]));
     let cpu_hist = app.proactive.cpu_history();
@@ -191,10 +347,10 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
     } else {
         0.0
     };
-    let mem_color = if mem_pct > 85.0 { Color::Red } else if mem_pct > 60.0 { Color::Yellow } else { Color::Green };
+    let mem_color = theme.level(mem_pct, 60.0, 85.0);
     let mem_bar = widgets::mini_bar(mem_pct, 100.0, 10);
     lines.push(Line::from(vec![
-        Span::styled(" Mem ", Style::default().fg(Color::White)),
+        Span::styled(" Mem ", Style::default().fg(theme.text)),
         Span::styled(mem_bar, Style::default().fg(mem_color)),
     ]));
     let mem_hist = app.proactive.mem_pct_history();
@@ -205,37 +361,110 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
             Style::default().fg(mem_color),
         )));
     }
+
+    // Swap bar, only when swap is configured
+    if app.telemetry.swap_total_mb > 0 {
+        let swap_pct = app.telemetry.swap_used_pct();
+        let swap_color = theme.level(swap_pct, 20.0, 50.0);
+        let swap_bar = widgets::mini_bar(swap_pct, 100.0, 10);
+        lines.push(Line::from(vec![
+            Span::styled(" Swp ", Style::default().fg(theme.text)),
+            Span::styled(swap_bar, Style::default().fg(swap_color)),
+        ]));
+    }
     lines.push(Line::from(""));
 
+    // Disk bar
+    if app.telemetry.disk_total_mb > 0 {
+        let used = app.telemetry.disk_total_mb.saturating_sub(app.telemetry.disk_avail_mb);
+        let disk_pct = (used as f64 / app.telemetry.disk_total_mb as f64) * 100.0;
+        let disk_color = theme.level(disk_pct, 75.0, 90.0);
+        let disk_bar = widgets::mini_bar(disk_pct, 100.0, 10);
+        lines.push(Line::from(vec![
+            Span::styled(" Dsk ", Style::default().fg(theme.text)),
+            Span::styled(disk_bar, Style::default().fg(disk_color)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    // World-model prediction error, only while cfcd is answering
+    let model_hist = app.proactive.prediction_error_history();
+    if app.proactive.cfcd_available() && !model_hist.is_empty() {
+        let (model_color, trend_mark) = match app.proactive.prediction_error_trend() {
+            "rising" => (theme.urgent, theme.mark(" \u{2191}")),
+            "falling" => (theme.ok, theme.mark(" \u{2193}")),
+            _ => (theme.accent, ""),
+        };
+        lines.push(Line::from(vec![
+            Span::styled(" Model ", Style::default().fg(theme.text)),
+            Span::styled(
+                format!("{:.2}{}", model_hist.last().copied().unwrap_or(0.0), trend_mark),
+                Style::default().fg(model_color),
+            ),
+        ]));
+        lines.push(Line::from(Span::styled(
+            format!(" {}", widgets::sparkline_in_range(&model_hist, 14, 0.0, 1.0)),
+            Style::default().fg(model_color),
+        )));
+        lines.push(Line::from(""));
+    }
+
+    // Hottest thermal zone
+    if let Some((zone, temp)) = app.telemetry.hottest_zone() {
+        let th = app.proactive.thresholds();
+        let temp_color = if *temp >= th.high_temp_c {
+            theme.urgent
+        } else if *temp >= th.warn_temp_c {
+            theme.warn
+        } else {
+            theme.ok
+        };
+        lines.push(Line::from(vec![
+            Span::styled(" Tmp ", Style::default().fg(theme.text)),
+            Span::styled(format!("{:.0}\u{b0}C ", temp), Style::default().fg(temp_color)),
+            Span::styled(zone.as_str(), Style::default().fg(theme.dim)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
     // Network
     lines.push(Line::from(vec![
-        Span::styled(" Net ", Style::default().fg(Color::White)),
-        Span::styled(&app.telemetry.ip_addr, Style::default().fg(Color::DarkGray)),
+        Span::styled(" Net ", Style::default().fg(theme.text)),
+        Span::styled(&app.telemetry.ip_addr, Style::default().fg(theme.dim)),
     ]));
     lines.push(Line::from(""));
 
     // Processes
     lines.push(Line::from(Span::styled(
         format!(" Procs: {}", app.telemetry.num_procs),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.dim),
     )));
     lines.push(Line::from(""));
 
     // Tasks
     lines.push(Line::from(Span::styled(
         " Tasks",
-        Style::default().fg(Color::White).bold(),
+        Style::default().fg(theme.text).bold(),
     )));
     let task_summary = app.task_manager.summary();
     lines.push(Line::from(Span::styled(
         format!("  {}", task_summary),
-        Style::default().fg(Color::DarkGray),
+        Style::default().fg(theme.dim),
     )));
-    for (name, elapsed) in app.task_manager.active_tasks() {
-        lines.push(Line::from(Span::styled(
-            format!("  > {} {}s", name, elapsed),
-            Style::default().fg(Color::Yellow),
-        )));
+    for (idx, task) in app.task_manager.active_tasks().into_iter().enumerate() {
+        // Red once a task is close to being timed out
+        let color = if task.near_timeout() { theme.urgent } else { theme.warn };
+        let style = if is_focused && idx == app.selected_task {
+            Style::default().fg(theme.badge_text).bg(color)
+        } else {
+            Style::default().fg(color)
+        };
+        let label = if task.near_timeout() {
+            format!("  > {} {}/{}s", task.name, task.elapsed_secs(), task.timeout.as_secs())
+        } else {
+            format!("  > {} {}s", task.name, task.elapsed_secs())
+        };
+        lines.push(Line::from(Span::styled(label, style)));
     }
 
     // Navigation hint
@@ -244,9 +473,10 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
         for _ in lines.len()..remaining.saturating_sub(1) {
             lines.push(Line::from(""));
         }
+        let hint = if is_focused { " j/k x:cancel" } else { " Tab:switch" };
         lines.push(Line::from(Span::styled(
-            " Tab:switch",
-            Style::default().fg(Color::DarkGray),
+            hint,
+            Style::default().fg(theme.dim),
         )));
     }
 
@@ -255,26 +485,39 @@ fn draw_sidebar(f: &mut Frame, area: Rect, app: &App) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(border_color))
-                .title(Span::styled(" System ", Style::default().fg(Color::White).bold())),
+                .title(Span::styled(" System ", Style::default().fg(theme.text).bold())),
         );
     f.render_widget(sidebar, area);
 }
 
-fn draw_feed(f: &mut Frame, area: Rect, app: &App) {
+fn draw_feed(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let is_focused = app.active_panel == ActivePanel::Feed;
-    let border_color = if is_focused { Color::Cyan } else { Color::DarkGray };
+    let border_color = if is_focused { theme.accent } else { theme.dim };
 
     let inner_height = area.height.saturating_sub(2) as usize;
     let inner_width = area.width.saturating_sub(2) as usize;
 
-    let visible = app.feed.visible_items();
+    let entries = app.feed_entries();
     let mut all_lines: Vec<Line> = Vec::new();
+    // Line range each row occupies in `all_lines`
+    let mut card_lines: Vec<(usize, usize)> = Vec::new();
+    // Links by the `all_lines` index they sit on, x relative to the panel
+    let mut card_links: Vec<(usize, FeedLink)> = Vec::new();
 
-    for (idx, item) in visible.iter().enumerate() {
+    for (idx, entry) in entries.iter().enumerate() {
         let is_selected = app.active_panel == ActivePanel::Feed
             && app.selected_feed_item == Some(idx);
 
-        render_feed_card(item, is_selected, inner_width, &mut all_lines);
+        let first = all_lines.len();
+        match entry {
+            FeedEntry::Item(item) => {
+                render_feed_card(item, is_selected, inner_width, theme, &mut all_lines, &mut card_links)
+            }
+            FeedEntry::Group { items, expanded, .. } => {
+                render_group_header(items, *expanded, is_selected, inner_width, theme, &mut all_lines)
+            }
+        }
+        card_lines.push((first, all_lines.len()));
     }
 
     // Thinking indicator
@@ -288,7 +531,7 @@ fn draw_feed(f: &mut Frame, area: Rect, app: &App) {
         all_lines.push(Line::from(""));
         all_lines.push(Line::from(Span::styled(
             format!("  Thinking{}", dots),
-            Style::default().fg(Color::Yellow).bold(),
+            Style::default().fg(theme.warn).bold(),
         )));
     }
 
@@ -297,7 +540,7 @@ fn draw_feed(f: &mut Frame, area: Rect, app: &App) {
         all_lines.push(Line::from(""));
         all_lines.push(Line::from(Span::styled(
             "  No items yet. Type something below.",
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         )));
     }
 
@@ -312,26 +555,212 @@ fn draw_feed(f: &mut Frame, area: Rect, app: &App) {
         Vec::new()
     };
 
+    // Map card line ranges through the scroll window onto screen rows
+    let top = area.y + 1;
+    let cards = card_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, (first, last))| *last > start && *first < end)
+        .map(|(idx, (first, last))| {
+            let first = (*first).max(start) - start;
+            let last = (*last).min(end) - start;
+            (top + first as u16, top + last as u16, idx)
+        })
+        .collect();
+    let links = card_links
+        .into_iter()
+        .filter(|(line, _)| (start..end).contains(line))
+        .map(|(line, link)| FeedLink { x: area.x + 1 + link.x, y: top + (line - start) as u16, ..link })
+        .collect();
+    *app.feed_layout.borrow_mut() = FeedLayout { area, cards, links };
+
     let feed = Paragraph::new(visible_lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(border_color))
-                .title(Span::styled(" Feed ", Style::default().fg(Color::White).bold())),
+                .title(Span::styled(" Feed ", Style::default().fg(theme.text).bold())),
         )
         .wrap(Wrap { trim: false });
     f.render_widget(feed, area);
 }
 
+/// Push `text` wrapped to `width`, with each of `runs` patched over `style`
+/// and URLs and absolute paths styled as links. Each line's piece of a link
+/// is noted in `links` under the index of the line it went to.
+fn push_linked(
+    text: &str,
+    width: usize,
+    style: Style,
+    runs: &[(Range<usize>, Style)],
+    link_style: Style,
+    lines: &mut Vec<Line<'_>>,
+    links: &mut Vec<(usize, FeedLink)>,
+) {
+    let found = links::find(text);
+    // wrap() keeps words in order and only splits the ones too long for a
+    // line, so each piece of a wrapped line is the next run of `text`
+    let mut at = 0;
+    for wrapped in widgets::wrap(text, width) {
+        if found.is_empty() && runs.is_empty() {
+            lines.push(Line::from(Span::styled(wrapped, style)));
+            continue;
+        }
+        let mut spans: Vec<Span> = Vec::new();
+        let mut rest = wrapped.as_str();
+        while !rest.is_empty() {
+            let gap = rest.len() - rest.trim_start().len();
+            push_span(&mut spans, &rest[..gap], style);
+            rest = &rest[gap..];
+            let piece = rest.split(char::is_whitespace).next().unwrap_or("");
+            if piece.is_empty() {
+                break;
+            }
+            rest = &rest[piece.len()..];
+            let Some(offset) = text[at..].find(piece) else {
+                push_span(&mut spans, piece, style);
+                continue;
+            };
+            let (first, last) = (at + offset, at + offset + piece.len());
+            at = last;
+
+            // Cut the piece wherever a run or link starts or ends
+            let mut cuts: Vec<usize> = runs
+                .iter()
+                .map(|(r, _)| r)
+                .chain(found.iter().map(|l| &l.range))
+                .flat_map(|r| [r.start, r.end])
+                .filter(|&i| i > first && i < last)
+                .chain([first, last])
+                .collect();
+            cuts.sort_unstable();
+            cuts.dedup();
+            for cut in cuts.windows(2) {
+                let (from, to) = (cut[0], cut[1]);
+                let styled = runs
+                    .iter()
+                    .filter(|(r, _)| r.contains(&from))
+                    .fold(style, |s, (_, run)| s.patch(*run));
+                match found.iter().find(|l| l.range.contains(&from)) {
+                    Some(link) => {
+                        let x = spans.iter().map(Span::width).sum::<usize>() as u16;
+                        let piece = FeedLink { x, y: 0, text: text[from..to].to_string(), url: link.url.clone() };
+                        links.push((lines.len(), piece));
+                        spans.push(Span::styled(text[from..to].to_string(), styled.patch(link_style)));
+                    }
+                    None => push_span(&mut spans, &text[from..to], styled),
+                }
+            }
+        }
+        lines.push(Line::from(spans));
+    }
+}
+
+/// A line of fenced code behind a rule. Code keeps its spacing, so it's cut
+/// at `width` rather than wrapped; unhighlighted code stays dim.
+fn code_line(md: &markdown::MdLine, width: usize, theme: &Theme) -> Line<'static> {
+    let plain = if md.spans.is_empty() { theme.dim } else { theme.text };
+    let mut spans = vec![Span::styled("  \u{2502} ", Style::default().fg(theme.dim))];
+    let mut room = width.saturating_sub(4);
+    let mut at = 0;
+    let tokens = md.spans.iter().filter_map(|(range, inline)| match inline {
+        markdown::Inline::Syntax(token) => Some((range.clone(), syntax_style(*token, theme))),
+        _ => None,
+    });
+    for (range, style) in tokens.chain([(md.text.len()..md.text.len(), Style::default())]) {
+        for (text, style) in [(&md.text[at..range.start], Style::default().fg(plain)), (&md.text[range.clone()], style)] {
+            let text: String = text.chars().take(room).collect();
+            room -= text.chars().count();
+            if !text.is_empty() {
+                spans.push(Span::styled(text, style));
+            }
+        }
+        at = range.end;
+    }
+    Line::from(spans)
+}
+
+fn syntax_style(token: Token, theme: &Theme) -> Style {
+    match token {
+        Token::Keyword => Style::default().fg(theme.magenta),
+        Token::String => Style::default().fg(theme.green),
+        Token::Comment => Style::default().fg(theme.dim).italic(),
+        Token::Number => Style::default().fg(theme.yellow),
+    }
+}
+
+/// Append to the last span when it has the same style.
+fn push_span(spans: &mut Vec<Span<'_>>, text: &str, style: Style) {
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.content.to_mut().push_str(text),
+        _ => spans.push(Span::styled(text.to_string(), style)),
+    }
+}
+
+/// Render the header of a group of similar cards ("3 System alerts"),
+/// with the newest card's title underneath while folded.
+fn render_group_header(items: &[&FeedItem], expanded: bool, selected: bool, max_width: usize, theme: &Theme, lines: &mut Vec<Line<'_>>) {
+    let Some(newest) = items.last() else {
+        return;
+    };
+    let source_color = theme.block(&newest.source.color());
+    let border_color = match newest.priority {
+        Priority::Urgent => theme.urgent,
+        _ if selected => theme.accent,
+        _ => theme.dim,
+    };
+    let select_indicator = if selected { ">" } else { " " };
+    let arrow = if expanded { "\u{25be}" } else { "\u{25b8}" };
+
+    let age = newest.age_str();
+    let urgent_mark = if newest.priority == Priority::Urgent { theme.mark("! ") } else { "" };
+    let label = format!("{}{} {} {}", urgent_mark, items.len(), newest.source.label(), newest.source.group_noun());
+    let title = widgets::truncate(&label, max_width.saturating_sub(age.len() + 10));
+    let padding = max_width.saturating_sub(title.chars().count() + age.len() + 9);
+
+    lines.push(Line::from(vec![
+        Span::styled(select_indicator, Style::default().fg(border_color)),
+        Span::styled(format!("{} ", arrow), Style::default().fg(theme.dim)),
+        Span::styled(
+            format!("[{}]", newest.source.icon()),
+            Style::default().fg(source_color).bold(),
+        ),
+        Span::raw(" "),
+        Span::styled(
+            title,
+            if selected {
+                Style::default().fg(theme.text).bold()
+            } else {
+                Style::default().fg(theme.text)
+            },
+        ),
+        Span::raw(" ".repeat(padding.max(1))),
+        Span::styled(age, Style::default().fg(theme.dim)),
+    ]));
+    if !expanded {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", widgets::truncate(&newest.title, max_width.saturating_sub(4))),
+            Style::default().fg(theme.dim),
+        )));
+    }
+}
+
 /// Render a single feed item as a card into the line buffer.
-fn render_feed_card(item: &FeedItem, selected: bool, max_width: usize, lines: &mut Vec<Line<'_>>) {
-    let source_color = item.source.color().to_color();
+fn render_feed_card(
+    item: &FeedItem,
+    selected: bool,
+    max_width: usize,
+    theme: &Theme,
+    lines: &mut Vec<Line<'_>>,
+    links: &mut Vec<(usize, FeedLink)>,
+) {
+    let source_color = theme.block(&item.source.color());
     let border_color = match item.priority {
-        Priority::Urgent => Color::Red,
+        Priority::Urgent => theme.urgent,
         Priority::Normal => {
-            if selected { Color::Cyan } else { Color::DarkGray }
+            if selected { theme.accent } else { theme.dim }
         }
-        Priority::Low => Color::DarkGray,
+        Priority::Low => theme.dim,
     };
 
     let select_indicator = if selected { ">" } else { " " };
@@ -339,13 +768,12 @@ fn render_feed_card(item: &FeedItem, selected: bool, max_width: usize, lines: &m
     // Header line: [icon] Title                     age
     let age = item.age_str();
     let icon = item.source.icon();
-    let title_max = max_width.saturating_sub(age.len() + 8);
-    let title = if item.title.len() > title_max {
-        format!("{}...", &item.title[..title_max.saturating_sub(3)])
-    } else {
-        item.title.clone()
-    };
-    let padding = max_width.saturating_sub(title.len() + age.len() + 7);
+    let pin = if item.pinned { "\u{2691} " } else { "" };
+    let urgent_mark = if item.priority == Priority::Urgent { theme.mark("! ") } else { "" };
+    let pin_width = pin.chars().count() + urgent_mark.len();
+    let title_max = max_width.saturating_sub(age.len() + 8 + pin_width);
+    let title = widgets::truncate(&item.title, title_max);
+    let padding = max_width.saturating_sub(title.chars().count() + age.len() + 7 + pin_width);
 
     lines.push(Line::from(vec![
         Span::styled(select_indicator, Style::default().fg(border_color)),
@@ -354,54 +782,111 @@ fn render_feed_card(item: &FeedItem, selected: bool, max_width: usize, lines: &m
             Style::default().fg(source_color).bold(),
         ),
         Span::raw(" "),
+        Span::styled(urgent_mark, Style::default().fg(theme.urgent).bold()),
+        Span::styled(pin, Style::default().fg(theme.warn)),
         Span::styled(
             title,
             if selected {
-                Style::default().fg(Color::White).bold()
+                Style::default().fg(theme.text).bold()
             } else if item.priority == Priority::Urgent {
-                Style::default().fg(Color::Red).bold()
+                Style::default().fg(theme.urgent).bold()
             } else {
-                Style::default().fg(Color::White)
+                Style::default().fg(theme.text)
             },
         ),
         Span::raw(" ".repeat(padding.max(1))),
-        Span::styled(age, Style::default().fg(Color::DarkGray)),
+        Span::styled(age, Style::default().fg(theme.dim)),
     ]));
 
     // Body lines (if not collapsed)
     if !item.collapsed {
-        // Show body text
-        for line in &item.body {
-            let truncated = if line.len() > max_width.saturating_sub(4) {
-                format!("{}...", &line[..max_width.saturating_sub(7)])
+        // Show body text, wrapped after the 2-space indent with a 2-column margin
+        let link_style = Style::default().fg(theme.accent).underlined();
+        let width = max_width.saturating_sub(2);
+        if item.markdown {
+            for md in markdown::parse(&item.body) {
+                let style = match md.kind {
+                    markdown::Kind::Text => Style::default().fg(theme.dim),
+                    markdown::Kind::Heading => Style::default().fg(theme.accent).bold(),
+                    markdown::Kind::Code => {
+                        lines.push(code_line(&md, width, theme));
+                        continue;
+                    }
+                };
+                let runs: Vec<(Range<usize>, Style)> = md
+                    .spans
+                    .iter()
+                    .map(|(range, inline)| {
+                        let run = match inline {
+                            markdown::Inline::Bold => Style::default().fg(theme.text).bold(),
+                            markdown::Inline::Italic => Style::default().italic(),
+                            markdown::Inline::Code => Style::default().fg(theme.yellow),
+                            markdown::Inline::Syntax(token) => syntax_style(*token, theme),
+                        };
+                        // Shifted past the indent
+                        (range.start + 2..range.end + 2, run)
+                    })
+                    .collect();
+                let text = format!("  {}", md.text);
+                push_linked(&text, width, style, &runs, link_style, lines, links);
+            }
+        } else {
+            for line in &item.body {
+                let text = format!("  {}", line);
+                push_linked(&text, width, Style::default().fg(theme.dim), &[], link_style, lines, links);
+            }
+        }
+
+        // Reasoning section: a one-line summary until expanded with 'r'
+        if !item.reasoning.is_empty() {
+            if item.reasoning_expanded {
+                lines.push(Line::from(Span::styled(
+                    "  \u{25be} Reasoning",
+                    Style::default().fg(theme.dim).italic(),
+                )));
+                for line in &item.reasoning {
+                    for wrapped in widgets::wrap(&format!("    {}", line), max_width.saturating_sub(2)) {
+                        lines.push(Line::from(Span::styled(
+                            wrapped,
+                            Style::default().fg(theme.dim).italic(),
+                        )));
+                    }
+                }
             } else {
-                line.clone()
-            };
-            lines.push(Line::from(Span::styled(
-                format!("  {}", truncated),
-                Style::default().fg(Color::DarkGray),
-            )));
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  \u{25b8} Reasoning ({} line{}, r to expand)",
+                        item.reasoning.len(),
+                        if item.reasoning.len() == 1 { "" } else { "s" }
+                    ),
+                    Style::default().fg(theme.dim).italic(),
+                )));
+            }
         }
 
         // Show widget if present
         if let Some(ref widget) = item.widget {
-            let wc = widget.color.to_color();
+            let wc = theme.block(&widget.color);
             let box_width = max_width.saturating_sub(4).min(56);
 
+            let wtitle = widgets::truncate(&widget.title, box_width.saturating_sub(6));
             let top = format!(
                 "  \u{250c}\u{2500} {} {}\u{2510}",
-                widget.title,
-                "\u{2500}".repeat(box_width.saturating_sub(widget.title.len() + 5))
+                wtitle,
+                "\u{2500}".repeat(box_width.saturating_sub(wtitle.chars().count() + 5))
             );
             lines.push(Line::from(Span::styled(top, Style::default().fg(wc))));
 
-            for wline in &widget.lines {
-                let content = if wline.len() > box_width.saturating_sub(4) {
-                    &wline[..box_width.saturating_sub(4)]
-                } else {
-                    wline.as_str()
-                };
-                let pad = box_width.saturating_sub(content.len() + 4);
+            // Structured weather and progress draw richly; otherwise lines
+            let body = match (&widget.weather, &widget.progress) {
+                (Some(weather), _) => widgets::weather_lines(weather, box_width.saturating_sub(4)),
+                (None, Some(progress)) => widgets::progress_lines(progress, box_width.saturating_sub(4)),
+                (None, None) => widget.lines.clone(),
+            };
+            for wline in &body {
+                // Count chars, not bytes: chart rows are multibyte braille
+                let content = widgets::truncate(wline, box_width.saturating_sub(4));
+                let pad = box_width.saturating_sub(content.chars().count() + 4);
                 let row = format!("  \u{2502} {}{} \u{2502}", content, " ".repeat(pad));
                 lines.push(Line::from(Span::styled(row, Style::default().fg(wc))));
             }
@@ -415,14 +900,10 @@ fn render_feed_card(item: &FeedItem, selected: bool, max_width: usize, lines: &m
     } else if !item.body.is_empty() {
         // Collapsed: show first line as preview
         let preview = &item.body[0];
-        let truncated = if preview.len() > max_width.saturating_sub(8) {
-            format!("{}...", &preview[..max_width.saturating_sub(11)])
-        } else {
-            preview.clone()
-        };
+        let truncated = widgets::truncate(preview, max_width.saturating_sub(8));
         lines.push(Line::from(Span::styled(
             format!("  {}", truncated),
-            Style::default().fg(Color::DarkGray),
+            Style::default().fg(theme.dim),
         )));
     }
 
@@ -430,23 +911,37 @@ fn render_feed_card(item: &FeedItem, selected: bool, max_width: usize, lines: &m
     lines.push(Line::from(""));
 }
 
-fn draw_input(f: &mut Frame, area: Rect, app: &App) {
+fn draw_input(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let is_focused = app.active_panel == ActivePanel::Input;
     let border_color = if app.thinking {
-        Color::Yellow
+        theme.warn
     } else if is_focused {
-        Color::Cyan
+        theme.accent
     } else {
-        Color::DarkGray
+        theme.dim
     };
 
     let prompt = if app.thinking {
-        Span::styled("> ", Style::default().fg(Color::Yellow).bold())
+        Span::styled("> ", Style::default().fg(theme.warn).bold())
     } else {
-        Span::styled("> ", Style::default().fg(Color::Cyan).bold())
+        Span::styled("> ", Style::default().fg(theme.accent).bold())
     };
 
-    let input = Paragraph::new(Line::from(vec![prompt, Span::raw(&app.input)]))
+    let mut spans = vec![prompt, Span::raw(&app.input)];
+    // Ghost list of completion candidates, the filled-in one highlighted
+    if let Some(completion) = &app.completion {
+        spans.push(Span::raw("  "));
+        for (i, candidate) in completion.candidates.iter().enumerate() {
+            let style = if completion.index == Some(i) {
+                Style::default().fg(theme.accent)
+            } else {
+                Style::default().fg(theme.dim)
+            };
+            spans.push(Span::styled(format!(" {}", candidate), style));
+        }
+    }
+
+    let input = Paragraph::new(Line::from(spans))
         .block(
             Block::default()
                 .borders(Borders::ALL)
@@ -456,9 +951,62 @@ fn draw_input(f: &mut Frame, area: Rect, app: &App) {
 
     // Position cursor only when input panel is focused
     if is_focused && !app.thinking {
+        // Columns, not bytes: wide (CJK) characters take two
+        let column = Span::raw(&app.input[..app.cursor]).width() as u16;
         f.set_cursor_position(Position::new(
-            area.x + 3 + app.cursor as u16,
+            area.x + 3 + column,
             area.y + 1,
         ));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::feed::{FeedSource, FeedStore, Priority, WidgetData};
+
+    fn render(item: &FeedItem, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut links = Vec::new();
+        render_feed_card(item, false, width, &Theme::dark(), &mut lines, &mut links);
+        lines.iter().map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect()).collect()
+    }
+
+    #[test]
+    fn emoji_titles_truncate_without_panicking() {
+        let emoji = "\u{1F326}\u{FE0F}\u{1F308}\u{1F525}".repeat(20);
+        let item = FeedItem::new(FeedSource::Brain, Priority::Normal, emoji.clone())
+            .with_body(vec![emoji.clone(), "na\u{EF}ve caf\u{E9} \u{2014} \u{65E5}\u{672C}\u{8A9E}".repeat(8)])
+            .with_widget(WidgetData {
+                widget_type: "info".to_string(),
+                title: emoji.clone(),
+                lines: vec![emoji, "\u{E9}".repeat(50)],
+                color: BlockColor::Cyan,
+                weather: None,
+                progress: None,
+            });
+        for width in [4, 7, 10, 13, 20, 33] {
+            let out = render(&item, width);
+            assert!(!out.is_empty(), "width {width}");
+        }
+    }
+
+    #[test]
+    fn reasoning_is_collapsed_until_toggled() {
+        let mut feed = FeedStore::new(10);
+        let id = feed.push(
+            FeedItem::new(FeedSource::Brain, Priority::Normal, "Answer".to_string())
+                .with_reasoning(vec!["checked the logs".to_string(), "disk is full".to_string()]),
+        );
+
+        let collapsed = render(feed.visible_items()[0], 60);
+        assert!(collapsed.iter().any(|l| l.contains("\u{25b8} Reasoning (2 lines, r to expand)")), "{collapsed:?}");
+        assert!(!collapsed.iter().any(|l| l.contains("checked the logs") || l.contains("disk is full")));
+
+        feed.toggle_reasoning(id);
+        let expanded = render(feed.visible_items()[0], 60);
+        assert!(expanded.iter().any(|l| l.contains("checked the logs")), "{expanded:?}");
+        assert!(expanded.iter().any(|l| l.contains("disk is full")), "{expanded:?}");
+        assert!(!expanded.iter().any(|l| l.contains("r to expand")));
+    }
+}
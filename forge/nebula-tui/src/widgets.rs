@@ -1,3 +1,5 @@
+use crate::feed::{sky_glyph, Progress, Weather};
+
 /// ASCII bar chart renderer.
 /// Returns a string like: [||||||||..........] 42%
 pub fn mini_bar(value: f64, max: f64, width: usize) -> String {
@@ -16,6 +18,15 @@ pub fn mini_bar(value: f64, max: f64, width: usize) -> String {
 /// Takes a slice of values (0.0-100.0) and renders a single-row trend line.
 /// Characters: _ . - ' ^ " for 6 levels of height.
 pub fn sparkline(values: &[f64], width: usize) -> String {
+    let slice = &values[values.len().saturating_sub(width)..];
+    let min = slice.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = slice.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    sparkline_in_range(values, width, min, max)
+}
+
+/// Sparkline scaled against a fixed `min..max` instead of the data's own
+/// range, so the height means the same thing from one frame to the next.
+pub fn sparkline_in_range(values: &[f64], width: usize, min: f64, max: f64) -> String {
     if values.is_empty() {
         return " ".repeat(width);
     }
@@ -30,14 +41,12 @@ pub fn sparkline(values: &[f64], width: usize) -> String {
     };
     let slice = &values[start..];
 
-    let min = slice.iter().cloned().fold(f64::INFINITY, f64::min);
-    let max = slice.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
     let range = max - min;
 
     let mut result = String::new();
     for &v in slice {
         let level = if range > 0.0 {
-            ((v - min) / range * 5.0).round() as usize
+            ((v.clamp(min, max) - min) / range * 5.0).round() as usize
         } else {
             2 // middle
         };
@@ -52,6 +61,105 @@ pub fn sparkline(values: &[f64], width: usize) -> String {
     result
 }
 
+/// Braille dot bits, indexed by [column][row] within a 2x4 character cell.
+const BRAILLE_BITS: [[u8; 4]; 2] = [[0x01, 0x02, 0x04, 0x40], [0x08, 0x10, 0x20, 0x80]];
+
+/// Braille time-series plot.
+/// Each character is a 2x4 dot grid, so the plot has `width * 2` columns and
+/// `height * 4` rows of resolution. Values are scaled against 0..`max` and
+/// resampled to fill the full width, newest on the right.
+pub fn braille_plot(values: &[f64], width: usize, height: usize, max: f64) -> Vec<String> {
+    let cols = width * 2;
+    let rows = height * 4;
+    let mut grid = vec![vec![0u8; width]; height];
+
+    if !values.is_empty() && cols > 0 && rows > 0 {
+        let n = values.len();
+        let sample = |c: usize| -> f64 {
+            if n == 1 || cols == 1 {
+                return values[n - 1];
+            }
+            let pos = c as f64 * (n - 1) as f64 / (cols - 1) as f64;
+            let i = pos.floor() as usize;
+            let frac = pos - i as f64;
+            if i + 1 < n {
+                values[i] * (1.0 - frac) + values[i + 1] * frac
+            } else {
+                values[n - 1]
+            }
+        };
+        let to_row = |v: f64| -> usize {
+            let pct = if max > 0.0 { (v / max).clamp(0.0, 1.0) } else { 0.0 };
+            ((1.0 - pct) * (rows - 1) as f64).round() as usize
+        };
+
+        // Plot each column, joining it vertically to the previous point
+        let mut prev_row: Option<usize> = None;
+        for c in 0..cols {
+            let r = to_row(sample(c));
+            let (lo, hi) = match prev_row {
+                Some(p) => (p.min(r), p.max(r)),
+                None => (r, r),
+            };
+            for rr in lo..=hi {
+                grid[rr / 4][c / 2] |= BRAILLE_BITS[c % 2][rr % 4];
+            }
+            prev_row = Some(r);
+        }
+    }
+
+    grid.iter()
+        .map(|row| {
+            row.iter()
+                .map(|&bits| char::from_u32(0x2800 + bits as u32).unwrap_or(' '))
+                .collect()
+        })
+        .collect()
+}
+
+/// Full-size history chart for a percentage metric.
+/// Wraps `braille_plot` with a 0-100% y-axis, a time axis and min/avg/max.
+/// `interval_secs` is the spacing between samples.
+pub fn history_chart(values: &[f64], width: usize, height: usize, interval_secs: u64) -> Vec<String> {
+    if values.len() < 2 {
+        return vec![format!(
+            "Collecting data... ({} sample{} so far)",
+            values.len(),
+            if values.len() == 1 { "" } else { "s" }
+        )];
+    }
+
+    let height = height.max(2);
+    let plot = braille_plot(values, width, height, 100.0);
+    let mut lines = Vec::new();
+    for (i, row) in plot.iter().enumerate() {
+        let label = if i == 0 {
+            "100\u{2524}".to_string()
+        } else if i == height / 2 {
+            " 50\u{2524}".to_string()
+        } else if i == height - 1 {
+            "  0\u{2524}".to_string()
+        } else {
+            "   \u{2502}".to_string()
+        };
+        lines.push(format!("{}{}", label, row));
+    }
+    lines.push(format!("   \u{2514}{}", "\u{2500}".repeat(width)));
+
+    let span = format!("-{}s", (values.len() as u64 - 1) * interval_secs);
+    let gap = width.saturating_sub(span.len() + 3);
+    lines.push(format!("    {}{}now", span, " ".repeat(gap)));
+
+    let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+    let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let avg = values.iter().sum::<f64>() / values.len() as f64;
+    lines.push(format!(
+        "    min {:.0}%  avg {:.0}%  max {:.0}%",
+        min, avg, max
+    ));
+    lines
+}
+
 /// Progress bar renderer.
 /// Returns: [=====>     ] 55%
 pub fn progress_bar(progress: f64, width: usize) -> String {
@@ -79,6 +187,170 @@ pub fn key_value_lines(pairs: &[(&str, &str)], key_width: usize) -> Vec<String>
         .collect()
 }
 
+/// Truncate to at most `max` chars, ending in "..." when cut.
+/// Counts chars rather than bytes so multibyte text never splits mid-char.
+pub fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        return s.to_string();
+    }
+    let keep = max.saturating_sub(3);
+    let end = s.char_indices().nth(keep).map(|(i, _)| i).unwrap_or(s.len());
+    format!("{}...", &s[..end])
+}
+
+/// Word-wrap `s` to `width` columns. Continuation lines repeat the line's
+/// leading indentation; a word longer than a whole line is split.
+pub fn wrap(s: &str, width: usize) -> Vec<String> {
+    let indent: String = s.chars().take_while(|c| c.is_whitespace()).collect();
+    let indent_width = indent.chars().count();
+    if s.chars().count() <= width || width <= indent_width {
+        return vec![s.to_string()];
+    }
+    let avail = width - indent_width;
+
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    let mut current_width = 0;
+    for word in s.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        while word.len() > avail {
+            if current_width > 0 {
+                lines.push(std::mem::take(&mut current));
+                current_width = 0;
+            }
+            lines.push(word.drain(..avail).collect());
+        }
+        if word.is_empty() {
+            continue;
+        }
+        if current_width > 0 && current_width + 1 + word.len() > avail {
+            lines.push(std::mem::take(&mut current));
+            current_width = 0;
+        }
+        if current_width > 0 {
+            current.push(' ');
+            current_width += 1;
+        }
+        current.extend(word.iter());
+        current_width += word.len();
+    }
+    if current_width > 0 {
+        lines.push(current);
+    }
+    lines.into_iter().map(|l| format!("{indent}{l}")).collect()
+}
+
+/// 3x5 bitmaps for `big_text`, one string per pixel row.
+fn big_glyph(c: char) -> Option<[&'static str; 5]> {
+    Some(match c.to_ascii_uppercase() {
+        '0' => ["###", "# #", "# #", "# #", "###"],
+        '1' => [" # ", "## ", " # ", " # ", "###"],
+        '2' => ["###", "  #", "###", "#  ", "###"],
+        '3' => ["###", "  #", "###", "  #", "###"],
+        '4' => ["# #", "# #", "###", "  #", "  #"],
+        '5' => ["###", "#  ", "###", "  #", "###"],
+        '6' => ["###", "#  ", "###", "# #", "###"],
+        '7' => ["###", "  #", "  #", "  #", "  #"],
+        '8' => ["###", "# #", "###", "# #", "###"],
+        '9' => ["###", "# #", "###", "  #", "###"],
+        '-' => ["   ", "   ", "###", "   ", "   "],
+        '+' => ["   ", " # ", "###", " # ", "   "],
+        '.' => [" ", " ", " ", " ", "#"],
+        '\u{00b0}' => ["##", "##", "  ", "  ", "  "],
+        'C' => ["###", "#  ", "#  ", "#  ", "###"],
+        'F' => ["###", "#  ", "###", "#  ", "#  "],
+        ' ' => [" ", " ", " ", " ", " "],
+        _ => return None,
+    })
+}
+
+/// `s` in 3-row block digits, for a headline number like "18°C".
+/// `None` if `s` has a character the font lacks.
+pub fn big_text(s: &str) -> Option<[String; 3]> {
+    let glyphs = s.chars().map(big_glyph).collect::<Option<Vec<_>>>()?;
+    let mut rows: [String; 3] = Default::default();
+    for (i, glyph) in glyphs.iter().enumerate() {
+        // Two pixel rows per text row, as upper and lower half blocks
+        for (row, out) in rows.iter_mut().enumerate() {
+            if i > 0 {
+                out.push(' ');
+            }
+            let top = glyph[row * 2].chars();
+            let bottom = glyph.get(row * 2 + 1).map_or("", |r| r).chars().chain(std::iter::repeat(' '));
+            out.extend(top.zip(bottom).map(|pair| match pair {
+                ('#', '#') => '\u{2588}',
+                ('#', _) => '\u{2580}',
+                (_, '#') => '\u{2584}',
+                _ => ' ',
+            }));
+        }
+    }
+    Some(rows)
+}
+
+/// A progress widget's rows: its label, then a bar across `width`.
+pub fn progress_lines(progress: &Progress, width: usize) -> Vec<String> {
+    let mut lines: Vec<String> = progress.label.iter().cloned().collect();
+    // mini_bar adds the brackets and up to " 100%"
+    lines.push(mini_bar(progress.value.clamp(0.0, 1.0), 1.0, width.saturating_sub(7)));
+    lines
+}
+
+/// A weather widget's rows: a big temperature beside the condition, highs
+/// and lows and wind, then the forecast packed into rows of `width`.
+pub fn weather_lines(weather: &Weather, width: usize) -> Vec<String> {
+    let range = match (&weather.high, &weather.low) {
+        (Some(h), Some(l)) => format!("H {}  L {}", h, l),
+        (Some(h), None) => format!("H {}", h),
+        (None, Some(l)) => format!("L {}", l),
+        (None, None) => String::new(),
+    };
+    let wind = weather.wind.as_ref().map(|w| format!("Wind {}", w)).unwrap_or_default();
+    let sky = format!("{} {}", weather.glyph(), weather.condition).trim_end().to_string();
+
+    let mut lines = match big_text(&weather.temp) {
+        Some(big) => {
+            let side = [sky, range, wind];
+            big.iter()
+                .zip(side.iter())
+                .map(|(b, s)| format!("{}   {}", b, s).trim_end().to_string())
+                .collect()
+        }
+        None => {
+            let details: Vec<&str> = [range.as_str(), wind.as_str()].into_iter().filter(|s| !s.is_empty()).collect();
+            let mut lines = vec![format!("{}  {}", weather.temp, sky)];
+            if !details.is_empty() {
+                lines.push(details.join(" \u{00b7} "));
+            }
+            lines
+        }
+    };
+
+    let days = weather.forecast.iter().map(|f| {
+        let glyph = f.icon.as_deref().map(sky_glyph).unwrap_or("");
+        let temps = match (&f.high, &f.low) {
+            (Some(h), Some(l)) => format!("{}/{}", h, l),
+            (Some(t), None) | (None, Some(t)) => t.clone(),
+            (None, None) => String::new(),
+        };
+        [f.day.as_str(), glyph, temps.as_str()].iter().filter(|s| !s.is_empty()).copied().collect::<Vec<_>>().join(" ")
+    });
+    let mut row = String::new();
+    for day in days {
+        if !row.is_empty() && row.chars().count() + 2 + day.chars().count() > width {
+            lines.push(std::mem::take(&mut row));
+        }
+        if !row.is_empty() {
+            row.push_str("  ");
+        }
+        row.push_str(&day);
+    }
+    if !row.is_empty() {
+        lines.push(row);
+    }
+    lines
+}
+
 /// Relative time formatting.
 pub fn relative_time(secs: u64) -> String {
     if secs < 5 {
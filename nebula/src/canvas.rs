@@ -3,10 +3,56 @@
 //! An infinite 2D space where content lives. Not windows—fluid regions
 //! that can be navigated, zoomed, and spatially arranged.
 
+use std::collections::HashMap;
+
 use glam::Vec2;
 
+use crate::facet::{self, Facet, FacetData, FacetInstance, FacetState, SaveResult};
+use crate::input::{Key, Modifiers, MouseButton};
 use crate::render::{Color, Rect, Renderer};
 
+/// Default size of a newly opened facet region, in world units.
+const FACET_REGION_SIZE: Vec2 = Vec2::new(640.0, 400.0);
+/// Smallest a region can be resized to, in world units.
+const MIN_REGION_SIZE: Vec2 = Vec2::new(120.0, 80.0);
+/// Size of the bottom-right resize grab zone, in screen pixels.
+const RESIZE_GRAB_PX: f32 = 16.0;
+/// How hard the camera is pulled toward a `fly_to` target, per second.
+const FLY_STIFFNESS: f32 = 6.0;
+/// Share of the screen the overview leaves for the regions' bounding box.
+const OVERVIEW_FILL: f32 = 0.85;
+/// Minimap size and its margin from the bottom-right corner, in screen pixels.
+const MINIMAP_SIZE: Vec2 = Vec2::new(180.0, 120.0);
+const MINIMAP_MARGIN: f32 = 16.0;
+
+/// What a pointer drag is doing to a region
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DragMode {
+    Move,
+    Resize,
+}
+
+/// An in-progress pointer drag
+#[derive(Clone, Copy, Debug)]
+struct Drag {
+    region: u64,
+    mode: DragMode,
+    /// Move: pointer minus region origin. Resize: pointer minus region
+    /// bottom-right corner. Both in world units.
+    grab_offset: Vec2,
+}
+
+/// A connection feeding one facet's `provide()` into another's `receive()`
+#[derive(Clone, Debug)]
+struct Pipe {
+    from: u64,
+    to: u64,
+    /// MIME type agreed from the source's `produces()` and the sink's `accepts()`.
+    mime: String,
+    /// Last data sent, so only changes propagate.
+    last: Option<FacetData>,
+}
+
 /// A region on the canvas containing content
 #[derive(Clone, Debug)]
 pub struct Region {
@@ -31,6 +77,11 @@ pub struct Camera {
     pub zoom: f32,
     velocity: Vec2,
     zoom_velocity: f32,
+    /// Position and zoom the camera is gliding toward.
+    target: Option<(Vec2, f32)>,
+    /// Screen point, relative to the screen center, that stays over the same
+    /// world point while zooming.
+    zoom_anchor: Option<Vec2>,
 }
 
 impl Camera {
@@ -40,37 +91,76 @@ impl Camera {
             zoom: 1.0,
             velocity: Vec2::ZERO,
             zoom_velocity: 0.0,
+            target: None,
+            zoom_anchor: None,
         }
     }
 
     fn update(&mut self, dt: f32) {
+        // Steer toward the target, if any, through the same velocities
+        if let Some((position, zoom)) = self.target {
+            if position.distance(self.position) < 0.5 && (zoom - self.zoom).abs() < 0.001 {
+                self.position = position;
+                self.zoom = zoom;
+                self.velocity = Vec2::ZERO;
+                self.zoom_velocity = 0.0;
+                self.target = None;
+            } else {
+                self.velocity = (position - self.position) * FLY_STIFFNESS;
+                self.zoom_velocity = (zoom - self.zoom) * FLY_STIFFNESS;
+            }
+        }
+
         // Apply velocity with damping
         let damping = 5.0;
         
         self.position += self.velocity * dt;
         self.velocity *= (-damping * dt).exp();
         
+        // World point under the anchor before zooming
+        let anchored = self.zoom_anchor.map(|a| (a, self.position + a / self.zoom));
+        
         self.zoom += self.zoom_velocity * dt;
         self.zoom_velocity *= (-damping * dt).exp();
         
         // Clamp zoom
         self.zoom = self.zoom.clamp(0.1, 5.0);
         
+        // Keep that point under the anchor
+        if let Some((a, world)) = anchored {
+            self.position = world - a / self.zoom;
+        }
+        
         // Stop when slow enough
         if self.velocity.length() < 0.1 {
             self.velocity = Vec2::ZERO;
         }
         if self.zoom_velocity.abs() < 0.001 {
             self.zoom_velocity = 0.0;
+            self.zoom_anchor = None;
         }
     }
 
     fn pan(&mut self, delta: Vec2) {
+        self.target = None;
         self.velocity += delta * 10.0;
     }
 
-    fn zoom_by(&mut self, factor: f32) {
+    fn is_moving(&self) -> bool {
+        self.velocity != Vec2::ZERO || self.zoom_velocity != 0.0 || self.target.is_some()
+    }
+
+    /// Zoom around `anchor`, a screen point relative to the screen center.
+    fn zoom_by(&mut self, factor: f32, anchor: Vec2) {
+        self.target = None;
         self.zoom_velocity += factor;
+        self.zoom_anchor = Some(anchor);
+    }
+
+    /// Glide to `position` at `zoom`. Manual panning or zooming cancels it.
+    fn fly_to(&mut self, position: Vec2, zoom: f32) {
+        self.target = Some((position, zoom.clamp(0.1, 5.0)));
+        self.zoom_anchor = None;
     }
 
     /// Transform world coordinates to screen coordinates
@@ -87,18 +177,64 @@ impl Camera {
 /// The Canvas
 pub struct Canvas {
     regions: Vec<Region>,
+    /// Live facets, keyed by the id of the region that hosts them.
+    facets: HashMap<u64, FacetInstance>,
+    /// Facet region receiving keyboard input.
+    focused: Option<u64>,
     camera: Camera,
     next_id: u64,
     pointer_pos: Vec2,
+    /// Screen center from the last frame, for pointer hit-testing.
+    screen_center: Vec2,
+    drag: Option<Drag>,
+    pipes: Vec<Pipe>,
+    /// Source region of an in-progress right-button pipe drag.
+    linking: Option<u64>,
+    /// Camera position and zoom from before the overview, while it's shown.
+    overview: Option<(Vec2, f32)>,
+}
+
+/// Placement of the minimap: world point `origin` maps to `offset` on
+/// screen, and world units shrink by `scale`.
+#[derive(Clone, Copy, Debug)]
+struct Minimap {
+    rect: Rect,
+    origin: Vec2,
+    offset: Vec2,
+    scale: f32,
+}
+
+impl Minimap {
+    fn screen_at(&self, world: Vec2) -> Vec2 {
+        (world - self.origin) * self.scale + self.offset
+    }
+
+    fn world_at(&self, screen: Vec2) -> Vec2 {
+        (screen - self.offset) / self.scale + self.origin
+    }
+
+    fn contains(&self, screen: Vec2) -> bool {
+        screen.x >= self.rect.x
+            && screen.x <= self.rect.x + self.rect.width
+            && screen.y >= self.rect.y
+            && screen.y <= self.rect.y + self.rect.height
+    }
 }
 
 impl Canvas {
     pub fn new() -> Self {
         let mut canvas = Self {
             regions: Vec::new(),
+            facets: HashMap::new(),
+            focused: None,
             camera: Camera::new(),
             next_id: 1,
             pointer_pos: Vec2::ZERO,
+            screen_center: Vec2::ZERO,
+            drag: None,
+            pipes: Vec::new(),
+            linking: None,
+            overview: None,
         };
         
         // Add some initial content for testing
@@ -127,14 +263,264 @@ impl Canvas {
         id
     }
 
-    pub fn handle_pointer(&mut self, position: Vec2) {
+    /// Jump the camera straight to `zoom`, e.g. the default zoom at startup.
+    pub fn set_zoom(&mut self, zoom: f32) {
+        self.camera.zoom = zoom.clamp(0.1, 5.0);
+    }
+
+    /// Open a facet in a new region centered on the camera.
+    pub fn open_facet(&mut self, mut facet: Box<dyn Facet>) -> u64 {
+        facet.init(None);
+        let size = FACET_REGION_SIZE;
+        let position = self.camera.position - size / 2.0;
+        let name = facet.id().to_string();
+        let id = self.add_region(position, size, RegionContent::Facet { name });
+
+        self.facets.insert(
+            id,
+            FacetInstance {
+                facet,
+                state: FacetState::Active,
+                position,
+                size,
+                z_index: id as i32,
+            },
+        );
+        self.set_focus(Some(id));
+        id
+    }
+
+    /// Give keyboard focus to a facet region (or none). The focused facet is
+    /// Active; the rest drop to Background.
+    fn set_focus(&mut self, id: Option<u64>) {
+        self.focused = id.filter(|id| self.facets.contains_key(id));
+        for (rid, instance) in self.facets.iter_mut() {
+            instance.state = if Some(*rid) == self.focused {
+                FacetState::Active
+            } else {
+                FacetState::Background
+            };
+        }
+    }
+
+    /// Topmost region containing `world`. Later regions draw on top.
+    pub fn region_at(&self, world: Vec2) -> Option<u64> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|r| {
+                world.x >= r.position.x
+                    && world.x <= r.position.x + r.size.x
+                    && world.y >= r.position.y
+                    && world.y <= r.position.y + r.size.y
+            })
+            .map(|r| r.id)
+    }
+
+    /// Move a region to the end of the draw order so it renders on top.
+    fn raise(&mut self, id: u64) {
+        if let Some(idx) = self.regions.iter().position(|r| r.id == id) {
+            let region = self.regions.remove(idx);
+            self.regions.push(region);
+        }
+    }
+
+    /// Start dragging whatever region is under the pointer.
+    fn begin_drag(&mut self, screen: Vec2) {
+        let world = self.camera.screen_to_world(screen, self.screen_center);
+        let Some(id) = self.region_at(world) else {
+            self.drag = None;
+            return;
+        };
+        let Some(region) = self.regions.iter().find(|r| r.id == id) else {
+            return;
+        };
+
+        let corner = region.position + region.size;
+        let grab = RESIZE_GRAB_PX / self.camera.zoom;
+        let (mode, grab_offset) = if world.x >= corner.x - grab && world.y >= corner.y - grab {
+            (DragMode::Resize, world - corner)
+        } else {
+            (DragMode::Move, world - region.position)
+        };
+        self.drag = Some(Drag { region: id, mode, grab_offset });
+        self.raise(id);
+    }
+
+    /// Apply the current drag for a pointer at `screen`.
+    fn update_drag(&mut self, screen: Vec2) {
+        let Some(drag) = self.drag else {
+            return;
+        };
+        let world = self.camera.screen_to_world(screen, self.screen_center);
+        let Some(region) = self.regions.iter_mut().find(|r| r.id == drag.region) else {
+            self.drag = None;
+            return;
+        };
+
+        match drag.mode {
+            DragMode::Move => region.position = world - drag.grab_offset,
+            DragMode::Resize => {
+                region.size = (world - drag.grab_offset - region.position).max(MIN_REGION_SIZE);
+            }
+        }
+        if let Some(instance) = self.facets.get_mut(&region.id) {
+            instance.position = region.position;
+            instance.size = region.size;
+        }
+    }
+
+    /// Pipe the facet in region `from` into the facet in region `to`.
+    /// Returns the MIME type the pipe carries, or why they can't connect.
+    pub fn connect(&mut self, from: u64, to: u64) -> Result<String, String> {
+        if from == to {
+            return Err("a facet can't pipe into itself".to_string());
+        }
+        let (Some(source), Some(sink)) = (self.facets.get(&from), self.facets.get(&to)) else {
+            return Err("both ends must be facets".to_string());
+        };
+        let Some(mime) = facet::pipe_type(source.facet.as_ref(), sink.facet.as_ref()) else {
+            return Err(format!(
+                "{} produces {:?}, {} accepts {:?}",
+                source.facet.name(),
+                source.facet.produces(),
+                sink.facet.name(),
+                sink.facet.accepts()
+            ));
+        };
+
+        self.pipes.retain(|p| !(p.from == from && p.to == to));
+        self.pipes.push(Pipe { from, to, mime: mime.clone(), last: None });
+        Ok(mime)
+    }
+
+    /// Pipe between the most recently opened facets with these ids.
+    pub fn connect_facets(&mut self, from: &str, to: &str) -> Result<String, String> {
+        let find = |name: &str| {
+            self.regions
+                .iter()
+                .filter(|r| matches!(&r.content, RegionContent::Facet { name: n } if n == name))
+                .map(|r| r.id)
+                .max()
+                .ok_or_else(|| format!("no open '{}' facet", name))
+        };
+        let (from, to) = (find(from)?, find(to)?);
+        self.connect(from, to)
+    }
+
+    /// Send each pipe's source data to its sink when it has changed.
+    pub fn propagate(&mut self) {
+        self.pipes.retain(|p| self.facets.contains_key(&p.from) && self.facets.contains_key(&p.to));
+        for pipe in &mut self.pipes {
+            let Some(data) = self.facets.get(&pipe.from).and_then(|i| i.facet.provide()) else {
+                continue;
+            };
+            if pipe.last.as_ref() == Some(&data) {
+                continue;
+            }
+            if let Some(sink) = self.facets.get_mut(&pipe.to) {
+                tracing::debug!("Pipe {} -> {} ({})", pipe.from, pipe.to, pipe.mime);
+                sink.facet.receive(data.clone());
+            }
+            pipe.last = Some(data);
+        }
+    }
+
+    /// Finish a pipe drag released at `screen`.
+    fn end_link(&mut self, screen: Vec2) {
+        let Some(from) = self.linking.take() else {
+            return;
+        };
+        let world = self.camera.screen_to_world(screen, self.screen_center);
+        let Some(to) = self.region_at(world) else {
+            return;
+        };
+        match self.connect(from, to) {
+            Ok(mime) => tracing::info!("Piped region {} into {} as {}", from, to, mime),
+            Err(e) => tracing::warn!("Can't pipe region {} into {}: {}", from, to, e),
+        }
+    }
+
+    /// Dotted line between two screen points.
+    fn draw_link(renderer: &mut Renderer, a: Vec2, b: Vec2, color: Color) {
+        let steps = (a.distance(b) / 12.0) as usize;
+        for i in 0..=steps {
+            let p = a.lerp(b, i as f32 / steps.max(1) as f32);
+            renderer.draw_rect(Rect::centered(p, 4.0, 4.0), color, 2.0);
+        }
+    }
+
+    pub fn set_screen_center(&mut self, center: Vec2) {
+        self.screen_center = center;
+    }
+
+    pub fn handle_pointer(&mut self, position: Vec2, button: Option<MouseButton>, pressed: bool) {
         self.pointer_pos = position;
+        match (button, pressed) {
+            (Some(MouseButton::Left), true) => {
+                if let Some(minimap) = self.minimap(self.screen_center * 2.0).filter(|m| m.contains(position)) {
+                    // From the overview, a minimap click goes back; otherwise
+                    // it glides to the clicked spot
+                    if self.in_overview() {
+                        self.exit_overview();
+                    } else {
+                        self.camera.fly_to(minimap.world_at(position), self.camera.zoom);
+                    }
+                    return;
+                }
+                // Clicking a region focuses and grabs it; empty space deselects
+                let world = self.camera.screen_to_world(position, self.screen_center);
+                let hit = self.region_at(world);
+                self.set_focus(hit);
+                self.begin_drag(position);
+            }
+            (Some(MouseButton::Left), false) => self.drag = None,
+            (Some(MouseButton::Right), true) => {
+                // Right-drag from one facet to another pipes them together
+                let world = self.camera.screen_to_world(position, self.screen_center);
+                self.linking = self.region_at(world).filter(|id| self.facets.contains_key(id));
+            }
+            (Some(MouseButton::Right), false) => self.end_link(position),
+            (None, _) => self.update_drag(position),
+            _ => {}
+        }
+    }
+
+    /// Whether a facet currently has keyboard focus.
+    pub fn has_focus(&self) -> bool {
+        self.focused.is_some()
+    }
+
+    pub fn clear_focus(&mut self) {
+        self.set_focus(None);
+    }
+
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers, pressed: bool) {
+        if let Some(instance) = self.focused.and_then(|id| self.facets.get_mut(&id)) {
+            instance.facet.on_key(key, modifiers, pressed);
+        }
+    }
+
+    /// Save the focused facet's document (see `Facet::save`).
+    pub fn save_focused(&mut self, path: Option<&std::path::Path>) -> SaveResult {
+        match self.focused.and_then(|id| self.facets.get_mut(&id)) {
+            Some(instance) => instance.facet.save(path),
+            None => SaveResult::Unsupported,
+        }
+    }
+
+    pub fn handle_text(&mut self, c: char) {
+        if let Some(instance) = self.focused.and_then(|id| self.facets.get_mut(&id)) {
+            let mut buf = [0u8; 4];
+            instance.facet.on_text(c.encode_utf8(&mut buf));
+        }
     }
 
     pub fn handle_scroll(&mut self, delta: Vec2) {
         // Vertical scroll = zoom, horizontal scroll = pan
         if delta.y.abs() > delta.x.abs() {
-            self.camera.zoom_by(delta.y * 0.01);
+            // Zoom around the pointer, as maps do
+            self.camera.zoom_by(delta.y * 0.01, self.pointer_pos - self.screen_center);
         } else {
             self.camera.pan(Vec2::new(delta.x, 0.0));
         }
@@ -144,8 +530,115 @@ impl Canvas {
         self.camera.pan(delta);
     }
 
+    /// Smallest world-space box holding every region, as (min, max).
+    fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        let mut regions = self.regions.iter();
+        let first = regions.next()?;
+        Some(regions.fold((first.position, first.position + first.size), |(min, max), r| {
+            (min.min(r.position), max.max(r.position + r.size))
+        }))
+    }
+
+    /// Whether the overview is shown (or on its way).
+    pub fn in_overview(&self) -> bool {
+        self.overview.is_some()
+    }
+
+    /// Zoom out to frame every region, or go back to where the camera was.
+    pub fn toggle_overview(&mut self) {
+        if self.in_overview() {
+            self.exit_overview();
+            return;
+        }
+        self.overview = Some((self.camera.position, self.camera.zoom));
+
+        let screen = self.screen_center * 2.0;
+        let (position, zoom) = match self.bounds() {
+            Some((min, max)) => {
+                let size = (max - min).max(Vec2::ONE);
+                // Never zoom in past 1x just to fill the screen
+                let zoom = if screen.x > 0.0 && screen.y > 0.0 {
+                    (screen * OVERVIEW_FILL / size).min_element().min(1.0)
+                } else {
+                    1.0
+                };
+                ((min + max) / 2.0, zoom)
+            }
+            None => (Vec2::ZERO, 1.0),
+        };
+        self.camera.fly_to(position, zoom);
+    }
+
+    /// Return the camera to where it was before the overview.
+    pub fn exit_overview(&mut self) {
+        if let Some((position, zoom)) = self.overview.take() {
+            self.camera.fly_to(position, zoom);
+        }
+    }
+
+    /// Where the minimap sits for a screen of `screen` pixels. It covers the
+    /// regions and the current viewport, so the viewport is always on it.
+    fn minimap(&self, screen: Vec2) -> Option<Minimap> {
+        let (min, max) = self.bounds()?;
+        let view_min = self.camera.screen_to_world(Vec2::ZERO, screen / 2.0);
+        let view_max = self.camera.screen_to_world(screen, screen / 2.0);
+        let (min, max) = (min.min(view_min), max.max(view_max));
+
+        let rect = Rect::new(
+            screen.x - MINIMAP_SIZE.x - MINIMAP_MARGIN,
+            screen.y - MINIMAP_SIZE.y - MINIMAP_MARGIN,
+            MINIMAP_SIZE.x,
+            MINIMAP_SIZE.y,
+        );
+        let inner = MINIMAP_SIZE - Vec2::splat(16.0);
+        let extent = (max - min).max(Vec2::ONE);
+        let scale = (inner / extent).min_element();
+        // Center the content within the minimap
+        let offset = rect.center() - extent * scale / 2.0;
+        Some(Minimap { rect, origin: min, offset, scale })
+    }
+
+    /// Whether the camera is still gliding or zooming.
+    pub fn is_animating(&self) -> bool {
+        self.camera.is_moving()
+    }
+
     pub fn update(&mut self, dt: f32) {
         self.camera.update(dt);
+        for instance in self.facets.values_mut() {
+            if instance.state == FacetState::Active {
+                instance.facet.update(dt);
+            }
+        }
+    }
+
+    /// Region outlines and the viewport frame, in the bottom-right corner.
+    fn render_minimap(&self, renderer: &mut Renderer, minimap: &Minimap, screen: Vec2) {
+        renderer.draw_rect(
+            minimap.rect,
+            Color::rgba(Color::SURFACE.r, Color::SURFACE.g, Color::SURFACE.b, 0.85),
+            6.0,
+        );
+
+        for region in &self.regions {
+            let pos = minimap.screen_at(region.position);
+            let size = (region.size * minimap.scale).max(Vec2::splat(2.0));
+            let color = if self.focused == Some(region.id) { Color::accent() } else { Color::TEXT_DIM };
+            renderer.draw_rect(Rect::new(pos.x, pos.y, size.x, size.y), color, 1.0);
+        }
+
+        // Viewport frame
+        let lo = minimap.screen_at(self.camera.screen_to_world(Vec2::ZERO, screen / 2.0));
+        let hi = minimap.screen_at(self.camera.screen_to_world(screen, screen / 2.0));
+        let (w, h) = (hi.x - lo.x, hi.y - lo.y);
+        for edge in [
+            Rect::new(lo.x, lo.y, w, 1.0),
+            Rect::new(lo.x, hi.y - 1.0, w, 1.0),
+            Rect::new(lo.x, lo.y, 1.0, h),
+            Rect::new(hi.x - 1.0, lo.y, 1.0, h),
+        ] {
+            renderer.draw_rect(edge, Color::accent(), 0.0);
+        }
     }
 
     pub fn render(&self, renderer: &mut Renderer) {
@@ -167,6 +660,15 @@ impl Canvas {
             
             let rect = Rect::new(screen_pos.x, screen_pos.y, screen_size.x, screen_size.y);
             
+            // Focus ring
+            if self.focused == Some(region.id) {
+                renderer.draw_rect(
+                    Rect::new(rect.x - 2.0, rect.y - 2.0, rect.width + 4.0, rect.height + 4.0),
+                    Color::rgba(Color::accent().r, Color::accent().g, Color::accent().b, 0.6),
+                    10.0 * self.camera.zoom,
+                );
+            }
+
             // Region background
             renderer.draw_rect(
                 rect,
@@ -195,17 +697,53 @@ impl Canvas {
                     }
                 }
                 RegionContent::Facet { name } => {
-                    // Facets would render their own content
-                    renderer.draw_text(
-                        &format!("[Facet: {}]", name),
-                        Vec2::new(screen_pos.x + 16.0, screen_pos.y + 16.0),
-                        14.0 * self.camera.zoom,
-                        Color::TEXT_DIM,
-                    );
+                    if let Some(instance) = self.facets.get(&region.id) {
+                        instance.facet.render(renderer, screen_pos, screen_size);
+                    } else {
+                        renderer.draw_text(
+                            &format!("[Facet: {}]", name),
+                            Vec2::new(screen_pos.x + 16.0, screen_pos.y + 16.0),
+                            14.0 * self.camera.zoom,
+                            Color::TEXT_DIM,
+                        );
+                    }
                 }
             }
+
+            // Resize grip in the bottom-right corner
+            renderer.draw_rect(
+                Rect::new(
+                    rect.x + rect.width - RESIZE_GRAB_PX,
+                    rect.y + rect.height - RESIZE_GRAB_PX,
+                    RESIZE_GRAB_PX - 4.0,
+                    RESIZE_GRAB_PX - 4.0,
+                ),
+                Color::rgba(Color::TEXT_DIM.r, Color::TEXT_DIM.g, Color::TEXT_DIM.b, 0.4),
+                2.0,
+            );
         }
         
+        // Pipes, drawn over the regions they join
+        let link_color = Color::rgba(Color::accent().r, Color::accent().g, Color::accent().b, 0.7);
+        let center_of = |id: u64| {
+            self.regions.iter().find(|r| r.id == id).map(|r| {
+                self.camera.world_to_screen(r.position + r.size / 2.0, screen_center)
+            })
+        };
+        for pipe in &self.pipes {
+            if let (Some(a), Some(b)) = (center_of(pipe.from), center_of(pipe.to)) {
+                Self::draw_link(renderer, a, b, link_color);
+            }
+        }
+        if let Some(a) = self.linking.and_then(center_of) {
+            Self::draw_link(renderer, a, self.pointer_pos, link_color);
+        }
+
+        let screen = Vec2::new(renderer.width() as f32, renderer.height() as f32);
+        if let Some(minimap) = self.minimap(screen) {
+            self.render_minimap(renderer, &minimap, screen);
+        }
+
         // Debug: show zoom level
         renderer.draw_text(
             &format!("Zoom: {:.1}x", self.camera.zoom),
@@ -0,0 +1,27 @@
+//! Clipboard
+//!
+//! The system clipboard through `aether_common::clipboard` (wl-copy/wl-paste or
+//! xclip), shared with the TUI. Copies are also kept in-process, so copy and
+//! paste between facets still works on a bare framebuffer with neither tool
+//! installed.
+
+use std::sync::Mutex;
+
+static LOCAL: Mutex<String> = Mutex::new(String::new());
+
+/// Copy `text` to the system clipboard, if there is one, and the local one.
+pub fn copy(text: &str) {
+    if let Ok(mut local) = LOCAL.lock() {
+        *local = text.to_string();
+    }
+    if aether_common::clipboard::copy(text).is_none() {
+        tracing::debug!("No system clipboard; copy kept in Nebula only");
+    }
+}
+
+/// The system clipboard's text, falling back to the last local copy.
+pub fn paste() -> Option<String> {
+    aether_common::clipboard::paste()
+        .or_else(|| LOCAL.lock().ok().map(|local| local.clone()))
+        .filter(|text| !text.is_empty())
+}
@@ -1,12 +1,20 @@
 //! Facets
 //!
 //! Facets are capabilities, not applications. They compose to accomplish tasks.
-//! Each facet is a sandboxed WASM module with a defined interface.
+//! Today every facet is a native struct registered with `FacetRegistry`;
+//! sandboxed WASM facets (see "Facet Protocol" in DESIGN.md) need a WASM
+//! runtime this crate doesn't depend on yet.
 
+use aether_common::highlight::{Highlighter, Token};
 use glam::Vec2;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
+use crate::clipboard;
+use crate::input::Modifiers;
+use crate::notify::{self, Notification};
 use crate::render::Renderer;
+use crate::settings::Settings;
 
 /// Capabilities a facet can declare
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -47,7 +55,7 @@ pub enum FacetState {
 }
 
 /// Data that can flow between facets
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum FacetData {
     Text(String),
     Binary(Vec<u8>),
@@ -55,6 +63,17 @@ pub enum FacetData {
     Reference { uri: String },
 }
 
+/// Outcome of `Facet::save`
+#[derive(Clone, Debug)]
+pub enum SaveResult {
+    Saved(PathBuf),
+    /// Nothing to save to yet; ask the user for a path.
+    NeedsPath,
+    Failed(String),
+    /// This facet has no document to save.
+    Unsupported,
+}
+
 /// Facet trait - what every facet must implement
 pub trait Facet: Send + Sync {
     /// Unique identifier
@@ -85,7 +104,7 @@ pub trait Facet: Send + Sync {
     fn on_text(&mut self, text: &str);
     
     /// Handle key press
-    fn on_key(&mut self, key: crate::input::Key, pressed: bool);
+    fn on_key(&mut self, key: crate::input::Key, modifiers: Modifiers, pressed: bool);
     
     /// Receive data from another facet
     fn receive(&mut self, data: FacetData);
@@ -95,6 +114,21 @@ pub trait Facet: Send + Sync {
     
     /// Suggest next action (for Omni-bar integration)
     fn suggest(&self) -> Option<String>;
+    
+    /// Save to `path`, or to where the document came from when `None`
+    fn save(&mut self, _path: Option<&Path>) -> SaveResult {
+        SaveResult::Unsupported
+    }
+}
+
+/// The MIME type a pipe from `from` to `to` would carry: the first type
+/// `from` produces that `to` accepts, or `None` if they don't fit.
+pub fn pipe_type(from: &dyn Facet, to: &dyn Facet) -> Option<String> {
+    let accepts = to.accepts();
+    from.produces()
+        .into_iter()
+        .find(|mime| accepts.contains(mime))
+        .map(str::to_string)
 }
 
 /// Facet instance wrapper
@@ -121,6 +155,7 @@ impl FacetRegistry {
         registry.register("terminal", || Box::new(TerminalFacet::new()));
         registry.register("editor", || Box::new(EditorFacet::new()));
         registry.register("files", || Box::new(FilesFacet::new()));
+        registry.register("settings", || Box::new(SettingsFacet::new()));
         
         registry
     }
@@ -231,7 +266,7 @@ impl Facet for TerminalFacet {
         self.current_line.push_str(text);
     }
     
-    fn on_key(&mut self, key: crate::input::Key, pressed: bool) {
+    fn on_key(&mut self, key: crate::input::Key, _modifiers: Modifiers, pressed: bool) {
         if !pressed { return; }
         
         match key {
@@ -278,8 +313,13 @@ impl Facet for TerminalFacet {
 /// Editor facet - text editing
 pub struct EditorFacet {
     content: String,
+    /// Byte index into `content`, always on a char boundary.
     cursor: usize,
+    /// Other end of the selection, which runs between it and `cursor`.
+    anchor: Option<usize>,
     filename: Option<String>,
+    /// Edited since the last load or save.
+    dirty: bool,
 }
 
 impl EditorFacet {
@@ -287,8 +327,105 @@ impl EditorFacet {
         Self {
             content: String::new(),
             cursor: 0,
+            anchor: None,
             filename: None,
+            dirty: false,
+        }
+    }
+
+    /// Load the file at `uri` (a path or file:// URI), if it's readable.
+    /// Refuses, with a notification, while there are unsaved changes.
+    fn open(&mut self, uri: &str) {
+        let path = uri.strip_prefix("file://").unwrap_or(uri);
+        if self.dirty {
+            let name = self.filename.as_deref().unwrap_or("this document");
+            notify::publish(
+                Notification::new(format!("Not opening {}", path))
+                    .with_line(format!("{} has unsaved changes; save it first (Ctrl+S)", name)),
+            );
+            return;
+        }
+        match std::fs::read_to_string(path) {
+            Ok(text) => {
+                self.content = text;
+                self.cursor = self.content.len();
+                self.anchor = None;
+                self.filename = Some(path.to_string());
+                self.dirty = false;
+            }
+            Err(e) => tracing::warn!("Can't open {}: {}", path, e),
+        }
+    }
+
+    fn set_content(&mut self, text: String) {
+        self.content = text;
+        self.cursor = self.content.len();
+        self.anchor = None;
+        self.dirty = true;
+    }
+
+    /// Selected byte range, start first; `None` when nothing is selected.
+    fn selection(&self) -> Option<(usize, usize)> {
+        let anchor = self.anchor.filter(|&a| a != self.cursor)?;
+        Some((anchor.min(self.cursor), anchor.max(self.cursor)))
+    }
+
+    fn selected_text(&self) -> Option<&str> {
+        self.selection().map(|(start, end)| &self.content[start..end])
+    }
+
+    /// Remove the selected text. Returns whether there was any.
+    fn delete_selection(&mut self) -> bool {
+        let Some((start, end)) = self.selection() else {
+            self.anchor = None;
+            return false;
+        };
+        self.content.replace_range(start..end, "");
+        self.cursor = start;
+        self.anchor = None;
+        self.dirty = true;
+        true
+    }
+
+    /// Type `text` at the cursor, replacing any selection.
+    fn insert(&mut self, text: &str) {
+        self.delete_selection();
+        self.content.insert_str(self.cursor, text);
+        self.cursor += text.len();
+        self.dirty = true;
+    }
+
+    /// Move the cursor to `pos`; with `extend` the selection grows to follow it.
+    fn move_to(&mut self, pos: usize, extend: bool) {
+        if extend {
+            self.anchor.get_or_insert(self.cursor);
+        } else {
+            self.anchor = None;
+        }
+        self.cursor = pos;
+    }
+
+    /// Delete the selection, or else the text between the cursor and `pos`.
+    fn delete_toward(&mut self, pos: usize) {
+        if self.delete_selection() || pos == self.cursor {
+            return;
         }
+        let (start, end) = (pos.min(self.cursor), pos.max(self.cursor));
+        self.content.replace_range(start..end, "");
+        self.cursor = start;
+        self.dirty = true;
+    }
+
+    fn prev_boundary(&self) -> usize {
+        self.content[..self.cursor].char_indices().next_back().map(|(i, _)| i).unwrap_or(0)
+    }
+
+    fn next_boundary(&self) -> usize {
+        self.content[self.cursor..]
+            .chars()
+            .next()
+            .map(|c| self.cursor + c.len_utf8())
+            .unwrap_or(self.content.len())
     }
 }
 
@@ -297,16 +434,18 @@ impl Facet for EditorFacet {
     fn name(&self) -> &str { "Editor" }
     
     fn capabilities(&self) -> Vec<Capability> {
-        vec![Capability::ReadText, Capability::WriteText, Capability::FileAccess]
+        vec![Capability::ReadText, Capability::WriteText, Capability::FileAccess, Capability::Clipboard]
     }
     
-    fn accepts(&self) -> Vec<&str> { vec!["text/plain", "text/markdown"] }
+    fn accepts(&self) -> Vec<&str> { vec!["text/plain", "text/markdown", "text/uri-list"] }
     fn produces(&self) -> Vec<&str> { vec!["text/plain", "text/markdown"] }
     
     fn init(&mut self, data: Option<FacetData>) {
-        if let Some(FacetData::Text(text)) = data {
-            self.content = text;
-            self.cursor = self.content.len();
+        match data {
+            Some(FacetData::Reference { uri }) => self.open(&uri),
+            Some(FacetData::Text(path)) if Path::new(&path).is_file() => self.open(&path),
+            Some(FacetData::Text(text)) => self.set_content(text),
+            _ => {}
         }
     }
     
@@ -322,10 +461,11 @@ impl Facet for EditorFacet {
             8.0,
         );
         
-        // Title bar
+        // Title bar, with a dot while there are unsaved changes
         let title = self.filename.as_deref().unwrap_or("Untitled");
+        let title = if self.dirty { format!("{} \u{2022}", title) } else { title.to_string() };
         renderer.draw_text(
-            title,
+            &title,
             Vec2::new(position.x + 12.0, position.y + 8.0),
             12.0,
             Color::TEXT_DIM,
@@ -334,50 +474,106 @@ impl Facet for EditorFacet {
         // Content
         let content_y = position.y + 32.0;
         let line_height = 20.0;
+        let selection = self.selection();
+        let mut highlighter = self
+            .filename
+            .as_deref()
+            .and_then(|f| Path::new(f).extension()?.to_str())
+            .and_then(Highlighter::for_name);
+        let mut line_start = 0;
         
-        for (i, line) in self.content.lines().enumerate() {
-            renderer.draw_text(
-                line,
-                Vec2::new(position.x + 12.0, content_y + (i as f32 * line_height)),
-                14.0,
-                Color::TEXT,
-            );
+        for (i, line) in self.content.split('\n').enumerate() {
+            let line_end = line_start + line.len();
+            let y = content_y + (i as f32 * line_height);
+            
+            // Selection highlight behind the glyphs; a selected line break
+            // shows as a sliver past the end of the line
+            if let Some((start, end)) = selection.filter(|&(s, e)| s <= line_end && e > line_start) {
+                let from = start.max(line_start) - line_start;
+                let to = end.min(line_end) - line_start;
+                let x0 = renderer.measure_text(&line[..from], 14.0);
+                let mut x1 = renderer.measure_text(&line[..to], 14.0);
+                if end > line_end {
+                    x1 += 6.0;
+                }
+                renderer.draw_rect(
+                    Rect::new(position.x + 12.0 + x0, y - 2.0, x1 - x0, line_height),
+                    Color::accent().with_alpha(0.35),
+                    2.0,
+                );
+            }
+            line_start = line_end + 1;
+            
+            // Plain text between the highlighted tokens
+            let mut at = 0;
+            let tokens = highlighter.as_mut().map(|h| h.line(line)).unwrap_or_default();
+            for (range, color) in tokens
+                .into_iter()
+                .map(|(range, token)| (range, syntax_color(token)))
+                .chain([(line.len()..line.len(), Color::TEXT)])
+            {
+                for (from, to, color) in [(at, range.start, Color::TEXT), (range.start, range.end, color)] {
+                    if from < to {
+                        let x = position.x + 12.0 + renderer.measure_text(&line[..from], 14.0);
+                        renderer.draw_text(&line[from..to], Vec2::new(x, y), 14.0, color);
+                    }
+                }
+                at = range.end;
+            }
         }
     }
     
     fn on_text(&mut self, text: &str) {
-        self.content.insert_str(self.cursor, text);
-        self.cursor += text.len();
+        self.insert(text);
     }
     
-    fn on_key(&mut self, key: crate::input::Key, pressed: bool) {
+    fn on_key(&mut self, key: crate::input::Key, modifiers: Modifiers, pressed: bool) {
+        use crate::input::Key;
         if !pressed { return; }
         
-        match key {
-            crate::input::Key::Backspace => {
-                if self.cursor > 0 {
-                    self.cursor -= 1;
-                    self.content.remove(self.cursor);
+        if modifiers.control {
+            match key {
+                Key::Char('a') => {
+                    self.anchor = Some(0);
+                    self.cursor = self.content.len();
                 }
+                Key::Char('c') => {
+                    if let Some(text) = self.selected_text() {
+                        clipboard::copy(text);
+                    }
+                }
+                Key::Char('x') => {
+                    if let Some(text) = self.selected_text() {
+                        clipboard::copy(text);
+                        self.delete_selection();
+                    }
+                }
+                Key::Char('v') => {
+                    if let Some(text) = clipboard::paste() {
+                        self.insert(&text);
+                    }
+                }
+                _ => {}
             }
-            crate::input::Key::Enter => {
-                self.content.insert(self.cursor, '\n');
-                self.cursor += 1;
-            }
-            crate::input::Key::Left => {
-                self.cursor = self.cursor.saturating_sub(1);
-            }
-            crate::input::Key::Right => {
-                self.cursor = (self.cursor + 1).min(self.content.len());
-            }
+            return;
+        }
+        
+        match key {
+            Key::Backspace => self.delete_toward(self.prev_boundary()),
+            Key::Delete => self.delete_toward(self.next_boundary()),
+            Key::Enter => self.insert("\n"),
+            Key::Left => self.move_to(self.prev_boundary(), modifiers.shift),
+            Key::Right => self.move_to(self.next_boundary(), modifiers.shift),
             _ => {}
         }
     }
     
     fn receive(&mut self, data: FacetData) {
-        if let FacetData::Text(text) = data {
-            self.content = text;
-            self.cursor = self.content.len();
+        match data {
+            FacetData::Text(text) => self.set_content(text),
+            // A path, e.g. piped from Files
+            FacetData::Reference { uri } => self.open(&uri),
+            _ => {}
         }
     }
     
@@ -386,28 +582,121 @@ impl Facet for EditorFacet {
     }
     
     fn suggest(&self) -> Option<String> {
-        Some("Save document".to_string())
+        self.dirty.then(|| "Save document (Ctrl+S)".to_string())
+    }
+    
+    fn save(&mut self, path: Option<&Path>) -> SaveResult {
+        let Some(path) = path.map(Path::to_path_buf).or_else(|| self.filename.as_ref().map(PathBuf::from)) else {
+            return SaveResult::NeedsPath;
+        };
+        match std::fs::write(&path, &self.content) {
+            Ok(()) => {
+                self.filename = Some(path.to_string_lossy().into_owned());
+                self.dirty = false;
+                SaveResult::Saved(path)
+            }
+            Err(e) => SaveResult::Failed(format!("{}: {}", path.display(), e)),
+        }
+    }
+}
+
+/// How the editor colours a highlighted token.
+fn syntax_color(token: Token) -> crate::render::Color {
+    use crate::render::Color;
+    match token {
+        Token::Keyword => Color::accent(),
+        Token::String => Color::rgb(0.55, 0.85, 0.55),
+        Token::Comment => Color::TEXT_DIM,
+        Token::Number => Color::rgb(0.95, 0.75, 0.4),
     }
 }
 
 /// Files facet - file browser
 pub struct FilesFacet {
-    current_path: String,
+    current_path: PathBuf,
+    /// Directory names end in '/'; ".." leads up unless at the root.
     entries: Vec<String>,
     selected: usize,
+    /// Last file opened with Enter, offered to pipes.
+    opened: Option<PathBuf>,
+    /// Shown under the listing: read errors, empty directories.
+    status: Option<String>,
 }
 
 impl FilesFacet {
     pub fn new() -> Self {
-        Self {
-            current_path: "/".to_string(),
-            entries: vec![
-                "..".to_string(),
-                "home/".to_string(),
-                "etc/".to_string(),
-                "tmp/".to_string(),
-            ],
+        let mut facet = Self {
+            current_path: PathBuf::from("/"),
+            entries: Vec::new(),
             selected: 0,
+            opened: None,
+            status: None,
+        };
+        facet.load();
+        facet
+    }
+
+    /// Switch to `path` and list it.
+    fn navigate(&mut self, path: PathBuf) {
+        self.current_path = path;
+        self.selected = 0;
+        self.load();
+    }
+
+    /// Re-read `current_path`: directories first, then files, by name.
+    fn load(&mut self) {
+        self.entries.clear();
+        self.status = None;
+        if self.current_path.parent().is_some() {
+            self.entries.push("..".to_string());
+        }
+
+        match std::fs::read_dir(&self.current_path) {
+            Ok(dir) => {
+                let mut listed: Vec<(bool, String)> = dir
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| {
+                        let is_dir = entry.path().is_dir();
+                        (is_dir, entry.file_name().to_string_lossy().into_owned())
+                    })
+                    .collect();
+                listed.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.to_lowercase().cmp(&b.1.to_lowercase())));
+                if listed.is_empty() {
+                    self.status = Some("Empty directory".to_string());
+                }
+                self.entries.extend(listed.into_iter().map(|(is_dir, name)| {
+                    if is_dir { format!("{}/", name) } else { name }
+                }));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                self.status = Some("Permission denied".to_string());
+            }
+            Err(e) => self.status = Some(format!("Can't read directory: {}", e)),
+        }
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Enter the selected directory, go up for "..", or open a file.
+    fn activate(&mut self) {
+        let Some(entry) = self.entries.get(self.selected).cloned() else {
+            return;
+        };
+        if entry == ".." {
+            let Some(parent) = self.current_path.parent().map(PathBuf::from) else {
+                return;
+            };
+            // Land on the directory we just left
+            let left = self.current_path.file_name().map(|n| format!("{}/", n.to_string_lossy()));
+            self.navigate(parent);
+            if let Some(idx) = left.and_then(|left| self.entries.iter().position(|e| *e == left)) {
+                self.selected = idx;
+            }
+        } else if let Some(dir) = entry.strip_suffix('/') {
+            self.navigate(self.current_path.join(dir));
+        } else {
+            let path = self.current_path.join(&entry);
+            self.status = Some(format!("Opened {}", entry));
+            self.opened = Some(path);
         }
     }
 }
@@ -425,8 +714,7 @@ impl Facet for FilesFacet {
     
     fn init(&mut self, data: Option<FacetData>) {
         if let Some(FacetData::Text(path)) = data {
-            self.current_path = path;
-            // Would reload entries here
+            self.navigate(PathBuf::from(path));
         }
     }
     
@@ -444,30 +732,32 @@ impl Facet for FilesFacet {
         
         // Path bar
         renderer.draw_text(
-            &self.current_path,
+            &self.current_path.to_string_lossy(),
             Vec2::new(position.x + 12.0, position.y + 8.0),
             12.0,
             Color::TEXT_DIM,
         );
         
-        // Entries
+        // Entries, scrolled to keep the selection visible above the status line
         let entry_height = 28.0;
         let content_y = position.y + 32.0;
+        let rows = (((size.y - 32.0 - 24.0) / entry_height) as usize).max(1);
+        let first = (self.selected + 1).saturating_sub(rows);
         
-        for (i, entry) in self.entries.iter().enumerate() {
-            let y = content_y + (i as f32 * entry_height);
+        for (i, entry) in self.entries.iter().enumerate().skip(first).take(rows) {
+            let y = content_y + ((i - first) as f32 * entry_height);
             
             // Selection highlight
             if i == self.selected {
                 renderer.draw_rect(
                     Rect::new(position.x + 4.0, y, size.x - 8.0, entry_height - 2.0),
-                    Color::ACCENT.with_alpha(0.2),
+                    Color::accent().with_alpha(0.2),
                     4.0,
                 );
             }
             
             // Entry name
-            let icon = if entry.ends_with('/') { "📁" } else { "📄" };
+            let icon = if entry.ends_with('/') || entry == ".." { "📁" } else { "📄" };
             renderer.draw_text(
                 &format!("{} {}", icon, entry),
                 Vec2::new(position.x + 12.0, y + 6.0),
@@ -475,11 +765,20 @@ impl Facet for FilesFacet {
                 Color::TEXT,
             );
         }
+
+        if let Some(status) = &self.status {
+            renderer.draw_text(
+                status,
+                Vec2::new(position.x + 12.0, position.y + size.y - 20.0),
+                12.0,
+                Color::TEXT_DIM,
+            );
+        }
     }
     
     fn on_text(&mut self, _text: &str) {}
     
-    fn on_key(&mut self, key: crate::input::Key, pressed: bool) {
+    fn on_key(&mut self, key: crate::input::Key, _modifiers: Modifiers, pressed: bool) {
         if !pressed { return; }
         
         match key {
@@ -489,9 +788,7 @@ impl Facet for FilesFacet {
             crate::input::Key::Down => {
                 self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
             }
-            crate::input::Key::Enter => {
-                // Would navigate into directory or open file
-            }
+            crate::input::Key::Enter => self.activate(),
             _ => {}
         }
     }
@@ -499,8 +796,8 @@ impl Facet for FilesFacet {
     fn receive(&mut self, _data: FacetData) {}
     
     fn provide(&self) -> Option<FacetData> {
-        self.entries.get(self.selected).map(|e| {
-            FacetData::Text(format!("{}{}", self.current_path, e))
+        self.opened.as_ref().map(|path| FacetData::Reference {
+            uri: path.to_string_lossy().into_owned(),
         })
     }
     
@@ -509,6 +806,139 @@ impl Facet for FilesFacet {
     }
 }
 
+/// Settings facet - edit and persist preferences
+pub struct SettingsFacet {
+    settings: Settings,
+    selected: usize,
+}
+
+impl SettingsFacet {
+    pub fn new() -> Self {
+        Self {
+            settings: Settings::load(),
+            selected: 0,
+        }
+    }
+
+    /// Take any recognised fields from a JSON object.
+    fn apply_json(&mut self, value: serde_json::Value) {
+        let mut current = serde_json::to_value(&self.settings).unwrap_or_default();
+        if let (Some(current), serde_json::Value::Object(updates)) = (current.as_object_mut(), value) {
+            current.extend(updates);
+        }
+        if let Ok(settings) = serde_json::from_value(current) {
+            self.settings = settings;
+            self.commit();
+        }
+    }
+
+    /// Save the edited settings and put them into effect.
+    fn commit(&self) {
+        self.settings.save();
+        self.settings.apply();
+    }
+}
+
+impl Facet for SettingsFacet {
+    fn id(&self) -> &str { "settings" }
+    fn name(&self) -> &str { "Settings" }
+    
+    fn capabilities(&self) -> Vec<Capability> {
+        vec![Capability::FileAccess]
+    }
+    
+    fn accepts(&self) -> Vec<&str> { vec!["application/json"] }
+    fn produces(&self) -> Vec<&str> { vec!["application/json"] }
+    
+    fn init(&mut self, data: Option<FacetData>) {
+        if let Some(FacetData::Json(value)) = data {
+            self.apply_json(value);
+        }
+    }
+    
+    fn update(&mut self, _dt: f32) {}
+    
+    fn render(&self, renderer: &mut Renderer, position: Vec2, size: Vec2) {
+        use crate::render::{Color, Rect};
+        
+        // Background
+        renderer.draw_rect(
+            Rect::new(position.x, position.y, size.x, size.y),
+            Color::SURFACE,
+            8.0,
+        );
+        
+        renderer.draw_text(
+            "Settings",
+            Vec2::new(position.x + 12.0, position.y + 8.0),
+            12.0,
+            Color::TEXT_DIM,
+        );
+        
+        // Rows: label on the left, value on the right
+        let row_height = 32.0;
+        let content_y = position.y + 32.0;
+        
+        for (i, (label, value)) in self.settings.rows().iter().enumerate() {
+            let y = content_y + (i as f32 * row_height);
+            
+            if i == self.selected {
+                renderer.draw_rect(
+                    Rect::new(position.x + 4.0, y, size.x - 8.0, row_height - 2.0),
+                    Color::accent().with_alpha(0.2),
+                    4.0,
+                );
+            }
+            
+            renderer.draw_text(label, Vec2::new(position.x + 12.0, y + 8.0), 14.0, Color::TEXT);
+            
+            let value = format!("< {} >", value);
+            let value_x = position.x + size.x - 12.0 - renderer.measure_text(&value, 14.0);
+            let color = if i == self.selected { Color::accent() } else { Color::TEXT_DIM };
+            renderer.draw_text(&value, Vec2::new(value_x, y + 8.0), 14.0, color);
+        }
+    }
+    
+    fn on_text(&mut self, _text: &str) {}
+    
+    fn on_key(&mut self, key: crate::input::Key, _modifiers: Modifiers, pressed: bool) {
+        if !pressed { return; }
+        
+        let rows = self.settings.rows().len();
+        match key {
+            crate::input::Key::Up => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            crate::input::Key::Down => {
+                self.selected = (self.selected + 1).min(rows - 1);
+            }
+            crate::input::Key::Left => {
+                self.settings.adjust(self.selected, -1);
+                self.commit();
+            }
+            crate::input::Key::Right | crate::input::Key::Enter => {
+                self.settings.adjust(self.selected, 1);
+                self.commit();
+            }
+            _ => {}
+        }
+    }
+    
+    fn receive(&mut self, data: FacetData) {
+        if let FacetData::Json(value) = data {
+            self.apply_json(value);
+        }
+    }
+    
+    fn provide(&self) -> Option<FacetData> {
+        serde_json::to_value(&self.settings).ok().map(FacetData::Json)
+    }
+    
+    fn suggest(&self) -> Option<String> {
+        Some("Adjust with \u{2190}/\u{2192}".to_string())
+    }
+}
+
 // Helper trait for Color
 trait ColorExt {
     fn with_alpha(self, a: f32) -> Self;
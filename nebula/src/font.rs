@@ -0,0 +1,176 @@
+//! Font Loading and Glyph Atlas
+//!
+//! Rasterizes a TTF with fontdue and packs glyph coverage into a single
+//! atlas so each (char, size) pair is only rasterized once. The renderer
+//! mirrors the atlas in a GPU texture, uploading the regions that changed,
+//! and draws glyphs as textured quads sampled from it.
+
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// Inter, compiled in so text never depends on what's installed.
+static BUNDLED_TTF: &[u8] = include_bytes!("../assets/fonts/Inter-Regular.ttf");
+
+/// Fonts tried before the bundled one; `NEBULA_FONT` comes first.
+const FONT_PATHS: &[&str] = &["/usr/share/aether/fonts/nebula.ttf"];
+
+/// Width and height of the square atlas, in texels.
+pub const ATLAS_SIZE: usize = 1024;
+
+/// The pixel size glyphs are rasterized and measured at, so advances match
+/// the bitmaps drawn.
+fn pixel_size(size: f32) -> f32 {
+    size.round().max(1.0)
+}
+
+/// Where a glyph lives in the atlas and how to place it relative to the pen.
+#[derive(Clone, Copy, Debug)]
+pub struct GlyphEntry {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    /// Offset of the bitmap's left edge from the pen position.
+    pub xmin: i32,
+    /// Offset of the bitmap's top edge above the baseline.
+    pub top: i32,
+}
+
+pub struct GlyphAtlas {
+    font: fontdue::Font,
+    /// One coverage byte per texel.
+    pixels: Vec<u8>,
+    entries: HashMap<(char, u32), GlyphEntry>,
+    // Shelf packer state
+    cursor_x: usize,
+    cursor_y: usize,
+    row_height: usize,
+    /// Texels written since the last `take_dirty`, as (x0, y0, x1, y1).
+    dirty: Option<(usize, usize, usize, usize)>,
+    /// Times the atlas filled up and was cleared.
+    resets: u64,
+}
+
+impl GlyphAtlas {
+    /// Load `NEBULA_FONT`, an installed override or the bundled font. `None`
+    /// (falling back to the bitmap font) only if none of them parse.
+    pub fn load() -> Option<Self> {
+        let env_path = std::env::var("NEBULA_FONT").ok();
+        let candidates = env_path.iter().map(String::as_str).chain(FONT_PATHS.iter().copied());
+
+        for path in candidates {
+            let Ok(bytes) = std::fs::read(path) else {
+                continue;
+            };
+            match fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()) {
+                Ok(font) => {
+                    info!("Loaded font {}", path);
+                    return Some(Self::new(font));
+                }
+                Err(e) => warn!("Failed to parse font {}: {}", path, e),
+            }
+        }
+
+        match fontdue::Font::from_bytes(BUNDLED_TTF, fontdue::FontSettings::default()) {
+            Ok(font) => Some(Self::new(font)),
+            Err(e) => {
+                warn!("Failed to parse the bundled font ({}), using the bitmap fallback", e);
+                None
+            }
+        }
+    }
+
+    fn new(font: fontdue::Font) -> Self {
+        Self {
+            font,
+            pixels: vec![0; ATLAS_SIZE * ATLAS_SIZE],
+            entries: HashMap::new(),
+            cursor_x: 0,
+            cursor_y: 0,
+            row_height: 0,
+            dirty: Some((0, 0, ATLAS_SIZE, ATLAS_SIZE)),
+            resets: 0,
+        }
+    }
+
+    /// Distance from the top of the line to the baseline.
+    pub fn ascent(&self, size: f32) -> f32 {
+        self.font
+            .horizontal_line_metrics(pixel_size(size))
+            .map(|m| m.ascent)
+            .unwrap_or(pixel_size(size) * 0.8)
+    }
+
+    pub fn advance(&self, ch: char, size: f32) -> f32 {
+        self.font.metrics(ch, pixel_size(size)).advance_width
+    }
+
+    /// Look up a glyph, rasterizing it into the atlas on first use.
+    pub fn glyph(&mut self, ch: char, size: f32) -> GlyphEntry {
+        let key = (ch, pixel_size(size) as u32);
+        if let Some(entry) = self.entries.get(&key) {
+            return *entry;
+        }
+
+        let (metrics, bitmap) = self.font.rasterize(ch, key.1 as f32);
+        let (w, h) = (metrics.width.min(ATLAS_SIZE), metrics.height.min(ATLAS_SIZE));
+
+        if self.cursor_x + w > ATLAS_SIZE {
+            self.cursor_x = 0;
+            self.cursor_y += self.row_height + 1;
+            self.row_height = 0;
+        }
+        if self.cursor_y + h > ATLAS_SIZE {
+            // Atlas is full: start over rather than grow
+            self.entries.clear();
+            self.pixels.fill(0);
+            self.cursor_x = 0;
+            self.cursor_y = 0;
+            self.row_height = 0;
+            self.dirty = Some((0, 0, ATLAS_SIZE, ATLAS_SIZE));
+            self.resets += 1;
+        }
+
+        let (x, y) = (self.cursor_x, self.cursor_y);
+        for row in 0..h {
+            let src = &bitmap[row * metrics.width..row * metrics.width + w];
+            let dst = (y + row) * ATLAS_SIZE + x;
+            self.pixels[dst..dst + w].copy_from_slice(src);
+        }
+        self.cursor_x += w + 1;
+        self.row_height = self.row_height.max(h);
+        if w > 0 && h > 0 {
+            self.dirty = Some(match self.dirty {
+                Some((x0, y0, x1, y1)) => (x0.min(x), y0.min(y), x1.max(x + w), y1.max(y + h)),
+                None => (x, y, x + w, y + h),
+            });
+        }
+
+        let entry = GlyphEntry {
+            x,
+            y,
+            width: w,
+            height: h,
+            xmin: metrics.xmin,
+            top: metrics.ymin + metrics.height as i32,
+        };
+        self.entries.insert(key, entry);
+        entry
+    }
+
+    /// One coverage byte per texel, `ATLAS_SIZE` bytes per row.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// The region written since the last call, as (x0, y0, x1, y1), if any.
+    pub fn take_dirty(&mut self) -> Option<(usize, usize, usize, usize)> {
+        self.dirty.take()
+    }
+
+    /// Bumped whenever the atlas fills up, which invalidates every
+    /// `GlyphEntry` handed out before.
+    pub fn resets(&self) -> u64 {
+        self.resets
+    }
+}
@@ -4,17 +4,21 @@
 //! Converts raw device events into semantic intents.
 
 use glam::Vec2;
+use instant::{Duration, Instant};
 use std::collections::HashSet;
 
-/// Keyboard keys we care about
+/// How long a key must be held before it starts repeating.
+pub const REPEAT_DELAY: Duration = Duration::from_millis(400);
+
+/// Time between synthesized repeats once repeating.
+pub const REPEAT_INTERVAL: Duration = Duration::from_millis(30);
+
+/// Keyboard keys we care about, for shortcuts and navigation. Typed text
+/// never comes through here; it arrives as `Event::Text`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Key {
-    // Letters
-    A, B, C, D, E, F, G, H, I, J, K, L, M,
-    N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
-    
-    // Numbers
-    Num0, Num1, Num2, Num3, Num4, Num5, Num6, Num7, Num8, Num9,
+    /// A letter, digit or symbol key, lowercased, for chords like Ctrl+S.
+    Char(char),
     
     // Special
     Space,
@@ -48,6 +52,23 @@ pub struct Modifiers {
     pub meta: bool,
 }
 
+impl Key {
+    /// Editing and navigation keys, which repeat while held.
+    pub fn repeats(&self) -> bool {
+        matches!(
+            self,
+            Key::Backspace
+                | Key::Delete
+                | Key::Left
+                | Key::Right
+                | Key::Up
+                | Key::Down
+                | Key::PageUp
+                | Key::PageDown
+        )
+    }
+}
+
 impl Modifiers {
     pub fn any(&self) -> bool {
         self.shift || self.control || self.alt || self.meta
@@ -88,6 +109,8 @@ pub struct InputHandler {
     pointer_position: Vec2,
     pressed_buttons: HashSet<MouseButton>,
     pending_events: Vec<Event>,
+    /// Held repeatable key and when it next fires.
+    repeat: Option<(Key, Instant)>,
 }
 
 impl InputHandler {
@@ -98,6 +121,7 @@ impl InputHandler {
             pointer_position: Vec2::ZERO,
             pressed_buttons: HashSet::new(),
             pending_events: Vec::new(),
+            repeat: None,
         })
     }
 
@@ -122,7 +146,8 @@ impl InputHandler {
         // In a real implementation, this would read from evdev or winit
         // For now, return pending events and simulate some basic input
         
-        let events = std::mem::take(&mut self.pending_events);
+        let mut events = std::mem::take(&mut self.pending_events);
+        let now = Instant::now();
         
         // Process events to update state
         for event in &events {
@@ -133,6 +158,13 @@ impl InputHandler {
                     } else {
                         self.pressed_keys.remove(key);
                     }
+
+                    // Like a real keyboard, only the last key pressed repeats
+                    if *pressed && key.repeats() {
+                        self.repeat = Some((*key, now + REPEAT_DELAY));
+                    } else if !*pressed && self.repeat.is_some_and(|(k, _)| k == *key) {
+                        self.repeat = None;
+                    }
                     
                     // Update modifiers
                     match key {
@@ -156,10 +188,30 @@ impl InputHandler {
                 _ => {}
             }
         }
+
+        // At most one repeat per poll, so a stalled frame doesn't burst
+        if let Some((key, next)) = self.repeat {
+            if now >= next {
+                events.push(Event::Key { key, pressed: true });
+                self.repeat = Some((key, now + REPEAT_INTERVAL));
+            }
+        }
         
         events
     }
 
+    /// When the held key next repeats, so an idle loop knows when to wake.
+    pub fn next_repeat(&self) -> Option<Instant> {
+        self.repeat.map(|(_, next)| next)
+    }
+
+    /// Forget held keys, e.g. when the window loses focus and releases go elsewhere.
+    pub fn release_all(&mut self) {
+        self.pressed_keys.clear();
+        self.modifiers = Modifiers::default();
+        self.repeat = None;
+    }
+
     /// Inject an event (for testing or from external sources)
     pub fn inject(&mut self, event: Event) {
         self.pending_events.push(event);
@@ -172,22 +224,52 @@ impl Default for InputHandler {
     }
 }
 
-/// Convert evdev key codes to our Key enum
+/// Convert evdev key codes to our Key enum. Scancodes are positions, so
+/// letters come out as US QWERTY labels; text has to go through a keymap.
 #[cfg(target_os = "linux")]
 pub fn evdev_to_key(code: u16) -> Key {
-    // Maps evdev key codes to our Key enum
-    // Simplified version:
+    /// Letter and digit rows by evdev scancode, as labeled on US QWERTY.
+    const EVDEV_ROWS: [(u16, &str); 4] = [
+        (2, "1234567890"),
+        (16, "qwertyuiop"),
+        (30, "asdfghjkl"),
+        (44, "zxcvbnm"),
+    ];
+
     match code {
         1 => Key::Escape,
         14 => Key::Backspace,
         15 => Key::Tab,
-        28 => Key::Enter,
-        29 => Key::Control,
-        42 => Key::Shift,
-        54 => Key::Shift,  // Right shift
-        56 => Key::Alt,
+        28 | 96 => Key::Enter, // main and keypad
+        29 | 97 => Key::Control,
+        42 | 54 => Key::Shift,
+        56 | 100 => Key::Alt,
         57 => Key::Space,
-        // ... etc
-        _ => Key::Unknown,
+        59 => Key::F1,
+        60 => Key::F2,
+        61 => Key::F3,
+        62 => Key::F4,
+        63 => Key::F5,
+        64 => Key::F6,
+        65 => Key::F7,
+        66 => Key::F8,
+        67 => Key::F9,
+        68 => Key::F10,
+        87 => Key::F11,
+        88 => Key::F12,
+        102 => Key::Home,
+        103 => Key::Up,
+        104 => Key::PageUp,
+        105 => Key::Left,
+        106 => Key::Right,
+        107 => Key::End,
+        108 => Key::Down,
+        109 => Key::PageDown,
+        111 => Key::Delete,
+        125 | 126 => Key::Meta,
+        _ => EVDEV_ROWS
+            .iter()
+            .find_map(|(start, row)| row.chars().nth(code.checked_sub(*start)? as usize))
+            .map_or(Key::Unknown, Key::Char),
     }
 }
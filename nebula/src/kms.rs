@@ -0,0 +1,232 @@
+//! DRM/KMS Display
+//!
+//! Shows frames on the primary connector with no windowing system, for
+//! running Nebula as the display server on a TTY. Frames are rendered
+//! offscreen and copied into two dumb buffers that are page-flipped on
+//! vblank. Dropping the display puts back whatever the CRTC showed before.
+
+use std::fs::{File, OpenOptions};
+use std::os::fd::{AsFd, BorrowedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{anyhow, Context, Result};
+use drm::buffer::{Buffer, DrmFourcc};
+use drm::control::dumbbuffer::DumbBuffer;
+use drm::control::{connector, crtc, framebuffer, Device as ControlDevice, Event, Mode, ModeTypeFlags, PageFlipFlags};
+use drm::Device;
+use tracing::{info, warn};
+
+/// Card opened unless `NEBULA_DRM_DEVICE` names another.
+const DEFAULT_DEVICE: &str = "/dev/dri/card0";
+
+static EXIT_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// A DRM card node
+struct Card(File);
+
+impl AsFd for Card {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl Device for Card {}
+impl ControlDevice for Card {}
+
+/// A scanout buffer and the framebuffer that wraps it
+struct ScanoutBuffer {
+    buffer: DumbBuffer,
+    fb: framebuffer::Handle,
+}
+
+/// The primary connector, driven directly
+pub struct Display {
+    card: Card,
+    connector: connector::Handle,
+    crtc: crtc::Handle,
+    mode: Mode,
+    /// Two buffers: one on screen, one being drawn.
+    buffers: Vec<ScanoutBuffer>,
+    /// Index of the buffer on screen; the other one is drawn into.
+    front: usize,
+    /// What the CRTC showed before we took it, to restore on exit.
+    saved: crtc::Info,
+}
+
+impl Display {
+    /// Open the card, pick the first connected connector and its preferred
+    /// mode (or `NEBULA_DRM_MODE`, e.g. "1920x1080"), and show a blank frame.
+    pub fn open() -> Result<Self> {
+        let path = std::env::var("NEBULA_DRM_DEVICE").unwrap_or_else(|_| DEFAULT_DEVICE.to_string());
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("open {}", path))?;
+        let card = Card(file);
+        if let Err(e) = card.acquire_master_lock() {
+            warn!("Not DRM master ({}); mode setting may fail", e);
+        }
+
+        let resources = card.resource_handles().context("read DRM resources")?;
+        let connector = resources
+            .connectors()
+            .iter()
+            .filter_map(|&h| card.get_connector(h, false).ok())
+            .find(|c| c.state() == connector::State::Connected && !c.modes().is_empty())
+            .ok_or_else(|| anyhow!("no connected display on {}", path))?;
+
+        for mode in connector.modes() {
+            let (w, h) = mode.size();
+            info!("  mode {}x{}@{}", w, h, mode.vrefresh());
+        }
+        let mode = pick_mode(connector.modes())?;
+
+        // Keep the CRTC already driving this connector, or take the first one that can
+        let crtc = connector
+            .current_encoder()
+            .and_then(|e| card.get_encoder(e).ok())
+            .and_then(|e| e.crtc())
+            .or_else(|| {
+                connector
+                    .encoders()
+                    .iter()
+                    .filter_map(|&e| card.get_encoder(e).ok())
+                    .find_map(|e| resources.filter_crtcs(e.possible_crtcs()).first().copied())
+            })
+            .ok_or_else(|| anyhow!("no CRTC for connector {:?}", connector.interface()))?;
+        let saved = card.get_crtc(crtc).context("read CRTC state")?;
+
+        let (width, height) = mode.size();
+        let size = (width as u32, height as u32);
+        let buffers = vec![scanout_buffer(&card, size)?, scanout_buffer(&card, size)?];
+        card.set_crtc(crtc, Some(buffers[0].fb), (0, 0), &[connector.handle()], Some(mode))
+            .context("set mode")?;
+
+        info!(
+            "DRM/KMS: {:?} at {}x{}@{} on {}",
+            connector.interface(),
+            width,
+            height,
+            mode.vrefresh(),
+            path
+        );
+
+        Ok(Self {
+            card,
+            connector: connector.handle(),
+            crtc,
+            mode,
+            buffers,
+            front: 0,
+            saved,
+        })
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        let (w, h) = self.mode.size();
+        (w as u32, h as u32)
+    }
+
+    /// Copy XRGB8888 rows `stride` bytes apart into the back buffer, flip it
+    /// on screen at the next vblank, and wait for the flip.
+    pub fn present(&mut self, pixels: &[u8], stride: usize) -> Result<()> {
+        let back = 1 - self.front;
+        let pitch = self.buffers[back].buffer.pitch() as usize;
+        let (width, height) = self.size();
+        let row = width as usize * 4;
+        {
+            let mut map = self.card.map_dumb_buffer(&mut self.buffers[back].buffer)?;
+            for y in 0..height as usize {
+                let (Some(src), Some(dst)) = (
+                    pixels.get(y * stride..y * stride + row),
+                    map.get_mut(y * pitch..y * pitch + row),
+                ) else {
+                    break;
+                };
+                dst.copy_from_slice(src);
+            }
+        }
+
+        self.card
+            .page_flip(self.crtc, self.buffers[back].fb, PageFlipFlags::EVENT, None)
+            .context("page flip")?;
+        // Blocks until vblank, which paces the frame loop to the refresh rate
+        'wait: loop {
+            for event in self.card.receive_events().context("DRM events")? {
+                if let Event::PageFlip(_) = event {
+                    break 'wait;
+                }
+            }
+        }
+        self.front = back;
+        Ok(())
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        if let Err(e) = self.card.set_crtc(
+            self.crtc,
+            self.saved.framebuffer(),
+            self.saved.position(),
+            &[self.connector],
+            self.saved.mode(),
+        ) {
+            warn!("Failed to restore the console mode: {}", e);
+        }
+        for scanout in self.buffers.drain(..) {
+            let _ = self.card.destroy_framebuffer(scanout.fb);
+            let _ = self.card.destroy_dumb_buffer(scanout.buffer);
+        }
+        let _ = self.card.release_master_lock();
+    }
+}
+
+/// `NEBULA_DRM_MODE` if the connector offers it, else the preferred mode,
+/// else the first.
+fn pick_mode(modes: &[Mode]) -> Result<Mode> {
+    if let Ok(wanted) = std::env::var("NEBULA_DRM_MODE") {
+        let found = modes.iter().find(|m| {
+            let (w, h) = m.size();
+            format!("{}x{}", w, h) == wanted
+        });
+        match found {
+            Some(mode) => return Ok(*mode),
+            None => warn!("NEBULA_DRM_MODE {} not offered by the display", wanted),
+        }
+    }
+    modes
+        .iter()
+        .find(|m| m.mode_type().contains(ModeTypeFlags::PREFERRED))
+        .or_else(|| modes.first())
+        .copied()
+        .ok_or_else(|| anyhow!("display offers no modes"))
+}
+
+fn scanout_buffer(card: &Card, size: (u32, u32)) -> Result<ScanoutBuffer> {
+    let buffer = card
+        .create_dumb_buffer(size, DrmFourcc::Xrgb8888, 32)
+        .context("create scanout buffer")?;
+    let fb = card.add_framebuffer(&buffer, 24, 32).context("add framebuffer")?;
+    Ok(ScanoutBuffer { buffer, fb })
+}
+
+extern "C" fn on_exit_signal(_: libc::c_int) {
+    EXIT_REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// Turn SIGINT and SIGTERM into `exit_requested`, so the frame loop ends
+/// and the console mode is restored instead of the process dying mid-mode.
+pub fn install_exit_handler() {
+    let handler = on_exit_signal as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    // SAFETY: the handler only stores to an atomic, which is signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, handler);
+        libc::signal(libc::SIGTERM, handler);
+    }
+}
+
+pub fn exit_requested() -> bool {
+    EXIT_REQUESTED.load(Ordering::SeqCst)
+}
@@ -4,26 +4,45 @@
 //! context composition.
 
 mod canvas;
+mod clipboard;
 mod color;
 mod facet;
+mod font;
 mod input;
+#[cfg(all(target_os = "linux", feature = "bare-metal"))]
+mod kms;
+mod notify;
 mod omnibar;
+mod power;
 mod render;
+mod settings;
 
 use anyhow::Result;
 use glam::Vec2;
 use std::sync::Arc;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
-use winit::event::{ElementState, Event, WindowEvent};
-use winit::event_loop::EventLoop;
-use winit::keyboard::{Key as WinitKey, NamedKey};
+use winit::event::{ElementState, Event, Ime, WindowEvent};
+use winit::event_loop::{ControlFlow, EventLoop};
+use winit::keyboard::{Key as WinitKey, KeyCode, NamedKey, PhysicalKey};
 use winit::window::{Window, WindowBuilder};
 
 use crate::canvas::Canvas;
+use crate::facet::{FacetRegistry, SaveResult};
 use crate::input::InputHandler;
-use crate::omnibar::OmniBar;
-use crate::render::Renderer;
+use crate::notify::{Notification, Toasts};
+use crate::omnibar::{OmniAction, OmniBar};
+use crate::power::PowerAction;
+use crate::render::{Color, Rect, Renderer};
+use crate::settings::Settings;
+
+/// Simulation timestep: springs and the camera advance in slices of this,
+/// however fast or slow frames are presented.
+const SIM_STEP: f32 = 1.0 / 120.0;
+
+/// Most steps one update runs (0.1s); after a longer stall the backlog is
+/// dropped rather than replayed.
+const MAX_SIM_STEPS: u32 = 12;
 
 /// Nebula shell state
 struct Nebula {
@@ -31,34 +50,63 @@ struct Nebula {
     window: Option<Arc<Window>>,
     canvas: Canvas,
     omnibar: OmniBar,
+    facets: FacetRegistry,
     input: InputHandler,
     running: bool,
     last_frame: instant::Instant,
+    /// Real time not yet simulated, in seconds.
+    sim_time: f32,
+    /// Something changed since the last frame was drawn.
+    needs_redraw: bool,
+    /// Nothing was moving after the last update, so the loop may have slept
+    /// since; the next update starts the clock fresh.
+    resting: bool,
+    /// A shutdown or reboot waiting out its undo window.
+    power: Option<power::Pending>,
+    toasts: Toasts,
 }
 
 impl Nebula {
     fn new() -> Self {
+        let facets = FacetRegistry::new();
+        let settings = Settings::load();
+        settings.apply();
+        let mut canvas = Canvas::new();
+        canvas.set_zoom(settings.default_zoom);
         Self {
             renderer: None,
             window: None,
-            canvas: Canvas::new(),
-            omnibar: OmniBar::new(),
+            canvas,
+            omnibar: OmniBar::new().with_facets(&facets.list()),
+            facets,
             input: InputHandler::new().unwrap(),
             running: true,
             last_frame: instant::Instant::now(),
+            sim_time: 0.0,
+            needs_redraw: true,
+            resting: true,
+            power: None,
+            toasts: Toasts::default(),
         }
     }
 
     fn handle_nebula_event(&mut self, event: input::Event) {
         use input::Event;
+        self.needs_redraw = true;
 
         match event {
             Event::Key { key, pressed } => {
                 if pressed {
                     match key {
                         input::Key::Escape => {
-                            if self.omnibar.is_visible() {
+                            if let Some(pending) = self.power.take() {
+                                info!("{} cancelled", pending.action.progressive());
+                            } else if self.omnibar.is_visible() {
                                 self.omnibar.hide();
+                            } else if self.canvas.in_overview() {
+                                self.canvas.exit_overview();
+                            } else if self.canvas.has_focus() {
+                                self.canvas.clear_focus();
                             } else {
                                 self.running = false;
                             }
@@ -66,21 +114,35 @@ impl Nebula {
                         input::Key::Space if self.input.modifiers().meta => {
                             self.omnibar.toggle();
                         }
+                        input::Key::Char('o') if self.input.modifiers().meta => {
+                            self.canvas.toggle_overview();
+                        }
+                        input::Key::Char('s') if self.input.modifiers().control && !self.omnibar.is_visible() => {
+                            self.save_focused(None);
+                        }
                         _ => {
                             if self.omnibar.is_visible() {
-                                self.omnibar.handle_key(key);
+                                if let Some(action) = self.omnibar.handle_key(key) {
+                                    self.run_omni_action(action);
+                                }
+                            } else {
+                                self.canvas.handle_key(key, self.input.modifiers(), pressed);
                             }
                         }
                     }
                 }
             }
+            // Ctrl and Meta chords are shortcuts, not typing
+            Event::Text(_) if self.input.modifiers().control || self.input.modifiers().meta => {}
             Event::Text(c) => {
                 if self.omnibar.is_visible() {
                     self.omnibar.handle_char(c);
+                } else {
+                    self.canvas.handle_text(c);
                 }
             }
-            Event::Pointer { position, .. } => {
-                self.canvas.handle_pointer(position);
+            Event::Pointer { position, button, pressed } => {
+                self.canvas.handle_pointer(position, button, pressed);
             }
             Event::Scroll { delta } => {
                 self.canvas.handle_scroll(delta);
@@ -91,20 +153,115 @@ impl Nebula {
         }
     }
 
+    /// Carry out an action chosen in the omnibar.
+    fn run_omni_action(&mut self, action: OmniAction) {
+        match action {
+            OmniAction::OpenFacet { name } => match self.facets.create(&name) {
+                Some(facet) => {
+                    let id = self.canvas.open_facet(facet);
+                    info!("Opened facet {} in region {}", name, id);
+                }
+                None => tracing::warn!("No facet registered as '{}'", name),
+            },
+            OmniAction::Execute { command } => match command.as_str() {
+                "quit" => self.running = false,
+                _ => match PowerAction::parse(&command) {
+                    Some(action) => {
+                        info!("{} in {}s; Escape cancels", action.progressive(), power::UNDO_WINDOW.as_secs());
+                        self.power = Some(power::Pending::new(action));
+                    }
+                    None => tracing::warn!("Unknown command: {}", command),
+                },
+            },
+            OmniAction::Navigate { path } => {
+                info!("Navigating to: {}", path);
+                // TODO: Navigate
+            }
+            OmniAction::Search { query } => {
+                info!("Searching: {}", query);
+                // TODO: Search
+            }
+            OmniAction::SaveAs { path } => self.save_focused(Some(path.into())),
+            OmniAction::Pipe { from, to } => match self.canvas.connect_facets(&from, &to) {
+                Ok(mime) => info!("Piped {} into {} as {}", from, to, mime),
+                Err(e) => tracing::warn!("Can't pipe {} into {}: {}", from, to, e),
+            },
+        }
+    }
+
+    /// Save the focused facet; without a known path, ask for one in the omnibar.
+    fn save_focused(&mut self, path: Option<std::path::PathBuf>) {
+        match self.canvas.save_focused(path.as_deref()) {
+            SaveResult::Saved(path) => info!("Saved {}", path.display()),
+            SaveResult::NeedsPath => self.omnibar.prompt("save as "),
+            SaveResult::Failed(e) => {
+                tracing::warn!("Save failed: {}", e);
+                notify::publish(Notification::new("Save failed").with_line(e).urgent());
+            }
+            SaveResult::Unsupported => {}
+        }
+    }
+
+    /// Whether to draw another frame: after input, or while springs and
+    /// the camera are still moving. Otherwise the loop sleeps.
+    fn wants_frame(&self) -> bool {
+        self.needs_redraw
+            || self.omnibar.is_animating()
+            || self.canvas.is_animating()
+            || self.power.is_some()
+            || self.toasts.is_active()
+    }
+
+    /// Carry out a pending power action once its undo window has passed.
+    fn tick_power(&mut self) {
+        let Some(pending) = self.power.filter(|p| p.is_due()) else {
+            return;
+        };
+        self.power = None;
+        match power::perform(pending.action) {
+            // The system is going down; give the console back first
+            Ok(()) => self.running = false,
+            Err(e) => {
+                tracing::error!("{} failed: {}", pending.action.progressive(), e);
+                eprintln!("{}", e);
+                notify::publish(Notification::new(format!("{} failed", pending.action.progressive())).with_line(e).urgent());
+            }
+        }
+    }
+
+    /// Advance the simulation by the real time since the last call, in
+    /// fixed `SIM_STEP`s. Called every loop iteration, apart from drawing.
     fn update(&mut self) {
         let now = instant::Instant::now();
-        let dt = now.duration_since(self.last_frame).as_secs_f32();
+        if !self.resting {
+            self.sim_time += now.duration_since(self.last_frame).as_secs_f32();
+        }
         self.last_frame = now;
 
-        let dt = dt.min(0.1); // Cap delta time to avoid physics explosions
+        // Animations run faster or slower than real time per settings
+        let animation_step = SIM_STEP * settings::animation_speed();
+        let mut steps = 0;
+        while self.sim_time >= SIM_STEP && steps < MAX_SIM_STEPS {
+            self.omnibar.update(animation_step);
+            self.canvas.update(animation_step);
+            self.toasts.update(SIM_STEP);
+            self.sim_time -= SIM_STEP;
+            steps += 1;
+        }
+        if steps == MAX_SIM_STEPS {
+            self.sim_time = self.sim_time.min(SIM_STEP);
+        }
 
-        self.omnibar.update(dt);
-        self.canvas.update(dt);
+        self.canvas.propagate();
+        self.tick_power();
+        self.resting = !(self.omnibar.is_animating() || self.canvas.is_animating() || self.toasts.is_active());
     }
 
     fn render(&mut self) {
+        self.needs_redraw = false;
         if let Some(renderer) = &mut self.renderer {
             renderer.begin_frame();
+            self.canvas.set_screen_center(renderer.center());
 
             // Render canvas (content layer)
             self.canvas.render(renderer);
@@ -114,6 +271,11 @@ impl Nebula {
                 self.omnibar.render(renderer);
             }
 
+            if let Some(pending) = &self.power {
+                render_power_countdown(renderer, pending);
+            }
+            self.toasts.render(renderer);
+
             if let Err(e) = renderer.end_frame() {
                 tracing::error!("Render error: {}", e);
             }
@@ -121,8 +283,24 @@ impl Nebula {
     }
 }
 
-fn map_winit_key(key: &WinitKey) -> input::Key {
-    match key {
+/// "Shutting down in 3s · Esc to cancel", across the top of the screen.
+fn render_power_countdown(renderer: &mut Renderer, pending: &power::Pending) {
+    let text = format!(
+        "{} in {}s \u{00b7} Esc to cancel",
+        pending.action.progressive(),
+        pending.seconds_left()
+    );
+    let size = 18.0;
+    let width = renderer.measure_text(&text, size) + 40.0;
+    let center = Vec2::new(renderer.center().x, 48.0);
+    renderer.draw_rect(Rect::centered(center, width, 44.0), Color::SURFACE, 12.0);
+    renderer.draw_text(&text, Vec2::new(center.x - width / 2.0 + 20.0, center.y - size / 2.0), size, Color::TEXT);
+}
+
+/// Map a winit key to the key used for shortcuts and navigation. Typed text
+/// doesn't come from here; it arrives as `KeyEvent::text` or an IME commit.
+fn map_winit_key(logical: &WinitKey, physical: PhysicalKey) -> input::Key {
+    match logical {
         WinitKey::Named(named) => match named {
             NamedKey::Space => input::Key::Space,
             NamedKey::Enter => input::Key::Enter,
@@ -156,52 +334,36 @@ fn map_winit_key(key: &WinitKey) -> input::Key {
             NamedKey::F12 => input::Key::F12,
             _ => input::Key::Unknown,
         },
-        WinitKey::Character(ch) => {
-            match ch.as_str() {
-                "a" | "A" => input::Key::A,
-                "b" | "B" => input::Key::B,
-                "c" | "C" => input::Key::C,
-                "d" | "D" => input::Key::D,
-                "e" | "E" => input::Key::E,
-                "f" | "F" => input::Key::F,
-                "g" | "G" => input::Key::G,
-                "h" | "H" => input::Key::H,
-                "i" | "I" => input::Key::I,
-                "j" | "J" => input::Key::J,
-                "k" | "K" => input::Key::K,
-                "l" | "L" => input::Key::L,
-                "m" | "M" => input::Key::M,
-                "n" | "N" => input::Key::N,
-                "o" | "O" => input::Key::O,
-                "p" | "P" => input::Key::P,
-                "q" | "Q" => input::Key::Q,
-                "r" | "R" => input::Key::R,
-                "s" | "S" => input::Key::S,
-                "t" | "T" => input::Key::T,
-                "u" | "U" => input::Key::U,
-                "v" | "V" => input::Key::V,
-                "w" | "W" => input::Key::W,
-                "x" | "X" => input::Key::X,
-                "y" | "Y" => input::Key::Y,
-                "z" | "Z" => input::Key::Z,
-                "0" => input::Key::Num0,
-                "1" => input::Key::Num1,
-                "2" => input::Key::Num2,
-                "3" => input::Key::Num3,
-                "4" => input::Key::Num4,
-                "5" => input::Key::Num5,
-                "6" => input::Key::Num6,
-                "7" => input::Key::Num7,
-                "8" => input::Key::Num8,
-                "9" => input::Key::Num9,
-                " " => input::Key::Space,
-                _ => input::Key::Unknown,
-            }
-        }
+        // Chords follow the layout's labels, so Ctrl+Z is on Z on AZERTY too
+        WinitKey::Character(ch) => match ch.chars().next() {
+            Some(c) if c.is_ascii_graphic() && ch.len() == 1 => input::Key::Char(c.to_ascii_lowercase()),
+            // Non-Latin layouts: fall back to the key's US QWERTY position
+            _ => physical_char(physical).map_or(input::Key::Unknown, input::Key::Char),
+        },
         _ => input::Key::Unknown,
     }
 }
 
+/// Letter and digit keys by position, as labeled on US QWERTY.
+const PHYSICAL_CHARS: [(KeyCode, char); 36] = [
+    (KeyCode::KeyA, 'a'), (KeyCode::KeyB, 'b'), (KeyCode::KeyC, 'c'), (KeyCode::KeyD, 'd'),
+    (KeyCode::KeyE, 'e'), (KeyCode::KeyF, 'f'), (KeyCode::KeyG, 'g'), (KeyCode::KeyH, 'h'),
+    (KeyCode::KeyI, 'i'), (KeyCode::KeyJ, 'j'), (KeyCode::KeyK, 'k'), (KeyCode::KeyL, 'l'),
+    (KeyCode::KeyM, 'm'), (KeyCode::KeyN, 'n'), (KeyCode::KeyO, 'o'), (KeyCode::KeyP, 'p'),
+    (KeyCode::KeyQ, 'q'), (KeyCode::KeyR, 'r'), (KeyCode::KeyS, 's'), (KeyCode::KeyT, 't'),
+    (KeyCode::KeyU, 'u'), (KeyCode::KeyV, 'v'), (KeyCode::KeyW, 'w'), (KeyCode::KeyX, 'x'),
+    (KeyCode::KeyY, 'y'), (KeyCode::KeyZ, 'z'), (KeyCode::Digit0, '0'), (KeyCode::Digit1, '1'),
+    (KeyCode::Digit2, '2'), (KeyCode::Digit3, '3'), (KeyCode::Digit4, '4'), (KeyCode::Digit5, '5'),
+    (KeyCode::Digit6, '6'), (KeyCode::Digit7, '7'), (KeyCode::Digit8, '8'), (KeyCode::Digit9, '9'),
+];
+
+fn physical_char(key: PhysicalKey) -> Option<char> {
+    let PhysicalKey::Code(code) = key else {
+        return None;
+    };
+    PHYSICAL_CHARS.iter().find(|(k, _)| *k == code).map(|(_, c)| *c)
+}
+
 fn main() -> Result<()> {
     // Initialize logging
     let subscriber = FmtSubscriber::builder()
@@ -220,9 +382,26 @@ fn main() -> Result<()> {
     "#
     );
 
+    notify::listen();
+
+    // Own the display when there's no windowing system; otherwise use a window
+    #[cfg(all(target_os = "linux", feature = "bare-metal"))]
+    match kms::Display::open() {
+        Ok(display) => return run_bare_metal(display),
+        Err(e) => tracing::warn!("DRM/KMS unavailable ({:#}), falling back to a window", e),
+    }
+
     let event_loop = EventLoop::new()?;
     let mut app = Nebula::new();
 
+    // Wake the idle loop when a notification arrives from another thread
+    let proxy = std::sync::Mutex::new(event_loop.create_proxy());
+    notify::set_waker(move || {
+        if let Ok(proxy) = proxy.lock() {
+            let _ = proxy.send_event(());
+        }
+    });
+
     event_loop.run(move |event, elwt| {
         match event {
             Event::Resumed => {
@@ -236,6 +415,7 @@ fn main() -> Result<()> {
                     .build(elwt)
                     .expect("Failed to create window");
 
+                window.set_ime_allowed(true);
                 let window = Arc::new(window);
 
                 match Renderer::new(window.clone()) {
@@ -263,21 +443,23 @@ fn main() -> Result<()> {
                         if let Some(renderer) = &mut app.renderer {
                             renderer.resize(new_size.width, new_size.height);
                         }
+                        app.needs_redraw = true;
                     }
 
                     WindowEvent::KeyboardInput { event: key_event, .. } => {
                         let pressed = key_event.state == ElementState::Pressed;
-                        let key = map_winit_key(&key_event.logical_key);
+                        let key = map_winit_key(&key_event.logical_key, key_event.physical_key);
 
-                        app.input.inject(input::Event::Key { key, pressed });
+                        // InputHandler synthesizes its own repeats; OS repeats
+                        // only keep feeding text
+                        if !key_event.repeat {
+                            app.input.inject(input::Event::Key { key, pressed });
+                        }
 
-                        if pressed {
-                            if let WinitKey::Character(ref ch) = key_event.logical_key {
-                                for c in ch.chars() {
-                                    if !c.is_control() {
-                                        app.input.inject(input::Event::Text(c));
-                                    }
-                                }
+                        // Text as the layout and dead keys composed it
+                        if let (true, Some(text)) = (pressed, &key_event.text) {
+                            for c in text.chars().filter(|c| !c.is_control()) {
+                                app.input.inject(input::Event::Text(c));
                             }
                         }
 
@@ -290,6 +472,16 @@ fn main() -> Result<()> {
                         }
                     }
 
+                    // Text composed through an input method
+                    WindowEvent::Ime(Ime::Commit(text)) => {
+                        for c in text.chars().filter(|c| !c.is_control()) {
+                            app.input.inject(input::Event::Text(c));
+                        }
+                        for ev in app.input.poll() {
+                            app.handle_nebula_event(ev);
+                        }
+                    }
+
                     WindowEvent::CursorMoved { position, .. } => {
                         app.input.inject(input::Event::Pointer {
                             position: Vec2::new(position.x as f32, position.y as f32),
@@ -331,25 +523,64 @@ fn main() -> Result<()> {
                         }
                     }
 
-                    WindowEvent::RedrawRequested => {
-                        app.update();
-                        app.render();
-                    }
+                    WindowEvent::Focused(false) => app.input.release_all(),
+
+                    WindowEvent::RedrawRequested => app.render(),
 
                     _ => {}
                 }
             }
 
             Event::AboutToWait => {
-                // Request continuous redraw for animation
-                if let Some(window) = &app.window {
+                // Deliver synthesized key repeats
+                for ev in app.input.poll() {
+                    app.handle_nebula_event(ev);
+                }
+                app.update();
+                if !app.running {
+                    elwt.exit();
+                }
+
+                // Redraw only while something changes; idle, sleep until
+                // input or the next key repeat
+                if let (Some(window), true) = (&app.window, app.wants_frame()) {
                     window.request_redraw();
                 }
+                elwt.set_control_flow(match app.input.next_repeat() {
+                    Some(at) => ControlFlow::WaitUntil(at),
+                    None => ControlFlow::Wait,
+                });
             }
 
+            Event::UserEvent(()) => app.needs_redraw = true,
+
             _ => {}
         }
     })?;
 
     Ok(())
 }
+
+/// Frame loop for a DRM/KMS display. Presenting waits for vblank, which
+/// paces the loop. Input devices aren't read here yet.
+#[cfg(all(target_os = "linux", feature = "bare-metal"))]
+fn run_bare_metal(mut display: kms::Display) -> Result<()> {
+    let (width, height) = display.size();
+    let mut app = Nebula::new();
+    app.renderer = Some(Renderer::offscreen(width, height)?);
+    kms::install_exit_handler();
+
+    while app.running && !kms::exit_requested() {
+        for ev in app.input.poll() {
+            app.handle_nebula_event(ev);
+        }
+        app.update();
+        app.render();
+        if let Some((pixels, stride)) = app.renderer.as_ref().and_then(|r| r.frame()) {
+            display.present(pixels, stride)?;
+        }
+    }
+
+    info!("Leaving DRM/KMS");
+    Ok(())
+}
@@ -0,0 +1,197 @@
+//! Notifications
+//!
+//! A bus for one-off alerts. Facets with the `Notifications` capability
+//! publish to it directly; daemons post to aurorad's `/v0/notify`, which is
+//! polled here. The shell shows each notification as a toast that fades
+//! after a few seconds.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use glam::Vec2;
+use serde::Deserialize;
+
+use crate::render::{Color, Rect, Renderer};
+
+/// How often aurorad is asked for new notifications.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+/// Seconds a toast stays up, including its fade.
+const TOAST_SECONDS: f32 = 6.0;
+const FADE_SECONDS: f32 = 0.5;
+/// Toasts shown at once; the rest wait their turn.
+const MAX_VISIBLE: usize = 3;
+
+static QUEUE: Mutex<Vec<Notification>> = Mutex::new(Vec::new());
+static WAKER: OnceLock<Box<dyn Fn() + Send + Sync>> = OnceLock::new();
+
+/// A notification, in the brain's widget shape plus a priority:
+/// `{"type":"notification","title":"…","lines":["…"],"priority":"normal"}`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Notification {
+    pub title: String,
+    #[serde(default)]
+    pub lines: Vec<String>,
+    /// "urgent", "normal" or "low".
+    #[serde(default)]
+    pub priority: String,
+}
+
+impl Notification {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            lines: Vec::new(),
+            priority: "normal".to_string(),
+        }
+    }
+
+    pub fn with_line(mut self, line: impl Into<String>) -> Self {
+        self.lines.push(line.into());
+        self
+    }
+
+    pub fn urgent(mut self) -> Self {
+        self.priority = "urgent".to_string();
+        self
+    }
+}
+
+/// Queue `notification` for the shell and wake it if it's idle.
+pub fn publish(notification: Notification) {
+    if let Ok(mut queue) = QUEUE.lock() {
+        queue.push(notification);
+    }
+    if let Some(wake) = WAKER.get() {
+        wake();
+    }
+}
+
+/// Everything published since the last call.
+pub fn drain() -> Vec<Notification> {
+    QUEUE.lock().map(|mut queue| std::mem::take(&mut *queue)).unwrap_or_default()
+}
+
+/// Called after each publish, so a sleeping event loop notices.
+pub fn set_waker(wake: impl Fn() + Send + Sync + 'static) {
+    let _ = WAKER.set(Box::new(wake));
+}
+
+/// Poll aurorad in the background and publish what it has.
+pub fn listen() {
+    std::thread::spawn(|| {
+        let mut after = None;
+        loop {
+            if let Ok((latest, notifications)) = fetch(after) {
+                // A restarted aurorad numbers from 1 again; follow it down
+                after = Some(latest);
+                notifications.into_iter().for_each(publish);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+    });
+}
+
+#[derive(Deserialize)]
+struct NotificationsResponse {
+    latest: u64,
+    #[serde(default)]
+    notifications: Vec<Notification>,
+}
+
+/// Notifications newer than `after` and the latest id; without `after`,
+/// only the id.
+fn fetch(after: Option<u64>) -> anyhow::Result<(u64, Vec<Notification>)> {
+    let path = match after {
+        Some(id) => format!("/v0/notifications?after={}", id),
+        None => "/v0/notifications".to_string(),
+    };
+    let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+    let timeout = Some(Duration::from_secs(2));
+
+    let mut response = String::new();
+    match aurorad_tcp_addr() {
+        Some(addr) => {
+            let mut stream = TcpStream::connect(addr)?;
+            stream.set_read_timeout(timeout)?;
+            stream.write_all(request.as_bytes())?;
+            stream.read_to_string(&mut response)?;
+        }
+        None => {
+            let sock = std::env::var("AURORAD_SOCKET").unwrap_or_else(|_| "/tmp/aurorad.sock".to_string());
+            let mut stream = UnixStream::connect(sock)?;
+            stream.set_read_timeout(timeout)?;
+            stream.write_all(request.as_bytes())?;
+            stream.read_to_string(&mut response)?;
+        }
+    }
+    let body = response.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+    let parsed: NotificationsResponse = serde_json::from_str(body)?;
+    Ok((parsed.latest, parsed.notifications))
+}
+
+/// `AURORAD_TCP_PORT` or `AURORAD_HOST`, as the TUI reads them; otherwise
+/// aurorad is reached over its Unix socket.
+fn aurorad_tcp_addr() -> Option<String> {
+    if let Some(port) = std::env::var("AURORAD_TCP_PORT").ok().and_then(|p| p.parse::<u16>().ok()) {
+        return Some(format!("127.0.0.1:{}", port));
+    }
+    std::env::var("AURORAD_HOST").ok()
+}
+
+/// Toasts on screen, oldest first
+#[derive(Default)]
+pub struct Toasts {
+    /// Each toast with the seconds it has been shown.
+    shown: Vec<(Notification, f32)>,
+    queued: Vec<Notification>,
+}
+
+impl Toasts {
+    /// Take in newly published notifications and age the ones showing.
+    pub fn update(&mut self, dt: f32) {
+        self.queued.extend(drain());
+        for (_, age) in &mut self.shown {
+            *age += dt;
+        }
+        self.shown.retain(|(_, age)| *age < TOAST_SECONDS);
+        while self.shown.len() < MAX_VISIBLE && !self.queued.is_empty() {
+            self.shown.push((self.queued.remove(0), 0.0));
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.shown.is_empty() || !self.queued.is_empty()
+    }
+
+    /// Stack the toasts down the top right corner.
+    pub fn render(&self, renderer: &mut Renderer) {
+        let width = 340.0;
+        let pad = 16.0;
+        let x = renderer.width() as f32 - width - 24.0;
+        let mut y = 24.0;
+        for (n, age) in &self.shown {
+            let alpha = ((TOAST_SECONDS - age) / FADE_SECONDS).clamp(0.0, 1.0);
+            let fade = |c: Color| Color::rgba(c.r, c.g, c.b, c.a * alpha);
+            let accent = match n.priority.as_str() {
+                "urgent" => Color::rgb(1.0, 0.35, 0.35),
+                "low" => Color::TEXT_DIM,
+                _ => Color::accent(),
+            };
+
+            let lines = &n.lines[..n.lines.len().min(3)];
+            let height = pad * 2.0 + 16.0 + lines.len() as f32 * 18.0;
+            renderer.draw_rect(Rect::new(x, y, width, height), fade(Color::SURFACE), 12.0);
+            renderer.draw_rect(Rect::new(x, y + 12.0, 3.0, height - 24.0), fade(accent), 1.5);
+
+            renderer.draw_text(&n.title, Vec2::new(x + pad, y + pad), 16.0, fade(Color::TEXT));
+            for (i, line) in lines.iter().enumerate() {
+                let line_y = y + pad + 20.0 + i as f32 * 18.0;
+                renderer.draw_text(line, Vec2::new(x + pad, line_y), 13.0, fade(Color::TEXT_DIM));
+            }
+            y += height + 8.0;
+        }
+    }
+}
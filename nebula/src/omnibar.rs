@@ -52,6 +52,11 @@ impl Animation {
     fn value(&self) -> f32 {
         self.current
     }
+
+    /// At its target and at rest; `update` snaps it there exactly.
+    fn is_settled(&self) -> bool {
+        self.current == self.target && self.velocity == 0.0
+    }
 }
 
 /// Search/command result
@@ -61,6 +66,86 @@ pub struct OmniResult {
     pub subtitle: Option<String>,
     pub icon: Option<String>,
     pub action: OmniAction,
+    /// Char indices of `title` matched by the query, for highlighting.
+    pub matched: Vec<usize>,
+}
+
+/// Something the omnibar can offer, with extra words it also answers to.
+#[derive(Clone, Debug)]
+struct CatalogEntry {
+    result: OmniResult,
+    keywords: Vec<&'static str>,
+}
+
+/// Presentation for known facets: (id, title, subtitle, icon, keywords).
+const FACET_ENTRIES: &[(&str, &str, &str, &str, &[&str])] = &[
+    ("terminal", "Terminal", "Open command line", "terminal", &["shell", "console", "term"]),
+    ("editor", "Write", "Open text editor", "edit", &["editor", "edit", "note", "text"]),
+    ("files", "Files", "Browse filesystem", "folder", &["browse", "folder", "explorer"]),
+    ("settings", "Settings", "System preferences", "settings", &["preferences", "config", "options"]),
+];
+
+// Fuzzy scoring weights, roughly fzf's
+const SCORE_MATCH: i32 = 16;
+const BONUS_CONSECUTIVE: i32 = 12;
+const BONUS_BOUNDARY: i32 = 10;
+const PENALTY_GAP: i32 = 2;
+const PENALTY_LEADING: i32 = 1;
+const MAX_LEADING_PENALTY: i32 = 6;
+
+/// Score `query` as a case-insensitive subsequence of `candidate`, rewarding
+/// consecutive runs and word starts and penalising gaps. Returns the score
+/// and the matched char indices, or `None` if it isn't a subsequence.
+pub fn fuzzy_match(query: &str, candidate: &str) -> Option<(i32, Vec<usize>)> {
+    let q: Vec<char> = query.to_lowercase().chars().collect();
+    let c: Vec<char> = candidate.chars().collect();
+    let lower: Vec<char> = c.iter().map(|ch| ch.to_lowercase().next().unwrap_or(*ch)).collect();
+    if q.is_empty() || q.len() > c.len() {
+        return None;
+    }
+
+    let boundary = |j: usize| j == 0 || matches!(c[j - 1], ' ' | '_' | '-' | '/' | '.');
+    // best[i][j]: best score with q[..=i] matched and q[i] at c[j]; from[i][j] is q[i-1]'s position
+    let mut best = vec![vec![None::<i32>; c.len()]; q.len()];
+    let mut from = vec![vec![0usize; c.len()]; q.len()];
+    for i in 0..q.len() {
+        for j in i..c.len() {
+            if lower[j] != q[i] {
+                continue;
+            }
+            let base = SCORE_MATCH + if boundary(j) { BONUS_BOUNDARY } else { 0 };
+            if i == 0 {
+                best[0][j] = Some(base - (j as i32 * PENALTY_LEADING).min(MAX_LEADING_PENALTY));
+                continue;
+            }
+            for k in (i - 1)..j {
+                let Some(prev) = best[i - 1][k] else {
+                    continue;
+                };
+                let link = if k + 1 == j {
+                    BONUS_CONSECUTIVE
+                } else {
+                    -((j - k - 1) as i32 * PENALTY_GAP)
+                };
+                let score = prev + base + link;
+                if best[i][j].is_none_or(|b| score > b) {
+                    best[i][j] = Some(score);
+                    from[i][j] = k;
+                }
+            }
+        }
+    }
+
+    let last = q.len() - 1;
+    let (mut j, score) = (0..c.len())
+        .filter_map(|j| best[last][j].map(|s| (j, s)))
+        .max_by_key(|(_, s)| *s)?;
+    let mut positions = vec![j; q.len()];
+    for i in (1..q.len()).rev() {
+        j = from[i][j];
+        positions[i - 1] = j;
+    }
+    Some((score, positions))
 }
 
 /// What happens when a result is selected
@@ -70,6 +155,10 @@ pub enum OmniAction {
     Execute { command: String },
     Navigate { path: String },
     Search { query: String },
+    /// Pipe the newest `from` facet's output into the newest `to` facet.
+    Pipe { from: String, to: String },
+    /// Save the focused facet's document to `path`.
+    SaveAs { path: String },
 }
 
 /// The Omni-Bar
@@ -79,6 +168,8 @@ pub struct OmniBar {
     cursor_pos: usize,
     results: Vec<OmniResult>,
     selected_index: usize,
+    /// Everything fuzzy matching ranks over.
+    catalog: Vec<CatalogEntry>,
 
     // Animations
     opacity: Animation,
@@ -94,12 +185,51 @@ impl OmniBar {
             cursor_pos: 0,
             results: Vec::new(),
             selected_index: 0,
+            catalog: Vec::new(),
             opacity: Animation::new(0.0),
             scale: Animation::new(0.95),
             y_offset: Animation::new(-20.0),
         }
     }
 
+    /// Offer the given facets (ids from the `FacetRegistry`) as results.
+    pub fn with_facets(mut self, ids: &[&str]) -> Self {
+        let mut ids = ids.to_vec();
+        ids.sort();
+        for id in ids {
+            let entry = match FACET_ENTRIES.iter().find(|e| e.0 == id) {
+                Some((_, title, subtitle, icon, keywords)) => CatalogEntry {
+                    result: OmniResult {
+                        title: title.to_string(),
+                        subtitle: Some(subtitle.to_string()),
+                        icon: Some(icon.to_string()),
+                        action: OmniAction::OpenFacet { name: id.to_string() },
+                        matched: Vec::new(),
+                    },
+                    keywords: keywords.to_vec(),
+                },
+                None => {
+                    let mut title: Vec<char> = id.chars().collect();
+                    if let Some(first) = title.first_mut() {
+                        *first = first.to_ascii_uppercase();
+                    }
+                    CatalogEntry {
+                        result: OmniResult {
+                            title: title.into_iter().collect(),
+                            subtitle: Some(format!("Open {} facet", id)),
+                            icon: None,
+                            action: OmniAction::OpenFacet { name: id.to_string() },
+                            matched: Vec::new(),
+                        },
+                        keywords: Vec::new(),
+                    }
+                }
+            };
+            self.catalog.push(entry);
+        }
+        self
+    }
+
     pub fn is_visible(&self) -> bool {
         self.visible || self.opacity.value() > 0.01
     }
@@ -117,6 +247,14 @@ impl OmniBar {
         self.y_offset.set_target(0.0);
     }
 
+    /// Show the bar with `text` already typed, e.g. to ask for an argument.
+    pub fn prompt(&mut self, text: &str) {
+        self.show();
+        self.input_text = text.to_string();
+        self.cursor_pos = self.input_text.len();
+        self.update_results();
+    }
+
     pub fn hide(&mut self) {
         self.visible = false;
 
@@ -134,7 +272,8 @@ impl OmniBar {
         }
     }
 
-    pub fn handle_key(&mut self, key: Key) {
+    /// Handle a key press. Returns the chosen action when Enter selects a result.
+    pub fn handle_key(&mut self, key: Key) -> Option<OmniAction> {
         match key {
             Key::Backspace => {
                 if self.cursor_pos > 0 {
@@ -170,7 +309,7 @@ impl OmniBar {
                 }
             }
             Key::Enter => {
-                self.execute_selected();
+                return self.execute_selected();
             }
             Key::Tab => {
                 // Autocomplete
@@ -181,6 +320,7 @@ impl OmniBar {
             }
             _ => {}
         }
+        None
     }
 
     pub fn handle_char(&mut self, c: char) {
@@ -204,67 +344,108 @@ impl OmniBar {
             return;
         }
 
-        let query = self.input_text.to_lowercase();
+        let query = self.input_text.trim().to_lowercase();
+
+        // Rank the catalog by the best of its title and keyword scores;
+        // only title matches are highlighted
+        let mut scored: Vec<(i32, OmniResult)> = Vec::new();
+        for entry in &self.catalog {
+            let title = fuzzy_match(&query, &entry.result.title);
+            let keyword = entry
+                .keywords
+                .iter()
+                .filter_map(|k| fuzzy_match(&query, k).map(|(score, _)| score))
+                .max();
+            let mut result = entry.result.clone();
+            let score = match (title, keyword) {
+                (Some((t, matched)), Some(k)) if t >= k => {
+                    result.matched = matched;
+                    t
+                }
+                (Some((t, matched)), None) => {
+                    result.matched = matched;
+                    t
+                }
+                (_, Some(k)) => k,
+                (None, None) => continue,
+            };
+            scored.push((score, result));
+        }
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.results.extend(scored.into_iter().map(|(_, r)| r));
 
-        // Built-in commands
-        if query.starts_with("term") || query.starts_with("shell") {
+        // System commands stay exact so a stray keystroke can't quit
+        let power = query == "power";
+        if power || query == "quit" || query == "exit" || query == "logout" {
             self.results.push(OmniResult {
-                title: "Terminal".to_string(),
-                subtitle: Some("Open command line".to_string()),
-                icon: Some("terminal".to_string()),
-                action: OmniAction::OpenFacet {
-                    name: "terminal".to_string(),
+                title: "Quit Nebula".to_string(),
+                subtitle: Some("Exit to console".to_string()),
+                icon: Some("power".to_string()),
+                action: OmniAction::Execute {
+                    command: "quit".to_string(),
                 },
+                matched: Vec::new(),
             });
         }
-
-        if query.starts_with("write") || query.starts_with("edit") || query.starts_with("note") {
+        if power || query == "shutdown" || query == "poweroff" {
             self.results.push(OmniResult {
-                title: "Write".to_string(),
-                subtitle: Some("Open text editor".to_string()),
-                icon: Some("edit".to_string()),
-                action: OmniAction::OpenFacet {
-                    name: "editor".to_string(),
+                title: "Shut Down".to_string(),
+                subtitle: Some("Power off after 3s; Esc cancels".to_string()),
+                icon: Some("power".to_string()),
+                action: OmniAction::Execute {
+                    command: "shutdown".to_string(),
                 },
+                matched: Vec::new(),
             });
         }
-
-        if query.starts_with("file") || query.starts_with("browse") {
+        if power || query == "reboot" || query == "restart" {
             self.results.push(OmniResult {
-                title: "Files".to_string(),
-                subtitle: Some("Browse filesystem".to_string()),
-                icon: Some("folder".to_string()),
-                action: OmniAction::OpenFacet {
-                    name: "files".to_string(),
+                title: "Reboot".to_string(),
+                subtitle: Some("Restart after 3s; Esc cancels".to_string()),
+                icon: Some("power".to_string()),
+                action: OmniAction::Execute {
+                    command: "reboot".to_string(),
                 },
+                matched: Vec::new(),
             });
         }
 
-        if query.starts_with("set") || query.starts_with("pref") {
-            self.results.push(OmniResult {
-                title: "Settings".to_string(),
-                subtitle: Some("System preferences".to_string()),
-                icon: Some("settings".to_string()),
-                action: OmniAction::OpenFacet {
-                    name: "settings".to_string(),
-                },
-            });
+        // "pipe files to editor", "pipe files editor"
+        if let Some(rest) = query.strip_prefix("pipe ") {
+            let ends: Vec<&str> = rest
+                .split_whitespace()
+                .filter(|w| !matches!(*w, "to" | "into" | "->"))
+                .collect();
+            if let [from, to] = ends[..] {
+                self.results.push(OmniResult {
+                    title: format!("Pipe {} \u{2192} {}", from, to),
+                    subtitle: Some(format!("Send {}'s output to {}", from, to)),
+                    icon: Some("link".to_string()),
+                    action: OmniAction::Pipe {
+                        from: from.to_string(),
+                        to: to.to_string(),
+                    },
+                    matched: Vec::new(),
+                });
+            }
         }
 
-        // System commands
-        if query == "quit" || query == "exit" || query == "logout" {
-            self.results.push(OmniResult {
-                title: "Quit Nebula".to_string(),
-                subtitle: Some("Exit to console".to_string()),
-                icon: Some("power".to_string()),
-                action: OmniAction::Execute {
-                    command: "quit".to_string(),
-                },
-            });
+        // "save as <path>", keeping the path's case
+        if query.starts_with("save as ") {
+            let path = self.input_text.trim()["save as ".len()..].trim();
+            if !path.is_empty() {
+                self.results.insert(0, OmniResult {
+                    title: format!("Save as {}", path),
+                    subtitle: Some("Save the focused document".to_string()),
+                    icon: Some("save".to_string()),
+                    action: OmniAction::SaveAs { path: path.to_string() },
+                    matched: Vec::new(),
+                });
+            }
         }
 
-        // Fallback: treat as search
-        if self.results.is_empty() && self.input_text.len() > 2 {
+        // Fallback, always last: treat as search
+        if self.input_text.len() > 2 {
             self.results.push(OmniResult {
                 title: format!("Search for \"{}\"", self.input_text),
                 subtitle: Some("Search files and content".to_string()),
@@ -272,33 +453,24 @@ impl OmniBar {
                 action: OmniAction::Search {
                     query: self.input_text.clone(),
                 },
+                matched: Vec::new(),
             });
         }
     }
 
-    fn execute_selected(&mut self) {
-        if let Some(result) = self.results.get(self.selected_index) {
-            match &result.action {
-                OmniAction::OpenFacet { name } => {
-                    tracing::info!("Opening facet: {}", name);
-                    // TODO: Actually open facet
-                }
-                OmniAction::Execute { command } => {
-                    tracing::info!("Executing: {}", command);
-                    // TODO: Execute command
-                }
-                OmniAction::Navigate { path } => {
-                    tracing::info!("Navigating to: {}", path);
-                    // TODO: Navigate
-                }
-                OmniAction::Search { query } => {
-                    tracing::info!("Searching: {}", query);
-                    // TODO: Search
-                }
-            }
-        }
-
+    /// Close the bar and hand the selected action back to the shell.
+    fn execute_selected(&mut self) -> Option<OmniAction> {
+        let action = self
+            .results
+            .get(self.selected_index)
+            .map(|result| result.action.clone());
         self.hide();
+        action
+    }
+
+    /// Whether the show/hide spring is still moving.
+    pub fn is_animating(&self) -> bool {
+        !(self.opacity.is_settled() && self.scale.is_settled() && self.y_offset.is_settled())
     }
 
     pub fn update(&mut self, dt: f32) {
@@ -317,7 +489,13 @@ impl OmniBar {
         let y_offset = self.y_offset.value();
 
         let center = renderer.center();
-        let bar_width = 600.0 * scale;
+        let text_size = 20.0 * scale;
+        let text_pad = 20.0;
+
+        // Grow the bar with the input, up to 90% of the screen
+        let max_width = (renderer.width() as f32 * 0.9).max(600.0 * scale);
+        let input_width = renderer.measure_text(&self.input_text, text_size) + text_pad * 2.0;
+        let bar_width = input_width.clamp(600.0 * scale, max_width);
         let bar_height = 56.0 * scale;
 
         // Background blur (conceptual - actual blur requires shader)
@@ -344,9 +522,9 @@ impl OmniBar {
         renderer.draw_rect(
             glow_rect,
             Color::rgba(
-                Color::ACCENT.r,
-                Color::ACCENT.g,
-                Color::ACCENT.b,
+                Color::accent().r,
+                Color::accent().g,
+                Color::accent().b,
                 0.3 * opacity,
             ),
             16.0,
@@ -364,11 +542,25 @@ impl OmniBar {
             12.0,
         );
 
-        // Input text
+        // Input text, clipped to the bar. If it still overflows, scroll so
+        // the cursor stays in view.
+        let avail = bar_width - text_pad * 2.0;
+        let cursor = self.cursor_pos.min(self.input_text.len());
+        let mut start = 0;
+        while start < cursor
+            && renderer.measure_text(&self.input_text[start..cursor], text_size) > avail
+        {
+            start += self.input_text[start..].chars().next().map_or(1, |c| c.len_utf8());
+        }
+        let mut end = self.input_text.len();
+        while end > cursor && renderer.measure_text(&self.input_text[start..end], text_size) > avail {
+            end -= self.input_text[..end].chars().next_back().map_or(1, |c| c.len_utf8());
+        }
+
         let text = if self.input_text.is_empty() {
             "What would you like to do?"
         } else {
-            &self.input_text
+            &self.input_text[start..end]
         };
 
         let text_color = if self.input_text.is_empty() {
@@ -384,18 +576,19 @@ impl OmniBar {
 
         renderer.draw_text(
             text,
-            Vec2::new(bar_rect.x + 20.0, bar_rect.y + bar_height / 2.0 - 10.0),
-            20.0 * scale,
+            Vec2::new(bar_rect.x + text_pad, bar_rect.y + bar_height / 2.0 - 10.0),
+            text_size,
             text_color,
         );
 
-        // Cursor
+        // Cursor, at the measured width of the text before it
         if self.visible && !self.input_text.is_empty() {
-            // Simple cursor rendering (would need proper text measurement)
-            let cursor_x = bar_rect.x + 20.0 + (self.cursor_pos as f32 * 10.0);
+            let cursor_x = bar_rect.x
+                + text_pad
+                + renderer.measure_text(&self.input_text[start..cursor], text_size);
             renderer.draw_rect(
                 Rect::new(cursor_x, bar_rect.y + 14.0, 2.0, bar_height - 28.0),
-                Color::rgba(Color::ACCENT.r, Color::ACCENT.g, Color::ACCENT.b, opacity),
+                Color::rgba(Color::accent().r, Color::accent().g, Color::accent().b, opacity),
                 1.0,
             );
         }
@@ -417,22 +610,36 @@ impl OmniBar {
                     renderer.draw_rect(
                         result_rect,
                         Color::rgba(
-                            Color::ACCENT.r,
-                            Color::ACCENT.g,
-                            Color::ACCENT.b,
+                            Color::accent().r,
+                            Color::accent().g,
+                            Color::accent().b,
                             0.2 * opacity,
                         ),
                         8.0,
                     );
                 }
 
-                // Title
-                renderer.draw_text(
-                    &result.title,
-                    Vec2::new(result_rect.x + 16.0, result_rect.y + 12.0),
-                    16.0 * scale,
-                    Color::rgba(Color::TEXT.r, Color::TEXT.g, Color::TEXT.b, opacity),
-                );
+                // Title, drawn in runs so matched characters stand out
+                let title_size = 16.0 * scale;
+                let mut x = result_rect.x + 16.0;
+                let chars: Vec<char> = result.title.chars().collect();
+                let mut i = 0;
+                while i < chars.len() {
+                    let hit = result.matched.contains(&i);
+                    let run_end = (i..chars.len())
+                        .find(|j| result.matched.contains(j) != hit)
+                        .unwrap_or(chars.len());
+                    let run: String = chars[i..run_end].iter().collect();
+                    let color = if hit { Color::accent() } else { Color::TEXT };
+                    renderer.draw_text(
+                        &run,
+                        Vec2::new(x, result_rect.y + 12.0),
+                        title_size,
+                        Color::rgba(color.r, color.g, color.b, opacity),
+                    );
+                    x += renderer.measure_text(&run, title_size);
+                    i = run_end;
+                }
 
                 // Subtitle
                 if let Some(subtitle) = &result.subtitle {
@@ -458,3 +665,44 @@ impl Default for OmniBar {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The facet the top result opens after typing `query`.
+    fn top_facet(query: &str) -> Option<String> {
+        let mut bar = OmniBar::new().with_facets(&["terminal", "editor", "files", "settings"]);
+        for c in query.chars() {
+            bar.handle_char(c);
+        }
+        match &bar.results.first()?.action {
+            OmniAction::OpenFacet { name } => Some(name.clone()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn abbreviations_find_their_facet() {
+        assert_eq!(top_facet("edt").as_deref(), Some("editor"));
+        assert_eq!(top_facet("term").as_deref(), Some("terminal"));
+        assert_eq!(top_facet("sett").as_deref(), Some("settings"));
+    }
+
+    #[test]
+    fn fuzzy_match_needs_a_subsequence() {
+        assert!(fuzzy_match("edt", "editor").is_some());
+        assert!(fuzzy_match("tde", "editor").is_none());
+        assert!(fuzzy_match("", "editor").is_none());
+    }
+
+    #[test]
+    fn fuzzy_match_prefers_runs_and_word_starts() {
+        let (run, positions) = fuzzy_match("term", "Terminal").unwrap();
+        assert_eq!(positions, vec![0, 1, 2, 3]);
+        let (scattered, _) = fuzzy_match("term", "the rum").unwrap();
+        assert!(run > scattered);
+        // "fb" hits both word starts of "foo bar"
+        assert_eq!(fuzzy_match("fb", "foo bar").unwrap().1, vec![0, 4]);
+    }
+}
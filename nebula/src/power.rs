@@ -0,0 +1,104 @@
+//! Power Actions
+//!
+//! Shutting down and rebooting from the omnibar. A chosen action waits out
+//! a short undo window, then syncs disks and hands over to reboot(2), or to
+//! systemctl when Nebula lacks the privilege. If neither is allowed, the
+//! command to run by hand is logged rather than failing silently.
+
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use instant::Instant;
+
+/// How long a chosen action waits, so Escape can still stop it.
+pub const UNDO_WINDOW: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerAction {
+    Shutdown,
+    Reboot,
+}
+
+impl PowerAction {
+    /// The omnibar command naming this action.
+    pub fn parse(command: &str) -> Option<Self> {
+        match command {
+            "shutdown" => Some(Self::Shutdown),
+            "reboot" => Some(Self::Reboot),
+            _ => None,
+        }
+    }
+
+    /// "Shutting down", "Rebooting"
+    pub fn progressive(self) -> &'static str {
+        match self {
+            Self::Shutdown => "Shutting down",
+            Self::Reboot => "Rebooting",
+        }
+    }
+
+    /// The command to run when Nebula can't do it itself.
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Shutdown => "systemctl poweroff",
+            Self::Reboot => "systemctl reboot",
+        }
+    }
+}
+
+/// A power action counting down to `at`.
+#[derive(Clone, Copy, Debug)]
+pub struct Pending {
+    pub action: PowerAction,
+    pub at: Instant,
+}
+
+impl Pending {
+    pub fn new(action: PowerAction) -> Self {
+        Self {
+            action,
+            at: Instant::now() + UNDO_WINDOW,
+        }
+    }
+
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.at
+    }
+
+    /// Whole seconds left, rounded up, for the countdown.
+    pub fn seconds_left(&self) -> u64 {
+        let left = self.at.saturating_duration_since(Instant::now());
+        left.as_millis().div_ceil(1000) as u64
+    }
+}
+
+/// Sync disks and power off or reboot: reboot(2) when we hold CAP_SYS_BOOT,
+/// else systemctl. On failure, the error says what to run by hand.
+pub fn perform(action: PowerAction) -> Result<(), String> {
+    #[cfg(target_os = "linux")]
+    {
+        // SAFETY: sync(2) takes no arguments and cannot fail
+        unsafe { libc::sync() };
+        let cmd = match action {
+            PowerAction::Shutdown => libc::RB_POWER_OFF,
+            PowerAction::Reboot => libc::RB_AUTOBOOT,
+        };
+        // SAFETY: reboot(2) takes no pointers; it only returns when refused
+        if unsafe { libc::reboot(cmd) } == 0 {
+            return Ok(());
+        }
+    }
+
+    let mut args = action.command().split_whitespace();
+    let program = args.next().unwrap_or_default();
+    let ran = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    match ran {
+        Ok(status) if status.success() => Ok(()),
+        _ => Err(format!("Not permitted. Run as root: {}", action.command())),
+    }
+}
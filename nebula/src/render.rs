@@ -1,17 +1,22 @@
 //! GPU Renderer
 //!
 //! Abstracts over wgpu to provide a simple 2D rendering API.
-//! Uses a CPU-side pixel buffer for 2D primitives, uploaded as a texture each frame.
-//! In development: uses winit window
-//! In production: uses DRM/KMS directly
-
+//! Rects are drawn on the GPU as instanced SDF quads. Text is drawn as
+//! instanced quads sampling a TTF glyph atlas texture. If no font parses,
+//! the built-in bitmap font is rasterized into CPU pixel buffers instead,
+//! one texture per text layer, re-uploaded only when that layer changes.
+//! In development: draws to a winit window surface
+//! In production: draws offscreen and hands the pixels to DRM/KMS (see kms.rs)
+
+use crate::font::{GlyphAtlas, ATLAS_SIZE};
 use anyhow::Result;
 use glam::Vec2;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tracing::info;
 
 /// Colors with alpha
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -33,9 +38,13 @@ impl Color {
     pub const SURFACE: Self = Self::rgb(0.08, 0.08, 0.12);
     pub const TEXT: Self = Self::rgb(0.9, 0.9, 0.92);
     pub const TEXT_DIM: Self = Self::rgba(0.9, 0.9, 0.92, 0.5);
-    pub const ACCENT: Self = Self::rgb(0.4, 0.6, 1.0);
     pub const GLOW: Self = Self::rgba(0.4, 0.6, 1.0, 0.3);
 
+    /// The accent chosen in settings (see `set_accent`).
+    pub fn accent() -> Self {
+        ACCENTS[ACCENT_INDEX.load(Ordering::Relaxed) % ACCENTS.len()].1
+    }
+
     fn to_rgba8(&self) -> [u8; 4] {
         [
             (self.r.clamp(0.0, 1.0) * 255.0) as u8,
@@ -46,8 +55,26 @@ impl Color {
     }
 }
 
+/// Accents the settings facet cycles through, by name; the first is the default.
+pub const ACCENTS: &[(&str, Color)] = &[
+    ("blue", Color::rgb(0.4, 0.6, 1.0)),
+    ("violet", Color::rgb(0.65, 0.5, 1.0)),
+    ("teal", Color::rgb(0.3, 0.8, 0.75)),
+    ("amber", Color::rgb(1.0, 0.7, 0.3)),
+];
+
+/// Index into `ACCENTS` of the accent `Color::accent` returns.
+static ACCENT_INDEX: AtomicUsize = AtomicUsize::new(0);
+
+/// Switch the accent to the named entry of `ACCENTS`; unknown names fall back
+/// to the default.
+pub fn set_accent(name: &str) {
+    let index = ACCENTS.iter().position(|(n, _)| *n == name).unwrap_or(0);
+    ACCENT_INDEX.store(index, Ordering::Relaxed);
+}
+
 /// A rectangle for rendering
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Rect {
     pub x: f32,
     pub y: f32,
@@ -75,7 +102,7 @@ impl Rect {
 }
 
 /// Render commands that accumulate during a frame
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum RenderCommand {
     Clear(Color),
     Rect {
@@ -95,21 +122,116 @@ pub enum RenderCommand {
     },
 }
 
+/// Floats per rect instance: position (2), size (2), color (4), radius (1).
+const RECT_INSTANCE_FLOATS: usize = 9;
+
+/// Floats per glyph instance: position (2), size (2), atlas uv rect (4), color (4).
+const GLYPH_INSTANCE_FLOATS: usize = 12;
+
+/// A bitmap-font text overlay texture. Commands are split into layers
+/// wherever a rect follows text, so the painter's order of rects and text is
+/// preserved.
+struct TextLayer {
+    texture: wgpu::Texture,
+    bind_group: wgpu::BindGroup,
+    /// Text commands last rasterized into `texture`, to skip unchanged uploads.
+    drawn: Vec<RenderCommand>,
+}
+
+/// One slice of the frame: rects drawn first, then text on top.
+#[derive(Default)]
+struct Layer {
+    rects: std::ops::Range<u32>,
+    texts: Vec<RenderCommand>,
+    /// Instances of `texts` in the glyph buffer, when drawing from the atlas.
+    glyphs: std::ops::Range<u32>,
+}
+
+/// Where finished frames go
+enum Target {
+    /// A window surface, presented by wgpu
+    Surface {
+        surface: wgpu::Surface<'static>,
+        config: wgpu::SurfaceConfiguration,
+    },
+    /// An offscreen texture copied into `frame` after each frame, for
+    /// presenting without a windowing system
+    Offscreen {
+        texture: wgpu::Texture,
+        readback: wgpu::Buffer,
+        frame: Vec<u8>,
+    },
+}
+
+/// Bytes per row of an offscreen frame, padded as texture copies require.
+fn padded_row_bytes(width: u32) -> u32 {
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    (width * 4).div_ceil(align) * align
+}
+
+/// Offscreen target of the given size, in `OFFSCREEN_FORMAT`.
+fn offscreen_target(device: &wgpu::Device, width: u32, height: u32) -> Target {
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("Offscreen Frame"),
+        size: wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: OFFSCREEN_FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+    let size = (padded_row_bytes(width) * height) as u64;
+    let readback = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("Offscreen Readback"),
+        size,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    Target::Offscreen {
+        texture,
+        readback,
+        frame: vec![0u8; size as usize],
+    }
+}
+
+/// BGRA byte order, which is XRGB8888 in memory, so frames copy straight
+/// into a DRM dumb buffer.
+const OFFSCREEN_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Bgra8Unorm;
+
 /// The renderer
 pub struct Renderer {
     width: u32,
     height: u32,
     commands: Vec<RenderCommand>,
+    /// Scratch buffer for rasterizing a bitmap-font text layer.
     pixels: Vec<u8>,
+    /// TTF glyphs; `None` falls back to the 5x7 bitmap font.
+    atlas: Option<GlyphAtlas>,
 
     // wgpu state
     device: wgpu::Device,
     queue: wgpu::Queue,
-    surface: wgpu::Surface<'static>,
-    surface_config: wgpu::SurfaceConfiguration,
-    render_pipeline: wgpu::RenderPipeline,
-    texture: wgpu::Texture,
-    texture_bind_group: wgpu::BindGroup,
+    target: Target,
+    format: wgpu::TextureFormat,
+    blit_pipeline: wgpu::RenderPipeline,
+    blit_bind_group_layout: wgpu::BindGroupLayout,
+    sampler: wgpu::Sampler,
+    text_layers: Vec<TextLayer>,
+    rect_pipeline: wgpu::RenderPipeline,
+    screen_buffer: wgpu::Buffer,
+    screen_bind_group: wgpu::BindGroup,
+    instance_buffer: wgpu::Buffer,
+    instance_capacity: usize,
+    glyph_pipeline: wgpu::RenderPipeline,
+    atlas_texture: wgpu::Texture,
+    atlas_bind_group: wgpu::BindGroup,
+    glyph_buffer: wgpu::Buffer,
+    glyph_capacity: usize,
 }
 
 impl Renderer {
@@ -127,23 +249,7 @@ impl Renderer {
 
         let surface = instance.create_surface(window)?;
 
-        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-            power_preference: wgpu::PowerPreference::LowPower,
-            compatible_surface: Some(&surface),
-            force_fallback_adapter: false,
-        }))
-        .ok_or_else(|| anyhow::anyhow!("Failed to find a suitable GPU adapter"))?;
-
-        info!("Using GPU: {}", adapter.get_info().name);
-
-        let (device, queue) = pollster::block_on(adapter.request_device(
-            &wgpu::DeviceDescriptor {
-                label: Some("Nebula Device"),
-                required_features: wgpu::Features::empty(),
-                required_limits: wgpu::Limits::default(),
-            },
-            None,
-        ))?;
+        let (adapter, device, queue) = request_device(&instance, Some(&surface))?;
 
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
@@ -158,39 +264,51 @@ impl Renderer {
             format: surface_format,
             width,
             height,
-            present_mode: wgpu::PresentMode::AutoVsync,
+            present_mode: present_mode(&surface_caps.present_modes),
             alpha_mode: surface_caps.alpha_modes[0],
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
         surface.configure(&device, &surface_config);
 
-        // Create the pixel buffer texture
-        let texture = device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Framebuffer Texture"),
-            size: wgpu::Extent3d {
-                width,
-                height,
-                depth_or_array_layers: 1,
-            },
-            mip_level_count: 1,
-            sample_count: 1,
-            dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
-            view_formats: &[],
-        });
+        let target = Target::Surface {
+            surface,
+            config: surface_config,
+        };
+        Ok(Self::with_target(device, queue, surface_format, width, height, target))
+    }
 
-        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+    /// A renderer with no window, whose frames are read back with `frame`.
+    #[cfg_attr(not(feature = "bare-metal"), allow(dead_code))]
+    pub fn offscreen(width: u32, height: u32) -> Result<Self> {
+        info!("Initializing offscreen wgpu renderer");
+
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        });
+        let (_, device, queue) = request_device(&instance, None)?;
+        let target = offscreen_target(&device, width, height);
+        Ok(Self::with_target(device, queue, OFFSCREEN_FORMAT, width, height, target))
+    }
 
+    /// Build pipelines and buffers for drawing into `target`.
+    fn with_target(
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        surface_format: wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+        target: Target,
+    ) -> Self {
         let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("Framebuffer Sampler"),
+            label: Some("Text Layer Sampler"),
             mag_filter: wgpu::FilterMode::Nearest,
             min_filter: wgpu::FilterMode::Nearest,
             ..Default::default()
         });
 
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+        let blit_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("Texture Bind Group Layout"),
             entries: &[
                 wgpu::BindGroupLayoutEntry {
@@ -212,33 +330,19 @@ impl Renderer {
             ],
         });
 
-        let texture_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Texture Bind Group"),
-            layout: &bind_group_layout,
-            entries: &[
-                wgpu::BindGroupEntry {
-                    binding: 0,
-                    resource: wgpu::BindingResource::TextureView(&texture_view),
-                },
-                wgpu::BindGroupEntry {
-                    binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
-                },
-            ],
-        });
-
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Fullscreen Blit Shader"),
-            source: wgpu::ShaderSource::Wgsl(BLIT_SHADER.into()),
+            source: wgpu::ShaderSource::Wgsl(shader_source(BLIT_SHADER, surface_format.is_srgb()).into()),
         });
 
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Blit Pipeline Layout"),
-            bind_group_layouts: &[&bind_group_layout],
+            bind_group_layouts: &[&blit_bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        // Text layers hold premultiplied color over a transparent background
+        let blit_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("Blit Pipeline"),
             layout: Some(&pipeline_layout),
             vertex: wgpu::VertexState {
@@ -251,7 +355,164 @@ impl Renderer {
                 entry_point: "fs_main",
                 targets: &[Some(wgpu::ColorTargetState {
                     format: surface_format,
-                    blend: Some(wgpu::BlendState::REPLACE),
+                    blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        // Rect pipeline: screen-size uniform plus a per-instance vertex buffer
+        let screen_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Screen Uniform"),
+            size: 16,
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let screen_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Screen Bind Group Layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::VERTEX,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            }],
+        });
+
+        let screen_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Screen Bind Group"),
+            layout: &screen_bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: screen_buffer.as_entire_binding(),
+            }],
+        });
+
+        let rect_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Rounded Rect Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source(RECT_SHADER, surface_format.is_srgb()).into()),
+        });
+
+        let rect_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Rect Pipeline Layout"),
+            bind_group_layouts: &[&screen_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let rect_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Rect Pipeline"),
+            layout: Some(&rect_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &rect_shader,
+                entry_point: "vs_rect",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: (RECT_INSTANCE_FLOATS * 4) as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x2,
+                        2 => Float32x4,
+                        3 => Float32,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &rect_shader,
+                entry_point: "fs_rect",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                ..Default::default()
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        });
+
+        let instance_capacity = 256;
+        let instance_buffer = create_instance_buffer(&device, "Rect Instances", RECT_INSTANCE_FLOATS, instance_capacity);
+
+        // Glyph pipeline: the screen uniform plus the coverage atlas
+        let atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Glyph Atlas"),
+            size: wgpu::Extent3d {
+                width: ATLAS_SIZE as u32,
+                height: ATLAS_SIZE as u32,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let atlas_view = atlas_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let atlas_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Glyph Atlas Bind Group"),
+            layout: &blit_bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(&atlas_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(&sampler),
+                },
+            ],
+        });
+
+        let glyph_shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Glyph Shader"),
+            source: wgpu::ShaderSource::Wgsl(shader_source(GLYPH_SHADER, surface_format.is_srgb()).into()),
+        });
+
+        let glyph_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Glyph Pipeline Layout"),
+            bind_group_layouts: &[&screen_bind_group_layout, &blit_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let glyph_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Glyph Pipeline"),
+            layout: Some(&glyph_pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &glyph_shader,
+                entry_point: "vs_glyph",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: (GLYPH_INSTANCE_FLOATS * 4) as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Instance,
+                    attributes: &wgpu::vertex_attr_array![
+                        0 => Float32x2,
+                        1 => Float32x2,
+                        2 => Float32x4,
+                        3 => Float32x4,
+                    ],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &glyph_shader,
+                entry_point: "fs_glyph",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: surface_format,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
                     write_mask: wgpu::ColorWrites::ALL,
                 })],
             }),
@@ -264,24 +525,39 @@ impl Renderer {
             multiview: None,
         });
 
+        let glyph_capacity = 1024;
+        let glyph_buffer = create_instance_buffer(&device, "Glyph Instances", GLYPH_INSTANCE_FLOATS, glyph_capacity);
+
         let pixel_count = (width * height * 4) as usize;
         let pixels = vec![0u8; pixel_count];
 
         info!("Renderer initialized: {}x{}", width, height);
 
-        Ok(Self {
+        Self {
             width,
             height,
             commands: Vec::new(),
             pixels,
+            atlas: GlyphAtlas::load(),
             device,
             queue,
-            surface,
-            surface_config,
-            render_pipeline,
-            texture,
-            texture_bind_group,
-        })
+            target,
+            format: surface_format,
+            blit_pipeline,
+            blit_bind_group_layout,
+            sampler,
+            text_layers: Vec::new(),
+            rect_pipeline,
+            screen_buffer,
+            screen_bind_group,
+            instance_buffer,
+            instance_capacity,
+            glyph_pipeline,
+            atlas_texture,
+            atlas_bind_group,
+            glyph_buffer,
+            glyph_capacity,
+        }
     }
 
     pub fn resize(&mut self, new_width: u32, new_height: u32) {
@@ -293,40 +569,43 @@ impl Renderer {
 
         self.width = new_width;
         self.height = new_height;
-        self.surface_config.width = new_width;
-        self.surface_config.height = new_height;
-        self.surface.configure(&self.device, &self.surface_config);
+        match &mut self.target {
+            Target::Surface { surface, config } => {
+                config.width = new_width;
+                config.height = new_height;
+                surface.configure(&self.device, config);
+            }
+            Target::Offscreen { .. } => {
+                self.target = offscreen_target(&self.device, new_width, new_height);
+            }
+        }
 
-        // Recreate pixel buffer and texture
+        // Text layers are recreated at the new size on the next frame
         self.pixels = vec![0u8; (new_width * new_height * 4) as usize];
+        self.text_layers.clear();
+    }
 
-        self.texture = self.device.create_texture(&wgpu::TextureDescriptor {
-            label: Some("Framebuffer Texture"),
+    /// Create a screen-sized text layer texture.
+    fn create_text_layer(&self) -> TextLayer {
+        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Text Layer Texture"),
             size: wgpu::Extent3d {
-                width: new_width,
-                height: new_height,
+                width: self.width,
+                height: self.height,
                 depth_or_array_layers: 1,
             },
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            format: wgpu::TextureFormat::Rgba8Unorm,
             usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
             view_formats: &[],
         });
 
-        let texture_view = self.texture.create_view(&wgpu::TextureViewDescriptor::default());
-        let sampler = self.device.create_sampler(&wgpu::SamplerDescriptor {
-            label: Some("Framebuffer Sampler"),
-            mag_filter: wgpu::FilterMode::Nearest,
-            min_filter: wgpu::FilterMode::Nearest,
-            ..Default::default()
-        });
-
-        let bind_group_layout = self.render_pipeline.get_bind_group_layout(0);
-        self.texture_bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-            label: Some("Texture Bind Group"),
-            layout: &bind_group_layout,
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Text Layer Bind Group"),
+            layout: &self.blit_bind_group_layout,
             entries: &[
                 wgpu::BindGroupEntry {
                     binding: 0,
@@ -334,10 +613,16 @@ impl Renderer {
                 },
                 wgpu::BindGroupEntry {
                     binding: 1,
-                    resource: wgpu::BindingResource::Sampler(&sampler),
+                    resource: wgpu::BindingResource::Sampler(&self.sampler),
                 },
             ],
         });
+
+        TextLayer {
+            texture,
+            bind_group,
+            drawn: Vec::new(),
+        }
     }
 
     pub fn width(&self) -> u32 {
@@ -374,67 +659,70 @@ impl Renderer {
         });
     }
 
+    /// Width in pixels `text` will occupy when drawn at `size`.
+    /// Matches the advance used to place glyphs.
+    pub fn measure_text(&self, text: &str, size: f32) -> f32 {
+        match &self.atlas {
+            Some(atlas) => text.chars().map(|ch| atlas.advance(ch, size)).sum(),
+            None => (text.chars().count() as i32 * glyph_advance(size)) as f32,
+        }
+    }
+
     pub fn draw_blur(&mut self, rect: Rect, radius: f32) {
         self.commands.push(RenderCommand::Blur { rect, radius });
     }
 
     pub fn end_frame(&mut self) -> Result<()> {
-        // Rasterize commands to pixel buffer
-        let commands = self.commands.clone();
-        for cmd in &commands {
-            match cmd {
-                RenderCommand::Clear(color) => {
-                    self.raster_clear(color);
-                }
-                RenderCommand::Rect { rect, color, corner_radius } => {
-                    self.raster_rect(rect, color, *corner_radius);
+        let commands = std::mem::take(&mut self.commands);
+        let (clear, mut layers, instances) = build_layers(&commands);
+        self.commands = commands;
+
+        self.upload_rects(&instances);
+        let srgb = self.format.is_srgb();
+        let screen = [self.width as f32, self.height as f32, 0.0, 0.0];
+        self.queue.write_buffer(&self.screen_buffer, 0, &floats_to_bytes(&screen));
+
+        if let Some(mut atlas) = self.atlas.take() {
+            let glyphs = build_glyphs(&mut atlas, &mut layers);
+            self.upload_atlas(&mut atlas);
+            self.upload_glyphs(&glyphs);
+            self.atlas = Some(atlas);
+        } else {
+            // Re-rasterize only the bitmap text layers whose contents changed
+            for (i, layer) in layers.iter().enumerate() {
+                if self.text_layers.len() <= i {
+                    let text_layer = self.create_text_layer();
+                    self.text_layers.push(text_layer);
                 }
-                RenderCommand::Text { text, position, size, color } => {
-                    self.raster_text(text, *position, *size, color);
-                }
-                RenderCommand::Blur { .. } => {
-                    // Blur is a no-op for now (would need multi-pass)
+                if self.text_layers[i].drawn != layer.texts {
+                    self.raster_text_layer(i, &layer.texts);
                 }
             }
         }
 
-        // Upload pixel buffer to GPU texture
-        self.queue.write_texture(
-            wgpu::ImageCopyTexture {
-                texture: &self.texture,
-                mip_level: 0,
-                origin: wgpu::Origin3d::ZERO,
-                aspect: wgpu::TextureAspect::All,
-            },
-            &self.pixels,
-            wgpu::ImageDataLayout {
-                offset: 0,
-                bytes_per_row: Some(4 * self.width),
-                rows_per_image: Some(self.height),
-            },
-            wgpu::Extent3d {
-                width: self.width,
-                height: self.height,
-                depth_or_array_layers: 1,
-            },
-        );
-
-        // Render the texture to screen
-        let output = self.surface.get_current_texture()?;
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let (output, view) = match &self.target {
+            Target::Surface { surface, .. } => {
+                let output = surface.get_current_texture()?;
+                let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (Some(output), view)
+            }
+            Target::Offscreen { texture, .. } => {
+                (None, texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            }
+        };
 
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Blit Encoder"),
+            label: Some("Frame Encoder"),
         });
 
         {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Blit Pass"),
+                label: Some("Frame Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
                     view: &view,
                     resolve_target: None,
                     ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        load: wgpu::LoadOp::Clear(clear_color(&clear, srgb)),
                         store: wgpu::StoreOp::Store,
                     },
                 })],
@@ -443,18 +731,170 @@ impl Renderer {
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.texture_bind_group, &[]);
-            render_pass.draw(0..6, 0..1);
+            for (i, layer) in layers.iter().enumerate() {
+                if !layer.rects.is_empty() {
+                    render_pass.set_pipeline(&self.rect_pipeline);
+                    render_pass.set_bind_group(0, &self.screen_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.instance_buffer.slice(..));
+                    render_pass.draw(0..6, layer.rects.clone());
+                }
+                if !layer.glyphs.is_empty() {
+                    render_pass.set_pipeline(&self.glyph_pipeline);
+                    render_pass.set_bind_group(0, &self.screen_bind_group, &[]);
+                    render_pass.set_bind_group(1, &self.atlas_bind_group, &[]);
+                    render_pass.set_vertex_buffer(0, self.glyph_buffer.slice(..));
+                    render_pass.draw(0..6, layer.glyphs.clone());
+                } else if !layer.texts.is_empty() && self.atlas.is_none() {
+                    render_pass.set_pipeline(&self.blit_pipeline);
+                    render_pass.set_bind_group(0, &self.text_layers[i].bind_group, &[]);
+                    render_pass.draw(0..6, 0..1);
+                }
+            }
+        }
+
+        if let Target::Offscreen { texture, readback, .. } = &self.target {
+            encoder.copy_texture_to_buffer(
+                texture.as_image_copy(),
+                wgpu::ImageCopyBuffer {
+                    buffer: readback,
+                    layout: wgpu::ImageDataLayout {
+                        offset: 0,
+                        bytes_per_row: Some(padded_row_bytes(self.width)),
+                        rows_per_image: None,
+                    },
+                },
+                texture.size(),
+            );
         }
 
         self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+        match output {
+            Some(output) => output.present(),
+            None => self.read_back()?,
+        }
 
         Ok(())
     }
 
-    // --- Software rasterization ---
+    /// Wait for the offscreen copy and keep it in `frame`.
+    fn read_back(&mut self) -> Result<()> {
+        let Target::Offscreen { readback, frame, .. } = &mut self.target else {
+            return Ok(());
+        };
+        let slice = readback.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv()??;
+        frame.copy_from_slice(&slice.get_mapped_range());
+        readback.unmap();
+        Ok(())
+    }
+
+    /// The last offscreen frame as BGRX rows, with the row stride in bytes.
+    /// `None` when drawing to a window.
+    #[cfg_attr(not(feature = "bare-metal"), allow(dead_code))]
+    pub fn frame(&self) -> Option<(&[u8], usize)> {
+        match &self.target {
+            Target::Offscreen { frame, .. } => Some((frame, padded_row_bytes(self.width) as usize)),
+            Target::Surface { .. } => None,
+        }
+    }
+
+    /// Write rect instances, growing the instance buffer if needed.
+    fn upload_rects(&mut self, instances: &[f32]) {
+        if instances.is_empty() {
+            return;
+        }
+        let count = instances.len() / RECT_INSTANCE_FLOATS;
+        if count > self.instance_capacity {
+            self.instance_capacity = count.next_power_of_two();
+            self.instance_buffer =
+                create_instance_buffer(&self.device, "Rect Instances", RECT_INSTANCE_FLOATS, self.instance_capacity);
+        }
+        self.queue.write_buffer(&self.instance_buffer, 0, &floats_to_bytes(instances));
+    }
+
+    /// Write glyph instances, growing the glyph buffer if needed.
+    fn upload_glyphs(&mut self, glyphs: &[f32]) {
+        if glyphs.is_empty() {
+            return;
+        }
+        let count = glyphs.len() / GLYPH_INSTANCE_FLOATS;
+        if count > self.glyph_capacity {
+            self.glyph_capacity = count.next_power_of_two();
+            self.glyph_buffer =
+                create_instance_buffer(&self.device, "Glyph Instances", GLYPH_INSTANCE_FLOATS, self.glyph_capacity);
+        }
+        self.queue.write_buffer(&self.glyph_buffer, 0, &floats_to_bytes(glyphs));
+    }
+
+    /// Copy the texels rasterized since the last frame into the atlas texture.
+    fn upload_atlas(&self, atlas: &mut GlyphAtlas) {
+        let Some((x0, y0, x1, y1)) = atlas.take_dirty() else {
+            return;
+        };
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.atlas_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: x0 as u32,
+                    y: y0 as u32,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            atlas.pixels(),
+            wgpu::ImageDataLayout {
+                offset: (y0 * ATLAS_SIZE + x0) as u64,
+                bytes_per_row: Some(ATLAS_SIZE as u32),
+                rows_per_image: None,
+            },
+            wgpu::Extent3d {
+                width: (x1 - x0) as u32,
+                height: (y1 - y0) as u32,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+
+    /// Rasterize `texts` in the bitmap font over a transparent buffer and
+    /// upload it as layer `index`.
+    fn raster_text_layer(&mut self, index: usize, texts: &[RenderCommand]) {
+        self.pixels.fill(0);
+        for cmd in texts {
+            if let RenderCommand::Text { text, position, size, color } = cmd {
+                self.raster_text_bitmap(text, *position, *size, color);
+            }
+        }
+
+        let layer = &mut self.text_layers[index];
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &layer.texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &self.pixels,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(4 * self.width),
+                rows_per_image: Some(self.height),
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        layer.drawn = texts.to_vec();
+    }
+
+    // --- Software rasterization (text only) ---
 
     fn set_pixel(&mut self, x: i32, y: i32, color: &Color) {
         if x < 0 || y < 0 || x >= self.width as i32 || y >= self.height as i32 {
@@ -483,66 +923,10 @@ impl Renderer {
         }
     }
 
-    fn raster_clear(&mut self, color: &Color) {
-        let rgba = color.to_rgba8();
-        for chunk in self.pixels.chunks_exact_mut(4) {
-            chunk[0] = rgba[0];
-            chunk[1] = rgba[1];
-            chunk[2] = rgba[2];
-            chunk[3] = rgba[3];
-        }
-    }
-
-    fn raster_rect(&mut self, rect: &Rect, color: &Color, corner_radius: f32) {
-        let x0 = rect.x as i32;
-        let y0 = rect.y as i32;
-        let x1 = (rect.x + rect.width) as i32;
-        let y1 = (rect.y + rect.height) as i32;
-        let cr = corner_radius.min(rect.width / 2.0).min(rect.height / 2.0);
-
-        for py in y0..y1 {
-            for px in x0..x1 {
-                if cr > 0.5 {
-                    // Check if pixel is within rounded corners
-                    let lx = px as f32 - rect.x;
-                    let ly = py as f32 - rect.y;
-                    let rx = rect.width - lx;
-                    let ry = rect.height - ly;
-
-                    let in_corner = if lx < cr && ly < cr {
-                        let dx = cr - lx;
-                        let dy = cr - ly;
-                        dx * dx + dy * dy <= cr * cr
-                    } else if rx < cr && ly < cr {
-                        let dx = cr - rx;
-                        let dy = cr - ly;
-                        dx * dx + dy * dy <= cr * cr
-                    } else if lx < cr && ry < cr {
-                        let dx = cr - lx;
-                        let dy = cr - ry;
-                        dx * dx + dy * dy <= cr * cr
-                    } else if rx < cr && ry < cr {
-                        let dx = cr - rx;
-                        let dy = cr - ry;
-                        dx * dx + dy * dy <= cr * cr
-                    } else {
-                        true
-                    };
-
-                    if in_corner {
-                        self.set_pixel(px, py, color);
-                    }
-                } else {
-                    self.set_pixel(px, py, color);
-                }
-            }
-        }
-    }
-
-    fn raster_text(&mut self, text: &str, position: Vec2, size: f32, color: &Color) {
+    fn raster_text_bitmap(&mut self, text: &str, position: Vec2, size: f32, color: &Color) {
         // Simple bitmap font rendering -- each glyph is a 5x7 pixel grid scaled to `size`
-        let scale = (size / 10.0).max(0.5);
-        let glyph_w = (6.0 * scale) as i32;
+        let scale = glyph_scale(size);
+        let glyph_w = glyph_advance(size);
         let mut cx = position.x as i32;
         let cy = position.y as i32;
 
@@ -570,6 +954,197 @@ impl Renderer {
     }
 }
 
+/// Lay out each layer's text as atlas glyph instances, recording their
+/// ranges in `layer.glyphs`. `position` is the top-left of a line; glyphs sit
+/// on the font's ascent below it.
+fn build_glyphs(atlas: &mut GlyphAtlas, layers: &mut [Layer]) -> Vec<f32> {
+    let uv = |texels: usize| texels as f32 / ATLAS_SIZE as f32;
+    let mut glyphs = Vec::new();
+    // If the atlas fills up mid-frame, the glyphs placed before the reset
+    // point at evicted texels: lay the frame out again into the fresh atlas.
+    for _ in 0..2 {
+        let resets = atlas.resets();
+        glyphs.clear();
+        for layer in layers.iter_mut() {
+            let start = (glyphs.len() / GLYPH_INSTANCE_FLOATS) as u32;
+            for cmd in &layer.texts {
+                let RenderCommand::Text { text, position, size, color } = cmd else {
+                    continue;
+                };
+                let baseline = (position.y + atlas.ascent(*size)).round();
+                let mut pen = position.x;
+                for ch in text.chars() {
+                    let glyph = atlas.glyph(ch, *size);
+                    if glyph.width > 0 && glyph.height > 0 {
+                        glyphs.extend_from_slice(&[
+                            pen.round() + glyph.xmin as f32,
+                            baseline - glyph.top as f32,
+                            glyph.width as f32,
+                            glyph.height as f32,
+                            uv(glyph.x),
+                            uv(glyph.y),
+                            uv(glyph.x + glyph.width),
+                            uv(glyph.y + glyph.height),
+                            color.r,
+                            color.g,
+                            color.b,
+                            color.a,
+                        ]);
+                    }
+                    pen += atlas.advance(ch, *size);
+                }
+            }
+            layer.glyphs = start..(glyphs.len() / GLYPH_INSTANCE_FLOATS) as u32;
+        }
+        if atlas.resets() == resets {
+            break;
+        }
+    }
+    glyphs
+}
+
+/// Split commands into layers and flatten rects into instance data.
+/// A new layer starts whenever a rect follows text, so later rects can
+/// cover earlier text. Returns the clear color, layers and instances.
+fn build_layers(commands: &[RenderCommand]) -> (Color, Vec<Layer>, Vec<f32>) {
+    let mut clear = Color::VOID;
+    let mut layers = vec![Layer::default()];
+    let mut instances = Vec::new();
+
+    for cmd in commands {
+        match cmd {
+            RenderCommand::Clear(color) => clear = *color,
+            RenderCommand::Rect { rect, color, corner_radius } => {
+                let count = (instances.len() / RECT_INSTANCE_FLOATS) as u32;
+                if layers.last().is_some_and(|l| !l.texts.is_empty()) {
+                    layers.push(Layer {
+                        rects: count..count,
+                        ..Default::default()
+                    });
+                }
+                let radius = corner_radius.min(rect.width / 2.0).min(rect.height / 2.0).max(0.0);
+                instances.extend_from_slice(&[
+                    rect.x,
+                    rect.y,
+                    rect.width,
+                    rect.height,
+                    color.r,
+                    color.g,
+                    color.b,
+                    color.a,
+                    radius,
+                ]);
+                if let Some(layer) = layers.last_mut() {
+                    layer.rects.end = count + 1;
+                }
+            }
+            RenderCommand::Text { .. } => {
+                if let Some(layer) = layers.last_mut() {
+                    layer.texts.push(cmd.clone());
+                }
+            }
+            RenderCommand::Blur { .. } => {
+                // Blur is a no-op for now (would need multi-pass)
+            }
+        }
+    }
+
+    (clear, layers, instances)
+}
+
+fn floats_to_bytes(floats: &[f32]) -> Vec<u8> {
+    floats.iter().flat_map(|f| f.to_ne_bytes()).collect()
+}
+
+fn create_instance_buffer(device: &wgpu::Device, label: &str, floats: usize, capacity: usize) -> wgpu::Buffer {
+    device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some(label),
+        size: (capacity * floats * 4) as wgpu::BufferAddress,
+        usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        mapped_at_creation: false,
+    })
+}
+
+/// Clear color for the render pass; sRGB targets expect linear values.
+fn clear_color(color: &Color, srgb: bool) -> wgpu::Color {
+    let channel = |c: f32| {
+        let c = c.clamp(0.0, 1.0) as f64;
+        if srgb {
+            c.powf(2.2)
+        } else {
+            c
+        }
+    };
+    wgpu::Color {
+        r: channel(color.r),
+        g: channel(color.g),
+        b: channel(color.b),
+        a: color.a.clamp(0.0, 1.0) as f64,
+    }
+}
+
+/// `NEBULA_PRESENT_MODE` ("fifo", "mailbox" for lower latency, or
+/// "immediate") if the surface supports it, otherwise vsync.
+fn present_mode(supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    let wanted = match std::env::var("NEBULA_PRESENT_MODE").as_deref() {
+        Ok("fifo") => wgpu::PresentMode::Fifo,
+        Ok("mailbox") => wgpu::PresentMode::Mailbox,
+        Ok("immediate") => wgpu::PresentMode::Immediate,
+        Ok(other) => {
+            tracing::warn!("Unknown NEBULA_PRESENT_MODE '{}', using vsync", other);
+            return wgpu::PresentMode::AutoVsync;
+        }
+        Err(_) => return wgpu::PresentMode::AutoVsync,
+    };
+    if supported.contains(&wanted) {
+        info!("Present mode: {:?}", wanted);
+        wanted
+    } else {
+        tracing::warn!("Present mode {:?} not supported, using vsync", wanted);
+        wgpu::PresentMode::AutoVsync
+    }
+}
+
+/// Pick a GPU, compatible with `surface` if there is one, and open it.
+fn request_device(
+    instance: &wgpu::Instance,
+    surface: Option<&wgpu::Surface<'_>>,
+) -> Result<(wgpu::Adapter, wgpu::Device, wgpu::Queue)> {
+    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+        power_preference: wgpu::PowerPreference::LowPower,
+        compatible_surface: surface,
+        force_fallback_adapter: false,
+    }))
+    .ok_or_else(|| anyhow::anyhow!("Failed to find a suitable GPU adapter"))?;
+
+    info!("Using GPU: {}", adapter.get_info().name);
+
+    let (device, queue) = pollster::block_on(adapter.request_device(
+        &wgpu::DeviceDescriptor {
+            label: Some("Nebula Device"),
+            required_features: wgpu::Features::empty(),
+            required_limits: wgpu::Limits::default(),
+        },
+        None,
+    ))?;
+    Ok((adapter, device, queue))
+}
+
+/// Prefix a shader with whether the surface encodes sRGB on write.
+fn shader_source(body: &str, srgb: bool) -> String {
+    format!("const SRGB_TARGET: bool = {};\n{}", srgb, body)
+}
+
+/// Pixel scale of the 5x7 bitmap font at a given text size.
+fn glyph_scale(size: f32) -> f32 {
+    (size / 10.0).max(0.5)
+}
+
+/// Horizontal advance per glyph: 5 columns plus 1 column of spacing.
+fn glyph_advance(size: f32) -> i32 {
+    (6.0 * glyph_scale(size)) as i32
+}
+
 /// Minimal 5x7 bitmap font for basic ASCII
 fn get_glyph(c: char) -> Option<[u8; 7]> {
     Some(match c {
@@ -676,6 +1251,133 @@ fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return textureSample(t_diffuse, s_diffuse, in.uv);
+    // Text layers are premultiplied sRGB; linearize for sRGB targets
+    let c = textureSample(t_diffuse, s_diffuse, in.uv);
+    if SRGB_TARGET && c.a > 0.0 {
+        return vec4<f32>(pow(c.rgb / c.a, vec3<f32>(2.2)) * c.a, c.a);
+    }
+    return c;
+}
+"#;
+
+const RECT_SHADER: &str = r#"
+struct Screen {
+    size: vec2<f32>,
+    _pad: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> screen: Screen;
+
+struct RectOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) local: vec2<f32>,
+    @location(1) half_size: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) radius: f32,
+};
+
+@vertex
+fn vs_rect(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) pos: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) color: vec4<f32>,
+    @location(3) radius: f32,
+) -> RectOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    // Pad by a pixel so the anti-aliased edge isn't clipped
+    let pad = 1.0;
+    let corner = corners[vertex_index];
+    let pixel = pos - vec2<f32>(pad) + corner * (size + vec2<f32>(2.0 * pad));
+    let ndc = pixel / screen.size * 2.0 - vec2<f32>(1.0);
+
+    var out: RectOutput;
+    out.position = vec4<f32>(ndc.x, -ndc.y, 0.0, 1.0);
+    out.half_size = size * 0.5;
+    out.local = pixel - pos - out.half_size;
+    out.color = color;
+    out.radius = radius;
+    return out;
+}
+
+@fragment
+fn fs_rect(in: RectOutput) -> @location(0) vec4<f32> {
+    // Signed distance to a rounded box
+    let q = abs(in.local) - in.half_size + vec2<f32>(in.radius);
+    let d = length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - in.radius;
+    // Fast path: fully inside, well clear of the edge
+    var coverage = 1.0;
+    if d > -1.0 {
+        // 1px smoothstep across the boundary
+        coverage = 1.0 - smoothstep(-0.5, 0.5, d);
+    }
+
+    var rgb = in.color.rgb;
+    if SRGB_TARGET {
+        rgb = pow(rgb, vec3<f32>(2.2));
+    }
+    return vec4<f32>(rgb, in.color.a * coverage);
+}
+"#;
+
+const GLYPH_SHADER: &str = r#"
+struct Screen {
+    size: vec2<f32>,
+    _pad: vec2<f32>,
+};
+
+@group(0) @binding(0) var<uniform> screen: Screen;
+@group(1) @binding(0) var t_atlas: texture_2d<f32>;
+@group(1) @binding(1) var s_atlas: sampler;
+
+struct GlyphOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+@vertex
+fn vs_glyph(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) pos: vec2<f32>,
+    @location(1) size: vec2<f32>,
+    @location(2) uv_rect: vec4<f32>,
+    @location(3) color: vec4<f32>,
+) -> GlyphOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(0.0, 0.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(0.0, 1.0),
+        vec2<f32>(1.0, 0.0),
+        vec2<f32>(1.0, 1.0),
+    );
+    let corner = corners[vertex_index];
+    let ndc = (pos + corner * size) / screen.size * 2.0 - vec2<f32>(1.0);
+
+    var out: GlyphOutput;
+    out.position = vec4<f32>(ndc.x, -ndc.y, 0.0, 1.0);
+    out.uv = mix(uv_rect.xy, uv_rect.zw, corner);
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fs_glyph(in: GlyphOutput) -> @location(0) vec4<f32> {
+    // The atlas holds coverage only; glyphs are placed on whole pixels so
+    // each texel maps to one pixel
+    let coverage = textureSample(t_atlas, s_atlas, in.uv).r;
+    var rgb = in.color.rgb;
+    if SRGB_TARGET {
+        rgb = pow(rgb, vec3<f32>(2.2));
+    }
+    return vec4<f32>(rgb, in.color.a * coverage);
 }
 "#;
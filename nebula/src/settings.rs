@@ -0,0 +1,114 @@
+//! User Preferences
+//!
+//! Settings edited in the settings facet, kept under the user's config
+//! directory so they survive a reboot. `apply` hands them to the parts of
+//! the shell that read them: the accent to every `Color::accent()` caller,
+//! the animation speed to the simulation step. Default zoom is read once at
+//! startup.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use crate::render;
+
+/// Where settings were kept before they moved out of /tmp; read as a fallback.
+const LEGACY_SETTINGS_FILE: &str = "/tmp/aether_nebula_settings.json";
+/// Used when there's no `$HOME` to put settings under.
+const SYSTEM_SETTINGS_FILE: &str = "/var/lib/aether/nebula_settings.json";
+
+/// `animation_speed` as f32 bits; 1.0 until settings are applied.
+static ANIMATION_SPEED: AtomicU32 = AtomicU32::new(0x3f80_0000);
+
+/// `$HOME/.config/aether/nebula_settings.json`, or the system path without a home.
+pub fn settings_path() -> PathBuf {
+    match std::env::var_os("HOME") {
+        Some(home) if !home.is_empty() => PathBuf::from(home).join(".config/aether/nebula_settings.json"),
+        _ => PathBuf::from(SYSTEM_SETTINGS_FILE),
+    }
+}
+
+/// Multiplier on animation speed from the applied settings.
+pub fn animation_speed() -> f32 {
+    f32::from_bits(ANIMATION_SPEED.load(Ordering::Relaxed))
+}
+
+/// User preferences edited by the settings facet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Name of one of `render::ACCENTS`.
+    pub accent: String,
+    /// Multiplier on animation speed.
+    pub animation_speed: f32,
+    /// Canvas zoom at startup.
+    pub default_zoom: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            accent: render::ACCENTS[0].0.to_string(),
+            animation_speed: 1.0,
+            default_zoom: 1.0,
+        }
+    }
+}
+
+impl Settings {
+    /// Load saved settings, falling back to ones left in /tmp by older
+    /// builds, then to defaults.
+    pub fn load() -> Self {
+        Self::load_from(&settings_path())
+            .or_else(|| Self::load_from(Path::new(LEGACY_SETTINGS_FILE)))
+            .unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Option<Self> {
+        let data = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    pub fn save(&self) {
+        if let Err(e) = self.save_to(&settings_path()) {
+            tracing::warn!("Failed to save settings: {}", e);
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let data = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, data)
+    }
+
+    /// Make these the settings the shell draws and animates with.
+    pub fn apply(&self) {
+        render::set_accent(&self.accent);
+        ANIMATION_SPEED.store(self.animation_speed.clamp(0.25, 3.0).to_bits(), Ordering::Relaxed);
+    }
+
+    /// Step row `row` by one notch in direction `dir` (+1 or -1).
+    pub fn adjust(&mut self, row: usize, dir: i32) {
+        match row {
+            0 => {
+                let accents = render::ACCENTS;
+                let idx = accents.iter().position(|(name, _)| *name == self.accent).unwrap_or(0) as i32;
+                let next = (idx + dir).rem_euclid(accents.len() as i32) as usize;
+                self.accent = accents[next].0.to_string();
+            }
+            1 => self.animation_speed = (self.animation_speed + 0.25 * dir as f32).clamp(0.25, 3.0),
+            _ => self.default_zoom = (self.default_zoom + 0.1 * dir as f32).clamp(0.5, 2.0),
+        }
+    }
+
+    /// (label, formatted value) for each editable row.
+    pub fn rows(&self) -> [(&'static str, String); 3] {
+        [
+            ("Theme accent", self.accent.clone()),
+            ("Animation speed", format!("{:.2}x", self.animation_speed)),
+            ("Default zoom", format!("{:.0}%", self.default_zoom * 100.0)),
+        ]
+    }
+}